@@ -1,127 +1,530 @@
+//! Import of the paths of an SVG file as bezier paths.
+//!
+//! Every subpath of every `path` element is imported. Lines, quadratic curves and elliptical arcs
+//! are converted to cubic segments, and the `transform` attributes of the paths and of their
+//! groups are applied.
+
 use crate::bezier_plane::{BezierPath, BezierPlaneId, BezierVertex};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_PI_2, PI};
 use svg::{
-    node::element::path::{Command, Data, Position},
+    node::{
+        Attributes,
+        element::{
+            path::{Command, Data, Position},
+            tag::Type,
+        },
+    },
     parser::Event,
 };
-use ultraviolet::{Vec2, Vec3};
+use ultraviolet::Vec2;
 
-const SCALE: Vec2 = Vec2 { x: 0.1, y: 0.1 };
-const ORIGIN: Vec2 = Vec2 {
-    x: 134.23425,
-    y: 13.5557,
-};
+/// Elements whose content is not drawn.
+const NON_RENDERED_ELEMENTS: &[&str] = &["defs", "clipPath", "mask", "marker", "pattern", "symbol"];
 
-pub fn read_first_svg_path(file_path: &std::path::Path) -> Result<BezierPath, SvgImportError> {
-    let mut content = String::new();
-    let events = svg::open(file_path, &mut content)?;
-
-    for event in events {
-        if let Event::Tag(_, _, attributes) = event {
-            let data = attributes
-                .get("d")
-                .ok_or_else(|| SvgImportError::AttributeNotFound(String::from("d")))?;
-            let data = Data::parse(data)?;
-
-            let mut ret = PathBuilder::default();
-            for command in data.iter() {
-                match command {
-                    Command::Close => return Ok(ret.close()),
-                    Command::Move(Position::Absolute, parameters) => match parameters.as_ref() {
-                        [x, y] => {
-                            let at = Vec2::new(*x, *y);
-                            ret.start(at)?;
-                        }
-                        _ => return Err(SvgImportError::BadParameters),
-                    },
-                    Command::CubicCurve(Position::Absolute, parameters) => {
-                        let arg = MoveToParameter::from_svg_parameter(parameters)?;
-                        ret.move_to(arg)?;
-                    }
-                    _ => (),
+/// Two points closer than this distance, in SVG user units, are considered to be the same.
+const SAME_POINT_EPSILON: f32 = 1e-3;
+
+/// How the coordinates of an SVG file are mapped onto a bezier plane.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SvgImportParameters {
+    /// The plane on which the imported paths are drawn.
+    pub plane_id: BezierPlaneId,
+    /// The length, in nanometers, of one SVG user unit.
+    pub scale: f32,
+    /// The point of the SVG document that is mapped to the origin of the plane.
+    pub origin: Vec2,
+}
+
+impl Default for SvgImportParameters {
+    fn default() -> Self {
+        Self {
+            plane_id: BezierPlaneId(0),
+            scale: 0.1,
+            origin: Vec2::zero(),
+        }
+    }
+}
+
+impl SvgImportParameters {
+    /// The position on the plane of a point of the SVG document.
+    ///
+    /// The y axis of SVG documents points down, so it is flipped.
+    fn plane_position(&self, point: Vec2) -> Vec2 {
+        Vec2::new(point.x - self.origin.x, self.origin.y - point.y) * self.scale
+    }
+}
+
+/// Read all the paths of an SVG file.
+pub fn read_svg_paths(
+    file_path: &std::path::Path,
+    parameters: &SvgImportParameters,
+) -> Result<Vec<BezierPath>, SvgImportError> {
+    let content = std::fs::read_to_string(file_path)?;
+    svg_paths_from_str(&content, parameters)
+}
+
+/// Read all the paths of the content of an SVG file.
+pub fn svg_paths_from_str(
+    content: &str,
+    parameters: &SvgImportParameters,
+) -> Result<Vec<BezierPath>, SvgImportError> {
+    let mut ret = Vec::new();
+    // The transformations of the groups containing the current element.
+    let mut transforms = vec![Transform::IDENTITY];
+    let mut non_rendered_depth = 0usize;
+
+    for event in svg::read(content)? {
+        let (name, tag_type, attributes) = match event {
+            Event::Error(e) => return Err(e.into()),
+            Event::Tag(name, tag_type, attributes) => (name, tag_type, attributes),
+            _ => continue,
+        };
+        if NON_RENDERED_ELEMENTS.contains(&name) {
+            match tag_type {
+                Type::Start => non_rendered_depth += 1,
+                Type::End => non_rendered_depth = non_rendered_depth.saturating_sub(1),
+                Type::Empty => (),
+            }
+            continue;
+        }
+        let parent_transform = transforms.last().copied().unwrap_or(Transform::IDENTITY);
+        match (name, tag_type) {
+            ("g", Type::Start) => {
+                transforms.push(element_transform(parent_transform, &attributes)?);
+            }
+            ("g", Type::End) => {
+                transforms.pop();
+            }
+            ("path", Type::Start | Type::Empty) if non_rendered_depth == 0 => {
+                let transform = element_transform(parent_transform, &attributes)?;
+                let data = attributes
+                    .get("d")
+                    .ok_or_else(|| SvgImportError::AttributeNotFound(String::from("d")))?;
+                let mut builder = PathBuilder::new(transform, parameters);
+                for command in Data::parse(data)?.iter() {
+                    builder.read_command(command)?;
                 }
+                ret.extend(builder.finish());
             }
-            return Ok(ret.finish());
+            _ => (),
         }
     }
-    Err(SvgImportError::NoPathFound)
+
+    if ret.is_empty() {
+        Err(SvgImportError::NoPathFound)
+    } else {
+        Ok(ret)
+    }
 }
 
-#[derive(Default)]
-struct PathBuilder {
+fn element_transform(
+    parent_transform: Transform,
+    attributes: &Attributes,
+) -> Result<Transform, SvgImportError> {
+    match attributes.get("transform") {
+        Some(transform) => Ok(parent_transform.then(Transform::parse(transform)?)),
+        None => Ok(parent_transform),
+    }
+}
+
+/// An affine transformation, written `matrix(a b c d e f)` in SVG.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transform {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+    f: f32,
+}
+
+impl Transform {
+    const IDENTITY: Self = Self::new(1., 0., 0., 1., 0., 0.);
+
+    const fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    fn apply(&self, point: Vec2) -> Vec2 {
+        Vec2::new(
+            self.a * point.x + self.c * point.y + self.e,
+            self.b * point.x + self.d * point.y + self.f,
+        )
+    }
+
+    /// The transformation that applies `other` and then `self`.
+    #[expect(clippy::suspicious_operation_groupings)]
+    fn then(self, other: Self) -> Self {
+        Self {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    /// Parse the value of a `transform` attribute.
+    fn parse(value: &str) -> Result<Self, SvgImportError> {
+        let bad_transform = || SvgImportError::BadTransform(value.to_owned());
+        let mut ret = Self::IDENTITY;
+        let mut rest = value.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        while !rest.is_empty() {
+            let (name, args) = rest.split_once('(').ok_or_else(bad_transform)?;
+            let (args, tail) = args.split_once(')').ok_or_else(bad_transform)?;
+            let args = args
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|s| !s.is_empty())
+                .map(|arg| arg.parse::<f32>().ok())
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(bad_transform)?;
+            let transform = match (name.trim(), args.as_slice()) {
+                ("matrix", &[a, b, c, d, e, f]) => Self::new(a, b, c, d, e, f),
+                ("translate", &[x]) => Self::new(1., 0., 0., 1., x, 0.),
+                ("translate", &[x, y]) => Self::new(1., 0., 0., 1., x, y),
+                ("scale", &[s]) => Self::new(s, 0., 0., s, 0., 0.),
+                ("scale", &[x, y]) => Self::new(x, 0., 0., y, 0., 0.),
+                ("rotate", &[angle]) => Self::rotation(angle),
+                ("rotate", &[angle, x, y]) => Self::new(1., 0., 0., 1., x, y)
+                    .then(Self::rotation(angle))
+                    .then(Self::new(1., 0., 0., 1., -x, -y)),
+                ("skewX", &[angle]) => Self::new(1., 0., angle.to_radians().tan(), 1., 0., 0.),
+                ("skewY", &[angle]) => Self::new(1., angle.to_radians().tan(), 0., 1., 0., 0.),
+                _ => return Err(bad_transform()),
+            };
+            ret = ret.then(transform);
+            rest = tail.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        }
+        Ok(ret)
+    }
+
+    fn rotation(angle_degrees: f32) -> Self {
+        let (sin, cos) = angle_degrees.to_radians().sin_cos();
+        Self::new(cos, sin, -sin, cos, 0., 0.)
+    }
+}
+
+/// Build the bezier paths of the subpaths of a `path` element.
+///
+/// Points are kept in the coordinates of the element, and are mapped to the plane when a subpath
+/// is finished.
+struct PathBuilder<'a> {
+    transform: Transform,
+    parameters: &'a SvgImportParameters,
+    paths: Vec<BezierPath>,
     vertices: Vec<BezierVertex>,
+    current: Vec2,
+    subpath_start: Vec2,
+    /// The second control point of the previous command, if it was a cubic curve.
+    last_cubic_control: Option<Vec2>,
+    /// The control point of the previous command, if it was a quadratic curve.
+    last_quadratic_control: Option<Vec2>,
 }
 
-impl PathBuilder {
-    fn start(&mut self, at: Vec2) -> Result<(), SvgImportError> {
-        if self.vertices.is_empty() {
-            self.vertices = vec![BezierVertex {
-                plane_id: BezierPlaneId(0),
-                position: SCALE * at - ORIGIN,
-                position_in: None,
-                position_out: None,
-                grid_translation: Vec3::zero(),
-                angle_with_plane: 0.,
-            }];
-        } else {
-            return Err(SvgImportError::UnexpectedCommand(String::from("Move")));
+impl<'a> PathBuilder<'a> {
+    fn new(transform: Transform, parameters: &'a SvgImportParameters) -> Self {
+        Self {
+            transform,
+            parameters,
+            paths: Vec::new(),
+            vertices: Vec::new(),
+            current: Vec2::zero(),
+            subpath_start: Vec2::zero(),
+            last_cubic_control: None,
+            last_quadratic_control: None,
         }
+    }
 
+    fn read_command(&mut self, command: &Command) -> Result<(), SvgImportError> {
+        let mut cubic_control = None;
+        let mut quadratic_control = None;
+        match command {
+            Command::Move(position, parameters) => {
+                for (i, p) in chunks::<2>(parameters)?.enumerate() {
+                    let at = self.point(position, p[0], p[1]);
+                    if i == 0 {
+                        self.move_to(at);
+                    } else {
+                        // Additional coordinates of a move are implicit lines.
+                        self.line_to(at);
+                    }
+                }
+            }
+            Command::Line(position, parameters) => {
+                for p in chunks::<2>(parameters)? {
+                    let to = self.point(position, p[0], p[1]);
+                    self.line_to(to);
+                }
+            }
+            Command::HorizontalLine(position, parameters) => {
+                for [x] in chunks::<1>(parameters)? {
+                    let to = match position {
+                        Position::Absolute => Vec2::new(x, self.current.y),
+                        Position::Relative => self.current + Vec2::new(x, 0.),
+                    };
+                    self.line_to(to);
+                }
+            }
+            Command::VerticalLine(position, parameters) => {
+                for [y] in chunks::<1>(parameters)? {
+                    let to = match position {
+                        Position::Absolute => Vec2::new(self.current.x, y),
+                        Position::Relative => self.current + Vec2::new(0., y),
+                    };
+                    self.line_to(to);
+                }
+            }
+            Command::CubicCurve(position, parameters) => {
+                for p in chunks::<6>(parameters)? {
+                    let control_1 = self.point(position, p[0], p[1]);
+                    let control_2 = self.point(position, p[2], p[3]);
+                    let to = self.point(position, p[4], p[5]);
+                    self.cubic_to(control_1, control_2, to);
+                    cubic_control = Some(control_2);
+                }
+            }
+            Command::SmoothCubicCurve(position, parameters) => {
+                for p in chunks::<4>(parameters)? {
+                    let control_1 = cubic_control
+                        .or(self.last_cubic_control)
+                        .map_or(self.current, |c| 2. * self.current - c);
+                    let control_2 = self.point(position, p[0], p[1]);
+                    let to = self.point(position, p[2], p[3]);
+                    self.cubic_to(control_1, control_2, to);
+                    cubic_control = Some(control_2);
+                }
+            }
+            Command::QuadraticCurve(position, parameters) => {
+                for p in chunks::<4>(parameters)? {
+                    let control = self.point(position, p[0], p[1]);
+                    let to = self.point(position, p[2], p[3]);
+                    self.quadratic_to(control, to);
+                    quadratic_control = Some(control);
+                }
+            }
+            Command::SmoothQuadraticCurve(position, parameters) => {
+                for p in chunks::<2>(parameters)? {
+                    let control = quadratic_control
+                        .or(self.last_quadratic_control)
+                        .map_or(self.current, |c| 2. * self.current - c);
+                    let to = self.point(position, p[0], p[1]);
+                    self.quadratic_to(control, to);
+                    quadratic_control = Some(control);
+                }
+            }
+            Command::EllipticalArc(position, parameters) => {
+                for [rx, ry, angle, large_arc, sweep, x, y] in chunks::<7>(parameters)? {
+                    let to = self.point(position, x, y);
+                    let arc = EllipticalArc {
+                        from: self.current,
+                        radii: Vec2::new(rx, ry),
+                        angle: angle.to_radians(),
+                        large_arc: large_arc != 0.,
+                        sweep: sweep != 0.,
+                        to,
+                    };
+                    for [control_1, control_2, to] in arc.to_cubic_segments() {
+                        self.cubic_to(control_1, control_2, to);
+                    }
+                    self.current = to;
+                }
+            }
+            Command::Close => self.close(),
+        }
+        self.last_cubic_control = cubic_control;
+        self.last_quadratic_control = quadratic_control;
         Ok(())
     }
 
-    fn move_to(&mut self, parameters: MoveToParameter) -> Result<(), SvgImportError> {
-        let prev_vertex = self
-            .vertices
-            .last_mut()
-            .ok_or_else(|| SvgImportError::UnexpectedCommand(String::from("CubicCurve")))?;
-        prev_vertex.position_out = Some(SCALE * parameters.control_1 - ORIGIN);
+    /// The point given by the coordinates of a command.
+    fn point(&self, position: &Position, x: f32, y: f32) -> Vec2 {
+        match position {
+            Position::Absolute => Vec2::new(x, y),
+            Position::Relative => self.current + Vec2::new(x, y),
+        }
+    }
 
-        let new_vertex = BezierVertex {
-            plane_id: BezierPlaneId(0),
-            position: SCALE * parameters.position - ORIGIN,
-            position_out: None,
-            position_in: Some(SCALE * parameters.control_2 - ORIGIN),
-            grid_translation: Vec3::zero(),
-            angle_with_plane: 0.,
-        };
-        self.vertices.push(new_vertex);
+    fn move_to(&mut self, at: Vec2) {
+        self.finish_subpath(false);
+        self.vertices
+            .push(BezierVertex::new(self.parameters.plane_id, at));
+        self.current = at;
+        self.subpath_start = at;
+    }
 
-        Ok(())
+    fn line_to(&mut self, to: Vec2) {
+        let from = self.current;
+        self.cubic_to(from + (to - from) / 3., from + (to - from) * (2. / 3.), to);
     }
 
-    fn close(self) -> BezierPath {
-        BezierPath {
-            vertices: self.vertices,
-            is_cyclic: true,
-            grid_type: None,
+    fn quadratic_to(&mut self, control: Vec2, to: Vec2) {
+        let from = self.current;
+        self.cubic_to(
+            from + (control - from) * (2. / 3.),
+            to + (control - to) * (2. / 3.),
+            to,
+        );
+    }
+
+    fn cubic_to(&mut self, control_1: Vec2, control_2: Vec2, to: Vec2) {
+        if self.vertices.is_empty() {
+            // A drawing command after a close starts a new subpath at the end of the previous one.
+            self.vertices
+                .push(BezierVertex::new(self.parameters.plane_id, self.current));
+            self.subpath_start = self.current;
         }
+        if let Some(last) = self.vertices.last_mut() {
+            last.position_out = Some(control_1);
+        }
+        let mut vertex = BezierVertex::new(self.parameters.plane_id, to);
+        vertex.position_in = Some(control_2);
+        self.vertices.push(vertex);
+        self.current = to;
     }
 
-    fn finish(self) -> BezierPath {
-        BezierPath {
-            vertices: self.vertices,
-            is_cyclic: false,
-            grid_type: None,
+    fn close(&mut self) {
+        let start = self.subpath_start;
+        if self.vertices.len() > 1
+            && let Some(last) = self.vertices.last()
+            && (last.position - start).mag() > SAME_POINT_EPSILON
+        {
+            self.line_to(start);
         }
+        // The last vertex is now at the start of the subpath, merge it with the first one.
+        if self.vertices.len() > 2
+            && let Some(last) = self.vertices.pop()
+        {
+            self.vertices[0].position_in = last.position_in;
+        }
+        self.finish_subpath(true);
+        self.current = start;
+    }
+
+    fn finish_subpath(&mut self, is_cyclic: bool) {
+        let vertices = std::mem::take(&mut self.vertices);
+        if vertices.len() < 2 {
+            return;
+        }
+        let map = |point: Vec2| self.parameters.plane_position(self.transform.apply(point));
+        let vertices = vertices
+            .into_iter()
+            .map(|mut v| {
+                v.position = map(v.position);
+                v.position_in = v.position_in.map(map);
+                v.position_out = v.position_out.map(map);
+                v
+            })
+            .collect();
+        self.paths.push(BezierPath {
+            vertices,
+            is_cyclic,
+            grid_type: None,
+        });
+    }
+
+    fn finish(mut self) -> Vec<BezierPath> {
+        self.finish_subpath(false);
+        self.paths
     }
 }
 
-struct MoveToParameter {
-    position: Vec2,
-    control_1: Vec2,
-    control_2: Vec2,
+/// Split the parameters of a command in groups of `N` values.
+fn chunks<const N: usize>(
+    parameters: &[f32],
+) -> Result<impl Iterator<Item = [f32; N]> + '_, SvgImportError> {
+    if parameters.is_empty() || !parameters.len().is_multiple_of(N) {
+        return Err(SvgImportError::BadParameters);
+    }
+    Ok(parameters
+        .chunks_exact(N)
+        .filter_map(|chunk| chunk.try_into().ok()))
 }
 
-impl MoveToParameter {
-    fn from_svg_parameter(parameters: &[f32]) -> Result<Self, SvgImportError> {
-        match parameters {
-            [c1x, c1y, c2x, c2y, px, py] => Ok(Self {
-                control_1: Vec2::new(*c1x, *c1y),
-                control_2: Vec2::new(*c2x, *c2y),
-                position: Vec2::new(*px, *py),
-            }),
-            _ => Err(SvgImportError::BadParameters),
+/// An elliptical arc, described as in the `A` command of SVG paths.
+struct EllipticalArc {
+    from: Vec2,
+    radii: Vec2,
+    /// The angle between the x axis and the x axis of the ellipse, in radians.
+    angle: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Vec2,
+}
+
+impl EllipticalArc {
+    /// Approximate the arc by cubic segments spanning at most a quarter of the ellipse each.
+    ///
+    /// Each segment is given by its two control points and its end. See
+    /// <https://www.w3.org/TR/SVG/implnote.html#ArcConversionEndpointToCenter> for the conversion
+    /// to the center parameterization.
+    fn to_cubic_segments(&self) -> Vec<[Vec2; 3]> {
+        let (from, to) = (self.from, self.to);
+        if (to - from).mag() < SAME_POINT_EPSILON {
+            return vec![];
+        }
+        let (mut rx, mut ry) = (self.radii.x.abs(), self.radii.y.abs());
+        if rx < SAME_POINT_EPSILON || ry < SAME_POINT_EPSILON {
+            return vec![[from + (to - from) / 3., from + (to - from) * (2. / 3.), to]];
+        }
+
+        let (sin, cos) = self.angle.sin_cos();
+        let rotate = |v: Vec2| Vec2::new(cos * v.x - sin * v.y, sin * v.x + cos * v.y);
+        let half_chord = (from - to) / 2.;
+        let p = Vec2::new(
+            cos * half_chord.x + sin * half_chord.y,
+            -sin * half_chord.x + cos * half_chord.y,
+        );
+
+        // Scale up the radii if they are too small for the arc to reach its end.
+        let lambda = (p.x / rx).powi(2) + (p.y / ry).powi(2);
+        if lambda > 1. {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator = (rx * ry).powi(2) - (rx * p.y).powi(2) - (ry * p.x).powi(2);
+        let denominator = (rx * p.y).powi(2) + (ry * p.x).powi(2);
+        let sign = if self.large_arc == self.sweep {
+            -1.
+        } else {
+            1.
+        };
+        let coef = sign * (numerator / denominator).max(0.).sqrt();
+        let center_p = Vec2::new(coef * rx * p.y / ry, -coef * ry * p.x / rx);
+        let center = rotate(center_p) + (from + to) / 2.;
+
+        let angle_between = |u: Vec2, v: Vec2| (u.x * v.y - u.y * v.x).atan2(u.dot(v));
+        let start_vector = Vec2::new((p.x - center_p.x) / rx, (p.y - center_p.y) / ry);
+        let end_vector = Vec2::new((-p.x - center_p.x) / rx, (-p.y - center_p.y) / ry);
+        let theta = angle_between(Vec2::unit_x(), start_vector);
+        let mut delta_theta = angle_between(start_vector, end_vector);
+        if !self.sweep && delta_theta > 0. {
+            delta_theta -= 2. * PI;
+        } else if self.sweep && delta_theta < 0. {
+            delta_theta += 2. * PI;
         }
+
+        // The tolerance avoids splitting exact half or full ellipses in an extra tiny segment.
+        let nb_segments = (delta_theta.abs() / FRAC_PI_2 - 1e-3).ceil().max(1.) as usize;
+        let step = delta_theta / nb_segments as f32;
+        let k = 4. / 3. * (step / 4.).tan();
+        let point = |t: f32| center + rotate(Vec2::new(rx * t.cos(), ry * t.sin()));
+        let derivative = |t: f32| rotate(Vec2::new(-rx * t.sin(), ry * t.cos()));
+
+        (0..nb_segments)
+            .map(|i| {
+                let t0 = theta + step * i as f32;
+                let t1 = t0 + step;
+                let end = if i + 1 == nb_segments { to } else { point(t1) };
+                [
+                    point(t0) + derivative(t0) * k,
+                    end - derivative(t1) * k,
+                    end,
+                ]
+            })
+            .collect()
     }
 }
 
@@ -131,8 +534,8 @@ pub enum SvgImportError {
     SvgParserError(svg::parser::Error),
     NoPathFound,
     AttributeNotFound(String),
-    UnexpectedCommand(String),
     BadParameters,
+    BadTransform(String),
 }
 
 impl From<std::io::Error> for SvgImportError {
@@ -146,3 +549,116 @@ impl From<svg::parser::Error> for SvgImportError {
         Self::SvgParserError(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> SvgImportParameters {
+        SvgImportParameters {
+            plane_id: BezierPlaneId(0),
+            scale: 1.,
+            origin: Vec2::zero(),
+        }
+    }
+
+    fn positions(path: &BezierPath) -> Vec<Vec2> {
+        path.vertices().iter().map(|v| v.position).collect()
+    }
+
+    fn assert_close(a: Vec2, b: Vec2) {
+        assert!((a - b).mag() < 1e-4, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn every_path_and_subpath_is_imported() {
+        let content = r#"<svg xmlns="http://www.w3.org/2000/svg">
+            <path d="M 0 0 L 10 0 L 10 10 Z m 20 0 l 5 0"/>
+            <defs><path d="M 0 0 L 1 1"/></defs>
+            <path d="M 0 0 H 4 V 4"/>
+        </svg>"#;
+        let paths = svg_paths_from_str(content, &parameters()).unwrap();
+        assert_eq!(paths.len(), 3);
+        assert!(paths[0].is_cyclic);
+        assert_eq!(paths[0].vertices().len(), 3);
+        assert!(!paths[1].is_cyclic);
+        assert_eq!(
+            positions(&paths[1]),
+            [Vec2::new(20., 0.), Vec2::new(25., 0.)]
+        );
+        assert_eq!(
+            positions(&paths[2]),
+            [Vec2::zero(), Vec2::new(4., 0.), Vec2::new(4., -4.)]
+        );
+    }
+
+    #[test]
+    fn closing_a_subpath_on_its_start_merges_the_end_vertices() {
+        let content = r#"<svg><path d="M 0 0 C 1 1 2 1 3 0 C 2 -1 1 -1 0 0 z"/></svg>"#;
+        let paths = svg_paths_from_str(content, &parameters()).unwrap();
+        let vertices = paths[0].vertices();
+        assert_eq!(vertices.len(), 2);
+        assert_eq!(vertices[0].position_in, Some(Vec2::new(1., 1.)));
+        assert_eq!(vertices[0].position_out, Some(Vec2::new(1., -1.)));
+    }
+
+    #[test]
+    fn transforms_of_groups_and_paths_are_applied() {
+        let content = r#"<svg>
+            <g transform="translate(10, 0)">
+                <path transform="scale(2)" d="M 1 1 l 1 0"/>
+            </g>
+            <path d="M 1 1 l 1 0"/>
+        </svg>"#;
+        let parameters = SvgImportParameters {
+            origin: Vec2::new(2., 2.),
+            scale: 0.5,
+            ..parameters()
+        };
+        let paths = svg_paths_from_str(content, &parameters).unwrap();
+        assert_eq!(positions(&paths[0]), [Vec2::new(5., 0.), Vec2::new(6., 0.)]);
+        assert_eq!(
+            positions(&paths[1]),
+            [Vec2::new(-0.5, 0.5), Vec2::new(0., 0.5)]
+        );
+    }
+
+    #[test]
+    fn quarter_arcs_are_converted_to_one_cubic_segment() {
+        let content = r#"<svg><path d="M 1 0 A 1 1 0 0 1 0 1"/></svg>"#;
+        let paths = svg_paths_from_str(content, &parameters()).unwrap();
+        let vertices = paths[0].vertices();
+        assert_eq!(vertices.len(), 2);
+        let k = 4. / 3. * (FRAC_PI_2 / 4.).tan();
+        assert_close(vertices[0].position_out.unwrap(), Vec2::new(1., -k));
+        assert_close(vertices[1].position_in.unwrap(), Vec2::new(k, -1.));
+        assert_close(vertices[1].position, Vec2::new(0., -1.));
+    }
+
+    #[test]
+    fn half_arcs_are_split_in_quarters() {
+        let content = r#"<svg><path d="M 0 0 a 5 5 0 1 0 10 0"/></svg>"#;
+        let paths = svg_paths_from_str(content, &parameters()).unwrap();
+        assert_eq!(paths[0].vertices().len(), 3);
+        assert_close(paths[0].vertices()[1].position, Vec2::new(5., -5.));
+    }
+
+    #[test]
+    fn smooth_curves_reflect_the_previous_control_point() {
+        let content = r#"<svg><path d="M 0 0 Q 1 1 2 0 T 4 0"/></svg>"#;
+        let paths = svg_paths_from_str(content, &parameters()).unwrap();
+        let vertices = paths[0].vertices();
+        // The control point of the second curve is (3, -1), flipped by the import.
+        assert_close(
+            vertices[1].position_out.unwrap(),
+            Vec2::new(2. + 2. / 3., 2. / 3.),
+        );
+    }
+
+    #[test]
+    fn parse_transform_lists() {
+        let transform = Transform::parse("translate(1 2) rotate(90), scale(2,3)").unwrap();
+        assert_close(transform.apply(Vec2::new(1., 1.)), Vec2::new(-2., 4.));
+        assert!(Transform::parse("translate(1 2").is_err());
+    }
+}
@@ -141,7 +141,7 @@ pub struct BezierPlanesMut<'a> {
 }
 
 impl BezierPlanesMut<'_> {
-    pub fn push(&mut self, desc: BezierPlaneDescriptor) {
+    pub fn push(&mut self, desc: BezierPlaneDescriptor) -> BezierPlaneId {
         let new_key = self
             .new_map
             .keys()
@@ -149,6 +149,7 @@ impl BezierPlanesMut<'_> {
             .map(|m| BezierPlaneId(m.0 + 1))
             .unwrap_or_default();
         self.new_map.insert(new_key, Arc::new(desc));
+        new_key
     }
 }
 
@@ -217,13 +218,14 @@ impl BezierPathsMut<'_> {
         self.new_map.values_mut().map(Arc::make_mut)
     }
 
-    pub fn push(&mut self, path: BezierPath) {
+    pub fn push(&mut self, path: BezierPath) -> BezierPathId {
         let id = self
             .new_map
             .keys()
             .max()
             .map_or(BezierPathId(0), |BezierPathId(n)| BezierPathId(n + 1));
         self.new_map.insert(id, Arc::new(path));
+        id
     }
 
     #[must_use]
@@ -658,7 +660,7 @@ impl BezierPathData {
         self.instantiated_paths
             .iter()
             .flat_map(|(path_id, path)| {
-                if let Some(grid_type) = path.source_path.grid_type {
+                if let Some(grid_type) = &path.source_path.grid_type {
                     path.source_path
                         .vertices
                         .iter()
@@ -670,7 +672,7 @@ impl BezierPathData {
                             };
                             let desc = GridDescriptor {
                                 invisible: false,
-                                grid_type,
+                                grid_type: grid_type.clone(),
                                 helix_parameters: None,
                                 orientation: self.orientation_vertex(vertex_id)?,
                                 position: self.position_vertex_2d(vertex_id)? + v.grid_translation,
@@ -7,7 +7,7 @@ use crate::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs::File,
     path::Path,
     sync::Arc,
@@ -149,21 +149,16 @@ fn make_strand(
         }
         println!("ready to build domain");
         let forward = current_3 >= current_5;
-        let start = if forward {
-            subtract_skips(current_5, current_helix, vstrands)
+        let (start, end) = if forward {
+            (current_5 as isize, current_3 as isize)
         } else {
-            subtract_skips(current_3, current_helix, vstrands)
-        };
-        let end = if forward {
-            subtract_skips(current_3, current_helix, vstrands)
-        } else {
-            subtract_skips(current_5, current_helix, vstrands)
+            (current_3 as isize, current_5 as isize)
         };
         for (j, n) in insertions_on_dom {
             insertions.push((
                 Nucl {
                     helix: current_helix,
-                    position: subtract_skips(j, current_helix, vstrands),
+                    position: j as isize,
                     forward,
                 },
                 n,
@@ -219,9 +214,15 @@ fn make_strand(
     ret
 }
 
-fn subtract_skips(nucl: usize, helix: usize, vstrands: &[VStrand]) -> isize {
-    let skips: isize = (0..=nucl).map(|n| vstrands[helix].skip[n]).sum();
-    nucl as isize + skips
+/// The positions of the skips of `vstrand`, which become deletions of the corresponding helix.
+fn read_skips(vstrand: &VStrand) -> BTreeSet<isize> {
+    vstrand
+        .skip
+        .iter()
+        .enumerate()
+        .filter(|(_, skip)| **skip < 0)
+        .map(|(n, _)| n as isize)
+        .collect()
 }
 
 impl Design {
@@ -251,7 +252,8 @@ impl Design {
         for (i, v) in vstrands.iter().enumerate() {
             num_to_helix.insert(v.num, i);
             let position = grid.position_helix(v.col, v.row);
-            let helix = Helix::new(position, Rotor3::identity());
+            let mut helix = Helix::new(position, Rotor3::identity());
+            helix.deletions = read_skips(v);
             helices.insert(i, Arc::new(helix));
             for (j, color) in &v.stap_colors {
                 colors.insert((i, *j as usize), *color as usize);
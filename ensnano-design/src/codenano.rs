@@ -334,6 +334,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: Default::default(),
         }
     }
 }
@@ -10,7 +10,19 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use ultraviolet::{DMat3, DVec3, Rotor3, Vec3};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, PartialOrd, Ord)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
 #[repr(usize)]
 /// A control point of a cubic bezier curve.
 ///
@@ -22,7 +34,7 @@ pub enum CubicBezierControlPoint {
     Control2,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 /// A control point of a bezier curve.
 pub enum BezierControlPoint {
     /// One of the control points of a cubic bezier curve.
@@ -547,12 +559,13 @@ impl InterpolatedC1PiecewiseBezierDescriptor {
             let q = self.points[1];
             let rotor = Rotor3::from_rotation_between(diffs[1].normalized(), (q - p).normalized());
             diffs[0] = (q - p).normalized().rotated_by(rotor) * (diffs[1].mag());
-            // last tangent
+            // last tangent, the reflection of the previous one with respect to the last chord.
+            // Computing it with a rotor, as for the first tangent, is undefined when the last
+            // three points are aligned.
             let p = self.points[n - 2];
             let q = self.points[n - 1];
-            let rotor =
-                Rotor3::from_rotation_between(diffs[n - 2].normalized(), (p - q).normalized());
-            diffs[n - 1] = (p - q).normalized().rotated_by(rotor) * (diffs[n - 2].mag());
+            let chord = (q - p).normalized();
+            diffs[n - 1] = 2. * diffs[n - 2].dot(chord) * chord - diffs[n - 2];
         } else {
             diffs[0] = (self.points[1] - self.points[n - 1]) / 6.;
             diffs[n - 1] = (self.points[0] - self.points[n - 2]) / 6.;
@@ -0,0 +1,260 @@
+//! Import of helix axes from files of 3D points, such as CSV or XYZ files.
+//!
+//! Each line of the file that contains at least three numbers, possibly preceded by a label such
+//! as the element of an XYZ file, is a point of the axis. The other lines (headers, comments, the
+//! number of atoms of an XYZ file...) are ignored.
+
+use crate::curves::{
+    CurveDescriptor, Curved as _, bezier::InterpolatedC1PiecewiseBezierDescriptor,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ultraviolet::Vec3;
+
+/// The distance, in nucleotides, between two consecutive points of a resampled axis.
+const RESAMPLING_SPACING: f32 = 8.;
+
+/// Points closer than this distance, in nanometers, are merged.
+const MIN_DISTANCE: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct AxisImportParameters {
+    /// The length, in nanometers, of one unit of the file.
+    pub unit: f32,
+    /// The number of passes of the smoothing filter applied to the points.
+    pub smoothing_passes: usize,
+    /// Resample the axis with evenly spaced points and scale it so that its length is a whole
+    /// number of nucleotides.
+    pub resample: bool,
+}
+
+impl Default for AxisImportParameters {
+    fn default() -> Self {
+        Self {
+            unit: 1.,
+            smoothing_passes: 0,
+            resample: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AxisImportError {
+    IOError(std::io::Error),
+    NotEnoughPoints,
+    BadParameters,
+}
+
+impl From<std::io::Error> for AxisImportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IOError(e)
+    }
+}
+
+/// The axis of an helix read from a file.
+#[derive(Debug, Clone)]
+pub struct ImportedAxis {
+    pub curve: CurveDescriptor,
+    /// The number of nucleotides that fit on the axis.
+    pub nb_nucl: usize,
+}
+
+/// Read the axis of an helix in the file at `path`. `rise` is the distance between two
+/// consecutive nucleotides along the axis.
+pub fn read_axis(
+    path: impl AsRef<Path>,
+    parameters: &AxisImportParameters,
+    rise: f32,
+) -> Result<ImportedAxis, AxisImportError> {
+    let content = std::fs::read_to_string(path)?;
+    axis_from_str(&content, parameters, rise)
+}
+
+pub fn axis_from_str(
+    content: &str,
+    parameters: &AxisImportParameters,
+    rise: f32,
+) -> Result<ImportedAxis, AxisImportError> {
+    if !(parameters.unit > 0. && rise > 0.) {
+        return Err(AxisImportError::BadParameters);
+    }
+    let mut points: Vec<Vec3> = content
+        .lines()
+        .filter_map(parse_point)
+        .map(|p| p * parameters.unit)
+        .collect();
+    points.dedup_by(|p, q| (*p - *q).mag() < MIN_DISTANCE);
+    if points.len() < 2 {
+        return Err(AxisImportError::NotEnoughPoints);
+    }
+    for _ in 0..parameters.smoothing_passes {
+        smooth(&mut points);
+    }
+    if parameters.resample {
+        points = resample(&points, RESAMPLING_SPACING * rise);
+    }
+    if let [p, q] = points[..] {
+        // The interpolation of two points is not a straight segment.
+        points.insert(1, (p + q) / 2.);
+    }
+
+    let length = interpolated_length(&points);
+    let nb_nucl = if parameters.resample {
+        // Scaling the points scales the length of the interpolated curve by the same factor.
+        let nb_nucl = (length / rise as f64).round().max(1.);
+        let origin = points[0];
+        let scale = (nb_nucl * rise as f64 / length) as f32;
+        for p in &mut points {
+            *p = origin + (*p - origin) * scale;
+        }
+        nb_nucl as usize
+    } else {
+        (length / rise as f64).floor() as usize
+    };
+    Ok(ImportedAxis {
+        curve: CurveDescriptor::InterpolatedPiecewiseBezier(interpolated_curve(points)),
+        nb_nucl,
+    })
+}
+
+fn interpolated_curve(points: Vec<Vec3>) -> InterpolatedC1PiecewiseBezierDescriptor {
+    InterpolatedC1PiecewiseBezierDescriptor {
+        points,
+        is_closed: false,
+        discretize_quickly: None,
+    }
+}
+
+/// The length of the curve interpolating `points`, integrated one bezier segment at a time since
+/// the speed of the curve is not smooth at the interpolated points.
+fn interpolated_length(points: &[Vec3]) -> f64 {
+    let curve = interpolated_curve(points.to_vec()).instantiate();
+    (0..points.len() - 1)
+        .map(|i| {
+            quadrature::integrate(|t| curve.speed(t).mag(), i as f64, (i + 1) as f64, 1e-5).integral
+        })
+        .sum()
+}
+
+/// The first three numbers of `line` if all its fields, except possibly the first one, are
+/// numbers.
+fn parse_point(line: &str) -> Option<Vec3> {
+    let mut fields = line
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|f| !f.is_empty())
+        .peekable();
+    if fields.peek()?.parse::<f32>().is_err() {
+        fields.next();
+    }
+    let numbers = fields
+        .map(|f| f.parse::<f32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match numbers.as_slice() {
+        [x, y, z, ..] => Some(Vec3::new(*x, *y, *z)),
+        _ => None,
+    }
+}
+
+/// Replace each point, except the extremities, by a weighted average with its neighbours.
+fn smooth(points: &mut [Vec3]) {
+    let original = points.to_vec();
+    for (p, window) in points[1..].iter_mut().zip(original.windows(3)) {
+        if let [a, b, c] = window {
+            *p = (*a + 2. * *b + *c) / 4.;
+        }
+    }
+}
+
+/// Points of the polyline `points`, evenly spaced by approximately `spacing`, that contain the
+/// extremities of the polyline.
+fn resample(points: &[Vec3], spacing: f32) -> Vec<Vec3> {
+    let length: f32 = points.windows(2).map(|w| (w[1] - w[0]).mag()).sum();
+    let nb_segments = (length / spacing).round().max(1.) as usize;
+    let spacing = length / nb_segments as f32;
+
+    let mut ret = Vec::with_capacity(nb_segments + 1);
+    ret.push(points[0]);
+    // The curvilinear abscissa of the start of the current segment of the polyline.
+    let mut segment_start = 0.;
+    let mut segments = points.windows(2);
+    let mut segment = segments.next();
+    for i in 1..nb_segments {
+        let abscissa = i as f32 * spacing;
+        while let Some(w) = segment {
+            let segment_length = (w[1] - w[0]).mag();
+            if segment_start + segment_length >= abscissa {
+                let t = (abscissa - segment_start) / segment_length;
+                ret.push(w[0] + (w[1] - w[0]) * t);
+                break;
+            }
+            segment_start += segment_length;
+            segment = segments.next();
+        }
+    }
+    ret.extend(points.last());
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RISE: f32 = 0.332;
+
+    #[test]
+    fn csv_and_xyz_files_are_read() {
+        let csv = "x,y,z\n0,0,0\n1.5,0,0\n3,0.5,0\n";
+        let xyz = "3\nexported by some tool\nC 0 0 0\nC 1.5 0 0\nC 3 0.5 0\n";
+        let parameters = AxisImportParameters {
+            resample: false,
+            ..Default::default()
+        };
+        for content in [csv, xyz] {
+            let axis = axis_from_str(content, &parameters, RISE).unwrap();
+            let CurveDescriptor::InterpolatedPiecewiseBezier(desc) = &axis.curve else {
+                panic!("unexpected curve {:?}", axis.curve);
+            };
+            assert_eq!(desc.points.len(), 3);
+            assert_eq!(desc.points[2], Vec3::new(3., 0.5, 0.));
+        }
+    }
+
+    #[test]
+    fn resampled_axis_fits_a_whole_number_of_nucleotides() {
+        for content in [
+            "0 0 0\n10 0 0\n10 7.3 0\n",
+            "0 0 0\n10.1 0 0\n",
+            "0 0 0\n3 1 0\n5 -2 1\n9 0 4\n12 1 3\n",
+        ] {
+            let axis = axis_from_str(content, &Default::default(), RISE).unwrap();
+            let CurveDescriptor::InterpolatedPiecewiseBezier(desc) = &axis.curve else {
+                panic!("unexpected curve {:?}", axis.curve);
+            };
+            let length = interpolated_length(&desc.points);
+            assert!(
+                (length - axis.nb_nucl as f64 * RISE as f64).abs() < 1e-3,
+                "{length}, {}",
+                axis.nb_nucl
+            );
+        }
+    }
+
+    #[test]
+    fn unit_is_applied() {
+        let parameters = AxisImportParameters {
+            unit: 0.1,
+            resample: false,
+            ..Default::default()
+        };
+        let axis = axis_from_str("0 0 0\n100 0 0\n", &parameters, RISE).unwrap();
+        assert_eq!(axis.nb_nucl, (10. / RISE).floor() as usize);
+    }
+
+    #[test]
+    fn a_single_point_is_not_an_axis() {
+        assert!(matches!(
+            axis_from_str("1 2 3\n1 2 3\n", &Default::default(), RISE),
+            Err(AxisImportError::NotEnoughPoints)
+        ));
+    }
+}
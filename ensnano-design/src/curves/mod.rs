@@ -2,7 +2,9 @@ pub mod bezier;
 mod chebyshev;
 pub mod circle_curve;
 mod discretization;
+pub mod import_from_points;
 mod legacy;
+pub mod parametric;
 pub mod revolution;
 pub mod sphere_concentric_circle;
 pub mod sphere_like_spiral;
@@ -36,6 +38,7 @@ use self::{
     },
     chebyshev::{PolynomialCoordinates, PolynomialCoordinates_},
     circle_curve::CircleDescriptor,
+    parametric::{ParametricCurve_, ParametricCurveDescriptor},
     revolution::{InterpolatedCurveDescriptor, InterpolationDescriptor},
     sphere_like_spiral::SphereLikeSpiralDescriptor,
     spiral_cylinder::SpiralCylinderDescriptor,
@@ -643,6 +646,7 @@ pub enum CurveDescriptor {
     SuperTwist(SuperTwist),
     InterpolatedCurve(InterpolatedCurveDescriptor),
     Chebyshev(PolynomialCoordinates),
+    Parametric(ParametricCurveDescriptor),
     #[cfg(feature = "ensnano_upcoming")]
     Logo(LogoCurveDescriptor),
 }
@@ -846,6 +850,9 @@ impl InstantiatedCurveDescriptor {
             CurveDescriptor::Chebyshev(coord) => {
                 InstantiatedCurveDescriptor_::Chebyshev(coord.clone().instantiated())
             }
+            CurveDescriptor::Parametric(desc) => {
+                InstantiatedCurveDescriptor_::Parametric(desc.instantiated())
+            }
             #[cfg(feature = "ensnano_upcoming")]
             CurveDescriptor::Logo(desc) =>
                 InstantiatedCurveDescriptor_::Logo(desc.clone()),
@@ -933,6 +940,9 @@ impl InstantiatedCurveDescriptor {
             CurveDescriptor::Chebyshev(coord) => Some(InstantiatedCurveDescriptor_::Chebyshev(
                 coord.clone().instantiated(),
             )),
+            CurveDescriptor::Parametric(desc) => Some(InstantiatedCurveDescriptor_::Parametric(
+                desc.instantiated(),
+            )),
             #[cfg(feature = "ensnano_upcoming")]
             CurveDescriptor::Logo(desc) => Some(
                 InstantiatedCurveDescriptor_::Logo(desc.clone())),
@@ -1052,6 +1062,7 @@ enum InstantiatedCurveDescriptor_ {
     },
     InterpolatedCurve(InterpolatedCurveDescriptor),
     Chebyshev(PolynomialCoordinates_),
+    Parametric(ParametricCurve_),
     #[cfg(feature = "ensnano_upcoming")]
     Logo(LogoCurveDescriptor),
 }
@@ -1234,6 +1245,7 @@ impl InstantiatedCurveDescriptor_ {
                 Arc::new(Curve::new(desc_clone.instantiate(true), helix_parameters))
             }
             Self::Chebyshev(coordinates) => Arc::new(Curve::new(coordinates, helix_parameters)),
+            Self::Parametric(curve) => Arc::new(Curve::new(curve, helix_parameters)),
         }
     }
 
@@ -1327,6 +1339,7 @@ impl InstantiatedCurveDescriptor_ {
             Self::Chebyshev(coordinates) => {
                 Some(Arc::new(Curve::new(coordinates.clone(), helix_parameters)))
             }
+            Self::Parametric(curve) => Some(Arc::new(Curve::new(curve.clone(), helix_parameters))),
         }
     }
 
@@ -1401,6 +1414,7 @@ impl InstantiatedCurveDescriptor_ {
                 Some(Curve::compute_length(desc.clone().instantiate(true)))
             }
             Self::Chebyshev(coord) => Some(Curve::compute_length(coord.clone())),
+            Self::Parametric(curve) => Some(Curve::compute_length(curve.clone())),
         }
     }
 
@@ -1476,6 +1490,7 @@ impl InstantiatedCurveDescriptor_ {
                 Some(Curve::path(desc_clone.instantiate(false)))
             }
             Self::Chebyshev(coordinates) => Some(Curve::path(coordinates.clone())),
+            Self::Parametric(curve) => Some(Curve::path(curve.clone())),
         }
     }
 
@@ -0,0 +1,535 @@
+//! Curves whose coordinates are given by expressions of a parameter `t`.
+//!
+//! The expressions are made of numbers, the variable `t`, the constants `pi`, `tau` and `e`, the
+//! operators `+`, `-`, `*`, `/` and `^`, parentheses and the functions `sin`, `cos`, `tan`,
+//! `asin`, `acos`, `atan`, `sinh`, `cosh`, `tanh`, `sqrt`, `exp`, `ln`, `log10` and `abs`.
+
+use crate::curves::{CurveBounds, Curved};
+use serde::{Deserialize, Serialize};
+use std::{
+    f64::consts::{E, PI, TAU},
+    iter::Peekable,
+    str::CharIndices,
+};
+use ultraviolet::DVec3;
+
+/// The range of `t` of the curve used in place of a curve whose description is invalid.
+const FALLBACK_T_MAX: f64 = 10.;
+
+/// The number of intervals of the regular subdivision of the range of `t` at whose ends the curve
+/// must be defined.
+const NB_CHECKED_INTERVALS: usize = 1000;
+
+/// A curve given by the expressions of its coordinates, in nanometers, as functions of `t`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParametricCurveDescriptor {
+    pub x: String,
+    pub y: String,
+    pub z: String,
+    pub t_min: f64,
+    pub t_max: f64,
+}
+
+impl Default for ParametricCurveDescriptor {
+    fn default() -> Self {
+        Self {
+            x: "5 * cos(t)".into(),
+            y: "5 * sin(t)".into(),
+            z: "t".into(),
+            t_min: 0.,
+            t_max: TAU,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionError {
+    UnexpectedCharacter(char),
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    UnknownVariable(String),
+    UnknownFunction(String),
+    /// The bounds of `t` are not finite or are not in increasing order.
+    BadRange,
+    /// A coordinate of the curve is infinite or not a number at the given value of `t`.
+    NotFinite(f64),
+}
+
+impl ParametricCurveDescriptor {
+    /// Check that the expressions can be parsed, that the range of `t` is valid and that the
+    /// curve is defined on this range.
+    pub fn check(&self) -> Result<(), ExpressionError> {
+        self.parse().map(|_| ())
+    }
+
+    fn parse(&self) -> Result<ParametricCurve_, ExpressionError> {
+        if !(self.t_min.is_finite() && self.t_max.is_finite() && self.t_min < self.t_max) {
+            return Err(ExpressionError::BadRange);
+        }
+        let curve = ParametricCurve_ {
+            x: Expression::parse(&self.x)?,
+            y: Expression::parse(&self.y)?,
+            z: Expression::parse(&self.z)?,
+            t_min: self.t_min,
+            t_max: self.t_max,
+        };
+        curve.check_finite()?;
+        Ok(curve)
+    }
+
+    /// Invalid descriptors can only come from hand-edited files, they are replaced by a straight
+    /// segment along the x axis.
+    pub(super) fn instantiated(&self) -> ParametricCurve_ {
+        self.parse().unwrap_or_else(|e| {
+            log::error!("Invalid parametric curve {self:?}: {e:?}");
+            ParametricCurve_ {
+                x: Expression::T,
+                y: Expression::Number(0.),
+                z: Expression::Number(0.),
+                t_min: 0.,
+                t_max: FALLBACK_T_MAX,
+            }
+        })
+    }
+}
+
+/// Check that `source` is a valid expression of `t`.
+pub fn check_expression(source: &str) -> Result<(), ExpressionError> {
+    Expression::parse(source).map(|_| ())
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ParametricCurve_ {
+    x: Expression,
+    y: Expression,
+    z: Expression,
+    t_min: f64,
+    t_max: f64,
+}
+
+impl ParametricCurve_ {
+    /// Sample the curve to reject expressions that are not defined on the whole range of `t`,
+    /// such as `ln(t)` when the range contains 0.
+    fn check_finite(&self) -> Result<(), ExpressionError> {
+        for i in 0..=NB_CHECKED_INTERVALS {
+            let s = i as f64 / NB_CHECKED_INTERVALS as f64;
+            let position = self.position(s);
+            if !(position.x.is_finite() && position.y.is_finite() && position.z.is_finite()) {
+                return Err(ExpressionError::NotFinite(
+                    self.t_min * (1. - s) + self.t_max * s,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Curved for ParametricCurve_ {
+    /// The curve is reparametrized so that `position` is defined on `[0, 1]`.
+    fn position(&self, t: f64) -> DVec3 {
+        let t = self.t_min * (1. - t) + self.t_max * t;
+        DVec3 {
+            x: self.x.eval(t),
+            y: self.y.eval(t),
+            z: self.z.eval(t),
+        }
+    }
+
+    fn bounds(&self) -> CurveBounds {
+        CurveBounds::Finite
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Number(f64),
+    T,
+    Neg(Box<Self>),
+    Binary(BinaryOp, Box<Self>, Box<Self>),
+    Call(Function, Box<Self>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Sqrt,
+    Exp,
+    Ln,
+    Log10,
+    Abs,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sin" => Self::Sin,
+            "cos" => Self::Cos,
+            "tan" => Self::Tan,
+            "asin" => Self::Asin,
+            "acos" => Self::Acos,
+            "atan" => Self::Atan,
+            "sinh" => Self::Sinh,
+            "cosh" => Self::Cosh,
+            "tanh" => Self::Tanh,
+            "sqrt" => Self::Sqrt,
+            "exp" => Self::Exp,
+            "ln" => Self::Ln,
+            "log10" => Self::Log10,
+            "abs" => Self::Abs,
+            _ => return None,
+        })
+    }
+
+    fn apply(self, x: f64) -> f64 {
+        match self {
+            Self::Sin => x.sin(),
+            Self::Cos => x.cos(),
+            Self::Tan => x.tan(),
+            Self::Asin => x.asin(),
+            Self::Acos => x.acos(),
+            Self::Atan => x.atan(),
+            Self::Sinh => x.sinh(),
+            Self::Cosh => x.cosh(),
+            Self::Tanh => x.tanh(),
+            Self::Sqrt => x.sqrt(),
+            Self::Exp => x.exp(),
+            Self::Ln => x.ln(),
+            Self::Log10 => x.log10(),
+            Self::Abs => x.abs(),
+        }
+    }
+}
+
+impl Expression {
+    fn parse(source: &str) -> Result<Self, ExpressionError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let expression = parser.sum()?;
+        match parser.next() {
+            None => Ok(expression),
+            Some(token) => Err(ExpressionError::UnexpectedToken(format!("{token:?}"))),
+        }
+    }
+
+    fn eval(&self, t: f64) -> f64 {
+        match self {
+            Self::Number(x) => *x,
+            Self::T => t,
+            Self::Neg(e) => -e.eval(t),
+            Self::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(t), rhs.eval(t));
+                match op {
+                    BinaryOp::Add => lhs + rhs,
+                    BinaryOp::Sub => lhs - rhs,
+                    BinaryOp::Mul => lhs * rhs,
+                    BinaryOp::Div => lhs / rhs,
+                    BinaryOp::Pow => lhs.powf(rhs),
+                }
+            }
+            Self::Call(function, argument) => function.apply(argument.eval(t)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Operator(BinaryOp),
+    OpeningParenthesis,
+    ClosingParenthesis,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '+' => Token::Operator(BinaryOp::Add),
+            '-' => Token::Operator(BinaryOp::Sub),
+            '*' => Token::Operator(BinaryOp::Mul),
+            '/' => Token::Operator(BinaryOp::Div),
+            '^' => Token::Operator(BinaryOp::Pow),
+            '(' => Token::OpeningParenthesis,
+            ')' => Token::ClosingParenthesis,
+            c if c.is_ascii_digit() || c == '.' => {
+                let end = number_end(source, &mut chars);
+                let number = &source[start..end];
+                number
+                    .parse()
+                    .ok()
+                    .map(Token::Number)
+                    .ok_or_else(|| ExpressionError::UnexpectedToken(number.to_owned()))?
+            }
+            c if c.is_alphabetic() => {
+                let mut end = source.len();
+                while let Some((i, c)) = chars.peek() {
+                    if c.is_alphanumeric() || *c == '_' {
+                        chars.next();
+                    } else {
+                        end = *i;
+                        break;
+                    }
+                }
+                Token::Identifier(source[start..end].to_owned())
+            }
+            c => return Err(ExpressionError::UnexpectedCharacter(c)),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Consume the rest of a number, which may be written in scientific notation, and return the index
+/// of its end in `source`.
+fn number_end(source: &str, chars: &mut Peekable<CharIndices>) -> usize {
+    let is_mantissa = |(_, c): &(usize, char)| c.is_ascii_digit() || *c == '.';
+    while chars.next_if(is_mantissa).is_some() {}
+    if let Some(&(i, 'e' | 'E')) = chars.peek() {
+        let exponent = source[i + 1..].trim_start_matches(['+', '-']);
+        if exponent.starts_with(|c: char| c.is_ascii_digit()) {
+            chars.next();
+            chars.next_if(|(_, c)| *c == '+' || *c == '-');
+            while chars.next_if(|(_, c)| c.is_ascii_digit()).is_some() {}
+        }
+    }
+    chars.peek().map_or(source.len(), |(i, _)| *i)
+}
+
+/// A recursive descent parser. From the lowest to the highest precedence, the rules are
+/// `sum`, `product`, `unary` and `power`, so that `-t^2` is `-(t^2)`. Exponentiation is right
+/// associative.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let ret = self.tokens.get(self.position);
+        self.position += 1;
+        ret
+    }
+
+    fn sum(&mut self) -> Result<Expression, ExpressionError> {
+        let mut ret = self.product()?;
+        while let Some(Token::Operator(op @ (BinaryOp::Add | BinaryOp::Sub))) = self.peek() {
+            let op = *op;
+            self.position += 1;
+            ret = Expression::Binary(op, Box::new(ret), Box::new(self.product()?));
+        }
+        Ok(ret)
+    }
+
+    fn product(&mut self) -> Result<Expression, ExpressionError> {
+        let mut ret = self.unary()?;
+        while let Some(Token::Operator(op @ (BinaryOp::Mul | BinaryOp::Div))) = self.peek() {
+            let op = *op;
+            self.position += 1;
+            ret = Expression::Binary(op, Box::new(ret), Box::new(self.unary()?));
+        }
+        Ok(ret)
+    }
+
+    fn unary(&mut self) -> Result<Expression, ExpressionError> {
+        match self.peek() {
+            Some(Token::Operator(BinaryOp::Sub)) => {
+                self.position += 1;
+                Ok(Expression::Neg(Box::new(self.unary()?)))
+            }
+            Some(Token::Operator(BinaryOp::Add)) => {
+                self.position += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Expression, ExpressionError> {
+        let base = self.atom()?;
+        if matches!(self.peek(), Some(Token::Operator(BinaryOp::Pow))) {
+            self.position += 1;
+            let exponent = self.unary()?;
+            Ok(Expression::Binary(
+                BinaryOp::Pow,
+                Box::new(base),
+                Box::new(exponent),
+            ))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expression, ExpressionError> {
+        match self.next().cloned() {
+            Some(Token::Number(x)) => Ok(Expression::Number(x)),
+            Some(Token::OpeningParenthesis) => {
+                let ret = self.sum()?;
+                self.closing_parenthesis()?;
+                Ok(ret)
+            }
+            Some(Token::Identifier(name)) => {
+                if matches!(self.peek(), Some(Token::OpeningParenthesis)) {
+                    let function =
+                        Function::from_name(&name).ok_or(ExpressionError::UnknownFunction(name))?;
+                    self.position += 1;
+                    let argument = self.sum()?;
+                    self.closing_parenthesis()?;
+                    Ok(Expression::Call(function, Box::new(argument)))
+                } else {
+                    match name.as_str() {
+                        "t" => Ok(Expression::T),
+                        "pi" => Ok(Expression::Number(PI)),
+                        "tau" => Ok(Expression::Number(TAU)),
+                        "e" => Ok(Expression::Number(E)),
+                        _ => Err(ExpressionError::UnknownVariable(name)),
+                    }
+                }
+            }
+            Some(token) => Err(ExpressionError::UnexpectedToken(format!("{token:?}"))),
+            None => Err(ExpressionError::UnexpectedEnd),
+        }
+    }
+
+    fn closing_parenthesis(&mut self) -> Result<(), ExpressionError> {
+        match self.next() {
+            Some(Token::ClosingParenthesis) => Ok(()),
+            Some(token) => Err(ExpressionError::UnexpectedToken(format!("{token:?}"))),
+            None => Err(ExpressionError::UnexpectedEnd),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::CurveDescriptor;
+
+    fn eval(source: &str, t: f64) -> f64 {
+        Expression::parse(source).unwrap().eval(t)
+    }
+
+    #[test]
+    #[expect(clippy::float_cmp)]
+    fn operators_follow_usual_precedence() {
+        assert_eq!(eval("1 + 2 * 3", 0.), 7.);
+        assert_eq!(eval("(1 + 2) * 3", 0.), 9.);
+        assert_eq!(eval("8 / 4 / 2", 0.), 1.);
+        assert_eq!(eval("2 ^ 3 ^ 2", 0.), 512.);
+        assert_eq!(eval("-t^2", 3.), -9.);
+        assert_eq!(eval("2^-1", 0.), 0.5);
+        assert_eq!(eval("1.5e1 - 2E-1 * t", 10.), 13.);
+    }
+
+    #[test]
+    #[expect(clippy::float_cmp)]
+    fn functions_and_constants_are_evaluated() {
+        assert!((eval("sin(pi / 2) + cos(tau)", 0.) - 2.).abs() < 1e-12);
+        assert!((eval("ln(e ^ t)", 4.) - 4.).abs() < 1e-12);
+        assert_eq!(eval("sqrt(abs(-t))", 16.), 4.);
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        assert_eq!(
+            Expression::parse("2 * (t + 1"),
+            Err(ExpressionError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Expression::parse("foo(t)"),
+            Err(ExpressionError::UnknownFunction("foo".into()))
+        );
+        assert_eq!(
+            Expression::parse("2 * u"),
+            Err(ExpressionError::UnknownVariable("u".into()))
+        );
+        assert_eq!(
+            Expression::parse("t # 2"),
+            Err(ExpressionError::UnexpectedCharacter('#'))
+        );
+        assert!(Expression::parse("2 t").is_err());
+        assert!(Expression::parse("").is_err());
+    }
+
+    #[test]
+    fn curve_is_reparametrized_on_the_unit_interval() {
+        let descriptor = ParametricCurveDescriptor {
+            x: "t".into(),
+            y: "2 * t".into(),
+            z: "0".into(),
+            t_min: -1.,
+            t_max: 3.,
+        };
+        let curve = descriptor.instantiated();
+        assert_eq!(curve.position(0.), DVec3::new(-1., -2., 0.));
+        assert_eq!(curve.position(0.5), DVec3::new(1., 2., 0.));
+        assert_eq!(curve.position(1.), DVec3::new(3., 6., 0.));
+        let reversed = ParametricCurveDescriptor {
+            t_min: 3.,
+            t_max: -1.,
+            ..descriptor
+        };
+        assert_eq!(reversed.check(), Err(ExpressionError::BadRange));
+    }
+
+    #[test]
+    fn curves_must_be_defined_on_their_whole_range() {
+        let descriptor = ParametricCurveDescriptor {
+            x: "ln(t)".into(),
+            y: "1 / (t - 2)".into(),
+            z: "0".into(),
+            t_min: 1.,
+            t_max: 3.,
+        };
+        assert_eq!(descriptor.check(), Err(ExpressionError::NotFinite(2.)));
+        let descriptor = ParametricCurveDescriptor {
+            y: "0".into(),
+            t_min: -1.,
+            ..descriptor
+        };
+        assert_eq!(descriptor.check(), Err(ExpressionError::NotFinite(-1.)));
+        let descriptor = ParametricCurveDescriptor {
+            t_min: 0.5,
+            ..descriptor
+        };
+        assert_eq!(descriptor.check(), Ok(()));
+    }
+
+    #[test]
+    fn length_of_a_parametric_circle() {
+        let circle = CurveDescriptor::Parametric(ParametricCurveDescriptor {
+            x: "10 * cos(t)".into(),
+            y: "10 * sin(t)".into(),
+            z: "0".into(),
+            t_min: 0.,
+            t_max: TAU,
+        });
+        let length = circle.compute_length().unwrap();
+        assert!((length - 10. * TAU).abs() < 1e-3, "{length}");
+    }
+}
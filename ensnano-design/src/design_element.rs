@@ -7,16 +7,19 @@ pub enum DesignElement {
     Grid {
         id: usize,
         visible: bool,
+        locked: bool,
     },
     Strand {
         id: usize,
         length: usize,
         domain_lengths: Vec<usize>,
+        locked: bool,
     },
     Helix {
         id: usize,
         group: Option<bool>,
         locked_for_simulations: bool,
+        locked: bool,
     },
     Nucleotide {
         helix: usize,
@@ -142,13 +145,21 @@ impl DesignElement {
         match self {
             Self::Helix {
                 group,
-                locked_for_simulations: locked,
+                locked_for_simulations,
+                locked,
                 ..
             } => vec![
                 DnaAttribute::XoverGroup(*group),
-                DnaAttribute::LockedForSimulations(*locked),
+                DnaAttribute::LockedForSimulations(*locked_for_simulations),
+                DnaAttribute::LockedForEdition(*locked),
             ],
-            Self::Grid { visible, .. } => vec![DnaAttribute::Visible(*visible)],
+            Self::Grid {
+                visible, locked, ..
+            } => vec![
+                DnaAttribute::Visible(*visible),
+                DnaAttribute::LockedForEdition(*locked),
+            ],
+            Self::Strand { locked, .. } => vec![DnaAttribute::LockedForEdition(*locked)],
             _ => vec![],
         }
     }
@@ -247,11 +258,13 @@ pub enum DesignElementSection {
     Nucleotide,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DnaAttribute {
     Visible(bool),
     XoverGroup(Option<bool>),
     LockedForSimulations(bool),
+    /// A locked helix, strand or grid cannot be moved, cut, recolored or deleted.
+    LockedForEdition(bool),
 }
 
 impl DnaAttribute {
@@ -260,6 +273,7 @@ impl DnaAttribute {
             Self::Visible(_) => DnaAttributeDiscriminant::Visible,
             Self::XoverGroup(_) => DnaAttributeDiscriminant::XoverGroup,
             Self::LockedForSimulations(_) => DnaAttributeDiscriminant::LockedForSimulations,
+            Self::LockedForEdition(_) => DnaAttributeDiscriminant::LockedForEdition,
         }
     }
 
@@ -267,6 +281,7 @@ impl DnaAttribute {
         match self {
             Self::Visible(b) => AttributeWidget::new(Self::Visible(!b)),
             Self::LockedForSimulations(b) => AttributeWidget::new(Self::LockedForSimulations(!b)),
+            Self::LockedForEdition(b) => AttributeWidget::new(Self::LockedForEdition(!b)),
             Self::XoverGroup(None) => AttributeWidget::new(Self::XoverGroup(Some(false))),
             Self::XoverGroup(Some(b)) => AttributeWidget::new(if *b {
                 Self::XoverGroup(None)
@@ -293,6 +308,11 @@ impl DnaAttribute {
             } else {
                 icondata::BsUnlock
             }),
+            Self::LockedForEdition(b) => AttributeDisplay::Icon(if *b {
+                icondata::BsShieldLockFill
+            } else {
+                icondata::BsShield
+            }),
         }
     }
 
@@ -307,11 +327,17 @@ pub enum DnaAttributeDiscriminant {
     Visible,
     XoverGroup,
     LockedForSimulations,
+    LockedForEdition,
 }
 
 impl DnaAttributeDiscriminant {
     pub fn all_discriminants() -> &'static [Self] {
-        &[Self::Visible, Self::XoverGroup, Self::LockedForSimulations]
+        &[
+            Self::Visible,
+            Self::XoverGroup,
+            Self::LockedForSimulations,
+            Self::LockedForEdition,
+        ]
     }
 }
 
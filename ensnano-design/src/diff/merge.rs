@@ -0,0 +1,504 @@
+//! Three-way merge of designs.
+//!
+//! The changes made in "theirs" since the common ancestor "base" are applied to "ours". Elements
+//! are matched by identifier. When both sides changed the same element differently, the version of
+//! "ours" is kept and a conflict is reported. Elements added on both sides with the same
+//! identifier are both kept, the one of "theirs" being given a new identifier.
+//!
+//! The other fields of the designs, such as the bezier curves, the organizer tree or the cameras,
+//! are merged as a whole: the version of "theirs" is taken if only "theirs" changed it, and a
+//! conflict is reported if both sides changed it differently.
+
+use super::{attributes, same, same_strand};
+use crate::{
+    Design,
+    bezier_plane::BezierPathId,
+    design_element::DesignElementKey,
+    domains::Domain,
+    grid::{GridId, grid_collection::FreeGridId},
+    nucl::Nucl,
+    strands::{Strand, read_junctions},
+};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+};
+
+/// The fields of a design that are merged by `Merger::merge_scaffold` rather than as a whole.
+const SCAFFOLD_FIELDS: [&str; 2] = ["scaffold_id", "scaffold_sequence"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// The element was modified differently on both sides.
+    BothModified(DesignElementKey),
+    /// The element was modified on one side and removed on the other side.
+    ModifiedAndRemoved(DesignElementKey),
+    /// The scaffold or its sequence was changed differently on both sides.
+    Scaffold,
+    /// A strand of theirs, identified by its identifier in theirs, uses a nucleotide that belongs
+    /// to another strand of the merged design.
+    StrandOverlap { strand: usize, nucl: Nucl },
+    /// A strand of theirs, identified by its identifier in theirs, is on a helix that was removed
+    /// from ours.
+    MissingHelix { strand: usize, helix: usize },
+    /// A helix of theirs, identified by its identifier in theirs, is on a bezier path that is
+    /// not in the merged design.
+    MissingBezierPath { helix: usize, path: BezierPathId },
+    /// Another field of the design, named as in saved files, was changed differently on both
+    /// sides.
+    Attribute(String),
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let element = |key: &DesignElementKey| match key {
+            DesignElementKey::Grid(id) => format!("grid {id}"),
+            DesignElementKey::Helix(id) => format!("helix {id}"),
+            DesignElementKey::Strand(id) => format!("strand {id}"),
+            key => format!("{key:?}"),
+        };
+        match self {
+            Self::BothModified(key) => write!(f, "{} was modified on both sides", element(key)),
+            Self::ModifiedAndRemoved(key) => write!(
+                f,
+                "{} was modified on one side and removed on the other side",
+                element(key)
+            ),
+            Self::Scaffold => write!(f, "the scaffold was changed on both sides"),
+            Self::StrandOverlap { strand, nucl } => write!(
+                f,
+                "strand {strand} of theirs overlaps another strand at nucleotide {nucl}"
+            ),
+            Self::MissingHelix { strand, helix } => write!(
+                f,
+                "strand {strand} of theirs is on helix {helix} that was removed"
+            ),
+            Self::MissingBezierPath { helix, path } => write!(
+                f,
+                "helix {helix} of theirs is on bezier path {} that is missing",
+                path.0
+            ),
+            Self::Attribute(name) => write!(f, "{name} was changed on both sides"),
+        }
+    }
+}
+
+/// The result of a three-way merge.
+pub struct MergedDesign {
+    pub design: Design,
+    /// The changes of theirs that could not be applied.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Resolution {
+    KeepOurs,
+    /// Take the version of theirs, or remove the element if theirs removed it.
+    TakeTheirs,
+    /// Both sides added an element with the same identifier.
+    AddedOnBothSides,
+    BothModified,
+    ModifiedAndRemoved,
+}
+
+impl Resolution {
+    fn conflict(self, key: DesignElementKey) -> Option<MergeConflict> {
+        match self {
+            Self::BothModified => Some(MergeConflict::BothModified(key)),
+            Self::ModifiedAndRemoved => Some(MergeConflict::ModifiedAndRemoved(key)),
+            _ => None,
+        }
+    }
+}
+
+fn resolve<T>(
+    base: Option<&T>,
+    ours: Option<&T>,
+    theirs: Option<&T>,
+    same: impl Fn(&T, &T) -> bool,
+) -> Resolution {
+    let eq = |a: Option<&T>, b: Option<&T>| match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => same(a, b),
+        _ => false,
+    };
+    if eq(ours, theirs) || eq(base, theirs) {
+        Resolution::KeepOurs
+    } else if eq(base, ours) {
+        Resolution::TakeTheirs
+    } else if base.is_none() {
+        Resolution::AddedOnBothSides
+    } else if ours.is_none() || theirs.is_none() {
+        Resolution::ModifiedAndRemoved
+    } else {
+        Resolution::BothModified
+    }
+}
+
+/// The resolution of every identifier used in one of the three versions.
+fn resolutions<'a, T>(
+    [base, ours, theirs]: [BTreeMap<usize, &'a T>; 3],
+    same: impl Fn(&T, &T) -> bool,
+) -> BTreeMap<usize, Resolution> {
+    let ids: BTreeSet<usize> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .copied()
+        .collect();
+    ids.into_iter()
+        .map(|id| {
+            let get = |map: &BTreeMap<usize, &'a T>| map.get(&id).copied();
+            (id, resolve(get(&base), get(&ours), get(&theirs), &same))
+        })
+        .collect()
+}
+
+/// Merge the changes made in `theirs` since `base` into `ours`.
+pub fn merge(base: &Design, ours: &Design, theirs: &Design) -> MergedDesign {
+    let mut merger = Merger {
+        design: ours.clone(),
+        conflicts: Vec::new(),
+        grid_ids: HashMap::new(),
+        helix_ids: HashMap::new(),
+        strand_ids: HashMap::new(),
+    };
+    merger.merge_attributes(base, ours, theirs);
+    merger.merge_grids(base, ours, theirs);
+    merger.merge_helices(base, ours, theirs);
+    merger.merge_strands(base, ours, theirs);
+    merger.restore_used_elements(ours);
+    merger.merge_scaffold(base, ours, theirs);
+    MergedDesign {
+        design: merger.design,
+        conflicts: merger.conflicts,
+    }
+}
+
+struct Merger {
+    design: Design,
+    conflicts: Vec<MergeConflict>,
+    /// The new identifiers of the elements of theirs that were added with an identifier also
+    /// added by ours.
+    grid_ids: HashMap<usize, usize>,
+    helix_ids: HashMap<usize, usize>,
+    strand_ids: HashMap<usize, usize>,
+}
+
+impl Merger {
+    /// Merge the fields of the designs that are not merged element by element.
+    fn merge_attributes(&mut self, base: &Design, ours: &Design, theirs: &Design) {
+        let [base, ours, theirs] = [base, ours, theirs].map(attributes);
+        let names: BTreeSet<&String> = base
+            .keys()
+            .chain(ours.keys())
+            .chain(theirs.keys())
+            .filter(|name| !SCAFFOLD_FIELDS.contains(&name.as_str()))
+            .collect();
+        let mut taken = Vec::new();
+        for name in names {
+            match resolve(
+                base.get(name),
+                ours.get(name),
+                theirs.get(name),
+                PartialEq::eq,
+            ) {
+                Resolution::KeepOurs => (),
+                Resolution::TakeTheirs => taken.push(name),
+                _ => self.conflicts.push(MergeConflict::Attribute(name.clone())),
+            }
+        }
+        if taken.is_empty() {
+            return;
+        }
+
+        let Ok(Value::Object(mut fields)) = serde_json::to_value(&self.design) else {
+            log::error!("Could not serialize design");
+            return;
+        };
+        for name in &taken {
+            if let Some(value) = theirs.get(*name) {
+                fields.insert((*name).clone(), value.clone());
+            } else {
+                fields.remove(*name);
+            }
+        }
+        match serde_json::from_value(Value::Object(fields)) {
+            Ok(design) => self.design = design,
+            Err(e) => {
+                log::error!("Could not merge the fields of the designs: {e}");
+                self.conflicts.extend(
+                    taken
+                        .into_iter()
+                        .map(|name| MergeConflict::Attribute(name.clone())),
+                );
+            }
+        }
+    }
+
+    fn merge_grids(&mut self, base: &Design, ours: &Design, theirs: &Design) {
+        let grids =
+            [base, ours, theirs].map(|d| d.free_grids.iter().map(|(id, g)| (id.0, g)).collect());
+        let resolutions = resolutions(grids, same);
+
+        let mut new_grids = self.design.free_grids.make_mut();
+        let mut added = Vec::new();
+        for (id, resolution) in resolutions {
+            match resolution {
+                Resolution::TakeTheirs => {
+                    if let Some(grid) = theirs.free_grids.get(&FreeGridId(id)) {
+                        new_grids.insert(FreeGridId(id), grid.clone());
+                    } else {
+                        new_grids.remove(&GridId::FreeGrid(id));
+                    }
+                }
+                Resolution::AddedOnBothSides => added.push(id),
+                resolution => self
+                    .conflicts
+                    .extend(resolution.conflict(DesignElementKey::Grid(id))),
+            }
+        }
+        for id in added {
+            if let Some(grid) = theirs.free_grids.get(&FreeGridId(id))
+                && let GridId::FreeGrid(new_id) = new_grids.push(grid.clone())
+            {
+                self.grid_ids.insert(id, new_id);
+            }
+        }
+    }
+
+    fn merge_helices(&mut self, base: &Design, ours: &Design, theirs: &Design) {
+        let helices =
+            [base, ours, theirs].map(|d| d.helices.iter().map(|(id, h)| (*id, h)).collect());
+        let resolutions = resolutions(helices, same);
+
+        let theirs_helix = |id: usize| {
+            let mut helix = theirs.helices.get(&id)?.clone();
+            if let Some(position) = helix.grid_position.as_mut()
+                && let GridId::FreeGrid(g_id) = position.grid
+                && let Some(new_id) = self.grid_ids.get(&g_id)
+            {
+                position.grid = GridId::FreeGrid(*new_id);
+            }
+            Some(helix)
+        };
+        // The helices of theirs on a bezier path that is not in the merged design cannot be taken.
+        let missing_paths: BTreeMap<usize, BezierPathId> = theirs
+            .helices
+            .iter()
+            .filter_map(|(id, helix)| {
+                let grid_path = helix.grid_position.and_then(|p| match p.grid {
+                    GridId::BezierPathGrid(vertex) => Some(vertex.path_id),
+                    GridId::FreeGrid(_) => None,
+                });
+                let path = helix.get_bezier_path_id().or(grid_path)?;
+                self.design
+                    .bezier_paths
+                    .get(&path)
+                    .is_none()
+                    .then_some((*id, path))
+            })
+            .collect();
+        let mut new_helices = self.design.helices.make_mut();
+        let mut added = Vec::new();
+        for (id, resolution) in resolutions {
+            if matches!(
+                resolution,
+                Resolution::TakeTheirs | Resolution::AddedOnBothSides
+            ) && let Some(path) = missing_paths.get(&id)
+            {
+                self.conflicts.push(MergeConflict::MissingBezierPath {
+                    helix: id,
+                    path: *path,
+                });
+                continue;
+            }
+            match resolution {
+                Resolution::TakeTheirs => {
+                    if let Some(helix) = theirs_helix(id) {
+                        new_helices.insert(id, helix);
+                    } else {
+                        new_helices.remove(&id);
+                    }
+                }
+                Resolution::AddedOnBothSides => added.push(id),
+                resolution => self
+                    .conflicts
+                    .extend(resolution.conflict(DesignElementKey::Helix(id))),
+            }
+        }
+        for id in added {
+            if let Some(helix) = theirs_helix(id) {
+                let new_id = new_helices.push_helix(helix);
+                self.helix_ids.insert(id, new_id);
+            }
+        }
+    }
+
+    fn merge_strands(&mut self, base: &Design, ours: &Design, theirs: &Design) {
+        let strands =
+            [base, ours, theirs].map(|d| d.strands.iter().map(|(id, s)| (*id, s)).collect());
+        let resolutions = resolutions(strands, same_strand);
+
+        // The nucleotides of the strands of ours that are kept.
+        let mut occupied: HashSet<Nucl> = ours
+            .strands
+            .iter()
+            .filter(|(id, _)| resolutions.get(id) != Some(&Resolution::TakeTheirs))
+            .flat_map(|(_, strand)| nucls(strand))
+            .collect();
+
+        let mut added = Vec::new();
+        for (id, resolution) in &resolutions {
+            match resolution {
+                Resolution::TakeTheirs => {
+                    if let Some(strand) = theirs.strands.get(id) {
+                        if let Some(strand) = self.accept_strand(*id, strand, &occupied) {
+                            occupied.extend(nucls(&strand));
+                            self.design.strands.insert(*id, strand);
+                        } else if let Some(strand) = ours.strands.get(id) {
+                            occupied.extend(nucls(strand));
+                        }
+                    } else {
+                        self.design.strands.remove(id);
+                    }
+                }
+                Resolution::AddedOnBothSides => added.push(*id),
+                resolution => self
+                    .conflicts
+                    .extend(resolution.conflict(DesignElementKey::Strand(*id))),
+            }
+        }
+        for id in added {
+            let Some(strand) = theirs.strands.get(&id) else {
+                continue;
+            };
+            if let Some(strand) = self.accept_strand(id, strand, &occupied) {
+                let new_id = self.design.strands.keys().max().map_or(0, |m| m + 1);
+                occupied.extend(nucls(&strand));
+                self.design.strands.insert(new_id, strand);
+                self.strand_ids.insert(id, new_id);
+            }
+        }
+    }
+
+    /// The strand `strand` of theirs, with its domains on the helices of the merged design, if it
+    /// can be added to the merged design.
+    fn accept_strand(
+        &mut self,
+        id: usize,
+        strand: &Strand,
+        occupied: &HashSet<Nucl>,
+    ) -> Option<Strand> {
+        let mut strand = strand.clone();
+        for domain in &mut strand.domains {
+            let Domain::HelixDomain(interval) = domain else {
+                continue;
+            };
+            if let Some(helix) = self.helix_ids.get(&interval.helix) {
+                interval.helix = *helix;
+            }
+            if !self.design.helices.contains_key(&interval.helix) {
+                self.conflicts.push(MergeConflict::MissingHelix {
+                    strand: id,
+                    helix: interval.helix,
+                });
+                return None;
+            }
+        }
+        if let Some(nucl) = nucls(&strand).find(|n| occupied.contains(n)) {
+            self.conflicts
+                .push(MergeConflict::StrandOverlap { strand: id, nucl });
+            return None;
+        }
+        // The junctions of theirs refer to identifiers of cross-overs of theirs.
+        strand.junctions = read_junctions(&strand.domains, strand.is_cyclic);
+        Some(strand)
+    }
+
+    /// Put back the helices and grids of ours that were removed by theirs but are still used by
+    /// the merged design.
+    fn restore_used_elements(&mut self, ours: &Design) {
+        let used_helices: BTreeSet<usize> = self
+            .design
+            .strands
+            .values()
+            .flat_map(|s| &s.domains)
+            .filter_map(|d| match d {
+                Domain::HelixDomain(interval) => Some(interval.helix),
+                Domain::Insertion { .. } => None,
+            })
+            .collect();
+        let mut new_helices = self.design.helices.make_mut();
+        for id in used_helices {
+            if new_helices.get(&id).is_none()
+                && let Some(helix) = ours.helices.get(&id)
+            {
+                new_helices.insert(id, helix.clone());
+                self.conflicts
+                    .push(MergeConflict::ModifiedAndRemoved(DesignElementKey::Helix(
+                        id,
+                    )));
+            }
+        }
+        drop(new_helices);
+
+        let used_grids: BTreeSet<usize> = self
+            .design
+            .helices
+            .values()
+            .filter_map(|h| match h.grid_position?.grid {
+                GridId::FreeGrid(id) => Some(id),
+                GridId::BezierPathGrid(_) => None,
+            })
+            .collect();
+        let mut new_grids = self.design.free_grids.make_mut();
+        for id in used_grids {
+            if new_grids.get_mut(&FreeGridId(id)).is_none()
+                && let Some(grid) = ours.free_grids.get(&FreeGridId(id))
+            {
+                new_grids.insert(FreeGridId(id), grid.clone());
+                self.conflicts
+                    .push(MergeConflict::ModifiedAndRemoved(DesignElementKey::Grid(
+                        id,
+                    )));
+            }
+        }
+    }
+
+    fn merge_scaffold(&mut self, base: &Design, ours: &Design, theirs: &Design) {
+        let theirs_id = theirs
+            .scaffold_id
+            .map(|id| self.strand_ids.get(&id).copied().unwrap_or(id));
+        let scaffold = |id: Option<usize>, d: &Design| (id, d.scaffold_sequence.clone());
+        let [base, ours, theirs] = [
+            scaffold(base.scaffold_id, base),
+            scaffold(ours.scaffold_id, ours),
+            scaffold(theirs_id, theirs),
+        ];
+        match resolve(Some(&base), Some(&ours), Some(&theirs), PartialEq::eq) {
+            Resolution::KeepOurs => (),
+            Resolution::TakeTheirs => {
+                (self.design.scaffold_id, self.design.scaffold_sequence) = theirs;
+            }
+            _ => self.conflicts.push(MergeConflict::Scaffold),
+        }
+    }
+}
+
+fn nucls(strand: &Strand) -> impl Iterator<Item = Nucl> + '_ {
+    strand.domains.iter().flat_map(|d| {
+        let interval = match d {
+            Domain::HelixDomain(interval) => Some(interval),
+            Domain::Insertion { .. } => None,
+        };
+        interval.into_iter().flat_map(|interval| {
+            interval.iter().map(|position| Nucl {
+                helix: interval.helix,
+                position,
+                forward: interval.forward,
+            })
+        })
+    })
+}
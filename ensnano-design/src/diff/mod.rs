@@ -0,0 +1,262 @@
+//! Structural comparison of designs.
+//!
+//! Unlike a textual diff of the saved files, the differences are expressed in terms of the
+//! elements of the designs: grids, helices, strands, domains and cross-overs. The other fields of
+//! the designs, such as the bezier curves, the organizer tree or the cameras, are compared as a
+//! whole and refered to by their name in the saved files.
+
+pub mod merge;
+
+use crate::{
+    Design,
+    domains::{Domain, helix_interval::HelixInterval},
+    nucl::Nucl,
+    strands::Strand,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl Change {
+    fn symbol(self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Removed => '-',
+            Self::Modified => '~',
+        }
+    }
+}
+
+/// The nucleotides covered by a domain, regardless of its sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DomainKey {
+    pub helix: usize,
+    pub start: isize,
+    pub end: isize,
+    pub forward: bool,
+}
+
+impl From<&HelixInterval> for DomainKey {
+    fn from(interval: &HelixInterval) -> Self {
+        Self {
+            helix: interval.helix,
+            start: interval.start,
+            end: interval.end,
+            forward: interval.forward,
+        }
+    }
+}
+
+impl fmt::Display for DomainKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let arrow = if self.forward { "->" } else { "<-" };
+        write!(
+            f,
+            "[H{}: {} {arrow} {}]",
+            self.helix,
+            self.start,
+            self.end - 1
+        )
+    }
+}
+
+/// The differences between two versions of a design.
+#[derive(Debug, Clone, Default)]
+pub struct DesignDiff {
+    pub grids: BTreeMap<usize, Change>,
+    pub helices: BTreeMap<usize, Change>,
+    pub strands: BTreeMap<usize, Change>,
+    /// The domains that only exist in the new version of a modified strand, with the identifier
+    /// of the strand.
+    pub added_domains: Vec<(usize, DomainKey)>,
+    /// The domains that only exist in the old version of a modified strand, with the identifier
+    /// of the strand.
+    pub removed_domains: Vec<(usize, DomainKey)>,
+    pub added_xovers: Vec<(Nucl, Nucl)>,
+    pub removed_xovers: Vec<(Nucl, Nucl)>,
+    /// The names, as saved, of the other fields of the design that changed.
+    pub attributes: BTreeSet<String>,
+}
+
+impl DesignDiff {
+    pub fn is_empty(&self) -> bool {
+        self.grids.is_empty()
+            && self.helices.is_empty()
+            && self.strands.is_empty()
+            && self.attributes.is_empty()
+    }
+}
+
+impl fmt::Display for DesignDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (id, change) in &self.grids {
+            writeln!(f, "{} grid {id}", change.symbol())?;
+        }
+        for (id, change) in &self.helices {
+            writeln!(f, "{} helix {id}", change.symbol())?;
+        }
+        for (id, change) in &self.strands {
+            writeln!(f, "{} strand {id}", change.symbol())?;
+            let domains = self
+                .removed_domains
+                .iter()
+                .map(|d| (Change::Removed, d))
+                .chain(self.added_domains.iter().map(|d| (Change::Added, d)));
+            for (change, (_, domain)) in domains.filter(|(_, (s_id, _))| s_id == id) {
+                writeln!(f, "    {} domain {domain}", change.symbol())?;
+            }
+        }
+        let xovers = self
+            .removed_xovers
+            .iter()
+            .map(|x| (Change::Removed, x))
+            .chain(self.added_xovers.iter().map(|x| (Change::Added, x)));
+        for (change, (prime5, prime3)) in xovers {
+            writeln!(f, "{} xover {prime5} -> {prime3}", change.symbol())?;
+        }
+        for name in &self.attributes {
+            writeln!(f, "{} {name}", Change::Modified.symbol())?;
+        }
+        Ok(())
+    }
+}
+
+/// The differences between the `old` and `new` versions of a design.
+pub fn diff(old: &Design, new: &Design) -> DesignDiff {
+    let grids = changes(
+        old.free_grids.iter().map(|(id, g)| (id.0, g)),
+        new.free_grids.iter().map(|(id, g)| (id.0, g)),
+        same,
+    );
+    let helices = changes(
+        old.helices.iter().map(|(id, h)| (*id, h)),
+        new.helices.iter().map(|(id, h)| (*id, h)),
+        same,
+    );
+    let strands = changes(
+        old.strands.iter().map(|(id, s)| (*id, s)),
+        new.strands.iter().map(|(id, s)| (*id, s)),
+        same_strand,
+    );
+
+    let mut added_domains = Vec::new();
+    let mut removed_domains = Vec::new();
+    for (id, _) in strands.iter().filter(|(_, c)| **c == Change::Modified) {
+        let old_domains = domain_keys(&old.strands.get(id).unwrap().domains);
+        let new_domains = domain_keys(&new.strands.get(id).unwrap().domains);
+        added_domains.extend(new_domains.difference(&old_domains).map(|d| (*id, *d)));
+        removed_domains.extend(old_domains.difference(&new_domains).map(|d| (*id, *d)));
+    }
+
+    let old_xovers = xovers(old);
+    let new_xovers = xovers(new);
+    let sorted = |xovers: HashSet<&(Nucl, Nucl)>| {
+        let mut ret: Vec<(Nucl, Nucl)> = xovers.into_iter().copied().collect();
+        ret.sort_by_key(|(a, b)| {
+            (
+                a.helix, a.position, a.forward, b.helix, b.position, b.forward,
+            )
+        });
+        ret
+    };
+
+    let old_attributes = attributes(old);
+    let new_attributes = attributes(new);
+    let attributes = old_attributes
+        .keys()
+        .chain(new_attributes.keys())
+        .filter(|name| old_attributes.get(*name) != new_attributes.get(*name))
+        .cloned()
+        .collect();
+
+    DesignDiff {
+        grids,
+        helices,
+        strands,
+        added_domains,
+        removed_domains,
+        added_xovers: sorted(new_xovers.difference(&old_xovers).collect()),
+        removed_xovers: sorted(old_xovers.difference(&new_xovers).collect()),
+        attributes,
+    }
+}
+
+/// The fields of a design as saved, except its grids, helices and strands that are compared
+/// element by element, and the version of ENSnano that saved it. The fields that are not saved
+/// because they have their default value are absent.
+fn attributes(design: &Design) -> BTreeMap<String, Value> {
+    const SKIPPED: [&str; 4] = ["free_grids", "helices", "strands", "ensnano_version"];
+    let Ok(Value::Object(fields)) = serde_json::to_value(design) else {
+        log::error!("Could not serialize design");
+        return BTreeMap::new();
+    };
+    fields
+        .into_iter()
+        .filter(|(name, _)| !SKIPPED.contains(&name.as_str()))
+        .collect()
+}
+
+fn changes<'a, T: 'a>(
+    old: impl Iterator<Item = (usize, &'a T)>,
+    new: impl Iterator<Item = (usize, &'a T)>,
+    same: impl Fn(&T, &T) -> bool,
+) -> BTreeMap<usize, Change> {
+    let old: BTreeMap<_, _> = old.collect();
+    let new: BTreeMap<_, _> = new.collect();
+    let ids: BTreeSet<usize> = old.keys().chain(new.keys()).copied().collect();
+    ids.into_iter()
+        .filter_map(|id| {
+            let change = match (old.get(&id), new.get(&id)) {
+                (None, _) => Change::Added,
+                (_, None) => Change::Removed,
+                (Some(a), Some(b)) if !same(a, b) => Change::Modified,
+                _ => return None,
+            };
+            Some((id, change))
+        })
+        .collect()
+}
+
+/// Whether two elements would be saved identically.
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Whether two strands are identical. The junctions are ignored since the identifiers of the
+/// cross-overs depend on the order in which the design was read.
+fn same_strand(a: &Strand, b: &Strand) -> bool {
+    let without_junctions = |s: &Strand| Strand {
+        junctions: Vec::new(),
+        ..s.clone()
+    };
+    same(&without_junctions(a), &without_junctions(b))
+}
+
+fn domain_keys(domains: &[Domain]) -> BTreeSet<DomainKey> {
+    domains
+        .iter()
+        .filter_map(|d| match d {
+            Domain::HelixDomain(interval) => Some(DomainKey::from(interval)),
+            Domain::Insertion { .. } => None,
+        })
+        .collect()
+}
+
+fn xovers(design: &Design) -> HashSet<(Nucl, Nucl)> {
+    design
+        .strands
+        .values()
+        .filter(|s| !s.domains.is_empty())
+        .flat_map(Strand::xovers)
+        .collect()
+}
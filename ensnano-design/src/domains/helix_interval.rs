@@ -1,4 +1,4 @@
-use crate::nucl::Nucl;
+use crate::{helices::Helices, nucl::Nucl};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
@@ -27,7 +27,7 @@ impl Iterator for DomainIter {
     }
 }
 
-#[derive(Default, Clone, Serialize, Deserialize)]
+#[derive(Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct HelixInterval {
     /// Index of the helix in the array of helices. Indices start at
     /// 0.
@@ -91,6 +91,14 @@ impl HelixInterval {
             forward: self.forward,
         }
     }
+
+    /// Iterate over the positions of the domain that hold a nucleotide, i.e. that are not
+    /// deleted on the helix of the domain.
+    pub fn iter_nucls<'a>(&self, helices: &'a Helices) -> impl Iterator<Item = isize> + 'a {
+        let helix = helices.get(&self.helix);
+        self.iter()
+            .filter(move |n| !helix.is_some_and(|h| h.has_deletion_at(*n)))
+    }
 }
 
 impl std::fmt::Display for HelixInterval {
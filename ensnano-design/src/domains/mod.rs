@@ -335,6 +335,40 @@ impl Domain {
     }
 }
 
+/// Two domains are equal if they describe the same nucleotides, regardless of the instantiation
+/// of insertions.
+///
+/// The instantiation of an insertion is not part of the design: it is not saved, and it is
+/// recomputed from the positions of the helices around the insertion when the design is updated.
+/// Comparing it would make a strand differ from its copy whose insertions are not instantiated yet,
+/// and make a locked strand appear modified when an unlocked helix on which it lies is moved.
+impl PartialEq for Domain {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::HelixDomain(a), Self::HelixDomain(b)) => a == b,
+            (
+                Self::Insertion {
+                    nb_nucl,
+                    sequence,
+                    attached_to_prime3,
+                    ..
+                },
+                Self::Insertion {
+                    nb_nucl: other_nb_nucl,
+                    sequence: other_sequence,
+                    attached_to_prime3: other_attached_to_prime3,
+                    ..
+                },
+            ) => {
+                nb_nucl == other_nb_nucl
+                    && sequence == other_sequence
+                    && attached_to_prime3 == other_attached_to_prime3
+            }
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for Domain {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
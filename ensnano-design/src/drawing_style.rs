@@ -149,7 +149,7 @@ impl FromStr for DrawingAttribute {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Copy, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, Default, PartialEq)]
 pub struct DrawingStyle {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub sphere_radius: Option<f32>,
@@ -244,6 +244,10 @@ impl From<Vec<DrawingAttribute>> for DrawingStyle {
 }
 
 impl DrawingStyle {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
     #[must_use]
     pub fn complete_with_attribute(&self, att: DrawingAttribute) -> Self {
         match att {
@@ -334,7 +338,7 @@ impl DrawingStyle {
                 .or(other.helix_as_cylinder_radius),
             sphere_color: self.sphere_color.or(other.sphere_color),
             bond_color: self.bond_color.or(other.bond_color),
-            xover_color: self.xover_color.or(other.bond_color),
+            xover_color: self.xover_color.or(other.xover_color),
             alpha: self.alpha.or(other.alpha),
             xover_alpha: self.xover_alpha.or(other.xover_alpha),
             helix_as_cylinder_color: self
@@ -351,3 +355,264 @@ impl DrawingStyle {
         }
     }
 }
+
+/// The prefix of the group names that used to hold drawing attributes.
+const STYLE_TAG_PREFIX: &str = "style:";
+
+/// Read the drawing attributes of a group name of the form `style: %sr(2) %hc(FF0000) name`.
+///
+/// Return the name without the prefix and the attributes, and the style that they define, or
+/// `None` if `name` does not start with `style:`.
+pub fn read_style_tags(name: &str) -> Option<(String, DrawingStyle)> {
+    let tags = name.trim().strip_prefix(STYLE_TAG_PREFIX)?;
+    let mut attributes = Vec::new();
+    let mut remaining = Vec::new();
+    for word in tags.split([' ', ':']).filter(|w| !w.is_empty()) {
+        if let Ok(attribute) = DrawingAttribute::from_str(word) {
+            attributes.push(attribute);
+        } else {
+            remaining.push(word);
+        }
+    }
+    Some((remaining.join(" "), DrawingStyle::from(attributes)))
+}
+
+/// A field of a [DrawingStyle] that is edited as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StyleField {
+    SphereRadius,
+    BondRadius,
+    HelixAsCylinderRadius,
+    SphereColor,
+    BondColor,
+    XoverColor,
+    HelixAsCylinderColor,
+    Alpha,
+    XoverAlpha,
+    ColorShade,
+    HueRange,
+    Curvature,
+    Torsion,
+}
+
+impl StyleField {
+    pub const ALL: [Self; 13] = [
+        Self::SphereRadius,
+        Self::BondRadius,
+        Self::HelixAsCylinderRadius,
+        Self::SphereColor,
+        Self::BondColor,
+        Self::XoverColor,
+        Self::HelixAsCylinderColor,
+        Self::Alpha,
+        Self::XoverAlpha,
+        Self::ColorShade,
+        Self::HueRange,
+        Self::Curvature,
+        Self::Torsion,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::SphereRadius => "Sphere radius (Å)",
+            Self::BondRadius => "Bond radius (Å)",
+            Self::HelixAsCylinderRadius => "Cylinder radius (Å)",
+            Self::SphereColor => "Sphere color",
+            Self::BondColor => "Bond color",
+            Self::XoverColor => "Crossover color",
+            Self::HelixAsCylinderColor => "Cylinder color",
+            Self::Alpha => "Opacity",
+            Self::XoverAlpha => "Crossover opacity",
+            Self::ColorShade => "Color shade",
+            Self::HueRange => "Hue range",
+            Self::Curvature => "Curvature (r min, r max)",
+            Self::Torsion => "Torsion (t min, t max)",
+        }
+    }
+
+    /// A hint on the expected format of the value.
+    pub fn placeholder(self) -> &'static str {
+        match self {
+            Self::SphereRadius | Self::BondRadius | Self::HelixAsCylinderRadius => "2.0",
+            Self::SphereColor
+            | Self::BondColor
+            | Self::XoverColor
+            | Self::HelixAsCylinderColor
+            | Self::ColorShade => "FF0000, AAFF0000 or Red500",
+            Self::Alpha | Self::XoverAlpha => "0.0 to 1.0",
+            Self::HueRange => "0.1",
+            Self::Curvature | Self::Torsion => "min, max",
+        }
+    }
+
+    /// The value of the field in `style`, or an empty string if it is not set.
+    pub fn read(self, style: &DrawingStyle) -> String {
+        let radius = |r: Option<f32>| r.map(|r| (r * 10.).to_string()); // stored in nm
+        let color = |c: Option<u32>| c.map(format_color);
+        let pair = |p: Option<(f32, f32)>| p.map(|(min, max)| format!("{min}, {max}"));
+        match self {
+            Self::SphereRadius => radius(style.sphere_radius),
+            Self::BondRadius => radius(style.bond_radius),
+            Self::HelixAsCylinderRadius => radius(style.helix_as_cylinder_radius),
+            Self::SphereColor => color(style.sphere_color),
+            Self::BondColor => color(style.bond_color),
+            Self::XoverColor => color(style.xover_color),
+            Self::HelixAsCylinderColor => {
+                color(style.helix_as_cylinder_color.map(ColorType::to_u32))
+            }
+            Self::Alpha => style.alpha.map(|a| a.to_string()),
+            Self::XoverAlpha => style.xover_alpha.map(|a| a.to_string()),
+            Self::ColorShade => color(style.color_shade),
+            Self::HueRange => style.hue_range.map(|h| h.to_string()),
+            Self::Curvature => pair(style.curvature),
+            Self::Torsion => pair(style.torsion),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Set the field of `style` to the value written in `text`. An empty text unsets the field.
+    pub fn write(self, style: &mut DrawingStyle, text: &str) -> Result<(), String> {
+        let text = text.trim();
+        if text.is_empty() {
+            self.clear(style);
+            return Ok(());
+        }
+        match self {
+            Self::SphereRadius => style.sphere_radius = Some(parse_radius(text)?),
+            Self::BondRadius => style.bond_radius = Some(parse_radius(text)?),
+            Self::HelixAsCylinderRadius => {
+                style.helix_as_cylinder_radius = Some(parse_radius(text)?);
+            }
+            Self::SphereColor => style.sphere_color = Some(parse_color(text)?),
+            Self::BondColor => style.bond_color = Some(parse_color(text)?),
+            Self::XoverColor => style.xover_color = Some(parse_color(text)?),
+            Self::HelixAsCylinderColor => {
+                style.helix_as_cylinder_color = Some(ColorType::Plain(parse_color(text)?));
+            }
+            Self::Alpha => style.alpha = Some(parse_alpha(text)?),
+            Self::XoverAlpha => style.xover_alpha = Some(parse_alpha(text)?),
+            Self::ColorShade => style.color_shade = Some(parse_color(text)?),
+            Self::HueRange => style.hue_range = Some(parse_float(text)?),
+            Self::Curvature => style.curvature = Some(parse_pair(text)?),
+            Self::Torsion => style.torsion = Some(parse_pair(text)?),
+        }
+        Ok(())
+    }
+
+    fn clear(self, style: &mut DrawingStyle) {
+        match self {
+            Self::SphereRadius => style.sphere_radius = None,
+            Self::BondRadius => style.bond_radius = None,
+            Self::HelixAsCylinderRadius => style.helix_as_cylinder_radius = None,
+            Self::SphereColor => style.sphere_color = None,
+            Self::BondColor => style.bond_color = None,
+            Self::XoverColor => style.xover_color = None,
+            Self::HelixAsCylinderColor => style.helix_as_cylinder_color = None,
+            Self::Alpha => style.alpha = None,
+            Self::XoverAlpha => style.xover_alpha = None,
+            Self::ColorShade => style.color_shade = None,
+            Self::HueRange => style.hue_range = None,
+            Self::Curvature => style.curvature = None,
+            Self::Torsion => style.torsion = None,
+        }
+    }
+}
+
+/// A field of a [DrawingStyle] that is either on, off or not set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StyleFlag {
+    RainbowStrand,
+    XoverColoring,
+    WithCones,
+    OnAxis,
+}
+
+impl StyleFlag {
+    pub const ALL: [Self; 4] = [
+        Self::RainbowStrand,
+        Self::XoverColoring,
+        Self::WithCones,
+        Self::OnAxis,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RainbowStrand => "Rainbow strands",
+            Self::XoverColoring => "Color crossovers by length",
+            Self::WithCones => "Cones on 3' ends",
+            Self::OnAxis => "Nucleotides on axis",
+        }
+    }
+
+    pub fn read(self, style: &DrawingStyle) -> Option<bool> {
+        match self {
+            Self::RainbowStrand => style.rainbow_strand,
+            Self::XoverColoring => style.xover_coloring,
+            Self::WithCones => style.with_cones,
+            Self::OnAxis => style.on_axis,
+        }
+    }
+
+    pub fn write(self, style: &mut DrawingStyle, value: Option<bool>) {
+        match self {
+            Self::RainbowStrand => style.rainbow_strand = value,
+            Self::XoverColoring => style.xover_coloring = value,
+            Self::WithCones => style.with_cones = value,
+            Self::OnAxis => style.on_axis = value,
+        }
+    }
+}
+
+fn parse_float<T: FromStr>(text: &str) -> Result<T, String> {
+    text.parse()
+        .ok()
+        .ok_or_else(|| format!("\"{text}\" is not a number"))
+}
+
+fn parse_radius(text: &str) -> Result<f32, String> {
+    let radius: f32 = parse_float(text)?;
+    if radius > 0. {
+        Ok(radius / 10.) // radius given in Å but stored in nm
+    } else {
+        Err("The radius must be positive".to_owned())
+    }
+}
+
+fn parse_alpha(text: &str) -> Result<f32, String> {
+    let alpha: f32 = parse_float(text)?;
+    if (0. ..=1.).contains(&alpha) {
+        Ok(alpha)
+    } else {
+        Err("The opacity must be between 0 and 1".to_owned())
+    }
+}
+
+fn parse_pair(text: &str) -> Result<(f32, f32), String> {
+    let Some((min, max)) = text.split_once(',') else {
+        return Err("Expected two numbers separated by a comma".to_owned());
+    };
+    Ok((parse_float(min.trim())?, parse_float(max.trim())?))
+}
+
+/// Parse a color given as RRGGBB, AARRGGBB or as the name of a material color.
+fn parse_color(text: &str) -> Result<u32, String> {
+    if let Ok(color) = MaterialColor::from_str(text) {
+        return Ok(0xFF_00_00_00 | color as u32);
+    }
+    let hex = text.trim_start_matches('#');
+    match (hex.len(), u32::from_str_radix(hex, 16)) {
+        (6, Ok(color)) => Ok(0xFF_00_00_00 | color),
+        (8, Ok(color)) => Ok(color),
+        _ => Err(format!(
+            "\"{text}\" is not a color. Expected RRGGBB, AARRGGBB or a material color name"
+        )),
+    }
+}
+
+fn format_color(color: u32) -> String {
+    if color >> 24 == 0xFF {
+        format!("{:06X}", color & 0xFF_FF_FF)
+    } else {
+        format!("{color:08X}")
+    }
+}
@@ -50,6 +50,16 @@ impl External3DObject {
             None
         }
     }
+
+    /// The triangles of the object's mesh, moved to the object's position and orientation in
+    /// the design. The coordinates of the mesh are expected to be in nanometers, as returned by
+    /// `ensnano_utils::obj_loader::load_triangles`.
+    pub fn place_triangles(&self, triangles: &[[Vec3; 3]]) -> Vec<[Vec3; 3]> {
+        triangles
+            .iter()
+            .map(|t| t.map(|v| self.orientation * v + self.position))
+            .collect()
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
@@ -66,6 +76,10 @@ impl External3DObjects {
         self.0.iter()
     }
 
+    pub fn get(&self, id: &External3DObjectId) -> Option<&External3DObject> {
+        self.0.get(id)
+    }
+
     pub fn was_updated(
         &self,
         old_stamp: Option<External3DObjectsStamp>,
@@ -142,6 +156,27 @@ fn diff_paths(path: impl AsRef<Path>, base: impl AsRef<Path>) -> Option<PathBuf>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn triangles_are_placed_at_the_position_of_the_object() {
+        let object = External3DObject {
+            opacity: DEFAULT_OPACITY,
+            color: DEFAULT_COLOR,
+            position: Vec3::new(1., 2., 3.),
+            orientation: Rotor3::from_rotation_xy(FRAC_PI_2),
+            source_file: String::new(),
+        };
+        let placed = object.place_triangles(&[[Vec3::zero(), Vec3::unit_x(), Vec3::unit_z()]]);
+        let expected = [
+            Vec3::new(1., 2., 3.),
+            Vec3::new(1., 3., 3.),
+            Vec3::new(1., 2., 4.),
+        ];
+        for (v, e) in placed[0].iter().zip(expected) {
+            assert!((*v - e).mag() < 1e-5, "{v:?} != {e:?}");
+        }
+    }
 
     #[test]
     fn absolute() {
@@ -97,7 +97,7 @@ impl Design {
                 position: source_grid.position - base_position,
                 helix_parameters: None,
                 orientation: base_orientation.reversed() * source_grid.orientation,
-                grid_type: source_grid.grid_type,
+                grid_type: source_grid.grid_type.clone(),
                 invisible: false,
                 bezier_vertex: None,
             };
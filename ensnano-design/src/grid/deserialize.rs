@@ -1,10 +1,10 @@
 use crate::{
     bezier_plane::BezierVertexId,
-    grid::{GridId, GridTypeDescr},
+    grid::{GridId, GridTypeDescr, lattice::Lattice},
 };
 use serde::Deserialize;
 
-#[derive(Clone, Copy, Deserialize)]
+#[derive(Clone, Deserialize)]
 enum NewGridTypeDescr {
     Square {
         #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -28,6 +28,11 @@ enum NewGridTypeDescr {
         #[serde(default)]
         nb_turn_per_100_nt: f64,
     },
+    Lattice {
+        lattice: Lattice,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        twist: Option<f64>,
+    },
 }
 
 impl NewGridTypeDescr {
@@ -51,6 +56,7 @@ impl NewGridTypeDescr {
                 forced_radius,
                 nb_turn_per_100_nt,
             },
+            Self::Lattice { lattice, twist } => GridTypeDescr::Lattice { lattice, twist },
         }
     }
 }
@@ -90,6 +90,10 @@ impl FreeGridsMut<'_> {
         GridId::FreeGrid(new_key.0)
     }
 
+    pub fn insert(&mut self, g_id: FreeGridId, desc: GridDescriptor) {
+        self.new_map.insert(g_id, Arc::new(desc));
+    }
+
     pub fn get_mut(&mut self, g_id: &FreeGridId) -> Option<&mut GridDescriptor> {
         self.new_map.get_mut(g_id).map(Arc::make_mut)
     }
@@ -0,0 +1,360 @@
+//! User-defined lattices, for grids that are neither square nor honeycomb.
+//!
+//! A lattice is given by two basis vectors and a motif of sites that is repeated in every cell.
+//! The grid coordinates of a site encode both its cell and its index in the motif: `x` is
+//! `cell_a * motif.len() + site` and `y` is `cell_b`, where `cell_a` and `cell_b` are the
+//! coordinates of the cell along the two basis vectors.
+
+use crate::{
+    curves::{CurveDescriptor, twist::twist_to_omega},
+    grid::{CurveInfo, Edge, GridDivision, basic_curve},
+    parameters::HelixParameters,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    f32::consts::{PI, TAU},
+    sync::Arc,
+};
+use ultraviolet::{Rotor3, Vec2};
+
+/// A site of the motif of a lattice.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatticeSite {
+    /// The position of the site in its cell, in units of the distance between two neighbouring
+    /// helices.
+    pub position: Vec2,
+    /// The angle in radians by which the helices on this site are rotated around their axis.
+    #[serde(default)]
+    pub roll: f32,
+}
+
+/// The definition of a lattice.
+///
+/// The lengths are expressed in units of the distance between two neighbouring helices, so that
+/// the lattice follows the helix parameters of the design.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lattice {
+    pub name: String,
+    pub basis_a: Vec2,
+    pub basis_b: Vec2,
+    pub motif: Vec<LatticeSite>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeError {
+    EmptyMotif,
+    /// The basis vectors are colinear.
+    DegenerateBasis,
+}
+
+impl std::fmt::Display for LatticeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyMotif => write!(f, "The motif of the lattice has no site"),
+            Self::DegenerateBasis => write!(f, "The basis vectors of the lattice are colinear"),
+        }
+    }
+}
+
+impl Lattice {
+    /// Helices packed on a triangular lattice, each helix having six neighbours.
+    pub fn hexagonal_close_packed() -> Self {
+        Self {
+            name: "Hexagonal close-packed".to_owned(),
+            basis_a: Vec2::new(1., 0.),
+            basis_b: Vec2::new(0.5, -3f32.sqrt() / 2.),
+            motif: vec![LatticeSite {
+                position: Vec2::zero(),
+                roll: 0.,
+            }],
+        }
+    }
+
+    /// Rows of helices whose stacking alternates between square and hexagonal packing.
+    pub fn hybrid() -> Self {
+        Self {
+            name: "Hybrid".to_owned(),
+            basis_a: Vec2::new(1., 0.),
+            basis_b: Vec2::new(0.5, -1. - 3f32.sqrt() / 2.),
+            motif: vec![
+                LatticeSite {
+                    position: Vec2::zero(),
+                    roll: 0.,
+                },
+                LatticeSite {
+                    position: Vec2::new(0., -1.),
+                    roll: PI,
+                },
+            ],
+        }
+    }
+
+    /// Square layers of helices, consecutive layers being rotated by half a turn as in gridiron
+    /// structures.
+    pub fn gridiron() -> Self {
+        Self {
+            name: "Gridiron".to_owned(),
+            basis_a: Vec2::new(1., 0.),
+            basis_b: Vec2::new(0., -2.),
+            motif: vec![
+                LatticeSite {
+                    position: Vec2::zero(),
+                    roll: 0.,
+                },
+                LatticeSite {
+                    position: Vec2::new(0., -1.),
+                    roll: PI,
+                },
+            ],
+        }
+    }
+
+    pub fn check(&self) -> Result<(), LatticeError> {
+        if self.motif.is_empty() {
+            Err(LatticeError::EmptyMotif)
+        } else if self.determinant().abs() < 1e-6 {
+            Err(LatticeError::DegenerateBasis)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn nb_sites(&self) -> isize {
+        self.motif.len().max(1) as isize
+    }
+
+    fn determinant(&self) -> f32 {
+        self.basis_a.x * self.basis_b.y - self.basis_a.y * self.basis_b.x
+    }
+
+    /// The cell along the first basis vector and the index in the motif of the site at grid
+    /// coordinate `x`.
+    fn split(&self, x: isize) -> (isize, usize) {
+        (
+            x.div_euclid(self.nb_sites()),
+            x.rem_euclid(self.nb_sites()) as usize,
+        )
+    }
+
+    fn site(&self, site: usize) -> LatticeSite {
+        self.motif
+            .get(site)
+            .copied()
+            .unwrap_or_else(|| LatticeSite {
+                position: Vec2::zero(),
+                roll: 0.,
+            })
+    }
+
+    /// The position of a site, in units of the distance between two neighbouring helices.
+    fn unit_position(&self, x: isize, y: isize) -> Vec2 {
+        let (cell_a, site) = self.split(x);
+        cell_a as f32 * self.basis_a + y as f32 * self.basis_b + self.site(site).position
+    }
+
+    /// The grid coordinates of the site that is the closest to `point`, and the square of its
+    /// distance to `point`, in units of the distance between two neighbouring helices.
+    fn nearest_site(&self, point: Vec2) -> ((isize, isize), f32) {
+        let det = self.determinant();
+        let (a, b) = (self.basis_a, self.basis_b);
+        let mut ret = (0, 0);
+        let mut best_dist = f32::INFINITY;
+        for site in 0..self.nb_sites() {
+            // Coordinates of the point in the basis, relatively to the site.
+            let v = point - self.site(site as usize).position;
+            let cell_a = ((v.x * b.y - v.y * b.x) / det).round() as isize;
+            let cell_b = ((a.x * v.y - a.y * v.x) / det).round() as isize;
+            for da in -1..=1 {
+                for db in -1..=1 {
+                    let guess = ((cell_a + da) * self.nb_sites() + site, cell_b + db);
+                    let dist = (self.unit_position(guess.0, guess.1) - point).mag_sq();
+                    if dist < best_dist {
+                        ret = guess;
+                        best_dist = dist;
+                    }
+                }
+            }
+        }
+        (ret, best_dist)
+    }
+
+    /// The difference between the rolls of two sites of the motif.
+    fn roll_change(&self, from_site: usize, to_site: usize) -> f32 {
+        self.site(to_site).roll - self.site(from_site).roll
+    }
+}
+
+/// True if two angles in radians are equal modulo a full turn.
+fn same_angle(a: f32, b: f32) -> bool {
+    let diff = (a - b).rem_euclid(TAU);
+    diff < 1e-4 || TAU - diff < 1e-4
+}
+
+#[derive(Debug, Clone)]
+pub struct LatticeGrid {
+    pub lattice: Lattice,
+    pub(super) twist: Option<f64>,
+}
+
+fn helix_distance(helix_parameters: &HelixParameters) -> f32 {
+    helix_parameters.helix_radius * 2. + helix_parameters.inter_helix_gap
+}
+
+impl GridDivision for LatticeGrid {
+    fn origin_helix(&self, helix_parameters: &HelixParameters, x: isize, y: isize) -> Vec2 {
+        self.lattice.unit_position(x, y) * helix_distance(helix_parameters)
+    }
+
+    fn orientation_helix(
+        &self,
+        _helix_parameters: &HelixParameters,
+        x: isize,
+        _y: isize,
+    ) -> Rotor3 {
+        let (_, site) = self.lattice.split(x);
+        Rotor3::from_rotation_yz(self.lattice.site(site).roll)
+    }
+
+    fn interpolate(&self, helix_parameters: &HelixParameters, x: f32, y: f32) -> (isize, isize) {
+        let point = Vec2::new(x, y) / helix_distance(helix_parameters);
+        self.lattice.nearest_site(point).0
+    }
+
+    fn translation_to_edge(&self, x1: isize, y1: isize, x2: isize, y2: isize) -> Edge {
+        let (cell_1, site_1) = self.lattice.split(x1);
+        let (cell_2, site_2) = self.lattice.split(x2);
+        Edge::Lattice {
+            x: cell_2 - cell_1,
+            y: y2 - y1,
+            from_site: site_1,
+            to_site: site_2,
+        }
+    }
+
+    /// Following an edge translates a site by the vector between the two sites of the edge.
+    ///
+    /// An edge can be followed from any site of the motif, as long as the translation leads to a
+    /// site and changes the roll as much as the edge does, so that translated helices keep their
+    /// neighbours and their orientation relatively to them. For example, helices copied from
+    /// several sites of the motif can be pasted in another cell.
+    fn translate_by_edge(&self, x1: isize, y1: isize, edge: Edge) -> Option<(isize, isize)> {
+        let Edge::Lattice {
+            x,
+            y,
+            from_site,
+            to_site,
+        } = edge
+        else {
+            return None;
+        };
+        let lattice = &self.lattice;
+        if from_site >= lattice.motif.len() || to_site >= lattice.motif.len() {
+            return None;
+        }
+        let (cell, site) = lattice.split(x1);
+        if site == from_site {
+            return Some(((cell + x) * lattice.nb_sites() + to_site as isize, y1 + y));
+        }
+        let edge_vector = x as f32 * lattice.basis_a
+            + y as f32 * lattice.basis_b
+            + lattice.site(to_site).position
+            - lattice.site(from_site).position;
+        let (position, dist_sq) = lattice.nearest_site(lattice.unit_position(x1, y1) + edge_vector);
+        let (_, new_site) = lattice.split(position.0);
+        let same_roll_change = same_angle(
+            lattice.roll_change(site, new_site),
+            lattice.roll_change(from_site, to_site),
+        );
+        (dist_sq < 1e-6 && same_roll_change).then_some(position)
+    }
+
+    fn curve(&self, x: isize, y: isize, info: CurveInfo) -> Option<Arc<CurveDescriptor>> {
+        let twist = self.twist?;
+        let omega = twist_to_omega(twist, &info.helix_parameters)?;
+        let grid_position = self.origin_helix(&info.helix_parameters, x, y);
+        Some(basic_curve(grid_position, omega, info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(lattice: Lattice) -> LatticeGrid {
+        LatticeGrid {
+            lattice,
+            twist: None,
+        }
+    }
+
+    #[test]
+    fn interpolate_finds_the_sites() {
+        let parameters = HelixParameters::GEARY_2014_DNA;
+        for lattice in [
+            Lattice::hexagonal_close_packed(),
+            Lattice::hybrid(),
+            Lattice::gridiron(),
+        ] {
+            lattice.check().unwrap();
+            let grid = grid(lattice);
+            for x in -5..5 {
+                for y in -3..3 {
+                    let position = grid.origin_helix(&parameters, x, y);
+                    assert_eq!(
+                        grid.interpolate(&parameters, position.x, position.y),
+                        (x, y)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn neighbouring_sites_are_one_helix_apart() {
+        let parameters = HelixParameters::GEARY_2014_DNA;
+        let grid = grid(Lattice::hybrid());
+        let distance = |(x1, y1), (x2, y2)| {
+            (grid.origin_helix(&parameters, x1, y1) - grid.origin_helix(&parameters, x2, y2)).mag()
+                / helix_distance(&parameters)
+        };
+        // Square stacking inside a cell, hexagonal stacking between cells.
+        assert!((distance((0, 0), (1, 0)) - 1.).abs() < 1e-5);
+        assert!((distance((1, 0), (0, 1)) - 1.).abs() < 1e-5);
+        assert!((distance((3, 0), (2, 1)) - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn edges_are_followed_from_equivalent_sites() {
+        let grid = grid(Lattice::hybrid());
+        let edge = grid.translation_to_edge(0, 0, 3, 1);
+        assert_eq!(grid.translate_by_edge(4, 2, edge), Some((7, 3)));
+        assert_eq!(grid.translate_by_edge(-2, 0, edge), Some((1, 1)));
+        // The translation does not lead to a site.
+        assert_eq!(grid.translate_by_edge(1, 2, edge), None);
+
+        // A translation by a whole cell can be followed from every site.
+        let edge = grid.translation_to_edge(0, 0, 2, 1);
+        assert_eq!(grid.translate_by_edge(1, 2, edge), Some((3, 3)));
+
+        let grid = self::grid(Lattice::gridiron());
+        // Moving down by one helix leads to a site rotated by half a turn from both sites.
+        let edge = grid.translation_to_edge(0, 0, 1, 0);
+        assert_eq!(grid.translate_by_edge(1, 0, edge), Some((0, 1)));
+        // If the rolls of the sites do not differ by half a turn, moving down from the second site
+        // changes the roll differently than moving down from the first one.
+        let mut lattice = Lattice::gridiron();
+        lattice.motif[1].roll = 1.;
+        let grid = self::grid(lattice);
+        let edge = grid.translation_to_edge(0, 0, 1, 0);
+        assert_eq!(grid.translate_by_edge(1, 0, edge), None);
+    }
+
+    #[test]
+    fn degenerate_lattices_are_rejected() {
+        let mut lattice = Lattice::hexagonal_close_packed();
+        lattice.basis_b = 2. * lattice.basis_a;
+        assert_eq!(lattice.check(), Err(LatticeError::DegenerateBasis));
+        lattice.motif.clear();
+        assert_eq!(lattice.check(), Err(LatticeError::EmptyMotif));
+    }
+}
@@ -2,8 +2,13 @@ pub mod copy_grid;
 mod deserialize;
 pub mod grid_collection;
 pub mod hyperboloid;
+pub mod lattice;
 
-use self::{grid_collection::FreeGrids, hyperboloid::Hyperboloid};
+use self::{
+    grid_collection::FreeGrids,
+    hyperboloid::Hyperboloid,
+    lattice::{Lattice, LatticeGrid},
+};
 use crate::{
     Design,
     bezier_plane::{BezierPathData, BezierPathId, BezierVertexId},
@@ -44,7 +49,7 @@ pub struct Grid {
     pub invisible: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GridDescriptor {
     pub position: Vec3,
     pub orientation: Rotor3,
@@ -57,7 +62,7 @@ pub struct GridDescriptor {
     pub bezier_vertex: Option<BezierVertexId>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub enum GridTypeDescr {
     Square {
         #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -89,6 +94,12 @@ pub enum GridTypeDescr {
         ///  * r is `self.radius`.
         nb_turn_per_100_nt: f64,
     },
+    /// A user-defined lattice.
+    Lattice {
+        lattice: Lattice,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        twist: Option<f64>,
+    },
 }
 
 impl GridDescriptor {
@@ -103,7 +114,7 @@ impl GridDescriptor {
         }
     }
 
-    pub fn to_grid(self, default_helix_parameters: HelixParameters) -> Grid {
+    pub fn to_grid(&self, default_helix_parameters: HelixParameters) -> Grid {
         Grid {
             position: self.position,
             orientation: self.orientation,
@@ -121,6 +132,7 @@ impl std::fmt::Display for GridTypeDescr {
             Self::Honeycomb { .. } => write!(f, "Honeycomb"),
             Self::RotatedHoneycomb { .. } => write!(f, "RotatedHoneycomb"),
             Self::Hyperboloid { .. } => write!(f, "Hyperboloid"),
+            Self::Lattice { lattice, .. } => write!(f, "{}", lattice.name),
         }
     }
 }
@@ -129,19 +141,21 @@ pub const SQUARE_GRID_TYPE: u32 = 0;
 pub const HONEYCOMB_GRID_TYPE: u32 = 1;
 pub const ROTATED_HONEYCOMB_GRID_TYPE: u32 = 2;
 pub const HYPERBOLOID_GRID_TYPE: u32 = 3;
+pub const LATTICE_GRID_TYPE: u32 = 4;
 
 impl GridTypeDescr {
-    pub fn to_u32(self) -> u32 {
+    pub fn to_u32(&self) -> u32 {
         match self {
             Self::Square { .. } => SQUARE_GRID_TYPE,
             Self::Honeycomb { .. } => HONEYCOMB_GRID_TYPE,
             Self::RotatedHoneycomb { .. } => ROTATED_HONEYCOMB_GRID_TYPE,
             Self::Hyperboloid { .. } => HYPERBOLOID_GRID_TYPE,
+            Self::Lattice { .. } => LATTICE_GRID_TYPE,
         }
     }
 
-    fn to_concrete(self) -> GridType {
-        match self {
+    fn to_concrete(&self) -> GridType {
+        match self.clone() {
             Self::Square { twist } => GridType::square(twist),
             Self::Honeycomb { twist } => GridType::honeycomb(twist),
             Self::RotatedHoneycomb { twist } => GridType::rotated_honeycomb(twist),
@@ -160,6 +174,7 @@ impl GridTypeDescr {
                 forced_radius,
                 nb_turn_per_100_nt,
             }),
+            Self::Lattice { lattice, twist } => GridType::Lattice(LatticeGrid { lattice, twist }),
         }
     }
 }
@@ -170,6 +185,7 @@ pub enum GridType {
     Honeycomb(HoneyComb),
     RotatedHoneycomb(RotatedHoneyComb),
     Hyperboloid(Hyperboloid),
+    Lattice(LatticeGrid),
 }
 
 impl GridDivision for GridType {
@@ -179,6 +195,7 @@ impl GridDivision for GridType {
             Self::Honeycomb(grid) => grid.origin_helix(helix_parameters, x, y),
             Self::RotatedHoneycomb(grid) => grid.origin_helix(helix_parameters, x, y),
             Self::Hyperboloid(grid) => grid.origin_helix(helix_parameters, x, y),
+            Self::Lattice(grid) => grid.origin_helix(helix_parameters, x, y),
         }
     }
 
@@ -188,6 +205,7 @@ impl GridDivision for GridType {
             Self::Honeycomb(grid) => grid.orientation_helix(helix_parameters, x, y),
             Self::RotatedHoneycomb(grid) => grid.orientation_helix(helix_parameters, x, y),
             Self::Hyperboloid(grid) => grid.orientation_helix(helix_parameters, x, y),
+            Self::Lattice(grid) => grid.orientation_helix(helix_parameters, x, y),
         }
     }
 
@@ -197,6 +215,7 @@ impl GridDivision for GridType {
             Self::Honeycomb(grid) => grid.interpolate(helix_parameters, x, y),
             Self::RotatedHoneycomb(grid) => grid.interpolate(helix_parameters, x, y),
             Self::Hyperboloid(grid) => grid.interpolate(helix_parameters, x, y),
+            Self::Lattice(grid) => grid.interpolate(helix_parameters, x, y),
         }
     }
 
@@ -206,6 +225,7 @@ impl GridDivision for GridType {
             Self::Honeycomb(grid) => grid.translation_to_edge(x1, y1, x2, y2),
             Self::RotatedHoneycomb(grid) => grid.translation_to_edge(x1, y1, x2, y2),
             Self::Hyperboloid(grid) => grid.translation_to_edge(x1, y1, x2, y2),
+            Self::Lattice(grid) => grid.translation_to_edge(x1, y1, x2, y2),
         }
     }
 
@@ -215,6 +235,7 @@ impl GridDivision for GridType {
             Self::Honeycomb(grid) => grid.translate_by_edge(x1, y1, edge),
             Self::RotatedHoneycomb(grid) => grid.translate_by_edge(x1, y1, edge),
             Self::Hyperboloid(grid) => grid.translate_by_edge(x1, y1, edge),
+            Self::Lattice(grid) => grid.translate_by_edge(x1, y1, edge),
         }
     }
 
@@ -224,6 +245,7 @@ impl GridDivision for GridType {
             Self::Square(grid) => grid.curve(x, y, info),
             Self::Honeycomb(grid) => grid.curve(x, y, info),
             Self::RotatedHoneycomb(grid) => grid.curve(x, y, info),
+            Self::Lattice(grid) => grid.curve(x, y, info),
         }
     }
 }
@@ -260,12 +282,18 @@ impl GridType {
                 forced_radius: h.forced_radius,
                 nb_turn_per_100_nt: h.nb_turn_per_100_nt,
             },
+            Self::Lattice(LatticeGrid { lattice, twist }) => GridTypeDescr::Lattice {
+                lattice: lattice.clone(),
+                twist: *twist,
+            },
         }
     }
 
     pub fn get_shift(&self) -> Option<f32> {
         match self {
-            Self::Square(_) | Self::Honeycomb(_) | Self::RotatedHoneycomb(_) => None,
+            Self::Square(_) | Self::Honeycomb(_) | Self::RotatedHoneycomb(_) | Self::Lattice(_) => {
+                None
+            }
             Self::Hyperboloid(h) => Some(h.shift),
         }
     }
@@ -276,12 +304,13 @@ impl GridType {
             Self::Honeycomb(h) => h.twist,
             Self::RotatedHoneycomb(h) => h.twist,
             Self::Hyperboloid(h) => Some(h.nb_turn_per_100_nt),
+            Self::Lattice(l) => l.twist,
         }
     }
 
     pub fn set_shift(&mut self, shift: f32, helix_parameters: &HelixParameters) {
         match self {
-            Self::Square(_) | Self::Honeycomb(_) | Self::RotatedHoneycomb(_) => {
+            Self::Square(_) | Self::Honeycomb(_) | Self::RotatedHoneycomb(_) | Self::Lattice(_) => {
                 println!("WARNING changing shift of non hyperboloid grid");
             }
             Self::Hyperboloid(h) => h.modify_shift(shift, helix_parameters),
@@ -779,9 +808,17 @@ pub enum Edge {
         start_parity: bool,
     },
     Circle(isize),
+    /// A translation by a number of cells of a user-defined lattice, between two sites of its
+    /// motif.
+    Lattice {
+        x: isize,
+        y: isize,
+        from_site: usize,
+        to_site: usize,
+    },
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// An object lying on a grid.
 pub enum GridObject {
     Helix(usize),
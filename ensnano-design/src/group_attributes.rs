@@ -1,3 +1,4 @@
+use crate::drawing_style::DrawingStyle;
 use serde::{Deserialize, Serialize};
 use ultraviolet::{Rotor3, Vec3};
 
@@ -5,6 +6,9 @@ use ultraviolet::{Rotor3, Vec3};
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GroupAttribute {
     pub pivot: Option<GroupPivot>,
+    /// The drawing style of the elements of the group.
+    #[serde(default, skip_serializing_if = "DrawingStyle::is_empty")]
+    pub drawing_style: DrawingStyle,
 }
 
 /// The position and orientation of the pivot used to rotate/translate the group.
@@ -19,7 +19,7 @@ use crate::{
 use ahash::HashMap;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     f32::consts::{FRAC_PI_2, PI, TAU},
     sync::Arc,
 };
@@ -61,6 +61,10 @@ impl Helices {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 pub struct HelicesMut<'a> {
@@ -220,15 +224,23 @@ pub struct Helix {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) path_id: Option<BezierPathId>,
+
+    /// Positions of the helix that hold no nucleotide (skips in cadnano). The nucleotides after
+    /// a deletion are placed as if the deleted position did not exist, which corrects the twist
+    /// of the helix.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub deletions: BTreeSet<isize>,
 }
 
 impl Helix {
+    /// Return a copy of the helix translated by `edge`, or `None` if the helix cannot follow the
+    /// edge.
     pub fn translated_by(&self, edge: Edge, grid_data: &GridData) -> Option<Self> {
         log::debug!("attempt to translate helix");
-        let grid_position = self
-            .grid_position
-            .as_ref()
-            .and_then(|gp| grid_data.translate_by_helix_and_edge(gp, &edge));
+        let grid_position = match self.grid_position.as_ref() {
+            Some(gp) => Some(grid_data.translate_by_helix_and_edge(gp, &edge)?),
+            None => None,
+        };
         let new_curve_descriptor = self
             .curve
             .as_ref()
@@ -268,6 +280,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: BTreeSet::new(),
         }
     }
 
@@ -298,6 +311,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: BTreeSet::new(),
         }
     }
 
@@ -321,6 +335,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: BTreeSet::new(),
         }
     }
 
@@ -344,6 +359,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: BTreeSet::new(),
         }
     }
 
@@ -367,6 +383,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: BTreeSet::new(),
         }
     }
 
@@ -446,6 +463,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: BTreeSet::new(),
         };
         // we can use a fake cache because we don't need it for bezier curves.
         let mut fake_cache = Default::default();
@@ -491,6 +509,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: Some(path_id),
+            deletions: BTreeSet::new(),
         };
         let mut fake_cache = Default::default();
         grid_manager.update_curve(&mut ret, &mut fake_cache);
@@ -540,6 +559,25 @@ impl Helix {
         // the backward strand is at vertical position on nucl 0
     }
 
+    /// True iff there is a deletion at position `n` of the helix.
+    pub fn has_deletion_at(&self, n: isize) -> bool {
+        self.deletions.contains(&n)
+    }
+
+    /// The index of position `n` along the helix once the deleted positions are removed.
+    ///
+    /// Position 0 keeps index 0 unless it is deleted, and the positions on each side of a
+    /// deletion get consecutive indices.
+    pub fn index_without_deletions(&self, n: isize) -> isize {
+        if self.deletions.is_empty() {
+            n
+        } else if n >= 0 {
+            n - self.deletions.range(0..n).count() as isize
+        } else {
+            n + self.deletions.range(n + 1..0).count() as isize
+        }
+    }
+
     /// 3D position of a nucleotide on this helix. `n` is the position along the axis, and `forward` is true iff the 5' to 3' direction of the strand containing that nucleotide runs in the same direction as the axis of the helix.
     pub fn space_pos(&self, p: &HelixParameters, n: isize, forward: bool) -> Vec3 {
         let p = self.helix_parameters.unwrap_or(*p);
@@ -618,7 +656,7 @@ impl Helix {
         //     None => p.clone(),
         //     Some(hp) => hp.clone(),
         // };
-        let n = self.initial_nt_index + n;
+        let n = self.initial_nt_index + self.index_without_deletions(n);
         let theta = self.theta(n, forward, &p) + shift;
         self.theta_n_to_space_pos(&p, n, theta, forward)
     }
@@ -656,6 +694,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: BTreeSet::new(),
         }
     }
 
@@ -717,7 +756,7 @@ impl Helix {
 
     pub fn axis_position(&self, p: &HelixParameters, n: isize, forward: bool) -> Vec3 {
         // WARNING: doesn't take the inclination into account!
-        let n = n + self.initial_nt_index;
+        let n = self.index_without_deletions(n) + self.initial_nt_index;
         if let Some(curve) = self.instantiated_curve.as_ref().map(|s| &s.curve)
             && let Some(point) = curve.axis_pos(n, forward).map(dvec_to_vec)
         {
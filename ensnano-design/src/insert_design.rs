@@ -0,0 +1,404 @@
+//! Insertion of a whole design into another one.
+//!
+//! All the identifiers of the inserted design are remapped onto new identifiers of the receiving
+//! design, and its elements are gathered in a new group of the organizer tree so that they can be
+//! selected and moved as a whole.
+
+use crate::{
+    Camera, CameraId, Design,
+    bezier_plane::{BezierPathId, BezierPlaneId, BezierVertexId},
+    curves::CurveDescriptor,
+    design_element::DesignElementKey,
+    domains::Domain,
+    grid::GridId,
+    group_attributes::{GroupAttribute, GroupPivot},
+    nucl::Nucl,
+    organizer_tree::{GroupId, OrganizerTree},
+    strands::DomainJunction,
+};
+use ahash::HashMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+use ultraviolet::{Rotor3, Vec3};
+
+/// The new identifiers of the elements of an inserted design.
+#[derive(Default)]
+struct IdMaps {
+    grids: HashMap<usize, usize>,
+    bezier_planes: BTreeMap<BezierPlaneId, BezierPlaneId>,
+    bezier_paths: HashMap<BezierPathId, BezierPathId>,
+    helices: HashMap<usize, usize>,
+    strands: HashMap<usize, usize>,
+}
+
+impl IdMaps {
+    fn grid_id(&self, grid_id: GridId) -> Option<GridId> {
+        match grid_id {
+            GridId::FreeGrid(id) => self.grids.get(&id).copied().map(GridId::FreeGrid),
+            GridId::BezierPathGrid(vertex) => {
+                self.bezier_vertex(vertex).map(GridId::BezierPathGrid)
+            }
+        }
+    }
+
+    fn bezier_vertex(&self, vertex: BezierVertexId) -> Option<BezierVertexId> {
+        self.bezier_paths
+            .get(&vertex.path_id)
+            .map(|path_id| BezierVertexId {
+                path_id: *path_id,
+                ..vertex
+            })
+    }
+
+    fn nucl(&self, nucl: Nucl) -> Option<Nucl> {
+        self.helices.get(&nucl.helix).map(|helix| Nucl {
+            helix: *helix,
+            ..nucl
+        })
+    }
+
+    fn element(&self, key: &DesignElementKey) -> Option<DesignElementKey> {
+        match key {
+            DesignElementKey::Grid(id) => self.grids.get(id).copied().map(DesignElementKey::Grid),
+            DesignElementKey::Helix(id) => {
+                self.helices.get(id).copied().map(DesignElementKey::Helix)
+            }
+            DesignElementKey::Strand(id) => {
+                self.strands.get(id).copied().map(DesignElementKey::Strand)
+            }
+            DesignElementKey::Nucleotide {
+                helix,
+                position,
+                forward,
+            } => self
+                .helices
+                .get(helix)
+                .map(|helix| DesignElementKey::Nucleotide {
+                    helix: *helix,
+                    position: *position,
+                    forward: *forward,
+                }),
+            // The identifiers of the cross-overs are recomputed when the design is updated.
+            DesignElementKey::CrossOver { .. } => None,
+        }
+    }
+
+    fn curve(&self, curve: &CurveDescriptor) -> Option<CurveDescriptor> {
+        match curve {
+            CurveDescriptor::TranslatedPath {
+                path_id,
+                translation,
+                legacy,
+            } => self
+                .bezier_paths
+                .get(path_id)
+                .map(|path_id| CurveDescriptor::TranslatedPath {
+                    path_id: *path_id,
+                    translation: *translation,
+                    legacy: *legacy,
+                }),
+            CurveDescriptor::PiecewiseBezier {
+                t_min,
+                t_max,
+                points,
+            } => {
+                let mut points = points.clone();
+                for p in &mut points {
+                    p.position.grid = self.grid_id(p.position.grid)?;
+                }
+                Some(CurveDescriptor::PiecewiseBezier {
+                    t_min: *t_min,
+                    t_max: *t_max,
+                    points,
+                })
+            }
+            curve => Some(curve.clone()),
+        }
+    }
+
+    /// A copy of `tree` referring to the new identifiers, in which the groups are given new
+    /// identifiers as well. The rules of the smart groups are dropped since they refer to the
+    /// identifiers of the inserted design.
+    fn organizer_tree(
+        &self,
+        tree: &OrganizerTree,
+        attributes: &HashMap<GroupId, GroupAttribute>,
+        new_attributes: &mut HashMap<GroupId, GroupAttribute>,
+    ) -> Option<OrganizerTree> {
+        match tree {
+            OrganizerTree::Leaf(key) => self.element(key).map(OrganizerTree::Leaf),
+            OrganizerTree::Node {
+                name,
+                children,
+                expanded,
+                id,
+                ..
+            } => {
+                let id = id.map(|id| {
+                    let new_id = rand::random();
+                    if let Some(attribute) = attributes.get(&id) {
+                        new_attributes.insert(new_id, attribute.clone());
+                    }
+                    new_id
+                });
+                Some(OrganizerTree::Node {
+                    name: name.clone(),
+                    children: children
+                        .iter()
+                        .filter_map(|c| self.organizer_tree(c, attributes, new_attributes))
+                        .collect(),
+                    expanded: *expanded,
+                    id,
+                    rule: None,
+                })
+            }
+        }
+    }
+}
+
+impl Design {
+    /// Insert all the elements of `other` into self, and gather them in a new group of the
+    /// organizer tree named `name`. The pivot of the group is placed at the center of the inserted
+    /// helices.
+    pub fn insert_design(&mut self, other: Self, name: String) -> GroupId {
+        let mut maps = IdMaps::default();
+
+        let mut new_planes = self.bezier_planes.make_mut();
+        for (id, plane) in other.bezier_planes.iter() {
+            maps.bezier_planes
+                .insert(*id, new_planes.push(plane.clone()));
+        }
+        drop(new_planes);
+
+        let mut new_paths = self.bezier_paths.make_mut();
+        for id in other.bezier_paths.keys() {
+            let Some(mut path) = other.bezier_paths.get(id).cloned() else {
+                continue;
+            };
+            for vertex in path.vertices_mut() {
+                if let Some(plane_id) = maps.bezier_planes.get(&vertex.plane_id) {
+                    vertex.plane_id = *plane_id;
+                }
+            }
+            maps.bezier_paths.insert(*id, new_paths.push(path));
+        }
+        drop(new_paths);
+
+        let mut new_grids = self.free_grids.make_mut();
+        for (id, grid) in other.free_grids.iter() {
+            let mut grid = grid.clone();
+            grid.bezier_vertex = grid.bezier_vertex.and_then(|v| maps.bezier_vertex(v));
+            grid.helix_parameters = grid.helix_parameters.or(other.helix_parameters);
+            if let GridId::FreeGrid(new_id) = new_grids.push(grid) {
+                maps.grids.insert(id.0, new_id);
+            }
+        }
+        drop(new_grids);
+
+        let mut new_helices = self.helices.make_mut();
+        for (id, helix) in other.helices.iter() {
+            let mut helix = helix.clone();
+            helix.grid_position = helix.grid_position.and_then(|mut position| {
+                position.grid = maps.grid_id(position.grid)?;
+                Some(position)
+            });
+            helix.curve = helix
+                .curve
+                .as_ref()
+                .and_then(|curve| maps.curve(curve))
+                .map(Arc::new);
+            helix.path_id = helix
+                .path_id
+                .and_then(|path_id| maps.bezier_paths.get(&path_id).copied());
+            helix.helix_parameters = helix.helix_parameters.or(other.helix_parameters);
+            helix.instantiated_curve = None;
+            helix.instantiated_descriptor = None;
+            maps.helices.insert(*id, new_helices.push_helix(helix));
+        }
+        for new_id in maps.helices.values() {
+            let support_helix = new_helices.get(new_id).and_then(|h| h.support_helix);
+            if let Some(mut helix) = new_helices.get(new_id).cloned() {
+                helix.support_helix = support_helix.and_then(|h| maps.helices.get(&h).copied());
+                new_helices.insert(*new_id, helix);
+            }
+        }
+        drop(new_helices);
+
+        for (id, strand) in other.strands.iter() {
+            let mut strand = strand.clone();
+            let mut valid = true;
+            for domain in &mut strand.domains {
+                match domain {
+                    Domain::HelixDomain(interval) => {
+                        if let Some(helix) = maps.helices.get(&interval.helix) {
+                            interval.helix = *helix;
+                        } else {
+                            valid = false;
+                        }
+                    }
+                    Domain::Insertion { instantiation, .. } => *instantiation = None,
+                }
+            }
+            if !valid {
+                log::warn!("Strand {id} of the inserted design is on an unknown helix");
+                continue;
+            }
+            for junction in &mut strand.junctions {
+                if let DomainJunction::IdentifiedXover(_) = junction {
+                    *junction = DomainJunction::UnidentifiedXover;
+                }
+            }
+            let new_id = self.strands.keys().max().map_or(0, |m| m + 1);
+            self.strands.insert(new_id, strand);
+            maps.strands.insert(*id, new_id);
+        }
+
+        if self.scaffold_id.is_none()
+            && let Some(scaffold_id) = other.scaffold_id.and_then(|s| maps.strands.get(&s))
+        {
+            self.scaffold_id = Some(*scaffold_id);
+            self.scaffold_sequence = other.scaffold_sequence.clone();
+            self.scaffold_shift = other.scaffold_shift;
+        }
+
+        self.anchors
+            .extend(other.anchors.iter().filter_map(|n| maps.nucl(*n)));
+        self.locked_elements
+            .extend(other.locked_elements.iter().filter_map(|k| maps.element(k)));
+        self.relaxed_positions.extend(
+            other
+                .relaxed_positions
+                .iter()
+                .filter_map(|(n, position)| Some((maps.nucl(*n)?, *position))),
+        );
+        Arc::make_mut(&mut self.no_phantoms)
+            .extend(other.no_phantoms.iter().filter_map(|g| maps.grid_id(*g)));
+        Arc::make_mut(&mut self.small_spheres)
+            .extend(other.small_spheres.iter().filter_map(|g| maps.grid_id(*g)));
+
+        for camera in other.cameras.values() {
+            let id = self
+                .cameras
+                .keys()
+                .max()
+                .map_or(CameraId(1), |id| CameraId(id.0 + 1));
+            self.cameras.insert(
+                id,
+                Camera {
+                    id,
+                    ..camera.clone()
+                },
+            );
+        }
+
+        let group_id = rand::random();
+        let pivot = GroupPivot {
+            position: self.center_of_helices(&maps.helices.values().copied().collect::<Vec<_>>()),
+            orientation: Rotor3::identity(),
+        };
+        self.group_attributes.insert(
+            group_id,
+            GroupAttribute {
+                pivot: Some(pivot),
+                ..Default::default()
+            },
+        );
+        let group = self.inserted_design_group(&maps, &other, name, group_id);
+        let tree = self.organizer_tree.get_or_insert_with(|| {
+            Arc::new(OrganizerTree::Node {
+                name: "root".to_owned(),
+                children: Vec::new(),
+                expanded: true,
+                id: None,
+                rule: None,
+            })
+        });
+        if let OrganizerTree::Node { children, .. } = Arc::make_mut(tree) {
+            children.push(group);
+        }
+        group_id
+    }
+
+    /// The organizer group of an inserted design. It contains the groups of the inserted design
+    /// and the inserted elements that are in none of them.
+    fn inserted_design_group(
+        &mut self,
+        maps: &IdMaps,
+        other: &Self,
+        name: String,
+        group_id: GroupId,
+    ) -> OrganizerTree {
+        let mut children = Vec::new();
+        if let Some(OrganizerTree::Node {
+            children: other_children,
+            ..
+        }) = other.organizer_tree.as_deref()
+        {
+            children.extend(other_children.iter().filter_map(|c| {
+                maps.organizer_tree(c, &other.group_attributes, &mut self.group_attributes)
+            }));
+        }
+        let mut grouped = BTreeSet::new();
+        for c in &children {
+            collect_leaves(c, &mut grouped);
+        }
+        let mut elements: Vec<_> = maps
+            .grids
+            .values()
+            .map(|id| DesignElementKey::Grid(*id))
+            .chain(maps.helices.values().map(|id| DesignElementKey::Helix(*id)))
+            .chain(
+                maps.strands
+                    .values()
+                    .map(|id| DesignElementKey::Strand(*id)),
+            )
+            .filter(|key| !grouped.contains(key))
+            .collect();
+        elements.sort();
+        children.extend(elements.into_iter().map(OrganizerTree::Leaf));
+        OrganizerTree::Node {
+            name,
+            children,
+            expanded: false,
+            id: Some(group_id),
+            rule: None,
+        }
+    }
+
+    fn center_of_helices(&self, helix_ids: &[usize]) -> Vec3 {
+        let helix_parameters = self.helix_parameters.unwrap_or_default();
+        let mut sum = Vec3::zero();
+        let mut nb_helices = 0;
+        for helix in helix_ids.iter().filter_map(|id| self.helices.get(id)) {
+            let on_free_grid = helix.grid_position.and_then(|position| {
+                let grid = self.free_grids.get_from_g_id(&position.grid)?;
+                Some(
+                    grid.to_grid(helix_parameters)
+                        .position_helix(position.x, position.y),
+                )
+            });
+            sum += on_free_grid.unwrap_or(helix.position);
+            nb_helices += 1;
+        }
+        if nb_helices > 0 {
+            sum / nb_helices as f32
+        } else {
+            sum
+        }
+    }
+}
+
+fn collect_leaves(tree: &OrganizerTree, leaves: &mut BTreeSet<DesignElementKey>) {
+    match tree {
+        OrganizerTree::Leaf(key) => {
+            leaves.insert(*key);
+        }
+        OrganizerTree::Node { children, .. } => {
+            for c in children {
+                collect_leaves(c, leaves);
+            }
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub mod consts;
 pub mod curves;
 pub mod design_element;
 pub mod design_operations;
+pub mod diff;
 pub mod domains;
 pub mod drawing_style;
 pub mod external_3d_objects;
@@ -20,6 +21,7 @@ pub mod grid;
 pub mod group_attributes;
 pub mod helices;
 pub mod id_generator;
+mod insert_design;
 mod insertions;
 pub mod interaction_modes;
 pub mod isometry3_descriptor;
@@ -29,15 +31,20 @@ pub mod organizer_tree;
 pub mod parameters;
 pub mod phantom_element;
 pub mod scadnano;
+pub mod smart_groups;
 pub mod strands;
 pub mod utils;
+pub mod voxelization;
+pub mod wireframe;
 
 use crate::{
     bezier_plane::{BezierPathData, BezierPaths, BezierPlanes},
     curves::CurveCache,
+    design_element::DesignElementKey,
     domains::Domain,
+    drawing_style::DrawingStyle,
     external_3d_objects::External3DObjects,
-    grid::grid_collection::FreeGrids,
+    grid::grid_collection::{FreeGridId, FreeGrids},
     grid::{GridData, GridDescriptor, GridId},
     group_attributes::GroupAttribute,
     helices::{Helices, Helix},
@@ -50,10 +57,14 @@ use crate::{
 use ahash::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use serde_with::{DefaultOnError, serde_as};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 use ultraviolet::{Rotor3, Similarity3, Vec3};
 
 /// The `ensnano` Design structure.
+#[serde_as]
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Design {
     /// The collection of all helices used in this design. Helices have a
@@ -115,6 +126,10 @@ pub struct Design {
     #[serde(skip_serializing_if = "HashSet::is_empty", default)]
     pub anchors: HashSet<Nucl>,
 
+    /// The helices, strands and grids that cannot be edited.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty", default)]
+    pub locked_elements: BTreeSet<DesignElementKey>,
+
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub organizer_tree: Option<Arc<OrganizerTree>>,
 
@@ -169,6 +184,17 @@ pub struct Design {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub clone_isometries: Option<Vec<Isometry3Descriptor>>,
+
+    /// Positions of nucleotides that override the ones given by their helix. These are the
+    /// positions obtained at the end of a physical relaxation.
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub relaxed_positions: std::collections::HashMap<Nucl, Vec3>,
+
+    /// The parameters of the last physical simulations made on this design. They are kept as
+    /// JSON because the simulation engines are defined in crates that depend on this one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    simulation_parameters: Option<serde_json::Value>,
 }
 
 impl Design {
@@ -281,6 +307,7 @@ impl Design {
             small_spheres: Default::default(),
             no_phantoms: Default::default(),
             anchors: Default::default(),
+            locked_elements: Default::default(),
             organizer_tree: None,
             ensnano_version: ensnano_version(),
             group_attributes: Default::default(),
@@ -298,6 +325,8 @@ impl Design {
             external_3d_objects: Default::default(),
             additional_structure: None,
             clone_isometries: Some(Vec::new()),
+            relaxed_positions: Default::default(),
+            simulation_parameters: None,
         }
     }
 
@@ -333,6 +362,11 @@ impl Design {
             mutate_all_helices(self, |h| h.roll *= -1.);
             self.ensnano_version = ensnano_version();
         }
+
+        // Drawing styles used to be written in the names of the groups.
+        if let Some(tree) = self.organizer_tree.as_mut() {
+            Arc::make_mut(tree).migrate_style_tags(&mut self.group_attributes);
+        }
     }
 
     /// Return a list of tuples (n1, n2, M) where n1 and n2 are nucleotides that are not on the same
@@ -345,7 +379,7 @@ impl Design {
         for s in self.strands.values() {
             for d in &s.domains {
                 if let Domain::HelixDomain(interval) = d {
-                    for i in interval.iter() {
+                    for i in interval.iter_nucls(&self.helices) {
                         let nucl = Nucl {
                             helix: interval.helix,
                             forward: interval.forward,
@@ -440,6 +474,54 @@ impl Design {
 
     pub fn prepare_for_save(&mut self, saving_information: SavingInformation) {
         self.saved_camera = saving_information.camera;
+        if let Some(parameters) = saving_information.simulation_parameters {
+            self.simulation_parameters = Some(parameters);
+        }
+    }
+
+    /// Recompute the content of the groups of the organizer tree that are defined by a rule.
+    pub fn update_smart_groups(&mut self) {
+        let new_tree = self
+            .organizer_tree
+            .as_ref()
+            .and_then(|tree| tree.with_updated_rules(&|rule| rule.members(self)));
+        if let Some(tree) = new_tree {
+            self.organizer_tree = Some(Arc::new(tree));
+        }
+    }
+
+    /// The first of the locked elements of `self` that is modified or deleted in `new_design`.
+    pub fn first_modified_locked_element(&self, new_design: &Self) -> Option<DesignElementKey> {
+        self.locked_elements
+            .iter()
+            .find(|key| !self.same_element(key, new_design))
+            .copied()
+    }
+
+    fn same_element(&self, key: &DesignElementKey, other: &Self) -> bool {
+        match key {
+            DesignElementKey::Helix(h_id) => {
+                same_serialized(self.helices.get(h_id), other.helices.get(h_id))
+            }
+            DesignElementKey::Strand(s_id) => self.strands.get(s_id) == other.strands.get(s_id),
+            DesignElementKey::Grid(g_id) => {
+                let g_id = FreeGridId(*g_id);
+                same_serialized(self.free_grids.get(&g_id), other.free_grids.get(&g_id))
+            }
+            DesignElementKey::Nucleotide { .. } | DesignElementKey::CrossOver { .. } => true,
+        }
+    }
+
+    /// The drawing style of each element of the design, given by the groups containing it.
+    pub fn drawing_styles(&self) -> HashMap<DesignElementKey, DrawingStyle> {
+        self.organizer_tree
+            .as_ref()
+            .map(|tree| tree.drawing_styles(&self.group_attributes))
+            .unwrap_or_default()
+    }
+
+    pub fn get_simulation_parameters(&self) -> Option<&serde_json::Value> {
+        self.simulation_parameters.as_ref()
     }
 
     pub fn get_nucl_position(&self, nucl: Nucl) -> Option<Vec3> {
@@ -579,6 +661,17 @@ fn groups_is_empty<K, V>(groups: &Arc<BTreeMap<K, V>>) -> bool {
     groups.as_ref().is_empty()
 }
 
+/// Whether `a` and `b` describe the same element. Helices and grids do not implement
+/// `PartialEq`, so they are only serialized when they are not shared by the two designs.
+fn same_serialized<T: Serialize>(a: Option<&T>, b: Option<&T>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            std::ptr::eq(a, b) || serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+        }
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
 impl Default for Design {
     fn default() -> Self {
         Self::new()
@@ -596,6 +689,9 @@ pub struct MutStrandAndData<'a> {
 
 pub struct SavingInformation {
     pub camera: Option<Camera>,
+    /// The parameters of the physical simulations, if some were used since the design was
+    /// opened.
+    pub simulation_parameters: Option<serde_json::Value>,
 }
 
 /// Apply a mutating function to the value wrapped in an `Arc<Helix>`. This will make `helix_ptr`
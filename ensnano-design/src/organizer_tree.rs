@@ -1,4 +1,9 @@
-use crate::design_element::{DesignElementKey, DnaAutoGroup};
+use crate::{
+    design_element::{DesignElementKey, DnaAutoGroup},
+    drawing_style::{self, DrawingStyle},
+    group_attributes::GroupAttribute,
+    smart_groups::GroupRule,
+};
 use ahash::RandomState;
 use rand::{
     Rng,
@@ -18,10 +23,59 @@ pub enum OrganizerTree {
         expanded: bool,
         #[serde(default)]
         id: Option<GroupId>,
+        /// If set, the children of the node are the elements satisfying this rule. They are
+        /// recomputed every time the design is updated.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rule: Option<GroupRule>,
     },
 }
 
 impl OrganizerTree {
+    /// Return a copy of self in which the children of the nodes having a rule are replaced by the
+    /// elements satisfying the rule, or `None` if this does not modify the tree.
+    pub fn with_updated_rules(
+        &self,
+        members: &impl Fn(&GroupRule) -> Vec<DesignElementKey>,
+    ) -> Option<Self> {
+        let Self::Node {
+            name,
+            children,
+            expanded,
+            id,
+            rule,
+        } = self
+        else {
+            return None;
+        };
+        let new_children = if let Some(rule) = rule {
+            let leaves: Vec<_> = members(rule).into_iter().map(Self::Leaf).collect();
+            let unchanged = leaves.len() == children.len()
+                && leaves.iter().zip(children).all(|(leaf, child)| {
+                    matches!((leaf, child), (Self::Leaf(a), Self::Leaf(b)) if a == b)
+                });
+            (!unchanged).then_some(leaves)
+        } else {
+            let updated: Vec<_> = children
+                .iter()
+                .map(|c| c.with_updated_rules(members))
+                .collect();
+            updated.iter().any(Option::is_some).then(|| {
+                updated
+                    .into_iter()
+                    .zip(children)
+                    .map(|(new, old)| new.unwrap_or_else(|| old.clone()))
+                    .collect()
+            })
+        };
+        new_children.map(|children| Self::Node {
+            name: name.clone(),
+            children,
+            expanded: *expanded,
+            id: *id,
+            rule: rule.clone(),
+        })
+    }
+
     pub fn get_names_of_groups_having(&self, element: &DesignElementKey) -> Vec<String> {
         let mut ret = Vec::new();
         match self {
@@ -96,44 +150,89 @@ impl OrganizerTree {
         }
     }
 
-    pub fn get_hashmap_to_all_group_names_with_prefix(
+    /// The drawing style of each element of the tree, given by the drawing styles of the groups
+    /// containing it. The style of a group takes precedence over the ones of its ancestors, and
+    /// over the ones of the groups that come after it.
+    pub fn drawing_styles(
         &self,
-        prefix: &str,
-    ) -> HashMap<DesignElementKey, Vec<&str>, RandomState> {
-        let mut hashmap = HashMap::default();
+        attributes: &HashMap<GroupId, GroupAttribute, RandomState>,
+    ) -> HashMap<DesignElementKey, DrawingStyle, RandomState> {
+        let mut styles = HashMap::default();
+        self.collect_drawing_styles(attributes, DrawingStyle::default(), &mut styles);
+        styles
+    }
 
+    fn collect_drawing_styles(
+        &self,
+        attributes: &HashMap<GroupId, GroupAttribute, RandomState>,
+        inherited: DrawingStyle,
+        styles: &mut HashMap<DesignElementKey, DrawingStyle, RandomState>,
+    ) {
         match self {
-            Self::Leaf(_) => (),
-            Self::Node { name, children, .. } => {
-                let trimmed_name = name.trim();
-                let has_prefix = trimmed_name.starts_with(prefix);
+            Self::Leaf(e) => {
+                if !inherited.is_empty() {
+                    let style = styles.entry(*e).or_default();
+                    *style = style.complete_with(&inherited);
+                }
+            }
+            Self::Node { children, id, .. } => {
+                let style = id
+                    .and_then(|id| attributes.get(&id))
+                    .map_or(inherited, |a| a.drawing_style.complete_with(&inherited));
                 for c in children {
-                    match c {
-                        Self::Leaf(e) => {
-                            let mut e_names: Vec<&str> =
-                                hashmap.get(e).cloned().unwrap_or(Vec::new());
-                            if has_prefix {
-                                e_names.push(trimmed_name);
-                            }
-                            hashmap.insert(*e, e_names);
-                        }
-                        Self::Node { .. } => {
-                            let c_hashmap = c.get_hashmap_to_all_group_names_with_prefix(prefix);
-                            for (e, e_names) in c_hashmap {
-                                let mut new_e_names: Vec<&str> =
-                                    hashmap.get(&e).cloned().unwrap_or(Vec::new());
-                                new_e_names.extend(e_names);
-                                if has_prefix {
-                                    new_e_names.push(trimmed_name);
-                                }
-                                hashmap.insert(e, new_e_names);
-                            }
-                        }
-                    }
+                    c.collect_drawing_styles(attributes, style, styles);
                 }
             }
         }
-        hashmap
+    }
+
+    /// The name of the first group of the tree that directly contains each strand.
+    pub fn strand_group_names(&self) -> HashMap<usize, String, RandomState> {
+        let mut names = HashMap::default();
+        self.collect_strand_group_names(&mut names);
+        names
+    }
+
+    fn collect_strand_group_names(&self, names: &mut HashMap<usize, String, RandomState>) {
+        let Self::Node { name, children, .. } = self else {
+            return;
+        };
+        for c in children {
+            match c {
+                Self::Leaf(DesignElementKey::Strand(s_id)) => {
+                    names.entry(*s_id).or_insert_with(|| name.clone());
+                }
+                Self::Leaf(_) => (),
+                node @ Self::Node { .. } => node.collect_strand_group_names(names),
+            }
+        }
+    }
+
+    /// Move the drawing attributes written in the names of the groups, such as
+    /// `style: %sr(2) %hc(FF0000)`, to the attributes of the groups.
+    pub fn migrate_style_tags(
+        &mut self,
+        attributes: &mut HashMap<GroupId, GroupAttribute, RandomState>,
+    ) {
+        let Self::Node {
+            name, children, id, ..
+        } = self
+        else {
+            return;
+        };
+        if let Some((new_name, style)) = drawing_style::read_style_tags(name) {
+            let id = id.get_or_insert_with(rand::random);
+            let attribute = attributes.entry(*id).or_default();
+            attribute.drawing_style = attribute.drawing_style.complete_with(&style);
+            *name = if new_name.is_empty() {
+                "Style".to_owned()
+            } else {
+                new_name
+            };
+        }
+        for c in children {
+            c.migrate_style_tags(attributes);
+        }
     }
 }
 
@@ -156,6 +255,8 @@ enum NewOrganizerTree {
         expanded: bool,
         #[serde(default)]
         id: Option<GroupId>,
+        #[serde(default)]
+        rule: Option<GroupRule>,
     },
 }
 
@@ -168,6 +269,7 @@ impl OldOrganizerTree {
                 children,
                 expanded: false,
                 id: None,
+                rule: None,
             },
         }
     }
@@ -182,11 +284,13 @@ impl NewOrganizerTree {
                 children,
                 expanded,
                 id,
+                rule,
             } => OrganizerTree::Node {
                 name,
                 children,
                 expanded,
                 id,
+                rule,
             },
         }
     }
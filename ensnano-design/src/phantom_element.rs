@@ -13,6 +13,7 @@
 //! adding PHANTOM_RANGE to it.
 
 use crate::nucl::Nucl;
+use serde::{Deserialize, Serialize};
 
 pub const PHANTOM_RANGE: i32 = 1000;
 
@@ -63,7 +64,7 @@ pub fn phantom_helix_decoder(id: u32) -> PhantomElement {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct PhantomElement {
     pub design_id: u32,
     pub helix_id: u32,
@@ -68,6 +68,17 @@ pub struct ScadnanoGroup {
 }
 
 impl ScadnanoGroup {
+    /// A group of helices on a grid of type `grid` (`"square"` or `"honeycomb"`) at `position`.
+    pub fn new(position: Vec3, grid: String) -> Self {
+        Self {
+            position,
+            pitch: None,
+            yaw: None,
+            roll: None,
+            grid,
+        }
+    }
+
     pub fn to_grid_desc(&self) -> Result<GridDescriptor, ScadnanoImportError> {
         let grid_type = match self.grid.as_str() {
             "square" => Ok(GridTypeDescr::Square { twist: None }),
@@ -205,19 +216,10 @@ impl ScadnanoInsertionsDeletions {
     pub(crate) fn read_domain(&mut self, domain: &ScadnanoDomain) {
         match domain {
             ScadnanoDomain::Loopout { .. } => (),
+            // Deletions are kept as deletions of the helices and do not shift the positions.
             ScadnanoDomain::HelixDomain {
-                deletions,
-                helix,
-                insertions,
-                ..
+                helix, insertions, ..
             } => {
-                if let Some(vec) = deletions {
-                    let entry = self.count.entry(*helix).or_default();
-                    for d in vec {
-                        let count_entry = entry.entry(*d).or_default();
-                        *count_entry -= 1;
-                    }
-                }
                 if let Some(vec) = insertions {
                     let entry = self.count.entry(*helix).or_default();
                     for insertion in vec {
@@ -365,6 +367,7 @@ impl Helix {
             initial_nt_index: 0,
             support_helix: None,
             path_id: None,
+            deletions: BTreeSet::new(),
         })
     }
 }
@@ -375,6 +378,7 @@ impl Design {
         let mut group_map = BTreeMap::new();
         let default_grid = scad.default_grid_descriptor()?;
         let mut insertion_deletions = ScadnanoInsertionsDeletions::default();
+        let mut deletions = BTreeMap::new();
         group_map.insert(String::from("default_group"), 0usize);
         grids.push(default_grid);
         let mut helices_per_group = vec![0];
@@ -392,10 +396,17 @@ impl Design {
             for d in &s.domains {
                 insertion_deletions.read_domain(d);
             }
+            s.read_deletions(&mut deletions);
         }
         let mut helices = BTreeMap::new();
         for (i, h) in scad.helices.iter().enumerate() {
-            let helix = Helix::from_scadnano(h, &group_map, &groups, &mut helices_per_group)?;
+            let mut helix = Helix::from_scadnano(h, &group_map, &groups, &mut helices_per_group)?;
+            if let Some(positions) = deletions.get(&i) {
+                helix.deletions = positions
+                    .iter()
+                    .map(|d| insertion_deletions.adjust(*d, i))
+                    .collect();
+            }
             helices.insert(i, Arc::new(helix));
         }
         let mut strands = BTreeMap::new();
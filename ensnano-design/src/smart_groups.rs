@@ -0,0 +1,468 @@
+//! Groups of the organizer tree whose content is defined by a rule.
+//!
+//! The members of such a group are recomputed every time the design is modified, so that it can
+//! be used like any other group for selection, visibility, drawing styles and staples export.
+//!
+//! Rules are written as queries, for example:
+//! - `strands where staple and on helix 12`
+//! - `strands where unpaired`
+//! - `strands where name matches ^core_`
+//! - `helices where on grid 3 and x < 5`
+//!
+//! A pattern that contains spaces, or that is the word `and`, must be written between double
+//! quotes, as in `strands where name matches "core and edge"`. Inside quotes, `\"` and `\\`
+//! stand for `"` and `\`.
+
+use crate::{
+    Design, design_element::DesignElementKey, domains::Domain, grid::GridId, helices::Helix,
+    nucl::Nucl, strands::Strand,
+};
+use ahash::HashSet;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A rule defining the members of a smart group.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum GroupRule {
+    /// The strands that satisfy all the conditions.
+    Strands(Vec<StrandCondition>),
+    /// The helices that satisfy all the conditions.
+    Helices(Vec<HelixCondition>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StrandCondition {
+    Staple,
+    Scaffold,
+    /// The strand has at least one nucleotide on the helix.
+    OnHelix(usize),
+    /// The strand has at least one nucleotide that is not paired.
+    Unpaired,
+    /// The name of the strand matches the regular expression.
+    NameMatches(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HelixCondition {
+    OnGrid(usize),
+    /// Condition on the x coordinate of the helix on its grid.
+    X(Comparison, isize),
+    /// Condition on the y coordinate of the helix on its grid.
+    Y(Comparison, isize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRuleError(pub String);
+
+impl std::fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseRuleError {}
+
+impl GroupRule {
+    /// The elements of `design` that satisfy the rule, in increasing order.
+    pub fn members(&self, design: &Design) -> Vec<DesignElementKey> {
+        match self {
+            Self::Strands(conditions) => {
+                let context = StrandContext::new(design, conditions);
+                design
+                    .strands
+                    .iter()
+                    .filter(|(id, strand)| {
+                        conditions
+                            .iter()
+                            .all(|c| context.satisfies(c, **id, strand))
+                    })
+                    .map(|(id, _)| DesignElementKey::Strand(*id))
+                    .collect()
+            }
+            Self::Helices(conditions) => design
+                .helices
+                .iter()
+                .filter(|(_, helix)| conditions.iter().all(|c| c.satisfied_by(helix)))
+                .map(|(id, _)| DesignElementKey::Helix(*id))
+                .collect(),
+        }
+    }
+}
+
+/// Data shared by the evaluation of the conditions on all the strands of a design.
+struct StrandContext<'a> {
+    design: &'a Design,
+    /// All the nucleotides of the design, computed only if needed.
+    nucls: HashSet<Nucl>,
+    /// The compiled regular expressions of the `NameMatches` conditions.
+    regexes: Vec<(String, Regex)>,
+}
+
+impl<'a> StrandContext<'a> {
+    fn new(design: &'a Design, conditions: &[StrandCondition]) -> Self {
+        let nucls = if conditions.contains(&StrandCondition::Unpaired) {
+            design.strands.values().flat_map(strand_nucls).collect()
+        } else {
+            Default::default()
+        };
+        let regexes = conditions
+            .iter()
+            .filter_map(|c| match c {
+                StrandCondition::NameMatches(pattern) => Regex::new(pattern)
+                    .ok()
+                    .map(|regex| (pattern.clone(), regex)),
+                _ => None,
+            })
+            .collect();
+        Self {
+            design,
+            nucls,
+            regexes,
+        }
+    }
+
+    fn satisfies(&self, condition: &StrandCondition, id: usize, strand: &Strand) -> bool {
+        match condition {
+            StrandCondition::Staple => self.design.scaffold_id != Some(id),
+            StrandCondition::Scaffold => self.design.scaffold_id == Some(id),
+            StrandCondition::OnHelix(helix) => {
+                strand.domains.iter().any(|d| d.helix() == Some(*helix))
+            }
+            StrandCondition::Unpaired => {
+                strand_nucls(strand).any(|nucl| !self.nucls.contains(&nucl.compl()))
+            }
+            StrandCondition::NameMatches(pattern) => {
+                let name = strand.name.as_deref().unwrap_or_default();
+                self.regexes
+                    .iter()
+                    .find(|(p, _)| p == pattern)
+                    .is_some_and(|(_, regex)| regex.is_match(name))
+            }
+        }
+    }
+}
+
+fn strand_nucls(strand: &Strand) -> impl Iterator<Item = Nucl> + '_ {
+    strand.domains.iter().flat_map(|domain| {
+        let interval = match domain {
+            Domain::HelixDomain(interval) => Some(interval),
+            Domain::Insertion { .. } => None,
+        };
+        interval.into_iter().flat_map(|interval| {
+            interval.iter().map(|position| Nucl {
+                helix: interval.helix,
+                position,
+                forward: interval.forward,
+            })
+        })
+    })
+}
+
+impl HelixCondition {
+    fn satisfied_by(&self, helix: &Helix) -> bool {
+        let Some(grid_position) = helix.grid_position.as_ref() else {
+            return false;
+        };
+        match self {
+            Self::OnGrid(grid) => grid_position.grid == GridId::FreeGrid(*grid),
+            Self::X(comparison, value) => comparison.holds(grid_position.x, *value),
+            Self::Y(comparison, value) => comparison.holds(grid_position.y, *value),
+        }
+    }
+}
+
+impl Comparison {
+    const ALL: [Self; 5] = [
+        Self::LessOrEqual,
+        Self::GreaterOrEqual,
+        Self::Less,
+        Self::Greater,
+        Self::Equal,
+    ];
+
+    fn holds(self, left: isize, right: isize) -> bool {
+        match self {
+            Self::Less => left < right,
+            Self::LessOrEqual => left <= right,
+            Self::Equal => left == right,
+            Self::GreaterOrEqual => left >= right,
+            Self::Greater => left > right,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Equal => "=",
+            Self::GreaterOrEqual => ">=",
+            Self::Greater => ">",
+        }
+    }
+}
+
+/// A word of a rule, or a text between double quotes.
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        !self.quoted && self.text == keyword
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, ParseRuleError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut text = String::new();
+        let quoted = c == '"';
+        if quoted {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') if matches!(chars.peek(), Some('"' | '\\')) => {
+                        text.extend(chars.next());
+                    }
+                    Some(c) => text.push(c),
+                    None => {
+                        return Err(ParseRuleError(format!("Missing closing quote in \"{s}\"")));
+                    }
+                }
+            }
+        } else {
+            text.push(c);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                text.push(c);
+            }
+        }
+        tokens.push(Token { text, quoted });
+    }
+    Ok(tokens)
+}
+
+/// The words of a condition separated by single spaces. Quoted texts are not allowed.
+fn words(tokens: &[Token]) -> Result<String, ParseRuleError> {
+    if let Some(token) = tokens.iter().find(|t| t.quoted) {
+        return Err(ParseRuleError(format!(
+            "Unexpected quoted text \"{}\"",
+            token.text
+        )));
+    }
+    Ok(tokens
+        .iter()
+        .map(|t| t.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+impl FromStr for GroupRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let Some((kind, rest)) = tokens.split_first() else {
+            return Err(ParseRuleError(
+                "A rule must start with \"strands\" or \"helices\"".to_owned(),
+            ));
+        };
+        let conditions: Vec<&[Token]> = match rest.split_first() {
+            None => Vec::new(),
+            Some((where_, conditions)) if where_.is_keyword("where") => {
+                conditions.split(|token| token.is_keyword("and")).collect()
+            }
+            Some((token, _)) => {
+                return Err(ParseRuleError(format!(
+                    "Expected \"where\", got \"{}\"",
+                    token.text
+                )));
+            }
+        };
+        if kind.is_keyword("strands") {
+            Ok(Self::Strands(
+                conditions
+                    .into_iter()
+                    .map(StrandCondition::from_tokens)
+                    .collect::<Result<_, _>>()?,
+            ))
+        } else if kind.is_keyword("helices") {
+            Ok(Self::Helices(
+                conditions
+                    .into_iter()
+                    .map(|tokens| words(tokens)?.parse())
+                    .collect::<Result<_, _>>()?,
+            ))
+        } else {
+            Err(ParseRuleError(format!(
+                "A rule must start with \"strands\" or \"helices\", got \"{}\"",
+                kind.text
+            )))
+        }
+    }
+}
+
+impl FromStr for StrandCondition {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_tokens(&tokenize(s)?)
+    }
+}
+
+impl StrandCondition {
+    fn from_tokens(tokens: &[Token]) -> Result<Self, ParseRuleError> {
+        if let [name, matches, pattern @ ..] = tokens
+            && name.is_keyword("name")
+            && matches.is_keyword("matches")
+        {
+            let [pattern] = pattern else {
+                return Err(ParseRuleError(
+                    "A pattern made of several words must be written between double quotes"
+                        .to_owned(),
+                ));
+            };
+            return Regex::new(&pattern.text)
+                .map(|_| Self::NameMatches(pattern.text.clone()))
+                .map_err(|e| ParseRuleError(format!("Invalid regular expression: {e}")));
+        }
+        let s = words(tokens)?;
+        if let Some(helix) = s.strip_prefix("on helix ") {
+            return parse_number(helix).map(Self::OnHelix);
+        }
+        match s.as_str() {
+            "staple" => Ok(Self::Staple),
+            "scaffold" => Ok(Self::Scaffold),
+            "unpaired" => Ok(Self::Unpaired),
+            _ => Err(ParseRuleError(format!(
+                "Unknown strand condition \"{s}\". Expected \"staple\", \"scaffold\", \
+                 \"unpaired\", \"on helix N\" or \"name matches REGEX\""
+            ))),
+        }
+    }
+}
+
+impl FromStr for HelixCondition {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(grid) = s.strip_prefix("on grid ") {
+            return parse_number(grid).map(Self::OnGrid);
+        }
+        for (coordinate, condition) in [
+            ("x", Self::X as fn(Comparison, isize) -> Self),
+            ("y", Self::Y),
+        ] {
+            let Some(rest) = s.strip_prefix(coordinate) else {
+                continue;
+            };
+            let rest = rest.trim_start();
+            for comparison in Comparison::ALL {
+                if let Some(value) = rest.strip_prefix(comparison.symbol()) {
+                    return parse_number(value).map(|value| condition(comparison, value));
+                }
+            }
+        }
+        Err(ParseRuleError(format!(
+            "Unknown helix condition \"{s}\". Expected \"on grid N\", \"x < N\" or \"y >= N\""
+        )))
+    }
+}
+
+fn parse_number<T: FromStr>(s: &str) -> Result<T, ParseRuleError> {
+    let s = s.trim();
+    s.parse()
+        .ok()
+        .ok_or_else(|| ParseRuleError(format!("Expected a number, got \"{s}\"")))
+}
+
+impl std::fmt::Display for GroupRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, conditions): (_, Vec<String>) = match self {
+            Self::Strands(conditions) => (
+                "strands",
+                conditions.iter().map(ToString::to_string).collect(),
+            ),
+            Self::Helices(conditions) => (
+                "helices",
+                conditions.iter().map(ToString::to_string).collect(),
+            ),
+        };
+        if conditions.is_empty() {
+            f.write_str(kind)
+        } else {
+            write!(f, "{kind} where {}", conditions.join(" and "))
+        }
+    }
+}
+
+impl std::fmt::Display for StrandCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Staple => f.write_str("staple"),
+            Self::Scaffold => f.write_str("scaffold"),
+            Self::OnHelix(helix) => write!(f, "on helix {helix}"),
+            Self::Unpaired => f.write_str("unpaired"),
+            Self::NameMatches(pattern) => {
+                f.write_str("name matches ")?;
+                write_pattern(f, pattern)
+            }
+        }
+    }
+}
+
+/// Write `pattern` so that it is read back as a single token, quoting it if needed.
+fn write_pattern(f: &mut std::fmt::Formatter<'_>, pattern: &str) -> std::fmt::Result {
+    if pattern.is_empty()
+        || pattern == "and"
+        || pattern.starts_with('"')
+        || pattern.contains(char::is_whitespace)
+    {
+        write!(
+            f,
+            "\"{}\"",
+            pattern.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    } else {
+        f.write_str(pattern)
+    }
+}
+
+impl std::fmt::Display for HelixCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OnGrid(grid) => write!(f, "on grid {grid}"),
+            Self::X(comparison, value) => write!(f, "x {} {value}", comparison.symbol()),
+            Self::Y(comparison, value) => write!(f, "y {} {value}", comparison.symbol()),
+        }
+    }
+}
+
+impl TryFrom<String> for GroupRule {
+    type Error = ParseRuleError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<GroupRule> for String {
+    fn from(rule: GroupRule) -> Self {
+        rule.to_string()
+    }
+}
@@ -220,7 +220,7 @@ impl DomainJunction {
 }
 
 /// A DNA strand. Strands are represented as sequences of `Domains`.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct Strand {
     /// The (ordered) vector of domains, where each domain is a
     /// directed interval of a helix.
@@ -307,6 +307,18 @@ impl Strand {
         self.domains.iter().map(Domain::length).sum()
     }
 
+    /// The number of nucleotides of the strand, not counting the positions that are deleted on
+    /// their helix.
+    pub fn nb_nucls(&self, helices: &Helices) -> usize {
+        self.domains
+            .iter()
+            .map(|d| match d {
+                Domain::HelixDomain(interval) => interval.iter_nucls(helices).count(),
+                Domain::Insertion { nb_nucl, .. } => *nb_nucl,
+            })
+            .sum()
+    }
+
     /// Merge all consecutive domains that are on the same helix.
     pub fn merge_consecutive_domains(&mut self) {
         let mut to_merge = vec![];
@@ -428,24 +440,23 @@ impl Strand {
         });
     }
 
-    pub fn get_nth_nucl(&self, n: usize) -> Option<Nucl> {
-        let mut seen = 0;
-        for d in &self.domains {
-            if seen + d.length() > n {
-                return if let Domain::HelixDomain(d) = d {
-                    let position = d.iter().nth(n - seen);
-                    position.map(|position| Nucl {
-                        position,
-                        helix: d.helix,
-                        forward: d.forward,
-                    })
-                } else {
-                    None
-                };
-            }
-            seen += d.length();
-        }
-        None
+    /// The `n`-th nucleotide of the strand that receives a base of the strand's sequence, i.e.
+    /// not counting the insertions nor the positions that are deleted on their helix.
+    pub fn get_nth_nucl(&self, n: usize, helices: &Helices) -> Option<Nucl> {
+        self.domains
+            .iter()
+            .filter_map(|d| match d {
+                Domain::HelixDomain(interval) => Some(interval),
+                Domain::Insertion { .. } => None,
+            })
+            .flat_map(|interval| {
+                interval.iter_nucls(helices).map(|position| Nucl {
+                    position,
+                    helix: interval.helix,
+                    forward: interval.forward,
+                })
+            })
+            .nth(n)
     }
 
     pub fn insertion_points(&self) -> Vec<(Option<Nucl>, Option<Nucl>)> {
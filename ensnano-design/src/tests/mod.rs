@@ -103,6 +103,131 @@ fn scadnano_import_one_loopout() {
     assert_good_strand(strand, "[H0: 8 -> 15] [@5] [H1: 8 <- 15]");
 }
 
+#[test]
+fn cadnano_skips_become_deletions() {
+    const LENGTH: usize = 32;
+    let no_link = (-1, -1, -1, -1);
+    let scaf: Vec<_> = (0..LENGTH as isize)
+        .map(|j| match j {
+            0 => (-1, -1, 0, 1),
+            1..15 => (0, j - 1, 0, j + 1),
+            15 => (0, 14, -1, -1),
+            _ => no_link,
+        })
+        .collect();
+    let mut skip = vec![0; LENGTH];
+    skip[4] = -1;
+    let cadnano: cadnano::CadnanoDesign = serde_json::from_value(serde_json::json!({
+        "name": "skips",
+        "vstrands": [{
+            "col": 0,
+            "row": 0,
+            "num": 0,
+            "loop": vec![0; LENGTH],
+            "scaf": scaf,
+            "scafLoop": [],
+            "skip": skip,
+            "stap": vec![no_link; LENGTH],
+            "stapLoop": [],
+            "stap_colors": [],
+        }],
+    }))
+    .unwrap();
+    let design = Design::from_cadnano(&cadnano);
+    let helix = design.helices.get(&0).unwrap();
+    assert_eq!(helix.deletions.iter().copied().collect::<Vec<_>>(), [4]);
+    assert_eq!(design.strands.len(), 1);
+    let strand = design.strands.values().next().unwrap();
+    assert_eq!(strand.length(), 16);
+    assert_eq!(strand.nb_nucls(&design.helices), 15);
+}
+
+#[test]
+fn nth_nucleotide_skips_deletions() {
+    let mut design = two_helix_design();
+    design
+        .helices
+        .make_mut()
+        .get_mut(&0)
+        .unwrap()
+        .deletions
+        .insert(3);
+    let strand = strand_on(&[(0, 0, 10, true), (1, 0, 10, false)]);
+    let nucl = |helix, position, forward| Nucl {
+        helix,
+        position,
+        forward,
+    };
+    assert_eq!(
+        strand.get_nth_nucl(2, &design.helices),
+        Some(nucl(0, 2, true))
+    );
+    assert_eq!(
+        strand.get_nth_nucl(5, &design.helices),
+        Some(nucl(0, 6, true))
+    );
+    // Helix 0 holds 9 nucleotides, the following ones are on helix 1 going backward.
+    assert_eq!(
+        strand.get_nth_nucl(11, &design.helices),
+        Some(nucl(1, 7, false))
+    );
+    assert_eq!(strand.get_nth_nucl(19, &design.helices), None);
+}
+
+#[test]
+fn scadnano_deletions_are_kept_on_the_helices() {
+    let input = r##" {
+  "version": "0.15.0",
+  "grid": "square",
+  "helices": [
+    {"grid_position": [0, 0]}
+  ],
+  "strands": [
+    {
+      "color": "#57bb00",
+      "domains": [
+        {"helix": 0, "forward": true, "start": 0, "end": 16, "deletions": [4, 11]}
+      ]
+    },
+    {
+      "color": "#57bb00",
+      "domains": [
+        {"helix": 0, "forward": false, "start": 0, "end": 16, "deletions": [4, 11]}
+      ]
+    }
+  ]
+      }"##;
+    let scadnano_design: ScadnanoDesign =
+        serde_json::from_str(input).expect("Failed to parse scadnano input");
+    let design = Design::from_scadnano(&scadnano_design).expect("Could not convert to ensnano");
+    let helix = design.helices.get(&0).unwrap();
+    assert_eq!(helix.deletions.iter().copied().collect::<Vec<_>>(), [4, 11]);
+    let strand = design.strands.get(&0).unwrap();
+    assert_good_strand(strand, "[H0: 0 -> 15]");
+    assert_eq!(strand.length(), 16);
+    assert_eq!(strand.nb_nucls(&design.helices), 14);
+}
+
+#[test]
+fn nucleotides_after_a_deletion_take_its_place() {
+    let parameters = HelixParameters::DEFAULT;
+    let mut helix = Helix::new(Vec3::zero(), Rotor3::identity());
+    let reference = helix.clone();
+    helix.deletions = [-3, 4].into_iter().collect();
+    assert_eq!(
+        (-5..8)
+            .map(|n| helix.index_without_deletions(n))
+            .collect::<Vec<_>>(),
+        [-4, -3, -3, -2, -1, 0, 1, 2, 3, 4, 4, 5, 6]
+    );
+    for (n, expected) in [(3, 3), (5, 4), (-4, -3)] {
+        assert_eq!(
+            helix.space_pos(&parameters, n, true),
+            reference.space_pos(&parameters, expected, true)
+        );
+    }
+}
+
 fn assert_good_strand<S: std::ops::Deref<Target = str>>(strand: &Strand, objective: S) {
     let re = Regex::new(r"\[[^\]]*\]").unwrap();
     let formatted_strand = strand.formatted_domains();
@@ -629,3 +754,606 @@ fn check_formatted_strand_with_insertion() {
     let strand = strand_with_insertion();
     assert_good_strand(&strand, formatted_strand_with_insertion());
 }
+
+#[test]
+fn relaxed_positions_survive_serialization() {
+    let mut design = Design::new();
+    let nucl = Nucl {
+        helix: 1,
+        position: -3,
+        forward: false,
+    };
+    design
+        .relaxed_positions
+        .insert(nucl, Vec3::new(1.0, 2.0, 3.0));
+
+    let json = serde_json::to_string(&design).unwrap();
+    let read: Design = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.relaxed_positions, design.relaxed_positions);
+
+    // Designs without relaxed positions do not mention them.
+    let json = serde_json::to_string(&Design::new()).unwrap();
+    assert!(!json.contains("relaxed_positions"));
+}
+
+#[test]
+fn group_rules_round_trip() {
+    use crate::smart_groups::{Comparison, GroupRule, HelixCondition, StrandCondition};
+
+    let rule: GroupRule = "strands where staple and on helix 12 and name matches ^core_"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        rule,
+        GroupRule::Strands(vec![
+            StrandCondition::Staple,
+            StrandCondition::OnHelix(12),
+            StrandCondition::NameMatches("^core_".to_owned()),
+        ])
+    );
+    let rule: GroupRule = "helices where on grid 3 and x<5 and y >= -2"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        rule,
+        GroupRule::Helices(vec![
+            HelixCondition::OnGrid(3),
+            HelixCondition::X(Comparison::Less, 5),
+            HelixCondition::Y(Comparison::GreaterOrEqual, -2),
+        ])
+    );
+    assert_eq!(
+        rule.to_string(),
+        "helices where on grid 3 and x < 5 and y >= -2"
+    );
+    assert_eq!(rule.to_string().parse::<GroupRule>(), Ok(rule));
+
+    assert!("staples".parse::<GroupRule>().is_err());
+    assert!(
+        "strands where on helix twelve"
+            .parse::<GroupRule>()
+            .is_err()
+    );
+    assert!("strands where name matches (".parse::<GroupRule>().is_err());
+    assert!(
+        "strands where name matches core edge"
+            .parse::<GroupRule>()
+            .is_err()
+    );
+    assert!(
+        "strands where name matches \"core"
+            .parse::<GroupRule>()
+            .is_err()
+    );
+
+    let rule: GroupRule = r#"strands where name matches "core and \"edge\"" and staple"#
+        .parse()
+        .unwrap();
+    assert_eq!(
+        rule,
+        GroupRule::Strands(vec![
+            StrandCondition::NameMatches(r#"core and "edge""#.to_owned()),
+            StrandCondition::Staple,
+        ])
+    );
+    assert_eq!(rule.to_string().parse::<GroupRule>(), Ok(rule));
+    for pattern in [r"^a\d+ b$", "and", r"\\", "a where b"] {
+        let rule = GroupRule::Strands(vec![StrandCondition::NameMatches(pattern.to_owned())]);
+        assert_eq!(rule.to_string().parse::<GroupRule>(), Ok(rule));
+    }
+}
+
+#[test]
+fn smart_groups_follow_the_design() {
+    let mut design = Design::new();
+    design.strands.insert(0, Strand::init(0, 0, true, 0));
+    design.strands.insert(1, Strand::init(1, 0, true, 0));
+    design.strands.insert(2, Strand::init(1, 0, false, 0));
+    design.strands.insert(3, Strand::init(2, 0, true, 0));
+    design.scaffold_id = Some(0);
+    let smart_group = |rule: &str| OrganizerTree::Node {
+        name: rule.to_owned(),
+        children: vec![],
+        expanded: false,
+        id: None,
+        rule: Some(rule.parse().unwrap()),
+    };
+    design.organizer_tree = Some(Arc::new(OrganizerTree::Node {
+        name: "root".to_owned(),
+        children: vec![
+            smart_group("strands where staple and on helix 1"),
+            smart_group("strands where unpaired"),
+        ],
+        expanded: true,
+        id: None,
+        rule: None,
+    }));
+
+    design.update_smart_groups();
+    let children = |design: &Design, group: usize| {
+        let Some(OrganizerTree::Node { children, .. }) = design.organizer_tree.as_deref() else {
+            unreachable!()
+        };
+        let OrganizerTree::Node { children, .. } = &children[group] else {
+            unreachable!()
+        };
+        children
+            .iter()
+            .map(|c| match c {
+                OrganizerTree::Leaf(DesignElementKey::Strand(s)) => *s,
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(children(&design, 0), [1, 2]);
+    assert_eq!(children(&design, 1), [0, 3]);
+
+    design.strands.remove(&2);
+    design.update_smart_groups();
+    assert_eq!(children(&design, 0), [1]);
+    assert_eq!(children(&design, 1), [0, 1, 3]);
+
+    // The rules are saved with the design.
+    let json = serde_json::to_string(&design).unwrap();
+    let read: Design = serde_json::from_str(&json).unwrap();
+    assert!(json.contains("strands where staple and on helix 1"));
+    assert_eq!(children(&read, 1), [0, 1, 3]);
+}
+
+#[test]
+fn style_tags_are_migrated_to_group_attributes() {
+    use crate::drawing_style::DrawingStyle;
+
+    let group = |name: &str, id: Option<GroupId>, children| OrganizerTree::Node {
+        name: name.to_owned(),
+        children,
+        expanded: false,
+        id,
+        rule: None,
+    };
+    let inner_id: GroupId = rand::random();
+    let inner = group(
+        "style: %sr(3) %cs(FF0000) core",
+        Some(inner_id),
+        vec![OrganizerTree::Leaf(DesignElementKey::Strand(0))],
+    );
+    let outer = group(
+        "style:%sr(5) %br(2)",
+        None,
+        vec![inner, OrganizerTree::Leaf(DesignElementKey::Strand(1))],
+    );
+    let mut design = Design::new();
+    design.organizer_tree = Some(Arc::new(group("root", None, vec![outer])));
+    design.update_version();
+
+    let Some(OrganizerTree::Node { children, .. }) = design.organizer_tree.as_deref() else {
+        unreachable!()
+    };
+    let OrganizerTree::Node {
+        name,
+        children,
+        id: Some(outer_id),
+        ..
+    } = &children[0]
+    else {
+        unreachable!()
+    };
+    assert_eq!(name, "Style");
+    assert!(matches!(&children[0], OrganizerTree::Node { name, .. } if name == "core"));
+    assert_eq!(
+        design.group_attributes[outer_id].drawing_style,
+        DrawingStyle {
+            sphere_radius: Some(0.5),
+            bond_radius: Some(0.2),
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        design.group_attributes[&inner_id].drawing_style.color_shade,
+        Some(0xFF0000)
+    );
+
+    // The style of the innermost group takes precedence.
+    let styles = design.drawing_styles();
+    let strand_0 = styles[&DesignElementKey::Strand(0)];
+    assert_eq!(strand_0.sphere_radius, Some(0.3));
+    assert_eq!(strand_0.bond_radius, Some(0.2));
+    assert_eq!(strand_0.color_shade, Some(0xFF0000));
+    let strand_1 = styles[&DesignElementKey::Strand(1)];
+    assert_eq!(strand_1.sphere_radius, Some(0.5));
+    assert_eq!(strand_1.color_shade, None);
+}
+
+#[test]
+fn style_fields_read_what_they_write() {
+    use crate::drawing_style::{DrawingStyle, StyleField};
+
+    let mut style = DrawingStyle::default();
+    for (field, text) in [
+        (StyleField::SphereRadius, "2.5"),
+        (StyleField::SphereColor, "FF0000"),
+        (StyleField::HelixAsCylinderColor, "80112233"),
+        (StyleField::Alpha, "0.5"),
+        (StyleField::Torsion, "1, 2.5"),
+    ] {
+        field.write(&mut style, text).unwrap();
+        assert_eq!(field.read(&style), text);
+    }
+    assert_eq!(style.sphere_radius, Some(0.25));
+    assert_eq!(style.sphere_color, Some(0xFF_FF_00_00));
+
+    StyleField::BondColor.write(&mut style, "Red500").unwrap();
+    assert_eq!(style.bond_color, Some(0xFF_F4_43_36));
+    StyleField::SphereRadius.write(&mut style, "").unwrap();
+    assert_eq!(style.sphere_radius, None);
+
+    assert!(StyleField::Alpha.write(&mut style, "2").is_err());
+    assert!(StyleField::BondColor.write(&mut style, "FF00").is_err());
+    assert!(StyleField::Curvature.write(&mut style, "1").is_err());
+    assert_eq!(style.alpha, Some(0.5));
+}
+
+#[test]
+fn inserted_design_is_remapped_into_a_group() {
+    use crate::grid::{GridDescriptor, GridTypeDescr};
+
+    let mut part = Design::new();
+    let grid = GridDescriptor {
+        position: Vec3::new(10., 0., 0.),
+        orientation: Rotor3::identity(),
+        helix_parameters: None,
+        grid_type: GridTypeDescr::Square { twist: None },
+        invisible: false,
+        bezier_vertex: None,
+    };
+    let grid_id = part.free_grids.make_mut().push(grid.clone());
+    let instantiated = grid.to_grid(HelixParameters::GEARY_2014_DNA);
+    let mut helices = part.helices.make_mut();
+    for x in 0..2 {
+        helices.push_helix(Helix::new_on_grid(&instantiated, x, 0, grid_id));
+    }
+    drop(helices);
+    part.strands.insert(0, Strand::init(1, 0, true, 0));
+    part.strands.insert(1, Strand::init(2, 0, false, 0));
+    part.scaffold_id = Some(1);
+    let part_group: GroupId = rand::random();
+    part.organizer_tree = Some(Arc::new(OrganizerTree::Node {
+        name: "root".to_owned(),
+        children: vec![OrganizerTree::Node {
+            name: "staples".to_owned(),
+            children: vec![OrganizerTree::Leaf(DesignElementKey::Strand(0))],
+            expanded: false,
+            id: Some(part_group),
+            rule: None,
+        }],
+        expanded: true,
+        id: None,
+        rule: None,
+    }));
+    part.group_attributes
+        .insert(part_group, GroupAttribute::default());
+
+    let mut design = Design::new();
+    design
+        .helices
+        .make_mut()
+        .push_helix(Helix::new(Vec3::zero(), Rotor3::identity()));
+    let first = design.insert_design(part.clone(), "part".to_owned());
+    let second = design.insert_design(part, "part".to_owned());
+    assert_ne!(first, second);
+
+    assert_eq!(design.helices.len(), 5);
+    assert_eq!(design.free_grids.keys().count(), 2);
+    assert_eq!(design.strands.keys().count(), 4);
+    // The first inserted scaffold becomes the scaffold of the design.
+    assert_eq!(design.scaffold_id, Some(1));
+    for (s_id, strand) in design.strands.iter() {
+        let Domain::HelixDomain(interval) = &strand.domains[0] else {
+            unreachable!()
+        };
+        let helix = design.helices.get(&interval.helix).unwrap();
+        let grid = helix.grid_position.unwrap().grid;
+        assert_eq!(grid, GridId::FreeGrid(*s_id / 2));
+    }
+
+    let Some(OrganizerTree::Node { children, .. }) = design.organizer_tree.as_deref() else {
+        unreachable!()
+    };
+    assert_eq!(children.len(), 2);
+    let OrganizerTree::Node {
+        name,
+        children,
+        id: Some(group_id),
+        ..
+    } = &children[1]
+    else {
+        unreachable!()
+    };
+    assert_eq!(name, "part");
+    assert_eq!(*group_id, second);
+    let OrganizerTree::Node {
+        children: staples,
+        id: Some(staples_id),
+        ..
+    } = &children[0]
+    else {
+        unreachable!()
+    };
+    assert_ne!(*staples_id, part_group);
+    assert!(design.group_attributes.contains_key(staples_id));
+    assert!(matches!(
+        staples[..],
+        [OrganizerTree::Leaf(DesignElementKey::Strand(2))]
+    ));
+    assert!(
+        children[1..]
+            .iter()
+            .any(|c| matches!(c, OrganizerTree::Leaf(DesignElementKey::Helix(4))))
+    );
+    let pivot = design.group_attributes[group_id].pivot.unwrap();
+    let expected = (instantiated.position_helix(0, 0) + instantiated.position_helix(1, 0)) / 2.;
+    assert!((pivot.position - expected).mag() < 1e-5);
+}
+
+fn strand_on(intervals: &[(usize, isize, isize, bool)]) -> Strand {
+    let domains: Vec<Domain> = intervals
+        .iter()
+        .map(|&(helix, start, end, forward)| {
+            Domain::HelixDomain(HelixInterval {
+                helix,
+                start,
+                end,
+                forward,
+                sequence: None,
+            })
+        })
+        .collect();
+    Strand {
+        junctions: read_junctions(&domains, false),
+        domains,
+        ..Default::default()
+    }
+}
+
+fn two_helix_design() -> Design {
+    let mut design = Design::new();
+    let mut helices = design.helices.make_mut();
+    for h_id in 0..2 {
+        helices.insert(h_id, Helix::new(Vec3::zero(), Rotor3::identity()));
+    }
+    drop(helices);
+    design.strands.insert(0, strand_on(&[(0, 0, 10, true)]));
+    design.strands.insert(1, strand_on(&[(1, 0, 10, false)]));
+    design
+}
+
+#[test]
+fn diff_reports_elements_domains_and_xovers() {
+    use crate::diff::{Change, DomainKey, diff};
+
+    let old = two_helix_design();
+    assert!(diff(&old, &old.clone()).is_empty());
+
+    let mut new = old.clone();
+    new.helices
+        .make_mut()
+        .push_helix(Helix::new(Vec3::zero(), Rotor3::identity()));
+    new.strands
+        .insert(0, strand_on(&[(0, 0, 10, true), (1, 10, 20, false)]));
+    new.strands.remove(&1);
+
+    let diff = diff(&old, &new);
+    assert!(diff.grids.is_empty());
+    assert_eq!(
+        diff.helices
+            .iter()
+            .map(|(id, c)| (*id, *c))
+            .collect::<Vec<_>>(),
+        [(2, Change::Added)]
+    );
+    assert_eq!(
+        diff.strands
+            .iter()
+            .map(|(id, c)| (*id, *c))
+            .collect::<Vec<_>>(),
+        [(0, Change::Modified), (1, Change::Removed)]
+    );
+    let added = DomainKey {
+        helix: 1,
+        start: 10,
+        end: 20,
+        forward: false,
+    };
+    assert_eq!(diff.added_domains, [(0, added)]);
+    assert!(diff.removed_domains.is_empty());
+    assert_eq!(
+        diff.added_xovers,
+        [(
+            Nucl {
+                helix: 0,
+                position: 9,
+                forward: true,
+            },
+            Nucl {
+                helix: 1,
+                position: 19,
+                forward: false,
+            }
+        )]
+    );
+    assert!(diff.removed_xovers.is_empty());
+
+    let report = diff.to_string();
+    assert!(report.contains("~ strand 0\n    + domain [H1: 10 <- 19]\n"));
+    assert!(report.contains("- strand 1\n"));
+
+    // The other fields of the design are compared as a whole.
+    let mut rainbow = old.clone();
+    rainbow.rainbow_scaffold = true;
+    let diff = diff::diff(&old, &rainbow);
+    assert!(!diff.is_empty());
+    assert_eq!(
+        diff.attributes.iter().collect::<Vec<_>>(),
+        ["rainbow_scaffold"]
+    );
+    assert_eq!(diff.to_string(), "~ rainbow_scaffold\n");
+}
+
+#[test]
+fn merge_applies_the_changes_of_both_sides() {
+    use crate::diff::merge::merge;
+
+    let base = two_helix_design();
+    let mut ours = base.clone();
+    ours.strands.insert(2, strand_on(&[(0, 0, 10, false)]));
+    let mut theirs = base.clone();
+    theirs.strands.insert(1, strand_on(&[(1, 0, 15, false)]));
+    theirs.strands.insert(2, strand_on(&[(1, 0, 10, true)]));
+
+    let merged = merge(&base, &ours, &theirs);
+    assert!(merged.conflicts.is_empty());
+    let strands = &merged.design.strands;
+    assert_eq!(strands.keys().count(), 4);
+    assert_eq!(
+        strands.get(&1).unwrap().formatted_domains(),
+        theirs.strands.get(&1).unwrap().formatted_domains()
+    );
+    assert_eq!(
+        strands.get(&2).unwrap().formatted_domains(),
+        ours.strands.get(&2).unwrap().formatted_domains()
+    );
+    // The strand added by theirs with an identifier also added by ours is given a new one.
+    assert_eq!(
+        strands.get(&3).unwrap().formatted_domains(),
+        theirs.strands.get(&2).unwrap().formatted_domains()
+    );
+}
+
+#[test]
+fn merge_reports_conflicting_changes() {
+    use crate::diff::merge::{MergeConflict, merge};
+
+    let base = two_helix_design();
+    let mut ours = base.clone();
+    ours.strands.insert(0, strand_on(&[(0, 0, 12, true)]));
+    let mut theirs = base.clone();
+    theirs.strands.insert(0, strand_on(&[(0, 0, 8, true)]));
+    theirs.strands.insert(5, strand_on(&[(1, 5, 8, false)]));
+
+    let merged = merge(&base, &ours, &theirs);
+    assert_eq!(
+        merged.conflicts,
+        [
+            MergeConflict::BothModified(DesignElementKey::Strand(0)),
+            MergeConflict::StrandOverlap {
+                strand: 5,
+                nucl: Nucl {
+                    helix: 1,
+                    position: 7,
+                    forward: false,
+                },
+            },
+        ]
+    );
+    assert_eq!(
+        merged.design.strands.get(&0).unwrap().formatted_domains(),
+        ours.strands.get(&0).unwrap().formatted_domains()
+    );
+    assert!(merged.design.strands.get(&5).is_none());
+}
+
+#[test]
+fn merge_takes_the_other_fields_of_theirs() {
+    use crate::{
+        bezier_plane::{BezierPath, BezierPathId},
+        diff::merge::{MergeConflict, merge},
+    };
+
+    let base = two_helix_design();
+    let mut ours = base.clone();
+    ours.locked_elements.insert(DesignElementKey::Strand(0));
+    ours.helix_parameters = Some(HelixParameters::ENSNANO_2021);
+    ours.bezier_paths.make_mut().push(BezierPath::default());
+    let mut theirs = base.clone();
+    theirs.rainbow_scaffold = true;
+    theirs.helix_parameters = Some(HelixParameters::GEARY_2014_RNA);
+    let mut paths = theirs.bezier_paths.make_mut();
+    paths.push(BezierPath::default());
+    let path_id = paths.push(BezierPath::default());
+    drop(paths);
+    let mut helix = Helix::new(Vec3::zero(), Rotor3::identity());
+    helix.path_id = Some(path_id);
+    theirs.helices.make_mut().insert(2, helix);
+
+    let merged = merge(&base, &ours, &theirs);
+    assert_eq!(
+        merged.conflicts,
+        [
+            MergeConflict::Attribute("bezier_paths".to_owned()),
+            MergeConflict::Attribute("dna_parameters".to_owned()),
+            MergeConflict::MissingBezierPath {
+                helix: 2,
+                path: BezierPathId(1),
+            },
+        ]
+    );
+    let design = merged.design;
+    assert!(design.rainbow_scaffold);
+    assert!(
+        design
+            .locked_elements
+            .contains(&DesignElementKey::Strand(0))
+    );
+    assert_eq!(
+        design.helix_parameters.map(|p| p.helix_radius),
+        Some(HelixParameters::ENSNANO_2021.helix_radius)
+    );
+    assert_eq!(design.bezier_paths.keys().count(), 1);
+    assert!(design.helices.get(&2).is_none());
+    assert_eq!(design.strands.keys().count(), 2);
+}
+
+#[test]
+fn lattice_grids_survive_serialization() {
+    use crate::grid::{GridDescriptor, GridTypeDescr, lattice::Lattice};
+
+    let mut design = Design::new();
+    let grid = GridDescriptor {
+        position: Vec3::zero(),
+        orientation: Rotor3::identity(),
+        helix_parameters: None,
+        grid_type: GridTypeDescr::Lattice {
+            lattice: Lattice::hybrid(),
+            twist: None,
+        },
+        invisible: false,
+        bezier_vertex: None,
+    };
+    let grid_id = design.free_grids.make_mut().push(grid.clone());
+    let instantiated = grid.to_grid(HelixParameters::GEARY_2014_DNA);
+    let h_id =
+        design
+            .helices
+            .make_mut()
+            .push_helix(Helix::new_on_grid(&instantiated, 3, -1, grid_id));
+
+    let json = serde_json::to_string(&design).unwrap();
+    let mut read: Design = serde_json::from_str(&json).unwrap();
+    let GridId::FreeGrid(g_id) = grid_id else {
+        unreachable!()
+    };
+    let Some(GridTypeDescr::Lattice { lattice, .. }) = read
+        .free_grids
+        .get(&FreeGridId(g_id))
+        .map(|g| g.grid_type.clone())
+    else {
+        panic!("The lattice was not read back")
+    };
+    assert_eq!(lattice, Lattice::hybrid());
+
+    let grid_data = read.get_updated_grid_data().clone();
+    let helix = read.helices.get(&h_id).unwrap();
+    assert!((helix.position - instantiated.position_helix(3, -1)).mag() < 1e-5);
+    let position = grid_data.get_helix_grid_position(h_id).unwrap();
+    assert_eq!((position.x, position.y), (3, -1));
+}
@@ -0,0 +1,485 @@
+//! Filling the volume of a mesh with helices packed on a lattice.
+//!
+//! The helices are placed on a new grid orthogonal to a chosen axis. The axis of the helix of each
+//! cell of the grid is intersected with the mesh, and a pair of strands is added on every interval
+//! of the axis that lies inside the mesh. Optionally, the strands on one side of the intervals are
+//! joined into a scaffold that visits the cells row by row. Each domain of the scaffold starts at
+//! the end of its interval closest to the end of the previous domain, and the scaffold is split
+//! where that gap is too long for a crossover, which happens in concave meshes.
+
+use crate::{
+    domains::{Domain, helix_interval::HelixInterval},
+    grid::{GridDescriptor, GridTypeDescr},
+    parameters::HelixParameters,
+    strands::{Strand, read_junctions},
+};
+use serde::{Deserialize, Serialize};
+use ultraviolet::{Rotor3, Vec3};
+
+/// Intersections of an helix axis with the mesh closer than this distance, in nanometers, are
+/// merged. This happens when the axis goes through an edge shared by two triangles.
+const INTERSECTION_EPSILON: f32 = 1e-4;
+
+/// Consecutive domains of the scaffold whose ends are further apart than this number of
+/// inter-helix distances are not joined by a crossover.
+const MAX_SCAFFOLD_JUMP: f32 = 2.;
+
+/// The direction of the helices filling the mesh.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum VoxelizationAxis {
+    #[default]
+    X,
+    Y,
+    Z,
+}
+
+impl VoxelizationAxis {
+    pub const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    fn direction(self) -> Vec3 {
+        match self {
+            Self::X => Vec3::unit_x(),
+            Self::Y => Vec3::unit_y(),
+            Self::Z => Vec3::unit_z(),
+        }
+    }
+
+    /// The orientation of a grid whose helices go along the axis.
+    fn grid_orientation(self) -> Rotor3 {
+        Rotor3::from_rotation_between(Vec3::unit_x(), self.direction())
+    }
+}
+
+impl std::fmt::Display for VoxelizationAxis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::X => write!(f, "X"),
+            Self::Y => write!(f, "Y"),
+            Self::Z => write!(f, "Z"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoxelizationParameters {
+    /// The lattice of the grid, either `GridTypeDescr::Square` or `GridTypeDescr::Honeycomb`.
+    pub grid_type: GridTypeDescr,
+    pub axis: VoxelizationAxis,
+    /// The number of nanometers per unit of the mesh. The mesh is scaled about the center of its
+    /// bounding box, so that it stays in place.
+    pub scale: f32,
+    /// Intervals shorter than this number of nucleotides are not filled.
+    pub min_length: usize,
+    /// Join the strands on one side of the helices into a scaffold.
+    pub route_scaffold: bool,
+}
+
+impl Default for VoxelizationParameters {
+    fn default() -> Self {
+        Self {
+            grid_type: GridTypeDescr::Honeycomb { twist: None },
+            axis: VoxelizationAxis::default(),
+            scale: 1.,
+            min_length: 8,
+            route_scaffold: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum VoxelizationError {
+    /// The mesh has no triangle.
+    EmptyMesh,
+    /// No interval of the lattice inside the mesh is long enough to be filled.
+    NothingInside,
+    BadParameters,
+}
+
+/// The grid, helices and strands filling the volume of a mesh.
+#[derive(Debug, Clone)]
+pub struct Voxelization {
+    pub grid: GridDescriptor,
+    /// The position on the grid of each helix. The helices are refered to by their index in this
+    /// vector in the domains of the strands.
+    pub helices: Vec<(isize, isize)>,
+    /// The longest part of the scaffold, if it is routed. Its other parts are in `strands`.
+    pub scaffold: Option<Strand>,
+    pub strands: Vec<Strand>,
+}
+
+/// Fill the volume of the mesh made of `triangles` with helices.
+pub fn voxelize(
+    triangles: &[[Vec3; 3]],
+    parameters: &VoxelizationParameters,
+    helix_parameters: &HelixParameters,
+) -> Result<Voxelization, VoxelizationError> {
+    let supported_lattice = matches!(
+        parameters.grid_type,
+        GridTypeDescr::Square { .. } | GridTypeDescr::Honeycomb { .. }
+    );
+    if !supported_lattice
+        || !parameters.scale.is_finite()
+        || parameters.scale <= 0.
+        || parameters.min_length == 0
+    {
+        return Err(VoxelizationError::BadParameters);
+    }
+    if triangles.is_empty() {
+        return Err(VoxelizationError::EmptyMesh);
+    }
+    let bounding_box = |triangles: &[[Vec3; 3]]| {
+        triangles.iter().flatten().fold(
+            (
+                Vec3::broadcast(f32::INFINITY),
+                Vec3::broadcast(f32::NEG_INFINITY),
+            ),
+            |(min, max), p| (min.min_by_component(*p), max.max_by_component(*p)),
+        )
+    };
+    let (min, max) = bounding_box(triangles);
+    let center = (min + max) / 2.;
+    let triangles: Vec<[Vec3; 3]> = triangles
+        .iter()
+        .map(|t| t.map(|p| center + (p - center) * parameters.scale))
+        .collect();
+    let (min, max) = bounding_box(&triangles);
+
+    // The grid goes through the center of the bounding box, shifted to its start along the axis
+    // so that the helices start at nucleotide 0.
+    let axis = parameters.axis.direction();
+    let grid = GridDescriptor {
+        position: center - axis * ((max - min).dot(axis) / 2.),
+        orientation: parameters.axis.grid_orientation(),
+        helix_parameters: None,
+        grid_type: parameters.grid_type.clone(),
+        invisible: false,
+        bezier_vertex: None,
+    };
+    let lattice = grid.to_grid(*helix_parameters);
+
+    let corners = (0..8).map(|i| {
+        Vec3::new(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        )
+    });
+    let cells: Vec<(isize, isize)> = corners
+        .filter_map(|corner| lattice.interpolate_helix(corner, axis))
+        .collect();
+    let x_range = cells.iter().map(|c| c.0).min().unwrap_or(0) - 1
+        ..=cells.iter().map(|c| c.0).max().unwrap_or(0) + 1;
+    let y_range = cells.iter().map(|c| c.1).min().unwrap_or(0) - 1
+        ..=cells.iter().map(|c| c.1).max().unwrap_or(0) + 1;
+
+    let rise = helix_parameters.rise;
+    // The helix of each cell with intervals inside the mesh, and these intervals, row by row.
+    let mut helices = Vec::new();
+    let mut rows = Vec::new();
+    for y in y_range {
+        let mut row = Vec::new();
+        for x in x_range.clone() {
+            let origin = lattice.position_helix(x, y);
+            let cell_segments: Vec<(isize, isize)> = inside_intervals(&triangles, origin, axis)
+                .into_iter()
+                .map(|(t0, t1)| {
+                    (
+                        (t0 / rise).ceil() as isize,
+                        (t1 / rise).floor() as isize + 1,
+                    )
+                })
+                .filter(|(start, end)| end - start >= parameters.min_length as isize)
+                .collect();
+            if !cell_segments.is_empty() {
+                row.push((helices.len(), cell_segments));
+                helices.push((x, y));
+            }
+        }
+        if !row.is_empty() {
+            rows.push(row);
+        }
+    }
+    if rows.is_empty() {
+        return Err(VoxelizationError::NothingInside);
+    }
+
+    // The intervals are visited row by row, every other row in the reverse order, so that the
+    // scaffold goes from one cell to a neighbouring one. The n-th intervals of all the cells are
+    // visited before the (n+1)-th ones, so that the parts of a concave mesh are filled one after
+    // the other.
+    let nb_layers = rows
+        .iter()
+        .flatten()
+        .map(|(_, segments)| segments.len())
+        .max()
+        .unwrap_or(0);
+    let mut visits = Vec::new();
+    let mut nb_rows = 0;
+    for layer in 0..nb_layers {
+        for row in &rows {
+            let mut row_visits: Vec<_> = row
+                .iter()
+                .filter_map(|(helix, segments)| Some((*helix, *segments.get(layer)?)))
+                .collect();
+            if row_visits.is_empty() {
+                continue;
+            }
+            if nb_rows % 2 == 1 {
+                row_visits.reverse();
+            }
+            nb_rows += 1;
+            visits.extend(row_visits);
+        }
+    }
+
+    let nucl_position = |helix: usize, position: isize| {
+        let (x, y) = helices[helix];
+        lattice.position_helix(x, y) + lattice.axis_helix() * (position as f32 * rise)
+    };
+    let max_jump =
+        MAX_SCAFFOLD_JUMP * (2. * helix_parameters.helix_radius + helix_parameters.inter_helix_gap);
+    let mut scaffold_parts: Vec<Vec<HelixInterval>> = Vec::new();
+    let mut strands = Vec::new();
+    let mut prime3 = None;
+    for (helix, (start, end)) in visits {
+        let interval = |forward| HelixInterval {
+            helix,
+            start,
+            end,
+            forward,
+            sequence: None,
+        };
+        // The scaffold starts this domain from the end closest to the end of the previous one.
+        let [first, last] = [start, end - 1].map(|n| nucl_position(helix, n));
+        let scaffold_forward =
+            prime3.is_none_or(|p: Vec3| (first - p).mag_sq() <= (last - p).mag_sq());
+        let (prime5, new_prime3) = if scaffold_forward {
+            (first, last)
+        } else {
+            (last, first)
+        };
+        if parameters.route_scaffold {
+            if prime3.is_none_or(|p: Vec3| (prime5 - p).mag() > max_jump) {
+                scaffold_parts.push(Vec::new());
+            }
+            if let Some(part) = scaffold_parts.last_mut() {
+                part.push(interval(scaffold_forward));
+            }
+        } else {
+            strands.push(make_strand(vec![interval(scaffold_forward)]));
+        }
+        prime3 = Some(new_prime3);
+        strands.push(make_strand(vec![interval(!scaffold_forward)]));
+    }
+
+    let mut scaffold_parts: Vec<Strand> = scaffold_parts.into_iter().map(make_strand).collect();
+    scaffold_parts.sort_by_key(|s| std::cmp::Reverse(s.length()));
+    let mut scaffold_parts = scaffold_parts.into_iter();
+    let scaffold = scaffold_parts.next();
+    strands.extend(scaffold_parts);
+
+    Ok(Voxelization {
+        grid,
+        helices,
+        scaffold,
+        strands,
+    })
+}
+
+fn make_strand(domains: Vec<HelixInterval>) -> Strand {
+    let domains: Vec<_> = domains.into_iter().map(Domain::HelixDomain).collect();
+    Strand {
+        junctions: read_junctions(&domains, false),
+        domains,
+        ..Default::default()
+    }
+}
+
+/// The intervals of abscissa `t` such that `origin + t * direction` is inside the mesh, assuming
+/// that the mesh is closed.
+fn inside_intervals(triangles: &[[Vec3; 3]], origin: Vec3, direction: Vec3) -> Vec<(f32, f32)> {
+    let mut intersections: Vec<f32> = triangles
+        .iter()
+        .filter_map(|t| line_triangle_intersection(origin, direction, t))
+        .collect();
+    intersections.sort_by(f32::total_cmp);
+    intersections.dedup_by(|t, u| (*t - *u).abs() < INTERSECTION_EPSILON);
+    intersections
+        .chunks_exact(2)
+        .filter_map(|pair| match pair {
+            [t0, t1] => Some((*t0, *t1)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The abscissa of the intersection between the line `origin + t * direction` and a triangle,
+/// computed with the Möller–Trumbore algorithm.
+fn line_triangle_intersection(origin: Vec3, direction: Vec3, triangle: &[Vec3; 3]) -> Option<f32> {
+    let [a, b, c] = *triangle;
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+    let s = origin - a;
+    let u = s.dot(p) / determinant;
+    let q = s.cross(edge1);
+    let v = direction.dot(q) / determinant;
+    // The intersections on the edges of the triangles are kept, and merged afterwards.
+    let tolerance = 1e-6;
+    (u >= -tolerance && v >= -tolerance && u + v <= 1. + tolerance)
+        .then(|| edge2.dot(q) / determinant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The triangles of an axis aligned box.
+    fn cuboid(size: Vec3) -> Vec<[Vec3; 3]> {
+        let v = |i: usize| {
+            Vec3::new(
+                (i & 1) as f32 * size.x,
+                ((i >> 1) & 1) as f32 * size.y,
+                ((i >> 2) & 1) as f32 * size.z,
+            )
+        };
+        [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ]
+        .iter()
+        .flat_map(|[a, b, c, d]| [[v(*a), v(*b), v(*c)], [v(*a), v(*c), v(*d)]])
+        .collect()
+    }
+
+    fn helix_interval(domain: &Domain) -> &HelixInterval {
+        match domain {
+            Domain::HelixDomain(interval) => interval,
+            Domain::Insertion { .. } => panic!("unexpected insertion"),
+        }
+    }
+
+    #[test]
+    fn box_is_filled_along_each_axis() {
+        let helix_parameters = HelixParameters::GEARY_2014_DNA;
+        let rise = helix_parameters.rise;
+        for axis in VoxelizationAxis::ALL {
+            let size = Vec3::broadcast(11.) + axis.direction() * 20.;
+            let parameters = VoxelizationParameters {
+                grid_type: GridTypeDescr::Square { twist: None },
+                axis,
+                route_scaffold: false,
+                ..Default::default()
+            };
+            let voxelization = voxelize(&cuboid(size), &parameters, &helix_parameters).unwrap();
+            // 2.65 nm between helices, the box is 11 nm wide.
+            assert_eq!(voxelization.helices.len(), 25, "{axis}");
+            assert_eq!(voxelization.strands.len(), 50);
+            assert!(voxelization.scaffold.is_none());
+            let length = (31. / rise).floor() as usize + 1;
+            for strand in &voxelization.strands {
+                assert_eq!(strand.length(), length);
+            }
+
+            let grid = voxelization.grid.to_grid(helix_parameters);
+            for (x, y) in &voxelization.helices {
+                let origin = grid.position_helix(*x, *y);
+                let last = origin + grid.axis_helix() * ((length - 1) as f32 * rise);
+                for p in [origin, last] {
+                    assert!(p.component_min() > -1e-3 && (size - p).component_min() > -1e-3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn concave_shapes_have_several_intervals_per_helix() {
+        let helix_parameters = HelixParameters::GEARY_2014_DNA;
+        // Two boxes one after the other along the helices.
+        let mut triangles = cuboid(Vec3::new(11., 11., 11.));
+        triangles.extend(
+            cuboid(Vec3::new(11., 11., 11.))
+                .into_iter()
+                .map(|t| t.map(|p| p + Vec3::new(20., 0., 0.))),
+        );
+        let parameters = VoxelizationParameters {
+            grid_type: GridTypeDescr::Square { twist: None },
+            ..Default::default()
+        };
+        let voxelization = voxelize(&triangles, &parameters, &helix_parameters).unwrap();
+        assert_eq!(voxelization.helices.len(), 25);
+
+        // The scaffold fills one box and is split to fill the other one.
+        let scaffold = voxelization.scaffold.unwrap();
+        assert_eq!(scaffold.domains.len(), 25);
+        assert_eq!(voxelization.strands.len(), 51);
+        let scaffold_parts: Vec<&Strand> = std::iter::once(&scaffold)
+            .chain(voxelization.strands.iter().filter(|s| s.domains.len() > 1))
+            .collect();
+        assert_eq!(scaffold_parts.len(), 2);
+        assert_eq!(scaffold_parts[1].domains.len(), 25);
+
+        // The nucleotides joined by each crossover of the scaffold are on neighbouring helices, at
+        // the same position along the helices.
+        let grid = voxelization.grid.to_grid(helix_parameters);
+        let nucl_position = |interval: &HelixInterval, prime3: bool| {
+            let (x, y) = voxelization.helices[interval.helix];
+            let position = if interval.forward == prime3 {
+                interval.end - 1
+            } else {
+                interval.start
+            };
+            grid.position_helix(x, y)
+                + grid.axis_helix() * (position as f32 * helix_parameters.rise)
+        };
+        let spacing = 2. * helix_parameters.helix_radius + helix_parameters.inter_helix_gap;
+        for part in scaffold_parts {
+            for (d1, d2) in part.domains.iter().zip(part.domains.iter().skip(1)) {
+                let source = nucl_position(helix_interval(d1), true);
+                let target = nucl_position(helix_interval(d2), false);
+                assert!(
+                    (source - target).mag() < spacing + 1e-3,
+                    "{source:?} {target:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_square_and_honeycomb_lattices_are_supported() {
+        let helix_parameters = HelixParameters::GEARY_2014_DNA;
+        let mesh = cuboid(Vec3::new(30., 20., 20.));
+        let count = |grid_type| {
+            let parameters = VoxelizationParameters {
+                grid_type,
+                ..Default::default()
+            };
+            voxelize(&mesh, &parameters, &helix_parameters)
+                .unwrap()
+                .helices
+                .len()
+        };
+        let square = count(GridTypeDescr::Square { twist: None });
+        let honeycomb = count(GridTypeDescr::Honeycomb { twist: None });
+        assert!(square > 0 && honeycomb > 0);
+        assert!(matches!(
+            voxelize(
+                &mesh,
+                &VoxelizationParameters {
+                    grid_type: GridTypeDescr::RotatedHoneycomb { twist: None },
+                    ..Default::default()
+                },
+                &helix_parameters
+            ),
+            Err(VoxelizationError::BadParameters)
+        ));
+    }
+}
@@ -0,0 +1,763 @@
+//! Generation of wireframe DNA origami from polyhedral meshes.
+//!
+//! Each edge of the mesh becomes one or two helices. The scaffold is routed along a spanning tree
+//! of the edges of the mesh:
+//!
+//! * With two helices per edge, as in DAEDALUS, the scaffold goes around the spanning tree, along
+//!   one helix of each of its edges and back along the other. The edges that are not in the tree
+//!   are visited from both of their extremities, with a scaffold crossover in their middle.
+//! * With one helix per edge, as in vHelix, the scaffold follows an eulerian circuit of the mesh.
+//!   The edges of the spanning tree that are needed to make all the degrees even are doubled, and
+//!   realised with two helices.
+//!
+//! Each junction of the scaffold is covered by a staple made of the complements of the
+//! extremities of the two joined domains. The rest of the domains is covered by straight staples.
+
+use crate::{
+    domains::{Domain, helix_interval::HelixInterval},
+    helices::Helix,
+    parameters::HelixParameters,
+    strands::{Strand, read_junctions},
+};
+use ahash::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, f32::consts::PI};
+use ultraviolet::{Rotor3, Vec3};
+
+/// The maximum length of the part of a domain used by the staple covering a scaffold junction.
+const STAPLE_ARM_MAX_LENGTH: isize = 21;
+
+/// The maximum length of the straight staples covering the middle of long domains.
+const STRAIGHT_STAPLE_MAX_LENGTH: isize = 42;
+
+/// The minimum accepted value of `WireframeParameters::min_edge_length`.
+pub const MIN_EDGE_LENGTH: usize = 10;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WireframeParameters {
+    /// Realise each edge with two helices instead of one.
+    pub two_helices_per_edge: bool,
+    /// The number of nucleotides on the helices of the shortest edge. The mesh is scaled
+    /// accordingly, about the centroid of its vertices.
+    pub min_edge_length: usize,
+}
+
+impl Default for WireframeParameters {
+    fn default() -> Self {
+        Self {
+            two_helices_per_edge: true,
+            min_edge_length: 42,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WireframeError {
+    /// The mesh has no edge.
+    EmptyMesh,
+    /// The edges of the mesh do not form a connected graph, so a single scaffold cannot visit
+    /// them all.
+    NotConnected,
+    BadParameters,
+}
+
+/// The helices and strands of a wireframe design.
+#[derive(Debug, Clone)]
+pub struct Wireframe {
+    /// The helices, refered to by their index in this vector in the domains of the strands.
+    pub helices: Vec<Helix>,
+    pub scaffold: Strand,
+    pub staples: Vec<Strand>,
+}
+
+/// Make a wireframe design from the triangles of a mesh.
+pub fn make_wireframe(
+    triangles: &[[Vec3; 3]],
+    parameters: &WireframeParameters,
+    helix_parameters: &HelixParameters,
+) -> Result<Wireframe, WireframeError> {
+    if parameters.min_edge_length < MIN_EDGE_LENGTH {
+        return Err(WireframeError::BadParameters);
+    }
+    let graph = MeshGraph::new(triangles);
+    if graph.edges.is_empty() {
+        return Err(WireframeError::EmptyMesh);
+    }
+    let tree = graph.spanning_tree().ok_or(WireframeError::NotConnected)?;
+
+    let mut builder = WireframeBuilder::new(&graph, parameters, helix_parameters);
+    let scaffold = if parameters.two_helices_per_edge {
+        builder.route_around_tree(&tree)
+    } else {
+        builder.route_eulerian_circuit(&tree)
+    };
+    let staples = staples(&scaffold);
+
+    Ok(Wireframe {
+        helices: builder.helices,
+        scaffold: make_strand(scaffold, true),
+        staples: staples.into_iter().map(|s| make_strand(s, false)).collect(),
+    })
+}
+
+fn make_strand(domains: Vec<HelixInterval>, is_cyclic: bool) -> Strand {
+    let domains: Vec<_> = domains.into_iter().map(Domain::HelixDomain).collect();
+    Strand {
+        junctions: read_junctions(&domains, is_cyclic),
+        domains,
+        is_cyclic,
+        ..Default::default()
+    }
+}
+
+/// The graph formed by the vertices and edges of a mesh.
+struct MeshGraph {
+    vertices: Vec<Vec3>,
+    /// The extremities of the edges, the smallest one first.
+    edges: Vec<(usize, usize)>,
+    /// The average normal of the faces adjacent to each edge.
+    edge_normals: Vec<Vec3>,
+    /// The edges incident to each vertex, sorted counterclockwise around the vertex normal.
+    incident_edges: Vec<Vec<usize>>,
+}
+
+impl MeshGraph {
+    fn new(triangles: &[[Vec3; 3]]) -> Self {
+        let (min, max) = triangles.iter().flatten().fold(
+            (
+                Vec3::broadcast(f32::INFINITY),
+                Vec3::broadcast(f32::NEG_INFINITY),
+            ),
+            |(min, max), p| (min.min_by_component(*p), max.max_by_component(*p)),
+        );
+        // Vertices closer than this distance are merged.
+        let epsilon = ((max - min).mag() * 1e-5).max(f32::MIN_POSITIVE);
+
+        let mut vertices = Vec::new();
+        let mut vertex_ids = HashMap::default();
+        let mut vertex_id = |p: Vec3| {
+            let key = [p.x, p.y, p.z].map(|x| (x / epsilon).round() as i64);
+            *vertex_ids.entry(key).or_insert_with(|| {
+                vertices.push(p);
+                vertices.len() - 1
+            })
+        };
+
+        let mut edges = Vec::new();
+        let mut edge_ids = HashMap::default();
+        let mut edge_normals: Vec<Vec3> = Vec::new();
+        let mut faces = Vec::new();
+        for triangle in triangles {
+            let ids = triangle.map(&mut vertex_id);
+            if ids[0] == ids[1] || ids[1] == ids[2] || ids[2] == ids[0] {
+                continue;
+            }
+            let normal = (triangle[1] - triangle[0])
+                .cross(triangle[2] - triangle[0])
+                .normalized();
+            faces.push((ids, normal));
+            for (a, b) in [(ids[0], ids[1]), (ids[1], ids[2]), (ids[2], ids[0])] {
+                let key = (a.min(b), a.max(b));
+                let id = *edge_ids.entry(key).or_insert_with(|| {
+                    edges.push(key);
+                    edge_normals.push(Vec3::zero());
+                    edges.len() - 1
+                });
+                if normal.mag_sq() > 0. {
+                    edge_normals[id] += normal;
+                }
+            }
+        }
+
+        let centroid =
+            vertices.iter().fold(Vec3::zero(), |acc, p| acc + *p) / (vertices.len().max(1) as f32);
+        let mut vertex_normals = vec![Vec3::zero(); vertices.len()];
+        for (ids, normal) in &faces {
+            if normal.mag_sq() > 0. {
+                for id in ids {
+                    vertex_normals[*id] += *normal;
+                }
+            }
+        }
+        for (normal, p) in vertex_normals.iter_mut().zip(vertices.iter()) {
+            *normal = non_zero_or(*normal, *p - centroid).normalized();
+        }
+
+        let mut incident_edges = vec![Vec::new(); vertices.len()];
+        for (id, (a, b)) in edges.iter().enumerate() {
+            incident_edges[*a].push(id);
+            incident_edges[*b].push(id);
+        }
+        for (v, incident) in incident_edges.iter_mut().enumerate() {
+            let normal = vertex_normals[v];
+            let e1 = normal.cross(any_orthogonal(normal)).normalized();
+            let e2 = normal.cross(e1);
+            let angle = |e: &usize| {
+                let (a, b) = edges[*e];
+                let other = if a == v { b } else { a };
+                let d = vertices[other] - vertices[v];
+                d.dot(e2).atan2(d.dot(e1))
+            };
+            incident.sort_by(|e, f| angle(e).total_cmp(&angle(f)));
+        }
+
+        let edge_normals = edge_normals
+            .into_iter()
+            .zip(edges.iter())
+            .map(|(normal, (a, b))| {
+                let middle = (vertices[*a] + vertices[*b]) / 2.;
+                non_zero_or(normal, middle - centroid).normalized()
+            })
+            .collect();
+
+        Self {
+            vertices,
+            edges,
+            edge_normals,
+            incident_edges,
+        }
+    }
+
+    fn other_end(&self, edge: usize, vertex: usize) -> usize {
+        let (a, b) = self.edges[edge];
+        if a == vertex { b } else { a }
+    }
+
+    /// A breadth first spanning tree of the graph, or `None` if the graph is not connected.
+    fn spanning_tree(&self) -> Option<SpanningTree> {
+        let root = self.edges[0].0;
+        let mut parent_edge = vec![None; self.vertices.len()];
+        let mut visited = vec![false; self.vertices.len()];
+        let mut order = vec![root];
+        let mut queue = VecDeque::from([root]);
+        visited[root] = true;
+        while let Some(v) = queue.pop_front() {
+            for e in &self.incident_edges[v] {
+                let w = self.other_end(*e, v);
+                if !visited[w] {
+                    visited[w] = true;
+                    parent_edge[w] = Some(*e);
+                    order.push(w);
+                    queue.push_back(w);
+                }
+            }
+        }
+        // Isolated vertices, that only belong to degenerate triangles, are ignored.
+        let all_visited =
+            (0..self.vertices.len()).all(|v| visited[v] || self.incident_edges[v].is_empty());
+        all_visited.then(|| {
+            let mut in_tree = vec![false; self.edges.len()];
+            for e in parent_edge.iter().flatten() {
+                in_tree[*e] = true;
+            }
+            SpanningTree {
+                root,
+                parent_edge,
+                order,
+                in_tree,
+            }
+        })
+    }
+}
+
+struct SpanningTree {
+    root: usize,
+    /// The edge between each vertex and its parent.
+    parent_edge: Vec<Option<usize>>,
+    /// The vertices, each one after its parent.
+    order: Vec<usize>,
+    in_tree: Vec<bool>,
+}
+
+/// Builds the helices of the edges and the route of the scaffold on them.
+struct WireframeBuilder<'a> {
+    graph: &'a MeshGraph,
+    helix_parameters: &'a HelixParameters,
+    /// The number of nanometers per unit of the mesh.
+    scale: f32,
+    /// The centroid of the vertices of the mesh, which the scaling leaves in place.
+    center: Vec3,
+    /// The number of nucleotides of the helices of each edge.
+    edge_lengths: Vec<isize>,
+    helices: Vec<Helix>,
+}
+
+/// The side of an edge `(a, b)` on which an helix lies, when looking at the mesh from the outside
+/// with `a` below `b`.
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Center,
+    Right,
+}
+
+impl<'a> WireframeBuilder<'a> {
+    fn new(
+        graph: &'a MeshGraph,
+        parameters: &WireframeParameters,
+        helix_parameters: &'a HelixParameters,
+    ) -> Self {
+        let rise = helix_parameters.rise;
+        // The helices stop before the vertices to leave room to the helices of the other edges.
+        let vertex_gap = helix_parameters.helix_radius + helix_parameters.inter_helix_gap;
+        let mesh_lengths: Vec<f32> = graph
+            .edges
+            .iter()
+            .map(|(a, b)| (graph.vertices[*b] - graph.vertices[*a]).mag())
+            .collect();
+        let min_length = mesh_lengths
+            .iter()
+            .copied()
+            .filter(|l| *l > 0.)
+            .fold(f32::INFINITY, f32::min);
+        let scale = if min_length.is_finite() {
+            (parameters.min_edge_length as f32 * rise + 2. * vertex_gap) / min_length
+        } else {
+            1.
+        };
+        let edge_lengths = mesh_lengths
+            .iter()
+            .map(|l| {
+                (((l * scale - 2. * vertex_gap) / rise).round() as isize)
+                    .max(parameters.min_edge_length as isize)
+            })
+            .collect();
+        let center = if graph.vertices.is_empty() {
+            Vec3::zero()
+        } else {
+            graph.vertices.iter().copied().sum::<Vec3>() / graph.vertices.len() as f32
+        };
+        Self {
+            graph,
+            helix_parameters,
+            scale,
+            center,
+            edge_lengths,
+            helices: Vec::new(),
+        }
+    }
+
+    /// Add an helix on the given side of `edge` and return its identifier.
+    fn add_helix(&mut self, edge: usize, side: Side) -> usize {
+        let (a, b) = self.graph.edges[edge];
+        let [start, end] =
+            [a, b].map(|v| self.center + (self.graph.vertices[v] - self.center) * self.scale);
+        let direction = (end - start).normalized();
+        let left = self.graph.edge_normals[edge].cross(direction);
+        let left = non_zero_or(left, any_orthogonal(direction)).normalized();
+        let offset =
+            self.helix_parameters.helix_radius + self.helix_parameters.inter_helix_gap / 2.;
+        let offset = match side {
+            Side::Left => left * offset,
+            Side::Center => Vec3::zero(),
+            Side::Right => -left * offset,
+        };
+        let length = (self.edge_lengths[edge] - 1) as f32 * self.helix_parameters.rise;
+        let origin = (start + end) / 2. + offset - direction * (length / 2.);
+        self.helices
+            .push(Helix::new(origin, rotation_to(direction)));
+        self.helices.len() - 1
+    }
+
+    /// Route the scaffold around the spanning tree, with two helices per edge.
+    fn route_around_tree(&mut self, tree: &SpanningTree) -> Vec<HelixInterval> {
+        let graph = self.graph;
+        // The helices on the right of (a, b) and on the right of (b, a).
+        let helices: Vec<(usize, usize)> = (0..graph.edges.len())
+            .map(|e| {
+                (
+                    self.add_helix(e, Side::Right),
+                    self.add_helix(e, Side::Left),
+                )
+            })
+            .collect();
+        // The domain of the scaffold along the right side of `edge` from `vertex` on the given
+        // part of the edge.
+        let along = |edge: usize, vertex: usize, range: (isize, isize)| {
+            let forward = graph.edges[edge].0 == vertex;
+            HelixInterval {
+                helix: if forward {
+                    helices[edge].0
+                } else {
+                    helices[edge].1
+                },
+                start: range.0,
+                end: range.1,
+                forward,
+                sequence: None,
+            }
+        };
+
+        let mut scaffold = Vec::new();
+        let first_edge = graph.incident_edges[tree.root]
+            .iter()
+            .copied()
+            .find(|e| tree.in_tree[*e])
+            .unwrap_or(0);
+        let (mut edge, mut vertex) = (first_edge, tree.root);
+        loop {
+            let length = self.edge_lengths[edge];
+            scaffold.push(along(edge, vertex, (0, length)));
+            vertex = graph.other_end(edge, vertex);
+            // Turn counterclockwise around the vertex until the next edge of the tree, going to
+            // the middle of the other edges and back.
+            let incident = &graph.incident_edges[vertex];
+            let position = incident.iter().position(|e| *e == edge).unwrap_or(0);
+            for i in 1..=incident.len() {
+                let next = incident[(position + i) % incident.len()];
+                if tree.in_tree[next] {
+                    edge = next;
+                    break;
+                }
+                let length = self.edge_lengths[next];
+                let middle = length / 2;
+                let (near, far) = if graph.edges[next].0 == vertex {
+                    ((0, middle), (0, middle))
+                } else {
+                    ((middle, length), (middle, length))
+                };
+                let other = graph.other_end(next, vertex);
+                scaffold.push(along(next, vertex, near));
+                scaffold.push(along(next, other, far));
+            }
+            if edge == first_edge && vertex == tree.root {
+                break;
+            }
+        }
+        scaffold
+    }
+
+    /// Route the scaffold along an eulerian circuit of the mesh in which the edges of the tree
+    /// needed to make every degree even are doubled. There is one helix per traversal of an edge.
+    fn route_eulerian_circuit(&mut self, tree: &SpanningTree) -> Vec<HelixInterval> {
+        let graph = self.graph;
+        let mut doubled = vec![false; graph.edges.len()];
+        let mut odd: Vec<bool> = graph
+            .incident_edges
+            .iter()
+            .map(|edges| edges.len() % 2 == 1)
+            .collect();
+        for v in tree.order.iter().rev() {
+            if let Some(e) = tree.parent_edge[*v].filter(|_| odd[*v]) {
+                doubled[e] = true;
+                let parent = graph.other_end(e, *v);
+                odd[parent] = !odd[parent];
+            }
+        }
+
+        // Each traversal of an edge, with its helix.
+        let mut traversals = Vec::new();
+        for (e, doubled) in doubled.iter().enumerate() {
+            if *doubled {
+                traversals.push((e, self.add_helix(e, Side::Right)));
+                traversals.push((e, self.add_helix(e, Side::Left)));
+            } else {
+                traversals.push((e, self.add_helix(e, Side::Center)));
+            }
+        }
+        let mut incident_traversals = vec![Vec::new(); graph.vertices.len()];
+        for (t, (e, _)) in traversals.iter().enumerate() {
+            let (a, b) = graph.edges[*e];
+            incident_traversals[a].push(t);
+            incident_traversals[b].push(t);
+        }
+
+        // Hierholzer's algorithm.
+        let mut used = vec![false; traversals.len()];
+        let mut next_incident = vec![0; graph.vertices.len()];
+        let mut stack = vec![(tree.root, None)];
+        let mut circuit = Vec::new();
+        while let Some(&(v, _)) = stack.last() {
+            let incident = &incident_traversals[v];
+            while next_incident[v] < incident.len() && used[incident[next_incident[v]]] {
+                next_incident[v] += 1;
+            }
+            if let Some(t) = incident.get(next_incident[v]).copied() {
+                used[t] = true;
+                stack.push((graph.other_end(traversals[t].0, v), Some(t)));
+            } else if let Some((v, Some(t))) = stack.pop() {
+                circuit.push((t, v));
+            }
+        }
+        circuit.reverse();
+
+        circuit
+            .into_iter()
+            .map(|(t, end)| {
+                let (edge, helix) = traversals[t];
+                HelixInterval {
+                    helix,
+                    start: 0,
+                    end: self.edge_lengths[edge],
+                    forward: graph.edges[edge].1 == end,
+                    sequence: None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The staples complementary to the cyclic `scaffold`.
+fn staples(scaffold: &[HelixInterval]) -> Vec<Vec<HelixInterval>> {
+    // The parts of each domain covered by the staples of its 5' and 3' junctions.
+    let arms: Vec<(isize, isize)> = scaffold
+        .iter()
+        .map(|d| {
+            let length = d.end - d.start;
+            (
+                (length / 2).min(STAPLE_ARM_MAX_LENGTH),
+                (length - length / 2).min(STAPLE_ARM_MAX_LENGTH),
+            )
+        })
+        .collect();
+    // The part of `domain` of length `length` on its 5' side if `prime5`, complemented.
+    let complement = |domain: &HelixInterval, length: isize, prime5: bool| {
+        let (start, end) = if prime5 == domain.forward {
+            (domain.start, domain.start + length)
+        } else {
+            (domain.end - length, domain.end)
+        };
+        HelixInterval {
+            start,
+            end,
+            forward: !domain.forward,
+            ..domain.clone()
+        }
+    };
+
+    let mut ret = Vec::new();
+    for (i, domain) in scaffold.iter().enumerate() {
+        let previous = (i + scaffold.len() - 1) % scaffold.len();
+        ret.push(vec![
+            complement(domain, arms[i].0, true),
+            complement(&scaffold[previous], arms[previous].1, false),
+        ]);
+
+        // The middle of the domain is covered by straight staples.
+        let (start, end) = if domain.forward {
+            (domain.start + arms[i].0, domain.end - arms[i].1)
+        } else {
+            (domain.start + arms[i].1, domain.end - arms[i].0)
+        };
+        let nb_staples =
+            (end - start + STRAIGHT_STAPLE_MAX_LENGTH - 1) / STRAIGHT_STAPLE_MAX_LENGTH;
+        for k in 0..nb_staples {
+            ret.push(vec![HelixInterval {
+                start: start + k * (end - start) / nb_staples,
+                end: start + (k + 1) * (end - start) / nb_staples,
+                forward: !domain.forward,
+                ..domain.clone()
+            }]);
+        }
+    }
+    ret
+}
+
+/// `v` if it is not zero, `fallback` otherwise.
+fn non_zero_or(v: Vec3, fallback: Vec3) -> Vec3 {
+    if v.mag_sq() > 1e-12 { v } else { fallback }
+}
+
+/// A unit vector orthogonal to `v`.
+fn any_orthogonal(v: Vec3) -> Vec3 {
+    let axis = if v.x.abs() < 0.9 {
+        Vec3::unit_x()
+    } else {
+        Vec3::unit_y()
+    };
+    non_zero_or(v.cross(axis), Vec3::unit_z()).normalized()
+}
+
+/// The orientation of an helix whose axis has the given direction.
+fn rotation_to(direction: Vec3) -> Rotor3 {
+    if direction.dot(Vec3::unit_x()) < -0.999 {
+        Rotor3::from_rotation_xy(PI)
+    } else {
+        Rotor3::from_rotation_between(Vec3::unit_x(), direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nucl::Nucl;
+    use ahash::HashSet;
+
+    fn tetrahedron() -> Vec<[Vec3; 3]> {
+        let v = [
+            Vec3::new(1., 1., 1.),
+            Vec3::new(1., -1., -1.),
+            Vec3::new(-1., 1., -1.),
+            Vec3::new(-1., -1., 1.),
+        ];
+        vec![
+            [v[0], v[1], v[2]],
+            [v[0], v[3], v[1]],
+            [v[0], v[2], v[3]],
+            [v[1], v[3], v[2]],
+        ]
+    }
+
+    /// A cube whose faces are split in two triangles.
+    fn cube() -> Vec<[Vec3; 3]> {
+        let v = |i: usize| {
+            Vec3::new(
+                (i & 1) as f32 * 2. - 1.,
+                ((i >> 1) & 1) as f32 * 2. - 1.,
+                ((i >> 2) & 1) as f32 * 2. - 1.,
+            )
+        };
+        let quads = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+        quads
+            .iter()
+            .flat_map(|[a, b, c, d]| [[v(*a), v(*b), v(*c)], [v(*a), v(*c), v(*d)]])
+            .collect()
+    }
+
+    fn nucls(domains: &[Domain]) -> Vec<Nucl> {
+        domains
+            .iter()
+            .flat_map(|d| match d {
+                Domain::HelixDomain(d) => {
+                    let nucl = |position| Nucl {
+                        helix: d.helix,
+                        position,
+                        forward: d.forward,
+                    };
+                    if d.forward {
+                        (d.start..d.end).map(nucl).collect()
+                    } else {
+                        (d.start..d.end).rev().map(nucl).collect()
+                    }
+                }
+                Domain::Insertion { .. } => vec![],
+            })
+            .collect()
+    }
+
+    /// Check that the scaffold and the staples cover every nucleotide of the helices once, and
+    /// that all the crossovers are short.
+    fn assert_good_wireframe(wireframe: &Wireframe, parameters: &WireframeParameters) {
+        let helix_parameters = HelixParameters::GEARY_2014_DNA;
+        assert!(wireframe.scaffold.is_cyclic);
+        let scaffold = nucls(&wireframe.scaffold.domains);
+        let scaffold_set: HashSet<_> = scaffold.iter().copied().collect();
+        assert_eq!(scaffold.len(), scaffold_set.len());
+
+        let staples: Vec<_> = wireframe
+            .staples
+            .iter()
+            .flat_map(|s| nucls(&s.domains))
+            .collect();
+        let staple_set: HashSet<_> = staples.iter().copied().collect();
+        assert_eq!(staples.len(), staple_set.len());
+        assert_eq!(
+            scaffold_set,
+            staple_set.iter().map(Nucl::compl).collect::<HashSet<_>>()
+        );
+        for (h, _) in wireframe.helices.iter().enumerate() {
+            let length = scaffold.iter().filter(|n| n.helix == h).count();
+            assert!(length >= parameters.min_edge_length / 2, "{length}");
+        }
+
+        for strand in std::iter::once(&wireframe.scaffold).chain(wireframe.staples.iter()) {
+            assert_eq!(strand.junctions.len(), strand.domains.len());
+            let nucls = nucls(&strand.domains);
+            let nb_bonds = if strand.is_cyclic {
+                nucls.len()
+            } else {
+                nucls.len() - 1
+            };
+            for i in 0..nb_bonds {
+                let (n1, n2) = (nucls[i], nucls[(i + 1) % nucls.len()]);
+                let position = |n: Nucl| {
+                    wireframe.helices[n.helix].axis_position(
+                        &helix_parameters,
+                        n.position,
+                        n.forward,
+                    )
+                };
+                let distance = (position(n1) - position(n2)).mag();
+                assert!(distance < 7., "{n1:?} {n2:?} {distance}");
+            }
+        }
+    }
+
+    #[test]
+    fn two_helices_per_edge() {
+        let parameters = WireframeParameters::default();
+        for mesh in [tetrahedron(), cube()] {
+            let wireframe =
+                make_wireframe(&mesh, &parameters, &HelixParameters::GEARY_2014_DNA).unwrap();
+            let nb_edges = if mesh.len() == 4 { 6 } else { 18 };
+            assert_eq!(wireframe.helices.len(), 2 * nb_edges);
+            assert_good_wireframe(&wireframe, &parameters);
+        }
+    }
+
+    #[test]
+    fn one_helix_per_edge() {
+        let parameters = WireframeParameters {
+            two_helices_per_edge: false,
+            ..Default::default()
+        };
+        let wireframe = make_wireframe(
+            &tetrahedron(),
+            &parameters,
+            &HelixParameters::GEARY_2014_DNA,
+        )
+        .unwrap();
+        // The four vertices have an odd degree and the spanning tree is a star, the three edges
+        // of the tree are doubled.
+        assert_eq!(wireframe.helices.len(), 9);
+        assert_good_wireframe(&wireframe, &parameters);
+
+        let wireframe =
+            make_wireframe(&cube(), &parameters, &HelixParameters::GEARY_2014_DNA).unwrap();
+        assert_good_wireframe(&wireframe, &parameters);
+    }
+
+    #[test]
+    fn shortest_edge_has_the_requested_length() {
+        let parameters = WireframeParameters {
+            min_edge_length: 31,
+            ..Default::default()
+        };
+        let mut mesh = tetrahedron();
+        // Move one vertex away to make longer edges.
+        for triangle in &mut mesh {
+            for v in triangle.iter_mut().filter(|v| v.x > 0. && v.y > 0.) {
+                *v *= 2.;
+            }
+        }
+        let wireframe =
+            make_wireframe(&mesh, &parameters, &HelixParameters::GEARY_2014_DNA).unwrap();
+        let scaffold = nucls(&wireframe.scaffold.domains);
+        let lengths: Vec<_> = (0..wireframe.helices.len())
+            .map(|h| scaffold.iter().filter(|n| n.helix == h).count())
+            .collect();
+        assert_eq!(lengths.iter().min(), Some(&31));
+        assert!(lengths.iter().any(|l| *l > 31));
+    }
+
+    #[test]
+    fn disconnected_meshes_are_rejected() {
+        let mut mesh = tetrahedron();
+        mesh.extend(
+            tetrahedron()
+                .into_iter()
+                .map(|t| t.map(|v| v + Vec3::broadcast(10.))),
+        );
+        assert!(matches!(
+            make_wireframe(&mesh, &Default::default(), &HelixParameters::GEARY_2014_DNA),
+            Err(WireframeError::NotConnected)
+        ));
+    }
+}
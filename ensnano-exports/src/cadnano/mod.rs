@@ -10,7 +10,7 @@ use ensnano_design::{
     nucl::Nucl,
 };
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 pub fn cadnano_export(design: &Design) -> Result<String, CadnanoError> {
     let mut exporter = init_cadnano_exporter(design)?;
@@ -69,7 +69,7 @@ fn get_grid_type(grids: &GridData) -> Result<GridType, CadnanoError> {
     let mut ret: Option<GridType> = None;
 
     for g in grids.source_free_grids.values() {
-        match g.grid_type {
+        match &g.grid_type {
             GridTypeDescr::RotatedHoneycomb { .. } => {
                 return Err(CadnanoError::NonHomogeneousGridTypes);
             }
@@ -85,8 +85,8 @@ fn get_grid_type(grids: &GridData) -> Result<GridType, CadnanoError> {
                 }
                 ret = Some(GridType::HoneyComb);
             }
-            t @ GridTypeDescr::Hyperboloid { .. } => {
-                return Err(CadnanoError::UnhandledGridType(t));
+            t @ (GridTypeDescr::Hyperboloid { .. } | GridTypeDescr::Lattice { .. }) => {
+                return Err(CadnanoError::UnhandledGridType(t.clone()));
             }
         }
     }
@@ -144,7 +144,10 @@ fn init_cadnano_exporter(design: &Design) -> Result<CadnanoExporter, CadnanoErro
                 ret
             };
 
-            let cadnano_helix = CadnanoHelix::new(num, candidate, bonds.max_nt_pos);
+            let mut cadnano_helix = CadnanoHelix::new(num, candidate, bonds.max_nt_pos);
+            if let Some(helix) = design.helices.get(h) {
+                cadnano_helix.set_skips(&helix.deletions, bonds.shift);
+            }
             cadnano_helices.insert(*h, cadnano_helix);
         }
         shift_x += max_x + 1;
@@ -353,6 +356,18 @@ impl CadnanoHelix {
             stap_loop: vec![],
         }
     }
+
+    /// Mark the deleted positions of the helix as skips.
+    fn set_skips(&mut self, deletions: &BTreeSet<isize>, shift: isize) {
+        for d in deletions {
+            if let Some(skip) = usize::try_from(d - shift)
+                .ok()
+                .and_then(|i| self.skip.get_mut(i))
+            {
+                *skip = -1;
+            }
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -3,6 +3,7 @@
 pub mod cadnano;
 pub mod oxdna;
 pub mod pdb;
+pub mod scadnano;
 
 use ahash::HashMap;
 use cadnano::CadnanoError;
@@ -10,13 +11,16 @@ use ensnano_design::{Design, helices::NuclCollection, nucl::Nucl};
 use ensnano_utils::export::ExportType;
 use pdb::PdbError;
 use rand::seq::IndexedRandom as _;
+use scadnano::ScadnanoError;
 use std::{io::Write as _, path::PathBuf};
+use ultraviolet::Vec3;
 
 /// A value returned by the export functions when exports was successful.
 ///
 /// This means that both the format conversion and the write to the output file were successful.
 pub enum ExportSuccess {
     Cadnano(PathBuf),
+    Scadnano(PathBuf),
     Pdb(PathBuf),
     Oxdna {
         topology: PathBuf,
@@ -31,7 +35,7 @@ impl ExportSuccess {
     /// the export was made.
     pub fn message(&self) -> String {
         match self {
-            Self::Cadnano(p) | Self::Pdb(p) => {
+            Self::Cadnano(p) | Self::Scadnano(p) | Self::Pdb(p) => {
                 format!("{SUCCESSFUL_EXPORT_MSG_PREFIX}\n{}", p.to_string_lossy())
             }
             Self::Oxdna {
@@ -49,8 +53,11 @@ impl ExportSuccess {
 #[derive(Debug)]
 pub enum ExportError {
     CadnanoConversion(CadnanoError),
+    ScadnanoConversion(ScadnanoError),
     PdbConversion(PdbError),
     IOError(std::io::Error),
+    /// The format cannot hold the positions of the nucleotides.
+    NoTrajectoryFormat(ExportType),
 }
 
 impl From<CadnanoError> for ExportError {
@@ -58,6 +65,11 @@ impl From<CadnanoError> for ExportError {
         Self::CadnanoConversion(e)
     }
 }
+impl From<ScadnanoError> for ExportError {
+    fn from(e: ScadnanoError) -> Self {
+        Self::ScadnanoConversion(e)
+    }
+}
 impl From<PdbError> for ExportError {
     fn from(e: PdbError) -> Self {
         Self::PdbConversion(e)
@@ -188,6 +200,56 @@ pub fn export(
             writeln!(&mut out_file, "{cadnano_content}")?;
             Ok(ExportSuccess::Cadnano(export_path.clone()))
         }
+        ExportType::Scadnano => {
+            let scadnano_content = scadnano::scadnano_export(design, basis_map)?;
+            let mut out_file = std::fs::File::create(export_path)?;
+            writeln!(&mut out_file, "{scadnano_content}")?;
+            Ok(ExportSuccess::Scadnano(export_path.clone()))
+        }
+    }
+}
+
+/// A frame of a trajectory.
+pub struct TrajectoryFrame {
+    /// The number of solver steps done since the start of the simulation.
+    pub step: usize,
+    /// The positions of the nucleotides. The nucleotides that are not in the frame are placed at
+    /// their position on their helix.
+    pub positions: std::collections::HashMap<Nucl, Vec3>,
+}
+
+/// Export a trajectory, given as a sequence of frames.
+///
+/// oxDNA trajectories are written as a sequence of configurations sharing the same topology, with
+/// the step of each frame as their time. PDB trajectories are written as a sequence of models.
+pub fn export_trajectory(
+    design: &Design,
+    export_type: ExportType,
+    basis_map: Option<&BasisMap>,
+    export_path: &PathBuf,
+    frames: &[TrajectoryFrame],
+) -> Result<ExportSuccess, ExportError> {
+    let basis_mapper = BasisMapper::new(basis_map);
+    match export_type {
+        ExportType::Oxdna => {
+            let configuration_path = export_path.clone();
+            let mut topology_path = export_path.clone();
+            topology_path.set_extension("top");
+            let (trajectory, topology) = oxdna::trajectory_to_oxdna(design, basis_mapper, frames);
+            trajectory.write(&configuration_path)?;
+            topology.write(&topology_path)?;
+            Ok(ExportSuccess::Oxdna {
+                topology: topology_path,
+                configuration: configuration_path,
+            })
+        }
+        ExportType::Pdb => {
+            pdb::pdb_trajectory_export(design, basis_mapper, export_path, frames)?;
+            Ok(ExportSuccess::Pdb(export_path.clone()))
+        }
+        ExportType::Cadnano | ExportType::Scadnano => {
+            Err(ExportError::NoTrajectoryFormat(export_type))
+        }
     }
 }
 
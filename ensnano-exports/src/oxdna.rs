@@ -1,4 +1,4 @@
-use crate::{BasisMapper, rand_base};
+use crate::{BasisMapper, TrajectoryFrame, rand_base};
 use ensnano_design::{
     Design, domains::Domain, helices::Helix, nucl::Nucl, parameters::HelixParameters,
 };
@@ -36,6 +36,12 @@ pub struct OxDnaConfig {
 impl OxDnaConfig {
     pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), std::io::Error> {
         let mut file = std::fs::File::create(path)?;
+        self.write_to(&mut file)
+    }
+
+    /// Write the configuration at the end of `file`. Trajectories are written as a sequence of
+    /// configurations in the same file.
+    fn write_to(&self, mut file: impl std::io::Write) -> Result<(), std::io::Error> {
         let max = self.boundaries[0].max(self.boundaries[1].max(self.boundaries[2]));
         writeln!(&mut file, "t = {}", self.time)?;
         writeln!(&mut file, "b = {max} {max} {max}")?;
@@ -129,6 +135,24 @@ pub fn oxdna_nucl(
     }
 }
 
+/// Same as [`oxdna_nucl`], but uses the relaxed positions stored in the design when there are
+/// some.
+fn design_oxdna_nucl(design: &Design, nucl: Nucl, helix_parameters: &HelixParameters) -> OxDnaNucl {
+    let helix = design.helices.get(&nucl.helix).unwrap();
+    let mut ox_nucl = oxdna_nucl(helix, nucl.position, nucl.forward, helix_parameters);
+    if let Some(backbone_position) = design.relaxed_positions.get(&nucl) {
+        let other_base = design
+            .relaxed_positions
+            .get(&nucl.compl())
+            .copied()
+            .unwrap_or_else(|| helix.space_pos(helix_parameters, nucl.position, !nucl.forward));
+        let a1 = (other_base - *backbone_position).normalized();
+        ox_nucl.position = *backbone_position * OXDNA_LEN_FACTOR + a1 * BACKBONE_TO_CM;
+        ox_nucl.backbone_base = a1;
+    }
+    ox_nucl
+}
+
 pub fn free_oxdna_nucl(
     pos: Vec3,
     previous_position: Option<Vec3>,
@@ -272,14 +296,13 @@ pub(super) fn to_oxdna(design: &Design, basis_map: BasisMapper) -> (OxDnaConfig,
 
         for d in &s.domains {
             if let Domain::HelixDomain(dom) = d {
-                for position in dom.iter() {
-                    let helix = design.helices.get(&dom.helix).unwrap();
-                    let ox_nucl = oxdna_nucl(helix, position, dom.forward, &helix_parameters);
+                for position in dom.iter_nucls(&design.helices) {
                     let nucl = Nucl {
                         position,
                         helix: dom.helix,
                         forward: dom.forward,
                     };
+                    let ox_nucl = design_oxdna_nucl(design, nucl, &helix_parameters);
                     strand_maker.add_ox_nucl(ox_nucl, Some(nucl));
                 }
             } else if let Domain::Insertion {
@@ -298,3 +321,72 @@ pub(super) fn to_oxdna(design: &Design, basis_map: BasisMapper) -> (OxDnaConfig,
 
     maker.end()
 }
+
+/// Make one configuration per frame of a trajectory, and the topology of the design.
+///
+/// The time of each configuration is the step of its frame.
+pub(super) fn trajectory_to_oxdna(
+    design: &Design,
+    basis_map: BasisMapper,
+    frames: &[TrajectoryFrame],
+) -> (OxDnaTrajectory, OxDnaTopology) {
+    let (_, topology) = to_oxdna(design, basis_map);
+    let mut design = design.clone();
+    let configurations = frames
+        .iter()
+        .map(|frame| {
+            design.relaxed_positions.clone_from(&frame.positions);
+            // only the topology holds the bases
+            let (mut config, _) = to_oxdna(&design, BasisMapper::new(None));
+            config.time = frame.step as f32;
+            config
+        })
+        .collect();
+    (OxDnaTrajectory { configurations }, topology)
+}
+
+/// A sequence of configurations sharing the same topology.
+pub struct OxDnaTrajectory {
+    configurations: Vec<OxDnaConfig>,
+}
+
+impl OxDnaTrajectory {
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), std::io::Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for configuration in &self.configurations {
+            configuration.write_to(&mut file)?;
+        }
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trajectory_configurations_are_timed_by_step() {
+        let mut design_path = std::path::PathBuf::from(std::env!("CARGO_MANIFEST_DIR"));
+        design_path.pop();
+        design_path.push("tests/one_xover.json");
+        let design: Design =
+            serde_json::from_str(&std::fs::read_to_string(design_path).unwrap()).unwrap();
+        let frames = [0, 250].map(|step| TrajectoryFrame {
+            step,
+            positions: Default::default(),
+        });
+
+        let (trajectory, _) = trajectory_to_oxdna(&design, BasisMapper::new(None), &frames);
+        let mut content = Vec::new();
+        for configuration in &trajectory.configurations {
+            configuration.write_to(&mut content).unwrap();
+        }
+        let times = String::from_utf8(content)
+            .unwrap()
+            .lines()
+            .filter(|line| line.starts_with("t = "))
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        assert_eq!(times, ["t = 0", "t = 250"]);
+    }
+}
@@ -2,7 +2,7 @@
 //! [tacOxDNA](https://github.com/lorenzo-rovigatti/tacoxDNA).
 
 use crate::{
-    BasisMapper,
+    BasisMapper, TrajectoryFrame,
     oxdna::{free_oxdna_nucl, oxdna_nucl},
     rand_base_from_symbol,
 };
@@ -602,6 +602,17 @@ impl PdbFormatter {
         })
     }
 
+    /// Start a new model. Atoms and chains are numbered from the start in each model.
+    pub fn start_model(&mut self, serial_number: usize) -> Result<(), PdbError> {
+        self.nb_atom = 0;
+        self.current_strand_id = 0;
+        writeln!(&mut self.out_file, "MODEL     {serial_number:>4}").map_err(PdbError::IOError)
+    }
+
+    pub fn end_model(&mut self) -> Result<(), PdbError> {
+        writeln!(&mut self.out_file, "ENDMDL").map_err(PdbError::IOError)
+    }
+
     /// Create a new strand. The returned value must be dropped with `PdbStrand::write`.
     pub fn start_strand(&mut self, cyclic: bool) -> PdbStrand<'_> {
         PdbStrand {
@@ -675,13 +686,52 @@ pub(super) fn pdb_export(
     space_position: &HashMap<u32, [f32; 3]>,
     nucl_collection: &NuclCollection,
 ) -> Result<(), PdbError> {
+    let na_kind = nucleic_acid_kind(design);
+    let mut exporter = PdbFormatter::new(out_path, na_kind)?;
+    write_pdb_strands(&mut exporter, design, &mut basis_map, na_kind, |nucl| {
+        let id = nucl_collection.get_identifier(nucl)?;
+        space_position.get(id).map(Vec3::from)
+    })
+}
+
+/// Export each frame of a trajectory as a model of a single PDB file.
+pub(super) fn pdb_trajectory_export(
+    design: &Design,
+    mut basis_map: BasisMapper,
+    out_path: &PathBuf,
+    frames: &[TrajectoryFrame],
+) -> Result<(), PdbError> {
+    let na_kind = nucleic_acid_kind(design);
+    let mut exporter = PdbFormatter::new(out_path, na_kind)?;
+    for (frame_idx, frame) in frames.iter().enumerate() {
+        exporter.start_model(frame_idx + 1)?;
+        write_pdb_strands(&mut exporter, design, &mut basis_map, na_kind, |nucl| {
+            frame.positions.get(nucl).copied()
+        })?;
+        exporter.end_model()?;
+    }
+    Ok(())
+}
+
+fn nucleic_acid_kind(design: &Design) -> NucleicAcidKind {
     let helix_parameters = design.helix_parameters.unwrap_or_default();
-    let na_kind = if helix_parameters.name().name.contains("RNA") {
+    if helix_parameters.name().name.contains("RNA") {
         NucleicAcidKind::Rna
     } else {
         NucleicAcidKind::Dna
-    };
-    let mut exporter = PdbFormatter::new(out_path, na_kind)?;
+    }
+}
+
+/// Write all the strands of the design, using `position_of` to override the position of the
+/// nucleotides.
+fn write_pdb_strands(
+    exporter: &mut PdbFormatter,
+    design: &Design,
+    basis_map: &mut BasisMapper,
+    na_kind: NucleicAcidKind,
+    position_of: impl Fn(&Nucl) -> Option<Vec3>,
+) -> Result<(), PdbError> {
+    let helix_parameters = design.helix_parameters.unwrap_or_default();
     let mut previous_position = None;
 
     for s in design.strands.values() {
@@ -689,23 +739,18 @@ pub(super) fn pdb_export(
 
         for d in &s.domains {
             if let Domain::HelixDomain(dom) = d {
-                for position in dom.iter() {
+                for position in dom.iter_nucls(&design.helices) {
                     let helix = design.helices.get(&dom.helix).unwrap();
                     let mut ox_nucl = oxdna_nucl(helix, position, dom.forward, &helix_parameters);
-                    // if space position contains a position, we use it to override the oxdna code
-                    if let Some(id) = nucl_collection.get_identifier(&Nucl {
-                        helix: dom.helix,
-                        position,
-                        forward: dom.forward,
-                    }) && let Some(position) = space_position.get(id)
-                    {
-                        ox_nucl.position = position.into();
-                    }
                     let nucl = Nucl {
                         position,
                         helix: dom.helix,
                         forward: dom.forward,
                     };
+                    // if a position is given, we use it to override the oxdna code
+                    if let Some(position) = position_of(&nucl) {
+                        ox_nucl.position = position;
+                    }
                     previous_position = Some(ox_nucl.position);
                     let symbol = basis_map.get_basis(&nucl, na_kind.compl_to_a());
                     let base = rand_base_from_symbol(symbol, na_kind.compl_to_a());
@@ -797,4 +842,44 @@ mod test {
             let _ = references.get_nucl(name).expect(name);
         }
     }
+
+    #[test]
+    fn trajectory_has_one_model_per_frame() {
+        let mut design_path = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"));
+        design_path.pop();
+        design_path.push("tests/one_xover.json");
+        let design: Design =
+            serde_json::from_str(&std::fs::read_to_string(design_path).unwrap()).unwrap();
+        let nucl = Nucl {
+            helix: 1,
+            position: 0,
+            forward: true,
+        };
+        let frames = [
+            TrajectoryFrame {
+                step: 0,
+                positions: std::collections::HashMap::from([(nucl, Vec3::zero())]),
+            },
+            TrajectoryFrame {
+                step: 100,
+                positions: std::collections::HashMap::from([(nucl, Vec3::unit_x())]),
+            },
+        ];
+
+        let out_path = std::env::temp_dir().join("ensnano_trajectory_test.pdb");
+        pdb_trajectory_export(&design, BasisMapper::new(None), &out_path, &frames).unwrap();
+        let content = std::fs::read_to_string(&out_path).unwrap();
+        std::fs::remove_file(&out_path).unwrap();
+
+        let models = content.split("ENDMDL").collect::<Vec<_>>();
+        assert_eq!(content.matches("MODEL ").count(), 2);
+        assert_eq!(models.len(), 3);
+        // the same atoms are written in each model, but the first nucleotide moved
+        assert_eq!(models[0].lines().count(), models[1].lines().count() - 1);
+        assert_ne!(
+            models[0].lines().nth(1),
+            models[1].lines().nth(2),
+            "The first atom should move between the frames"
+        );
+    }
 }
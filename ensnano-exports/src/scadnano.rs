@@ -0,0 +1,261 @@
+//! Export of designs to the [scadnano](https://scadnano.org) file format.
+//!
+//! All helices must be on a square or honeycomb grid. When the helices are on several grids, each
+//! grid becomes a helix group of the scadnano design. The orientation of the grids is not
+//! exported.
+
+use crate::BasisMap;
+use ensnano_design::{
+    Design,
+    domains::Domain,
+    grid::{GridId, GridTypeDescr, grid_collection::FreeGridId},
+    helices::Helices,
+    nucl::Nucl,
+    scadnano::{ScadnanoDesign, ScadnanoDomain, ScadnanoGroup, ScadnanoHelix, ScadnanoStrand},
+    strands::Strand,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, btree_map};
+
+/// The version of the scadnano file format written by the export.
+const SCADNANO_VERSION: &str = "0.19.1";
+
+#[derive(Debug)]
+pub enum ScadnanoError {
+    HelixNotOnGrid(usize),
+    /// A strand has a domain on a helix that does not exist.
+    HelixDoesNotExist(usize),
+    UnhandledGridType(GridTypeDescr),
+    SerdeError(serde_json::Error),
+}
+
+pub fn scadnano_export(
+    design: &Design,
+    basis_map: Option<&BasisMap>,
+) -> Result<String, ScadnanoError> {
+    // scadnano offsets cannot be negative.
+    let shift = design
+        .strands
+        .values()
+        .flat_map(|s| s.domains.iter())
+        .filter_map(|d| match d {
+            Domain::HelixDomain(interval) => Some(interval.start),
+            Domain::Insertion { .. } => None,
+        })
+        .min()
+        .unwrap_or(0)
+        .min(0);
+
+    let mut grids = BTreeMap::new();
+    let mut helix_grids = Vec::with_capacity(design.helices.len());
+    for (h_id, helix) in design.helices.iter() {
+        let grid_position = helix
+            .grid_position
+            .as_ref()
+            .ok_or(ScadnanoError::HelixNotOnGrid(*h_id))?;
+        let GridId::FreeGrid(g_id) = grid_position.grid else {
+            return Err(ScadnanoError::HelixNotOnGrid(*h_id));
+        };
+        if let btree_map::Entry::Vacant(entry) = grids.entry(g_id) {
+            let descriptor = design
+                .free_grids
+                .get(&FreeGridId(g_id))
+                .ok_or(ScadnanoError::HelixNotOnGrid(*h_id))?;
+            let grid_type = match &descriptor.grid_type {
+                GridTypeDescr::Square { .. } => "square",
+                GridTypeDescr::Honeycomb { .. } => "honeycomb",
+                t => return Err(ScadnanoError::UnhandledGridType(t.clone())),
+            };
+            entry.insert((descriptor.position, grid_type));
+        }
+        helix_grids.push((*h_id, g_id, grid_position.x, grid_position.y));
+    }
+
+    let several_grids = grids.len() > 1;
+    let group_name = |g_id: usize| format!("grid_{g_id}");
+    let helix_idx: HashMap<usize, usize> = helix_grids
+        .iter()
+        .enumerate()
+        .map(|(idx, (h_id, ..))| (*h_id, idx))
+        .collect();
+    let max_offsets = max_offsets(design, shift);
+    let helices = helix_grids
+        .iter()
+        .map(|(h_id, g_id, x, y)| ScadnanoHelix {
+            max_offset: max_offsets.get(h_id).copied().unwrap_or(1),
+            grid_position: vec![*x, *y],
+            group: several_grids.then(|| group_name(*g_id)),
+        })
+        .collect();
+    let groups = several_grids.then(|| {
+        grids
+            .iter()
+            .map(|(g_id, (position, grid_type))| {
+                (
+                    group_name(*g_id),
+                    ScadnanoGroup::new(*position, (*grid_type).to_owned()),
+                )
+            })
+            .collect()
+    });
+    let grid = grids
+        .values()
+        .next()
+        .filter(|_| !several_grids)
+        .map_or("square", |(_, grid_type)| grid_type);
+
+    let strands = design
+        .strands
+        .iter()
+        .map(|(s_id, strand)| {
+            Ok(ScadnanoStrand {
+                is_scaffold: design.scaffold_id == Some(*s_id),
+                sequence: basis_map.and_then(|map| strand_sequence(strand, &design.helices, map)),
+                color: format!("#{:06x}", strand.color & 0xFF_FF_FF),
+                domains: scadnano_domains(strand, &design.helices, &helix_idx, shift)?,
+                prime5_modification: None,
+                prime3_modification: None,
+                circular: strand.is_cyclic,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    serde_json::to_string_pretty(&ScadnanoDesign {
+        version: SCADNANO_VERSION.to_owned(),
+        grid: grid.to_owned(),
+        groups,
+        helices,
+        strands,
+        modifications_in_design: None,
+    })
+    .map_err(ScadnanoError::SerdeError)
+}
+
+/// The smallest `max_offset` of each helix that contains all the domains on it.
+fn max_offsets(design: &Design, shift: isize) -> HashMap<usize, usize> {
+    let mut ret = HashMap::new();
+    for domain in design.strands.values().flat_map(|s| s.domains.iter()) {
+        if let Domain::HelixDomain(interval) = domain {
+            let offset = ret.entry(interval.helix).or_insert(1);
+            *offset = (*offset).max((interval.end - shift) as usize);
+        }
+    }
+    ret
+}
+
+fn scadnano_domains(
+    strand: &Strand,
+    helices: &Helices,
+    helix_idx: &HashMap<usize, usize>,
+    shift: isize,
+) -> Result<Vec<ScadnanoDomain>, ScadnanoError> {
+    let last = strand.domains.len().saturating_sub(1);
+    strand
+        .domains
+        .iter()
+        .enumerate()
+        .filter_map(|(i, domain)| match domain {
+            Domain::HelixDomain(interval) => {
+                let deletions: Vec<isize> = helices
+                    .get(&interval.helix)
+                    .map(|h| &h.deletions)
+                    .into_iter()
+                    .flat_map(BTreeSet::iter)
+                    .filter(|d| (interval.start..interval.end).contains(*d))
+                    .map(|d| d - shift)
+                    .collect();
+                let helix = helix_idx
+                    .get(&interval.helix)
+                    .copied()
+                    .ok_or(ScadnanoError::HelixDoesNotExist(interval.helix));
+                Some(helix.map(|helix| ScadnanoDomain::HelixDomain {
+                    helix,
+                    start: interval.start - shift,
+                    end: interval.end - shift,
+                    forward: interval.forward,
+                    insertions: None,
+                    deletions: (!deletions.is_empty()).then_some(deletions),
+                }))
+            }
+            // scadnano does not allow loopouts at the ends of a strand.
+            Domain::Insertion { .. } if i == 0 || i == last => {
+                log::warn!("Insertion at the end of a strand cannot be exported to scadnano");
+                None
+            }
+            Domain::Insertion { nb_nucl, .. } => {
+                Some(Ok(ScadnanoDomain::Loopout { loopout: *nb_nucl }))
+            }
+        })
+        .collect()
+}
+
+/// The sequence of the strand, if all its nucleotides have a basis. Insertions have no basis.
+fn strand_sequence(strand: &Strand, helices: &Helices, basis_map: &BasisMap) -> Option<String> {
+    let mut sequence = String::with_capacity(strand.length());
+    for domain in &strand.domains {
+        let Domain::HelixDomain(interval) = domain else {
+            return None;
+        };
+        for position in interval.iter_nucls(helices) {
+            let nucl = Nucl {
+                helix: interval.helix,
+                position,
+                forward: interval.forward,
+            };
+            sequence.push(*basis_map.get(&nucl)?);
+        }
+    }
+    Some(sequence)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deletions_survive_a_scadnano_round_trip() {
+        let input = r##"{
+  "version": "0.15.0",
+  "grid": "square",
+  "helices": [{"grid_position": [0, 0]}, {"grid_position": [0, 1]}],
+  "strands": [
+    {
+      "color": "#57bb00",
+      "domains": [
+        {"helix": 0, "forward": true, "start": 0, "end": 16, "deletions": [4]},
+        {"helix": 1, "forward": false, "start": 0, "end": 16, "deletions": [11]}
+      ]
+    }
+  ]
+}"##;
+        let scadnano: ScadnanoDesign = serde_json::from_str(input).unwrap();
+        let design = Design::from_scadnano(&scadnano).unwrap();
+        let exported = scadnano_export(&design, None).unwrap();
+        let scadnano: ScadnanoDesign = serde_json::from_str(&exported).unwrap();
+        let design = Design::from_scadnano(&scadnano).unwrap();
+        let deletions = |h_id| {
+            let helix = design.helices.get(&h_id).unwrap();
+            helix.deletions.iter().copied().collect::<Vec<_>>()
+        };
+        assert_eq!(deletions(0), [4]);
+        assert_eq!(deletions(1), [11]);
+        let strand = design.strands.values().next().unwrap();
+        assert_eq!(strand.nb_nucls(&design.helices), 30);
+    }
+
+    #[test]
+    fn strands_on_unknown_helices_are_not_exported() {
+        let input = r##"{
+  "version": "0.15.0",
+  "grid": "square",
+  "helices": [{"grid_position": [0, 0]}],
+  "strands": [{"color": "#57bb00", "domains": [{"helix": 0, "forward": true, "start": 0, "end": 16}]}]
+}"##;
+        let scadnano: ScadnanoDesign = serde_json::from_str(input).unwrap();
+        let mut design = Design::from_scadnano(&scadnano).unwrap();
+        design.strands.insert(1, Strand::init(7, 0, true, 0));
+        assert!(matches!(
+            scadnano_export(&design, None),
+            Err(ScadnanoError::HelixDoesNotExist(7))
+        ));
+    }
+}
@@ -403,7 +403,7 @@ impl ControllerState for NormalState {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             _ => Transition::nothing(),
@@ -432,7 +432,7 @@ impl ControllerState for Translating {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -483,7 +483,7 @@ impl ControllerState for Translating {
                 })
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -528,7 +528,7 @@ impl ControllerState for MovingCamera {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -574,7 +574,7 @@ impl ControllerState for MovingCamera {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             _ => Transition::nothing(),
@@ -975,7 +975,7 @@ impl ControllerState for ReleasedPivot {
                     Transition::consequence(csq)
                 }
                 _ => {
-                    controller.process_keyboard(event);
+                    controller.process_keyboard(event, main_state.app_state.get_keymap());
                     Transition::nothing()
                 }
             },
@@ -1023,7 +1023,7 @@ impl ControllerState for LeavingPivot {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -1083,7 +1083,7 @@ impl ControllerState for LeavingPivot {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -1161,7 +1161,7 @@ impl ControllerState for Rotating {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -1243,7 +1243,7 @@ impl ControllerState for Rotating {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -1281,7 +1281,7 @@ impl ControllerState for AddOrXover {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -1345,7 +1345,7 @@ impl ControllerState for AddOrXover {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -1387,7 +1387,7 @@ impl ControllerState for InitAttachment {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -1434,7 +1434,7 @@ impl ControllerState for InitAttachment {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -1599,7 +1599,7 @@ impl ControllerState for InitBuilding {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -1635,7 +1635,7 @@ impl ControllerState for MovingFreeEnd {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -1717,7 +1717,7 @@ impl ControllerState for MovingFreeEnd {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -1757,7 +1757,7 @@ impl ControllerState for Building {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -1811,7 +1811,7 @@ impl ControllerState for Building {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -1853,7 +1853,7 @@ impl ControllerState for Crossing {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -1906,7 +1906,7 @@ impl ControllerState for Crossing {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -1945,7 +1945,7 @@ impl ControllerState for Cutting {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -1998,7 +1998,7 @@ impl ControllerState for Cutting {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2031,7 +2031,7 @@ impl ControllerState for RmHelix {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -2055,7 +2055,7 @@ impl ControllerState for RmHelix {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2089,7 +2089,7 @@ impl ControllerState for FlipGroup {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -2130,7 +2130,7 @@ impl ControllerState for FlipGroup {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2165,7 +2165,7 @@ impl ControllerState for FlipVisibility {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -2206,7 +2206,7 @@ impl ControllerState for FlipVisibility {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2242,7 +2242,7 @@ impl ControllerState for FollowingSuggestion {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -2305,7 +2305,7 @@ impl ControllerState for FollowingSuggestion {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2339,7 +2339,7 @@ impl ControllerState for Pasting {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -2377,7 +2377,7 @@ impl ControllerState for Pasting {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2491,7 +2491,7 @@ impl ControllerState for DraggingSelection {
                 ))
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2680,7 +2680,7 @@ impl ControllerState for AddCirclePivot {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2756,7 +2756,7 @@ impl ControllerState for InitHelixTranslation {
                 }
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -2802,7 +2802,7 @@ impl ControllerState for TranslatingHandle {
         event: &WindowEvent,
         position: PhysicalPosition<f64>,
         controller: &FlatSceneController,
-        _: &mut MainState,
+        main_state: &mut MainState,
     ) -> Transition {
         match event {
             WindowEvent::MouseInput {
@@ -2838,7 +2838,7 @@ impl ControllerState for TranslatingHandle {
                 Transition::nothing()
             }
             WindowEvent::KeyboardInput { .. } => {
-                controller.process_keyboard(event);
+                controller.process_keyboard(event, main_state.app_state.get_keymap());
                 Transition::nothing()
             }
             WindowEvent::MouseWheel { delta, .. } => {
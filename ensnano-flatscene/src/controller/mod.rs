@@ -7,7 +7,7 @@
 
 mod automata;
 
-use self::automata::{ControllerState, NormalState, Transition, ctrl};
+use self::automata::{ControllerState, NormalState, Transition};
 use crate::{
     CameraPtr, DataPtr, ViewPtr,
     data::{ClickResult, strand::FreeEnd},
@@ -15,13 +15,16 @@ use crate::{
 };
 use ensnano_design::interaction_modes::ActionMode;
 use ensnano_state::{design::selection::Selection, state::MainState};
-use ensnano_utils::graphics::PhySize;
+use ensnano_utils::{
+    graphics::PhySize,
+    keymap::{Keymap, KeymapAction, KeymapScope},
+};
 use std::cell::RefCell;
 use ultraviolet::Vec2;
 use winit::{
     dpi::PhysicalPosition,
     event::{ElementState, KeyEvent, WindowEvent},
-    keyboard::{Key, ModifiersState, NamedKey},
+    keyboard::ModifiersState,
     window::CursorIcon,
 };
 
@@ -222,7 +225,7 @@ impl FlatSceneController {
         self.view.borrow_mut().set_hovered_nucl(nucl);
     }
 
-    pub(crate) fn process_keyboard(&self, event: &WindowEvent) {
+    pub(crate) fn process_keyboard(&self, event: &WindowEvent, keymap: &Keymap) {
         if let WindowEvent::KeyboardInput {
             event:
                 KeyEvent {
@@ -234,23 +237,15 @@ impl FlatSceneController {
         } = event
         {
             let camera = self.get_camera(self.mouse_position.y);
-            match logical_key.as_ref() {
-                Key::Named(NamedKey::ArrowLeft) if self.modifiers.alt_key() => {
-                    camera.borrow_mut().tilt_left();
-                }
-                Key::Named(NamedKey::ArrowRight) if self.modifiers.alt_key() => {
-                    camera.borrow_mut().tilt_right();
-                }
-                Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowRight) if ctrl(&self.modifiers) => {
-                    camera.borrow_mut().apply_symmetry_x();
-                }
-                Key::Named(NamedKey::ArrowUp | NamedKey::ArrowDown) if ctrl(&self.modifiers) => {
-                    camera.borrow_mut().apply_symmetry_y();
-                }
-                Key::Character("J") => {
+            match keymap.action_for_key(KeymapScope::Scene2D, logical_key, &self.modifiers) {
+                Some(KeymapAction::TiltCamera2DLeft) => camera.borrow_mut().tilt_left(),
+                Some(KeymapAction::TiltCamera2DRight) => camera.borrow_mut().tilt_right(),
+                Some(KeymapAction::SymmetryX2D) => camera.borrow_mut().apply_symmetry_x(),
+                Some(KeymapAction::SymmetryY2D) => camera.borrow_mut().apply_symmetry_y(),
+                Some(KeymapAction::MoveHelixBackward2D) => {
                     self.data.borrow_mut().move_helix_backward();
                 }
-                Key::Character("K") => {
+                Some(KeymapAction::MoveHelixForward2D) => {
                     self.data.borrow_mut().move_helix_forward();
                 }
                 _ => (),
@@ -286,6 +286,7 @@ impl Design2d {
                     .get_visibility_helix(segment.helix_idx)
                     .unwrap_or(false),
                 abscissa_converter: Arc::new(self.design.get_abscissa_converter(segment.helix_idx)),
+                deletions: self.design.get_deletions(segment.helix_idx),
             });
         } else {
             // unwrap Ok because we know that the key exists
@@ -294,6 +295,7 @@ impl Design2d {
             helix2d.isometry = FullIsometry::from_isometry_symmetry(isometry, symmetry);
             helix2d.abscissa_converter =
                 Arc::new(self.design.get_abscissa_converter(segment.helix_idx));
+            helix2d.deletions = self.design.get_deletions(segment.helix_idx);
         }
     }
 
@@ -305,6 +307,10 @@ impl Design2d {
         &self.strands
     }
 
+    pub(super) fn get_design_reader(&self) -> &DesignInteractor {
+        &self.design
+    }
+
     pub(super) fn get_pasted_strand(&self) -> &[Strand] {
         &self.pasted_strands
     }
@@ -480,6 +486,8 @@ pub struct Helix2d {
     pub visible: bool,
     pub abscissa_converter: Arc<AbscissaConverter>,
     pub segment_idx: usize,
+    /// The deleted positions of the helix, in increasing order.
+    pub deletions: Vec<isize>,
 }
 
 impl Helix2d {
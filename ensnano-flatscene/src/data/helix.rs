@@ -6,6 +6,7 @@ use crate::{
     data::design::Helix2d,
     flat_types::{Flat, FlatHelix, FlatHelixMaps, FlatNucl, FlatPosition, HelixSegment},
     full_isometry::FullIsometry,
+    vector_export::{PathCommand, VectorShape},
     view::{
         EditionInfo,
         insertion::{InsertionDescriptor, InsertionInstance},
@@ -42,6 +43,9 @@ type Vertices = VertexBuffers<GpuVertex, u16>;
 
 const CIRCLE_WIDGET_RADIUS: f32 = 1.5;
 const ZOOM_THRESHOLD: f32 = 7.0;
+const VECTOR_OUTLINE_WIDTH: f32 = 0.05;
+const VECTOR_TEXT_COLOR: u32 = 0xFF_00_00_00;
+const VECTOR_UNKNOWN_BASIS_COLOR: u32 = 0xFF_4D_4D_4D;
 
 #[derive(Debug, Clone)]
 pub struct Helix {
@@ -59,6 +63,8 @@ pub struct Helix {
     pub real_id: usize,
     pub visible: bool,
     abscissa_converter: Arc<AbscissaConverter>,
+    /// The deleted positions of the helix, drawn as crosses.
+    deletions: Vec<isize>,
 }
 
 impl Flat for Helix {}
@@ -82,6 +88,7 @@ impl Helix {
         real_id: usize,
         visible: bool,
         abscissa_converter_: Arc<AbscissaConverter_>,
+        deletions: Vec<isize>,
     ) -> Self {
         Self {
             left,
@@ -98,6 +105,7 @@ impl Helix {
                 converter: abscissa_converter_,
                 left: flat_id.segment_left,
             }),
+            deletions,
         }
     }
 
@@ -123,6 +131,7 @@ impl Helix {
             converter: helix2d.abscissa_converter.clone(),
             left,
         });
+        self.deletions.clone_from(&helix2d.deletions);
     }
 
     pub fn background_vertices(&self) -> Vertices {
@@ -195,6 +204,11 @@ impl Helix {
         builder.begin(point(left, 1.));
         builder.line_to(point(right, 1.));
         builder.end(false);
+        for (from, to) in self.deletion_crosses() {
+            builder.begin(point(from.x, from.y));
+            builder.line_to(point(to.x, to.y));
+            builder.end(false);
+        }
         let path = builder.build();
         stroke_tess
             .tessellate_path(
@@ -551,7 +565,17 @@ impl Helix {
         } else {
             Some(self.x_position(left + CIRCLE_WIDGET_RADIUS, HelixLine::Middle))
         };
-        let color = if !self.visible {
+        let color = self.circle_color(groups);
+        let radius = if camera.borrow().get_globals().zoom < ZOOM_THRESHOLD {
+            CIRCLE_WIDGET_RADIUS * 2.
+        } else {
+            CIRCLE_WIDGET_RADIUS
+        };
+        center.map(|c| CircleInstance::new(c, radius, self.flat_id.flat.0 as i32, color))
+    }
+
+    fn circle_color(&self, groups: &BTreeMap<usize, bool>) -> u32 {
+        if !self.visible {
             CIRCLE2D_GREY
         } else {
             match groups.get(&self.real_id) {
@@ -559,13 +583,7 @@ impl Helix {
                 Some(true) => CIRCLE2D_RED,
                 Some(false) => CIRCLE2D_GREEN,
             }
-        };
-        let radius = if camera.borrow().get_globals().zoom < ZOOM_THRESHOLD {
-            CIRCLE_WIDGET_RADIUS * 2.
-        } else {
-            CIRCLE_WIDGET_RADIUS
-        };
-        center.map(|c| CircleInstance::new(c, radius, self.flat_id.flat.0 as i32, color))
+        }
     }
 
     pub fn get_circle_nucl(
@@ -857,6 +875,151 @@ impl Helix {
         }
     }
 
+    /// The segments of the crosses drawn over the deleted positions, in the coordinates of the
+    /// helix. Each cross spans both strands of the helix.
+    fn deletion_crosses(&self) -> Vec<(Vec2, Vec2)> {
+        self.deletions
+            .iter()
+            .filter(|d| (self.left..self.right).contains(*d))
+            .flat_map(|d| {
+                let x_conversion = |n| {
+                    self.abscissa_converter
+                        .nucl_to_x_conversion(FlatPosition::from_real(n, self.flat_id.segment_left))
+                        as f32
+                };
+                let (left, right) = (x_conversion(*d), x_conversion(*d + 1));
+                [
+                    (Vec2::new(left, 0.), Vec2::new(right, 2.)),
+                    (Vec2::new(left, 2.), Vec2::new(right, 0.)),
+                ]
+            })
+            .collect()
+    }
+
+    /// The border, the nucleotide separators and the axis of the helix, for vector exports.
+    pub(crate) fn vector_outline(&self) -> VectorShape {
+        let to_world = |x: f32, y: f32| {
+            self.isometry
+                .into_homogeneous_matrix()
+                .transform_point2(self.scale * Vec2::new(x, y))
+        };
+        let left = self.leftmost_x();
+        let right = self.abscissa_converter.nucl_to_x_conversion(
+            self.get_flat_right()
+                .right()
+                .max(self.get_flat_left().right()),
+        ) as f32;
+        let mut commands = vec![
+            PathCommand::MoveTo(to_world(left, 0.)),
+            PathCommand::LineTo(to_world(right, 0.)),
+            PathCommand::LineTo(to_world(right, 2.)),
+            PathCommand::LineTo(to_world(left, 2.)),
+            PathCommand::Close,
+            PathCommand::MoveTo(to_world(left, 1.)),
+            PathCommand::LineTo(to_world(right, 1.)),
+        ];
+        for i in (self.left + 1)..=self.right {
+            let x = self
+                .abscissa_converter
+                .nucl_to_x_conversion(FlatPosition::from_real(i, self.flat_id.segment_left))
+                as f32;
+            commands.push(PathCommand::MoveTo(to_world(x, 0.)));
+            commands.push(PathCommand::LineTo(to_world(x, 2.)));
+        }
+        for (from, to) in self.deletion_crosses() {
+            commands.push(PathCommand::MoveTo(to_world(from.x, from.y)));
+            commands.push(PathCommand::LineTo(to_world(to.x, to.y)));
+        }
+        VectorShape::Path {
+            commands,
+            color: self.color,
+            width: VECTOR_OUTLINE_WIDTH,
+        }
+    }
+
+    /// The circle showing the number of the helix, on its left, for vector exports.
+    pub(crate) fn vector_number(&self, groups: &BTreeMap<usize, bool>) -> [VectorShape; 2] {
+        let center = self.x_position(
+            self.leftmost_x() - 1. - CIRCLE_WIDGET_RADIUS,
+            HelixLine::Middle,
+        );
+        let text = self.real_id.to_string();
+        [
+            VectorShape::Disk {
+                center,
+                radius: CIRCLE_WIDGET_RADIUS,
+                color: self.circle_color(groups),
+            },
+            VectorShape::Text {
+                center,
+                size: 3. / text.len() as f32,
+                text,
+                color: VECTOR_TEXT_COLOR,
+            },
+        ]
+    }
+
+    /// The positions written above the helix and, if `basis_map` is given, the bases of its
+    /// nucleotides, for vector exports.
+    pub(crate) fn vector_labels(
+        &self,
+        basis_map: Option<&HashMap<Nucl, char, RandomState>>,
+        nucl_collection: &NuclCollection,
+    ) -> Vec<VectorShape> {
+        const SIZE: f32 = 1.4;
+        let label = |flat_position: FlatPosition, y: f32, text: String, color: u32| {
+            let x = self.x_conversion(flat_position.0 as f32 + 0.5);
+            VectorShape::Text {
+                center: self
+                    .isometry
+                    .into_homogeneous_matrix()
+                    .transform_point2(Vec2::new(x, y)),
+                text,
+                size: SIZE,
+                color,
+            }
+        };
+        let mut labels = Vec::new();
+        // The positions are written above the bases if these are shown.
+        let position_y = if basis_map.is_some() {
+            -1. - SIZE / 2.
+        } else {
+            -SIZE / 2.
+        };
+        for pos in self.left..=self.right {
+            let flat_position = FlatPosition::from_real(pos, self.flat_id.segment_left);
+            if pos % 8 == 0 {
+                labels.push(label(
+                    flat_position,
+                    position_y,
+                    pos.to_string(),
+                    VECTOR_TEXT_COLOR,
+                ));
+            }
+            let Some(basis_map) = basis_map else {
+                continue;
+            };
+            for forward in [true, false] {
+                let nucl = FlatNucl {
+                    helix: self.flat_id,
+                    flat_position,
+                    forward,
+                }
+                .to_real();
+                if !nucl_collection.contains_nucl(&nucl) {
+                    continue;
+                }
+                let (basis, color) = match basis_map.get(&nucl) {
+                    Some(c) => (*c, VECTOR_TEXT_COLOR),
+                    None => ('?', VECTOR_UNKNOWN_BASIS_COLOR),
+                };
+                let y = if forward { -SIZE / 2. } else { 2. + SIZE / 2. };
+                labels.push(label(flat_position, y, basis.to_string(), color));
+            }
+        }
+        labels
+    }
+
     pub fn get_flat_left(&self) -> FlatPosition {
         FlatPosition::from_real(self.get_left(), self.flat_id.segment_left)
     }
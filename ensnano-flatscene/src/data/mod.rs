@@ -13,6 +13,7 @@ use crate::{
     flat_types::{
         FlatHelix, FlatHelixMaps, FlatIdx, FlatNucl, FlatSelection, HelixSegment, HelixVec,
     },
+    vector_export::VectorDrawing,
     view::EditionInfo,
 };
 use ahash::RandomState;
@@ -283,6 +284,7 @@ impl Data {
                     h.id,
                     h.visible,
                     h.abscissa_converter.clone(),
+                    h.deletions.clone(),
                 ));
                 self.nb_helices_created += 1;
             }
@@ -639,6 +641,40 @@ impl Data {
         .unwrap()
     }
 
+    /// The helices and strands of the design as drawn in the view, with one layer per group of
+    /// strands. The bases of the nucleotides are written if `show_sequence` is true.
+    pub(crate) fn vector_drawing(&self, show_sequence: bool) -> VectorDrawing {
+        let reader = self.design.get_design_reader();
+        let group_names = reader.get_strand_group_names();
+        let basis_map = reader.get_basis_map();
+        let nucl_collection = reader.get_nucl_collection();
+        let groups = reader.get_group_map();
+
+        let mut drawing = VectorDrawing::default();
+        drawing.add_shapes("Helices", self.helices.iter().map(Helix::vector_outline));
+        let mut strands: Vec<_> = self.design.get_strands().iter().collect();
+        strands.sort_by_key(|s| s.id);
+        for strand in strands {
+            let layer = match group_names.get(&strand.id) {
+                Some(name) => name.as_str(),
+                None if reader.is_id_of_scaffold(strand.id) => "Scaffold",
+                None => "Staples",
+            };
+            drawing.add_shapes(layer, strand.vector_shapes(&self.helices));
+        }
+        drawing.add_shapes(
+            "Helix numbers",
+            self.helices.iter().flat_map(|h| h.vector_number(&groups)),
+        );
+        drawing.add_shapes(
+            "Nucleotides",
+            self.helices.iter().flat_map(|h| {
+                h.vector_labels(show_sequence.then_some(&*basis_map), &nucl_collection)
+            }),
+        );
+        drawing
+    }
+
     pub fn is_xover_end(&self, nucl: &FlatNucl) -> Option<bool> {
         self.design.is_xover_end(&nucl.to_real())
     }
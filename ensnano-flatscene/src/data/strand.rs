@@ -2,6 +2,7 @@ use crate::{
     CameraPtr,
     data::helix::{Helix, Shift},
     flat_types::FlatNucl,
+    vector_export::{PathCommand, VectorShape},
     view::insertion::InsertionInstance,
 };
 use ensnano_utils::instance::Instance;
@@ -134,6 +135,70 @@ impl Strand {
         ret
     }
 
+    /// The path of the strand followed by its insertions, for vector exports.
+    pub(crate) fn vector_shapes(&self, helices: &[Helix]) -> Vec<VectorShape> {
+        let mut commands = Vec::with_capacity(self.points.len() + 1);
+        let mut topology_reader = StrandTopologyReader::init(helices);
+        let mut last_point = None;
+        let mut instructions: Vec<_> = self
+            .points
+            .iter()
+            .map(|nucl| topology_reader.read_nucl(*nucl))
+            .collect();
+        instructions.extend(topology_reader.finish(None));
+        for instruction in instructions {
+            let (command, point) = match instruction {
+                DrawingInstruction::StartAt { position, .. } => {
+                    (PathCommand::MoveTo(position), position)
+                }
+                DrawingInstruction::LineTo { position, .. }
+                | DrawingInstruction::FreeEndPrime3(position) => {
+                    (PathCommand::LineTo(position), position)
+                }
+                DrawingInstruction::XoverTo {
+                    normal_source,
+                    normal_target,
+                    to,
+                    ..
+                } => {
+                    let xover = MainXoverDescriptor {
+                        origin: last_point.unwrap_or(to),
+                        target: to,
+                        normal_source,
+                        normal_target,
+                    };
+                    let (control_1, control_2) = xover.control_points();
+                    let command = PathCommand::CubicTo {
+                        control_1,
+                        control_2,
+                        to,
+                    };
+                    (command, to)
+                }
+            };
+            commands.push(command);
+            last_point = Some(point);
+        }
+        let mut shapes = Vec::with_capacity(self.insertions.len() + 1);
+        if !commands.is_empty() {
+            shapes.push(VectorShape::Path {
+                commands,
+                color: self.color,
+                width: VECTOR_STRAND_WIDTH,
+            });
+        }
+        shapes.extend(
+            self.get_insertions(helices)
+                .iter()
+                .map(|insertion| VectorShape::Path {
+                    commands: insertion.vector_path(),
+                    color: self.color,
+                    width: VECTOR_STRAND_WIDTH,
+                }),
+        );
+        shapes
+    }
+
     pub fn indication(nucl1: FlatNucl, nucl2: FlatNucl, helices: &[Helix]) -> Vertices {
         let mut vertices = Vertices::new();
         let mut builder = Path::builder_with_attributes(2);
@@ -194,6 +259,9 @@ pub struct WithAttributes {
     highlight: Option<f32>,
 }
 
+/// The width of the strands in vector exports, close to the one they have in the view.
+const VECTOR_STRAND_WIDTH: f32 = 0.25;
+
 const THINNING_POWER: f32 = 1.3;
 const MINIMUM_THICKNESS: f32 = 0.7;
 
@@ -337,6 +405,19 @@ struct MainXoverDescriptor {
     normal_target: Vec2,
 }
 
+impl MainXoverDescriptor {
+    /// The control points of the Bézier curve representing the cross-over.
+    fn control_points(&self) -> (Vec2, Vec2) {
+        let dist = (self.target - self.origin).mag();
+        let normal_1 = (self.normal_source - self.origin).normalized();
+        let normal_2 = (self.normal_target - self.target).normalized();
+        (
+            self.origin + (dist.sqrt() / 2.) * normal_1,
+            self.target + (dist.sqrt() / 2.) * normal_2,
+        )
+    }
+}
+
 impl<'a> StrandVertexBuilder<'a> {
     /// Initialize the builder.
     pub(crate) fn init(initializer: StrandVertexBuilderInitializer<'a>) -> Self {
@@ -436,11 +517,7 @@ impl<'a> StrandVertexBuilder<'a> {
         // We flip the sign so that the curve will be thin in its middle
         self.sign *= -1.0;
 
-        let dist = (xover.target - xover.origin).mag();
-        let normal_1 = (xover.normal_source - xover.origin).normalized();
-        let normal_2 = (xover.normal_target - xover.target).normalized();
-        let control_1 = xover.origin + (dist.sqrt() / 2.) * normal_1;
-        let control_2 = xover.target + (dist.sqrt() / 2.) * normal_2;
+        let (control_1, control_2) = xover.control_points();
         let target = xover.target;
         self.main_path_builder.cubic_bezier_to(
             vec2_to_point!(control_1),
@@ -22,6 +22,7 @@ pub mod data;
 mod flat_types;
 mod full_isometry;
 mod ndc;
+mod vector_export;
 mod view;
 
 use crate::{
@@ -48,6 +49,7 @@ use ensnano_state::{
 use ensnano_utils::{
     buffer_dimensions::BufferDimensions,
     consts::{EXPORT_2D_MARGIN, EXPORT_2D_MAX_SIZE},
+    export::VectorExportFormat,
     filename::derive_path_with_prefix_and_time_stamp_and_suffix,
     graphics::{DrawArea, PhySize},
 };
@@ -55,7 +57,7 @@ use itertools::Itertools as _;
 use std::{
     cell::RefCell,
     io::Write as _,
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
     sync::{Arc, Mutex},
     time::{Duration, Instant},
@@ -578,6 +580,30 @@ impl FlatScene {
         (texture, view)
     }
 
+    /// Export the whole design, as drawn in the view, into an SVG or PDF file.
+    fn export_2d_vector(&self, design_path: Option<Arc<Path>>, format: VectorExportFormat) {
+        let show_sequence = self.view[0].borrow().get_show_sec();
+        let drawing = self.data[0].borrow().vector_drawing(show_sequence);
+        let path = derive_path_with_prefix_and_time_stamp_and_suffix(
+            design_path,
+            Some("export_2d"),
+            None,
+            Some(format.extension()),
+        );
+        let content = match format {
+            VectorExportFormat::Svg => drawing.to_svg().into_bytes(),
+            VectorExportFormat::Pdf => drawing.to_pdf(),
+        };
+        match std::fs::write(&path, content) {
+            Ok(()) => log::info!("2D vector export to {}", path.display()),
+            Err(e) => {
+                let message = format!("Could not export the 2D view to {}: {e}", path.display());
+                log::error!("{message}");
+                self.requests.lock().unwrap().show_error(message);
+            }
+        }
+    }
+
     /// Export the scene into a PNG file.
     fn export_2d_png(&self, path: PathBuf, glob: camera2d::Globals) {
         let device = self.device.as_ref();
@@ -767,6 +793,9 @@ impl Application for FlatScene {
                     }
                 }
             }
+            Notification::VectorExport2D(design_path, format) => {
+                self.export_2d_vector(design_path, format);
+            }
             Notification::FitRequest  // Temporarily don't fit to make the moebius ring
             | Notification::CameraTarget(_)
             | Notification::CameraRotation(_, _, _)
@@ -0,0 +1,454 @@
+//! Export of the 2D view as vector graphics.
+//!
+//! The elements of the view are first collected in a [VectorDrawing], in world coordinates, which
+//! is then written either as an SVG or as a PDF document. Each layer of the drawing becomes an
+//! Inkscape layer in the SVG document and an optional content group in the PDF document, so that
+//! the strands of each group of the organizer can be shown, hidden or restyled together in a
+//! vector graphics editor.
+
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::fmt::Write as _;
+use ultraviolet::Vec2;
+
+/// The size, in SVG pixels or PDF points, of one unit of the world coordinates, which is roughly
+/// the width of a nucleotide.
+const POINTS_PER_UNIT: f32 = 10.;
+
+/// The space left around the drawing, in world coordinates.
+const MARGIN: f32 = 2.;
+
+/// The ratio between the advance of a character and the font size, used to center text in PDF
+/// documents in which the width of the glyphs is not known.
+const PDF_CHAR_WIDTH: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PathCommand {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    CubicTo {
+        control_1: Vec2,
+        control_2: Vec2,
+        to: Vec2,
+    },
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum VectorShape {
+    /// A stroked path.
+    Path {
+        commands: Vec<PathCommand>,
+        color: u32,
+        width: f32,
+    },
+    /// A filled disk.
+    Disk {
+        center: Vec2,
+        radius: f32,
+        color: u32,
+    },
+    /// A text centered on `center`.
+    Text {
+        center: Vec2,
+        text: String,
+        size: f32,
+        color: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VectorLayer {
+    pub name: String,
+    pub shapes: Vec<VectorShape>,
+}
+
+/// The elements of the 2D view, in world coordinates, with the y axis pointing down.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct VectorDrawing {
+    /// The layers of the drawing, from bottom to top.
+    pub layers: Vec<VectorLayer>,
+}
+
+impl VectorDrawing {
+    /// Add `shapes` to the layer named `name`, creating it on top of the others if needed.
+    pub(crate) fn add_shapes(&mut self, name: &str, shapes: impl IntoIterator<Item = VectorShape>) {
+        let layer = if let Some(idx) = self.layers.iter().position(|l| l.name == name) {
+            &mut self.layers[idx]
+        } else {
+            self.layers.push(VectorLayer {
+                name: name.to_owned(),
+                shapes: Vec::new(),
+            });
+            self.layers.last_mut().unwrap()
+        };
+        layer.shapes.extend(shapes);
+    }
+
+    /// The top left and bottom right corners of the drawing, margin included.
+    fn bounds(&self) -> (Vec2, Vec2) {
+        let mut min = Vec2::broadcast(f32::INFINITY);
+        let mut max = Vec2::broadcast(f32::NEG_INFINITY);
+        let mut extend = |point: Vec2, radius: f32| {
+            min = min.min_by_component(point - Vec2::broadcast(radius));
+            max = max.max_by_component(point + Vec2::broadcast(radius));
+        };
+        for shape in self.layers.iter().flat_map(|l| &l.shapes) {
+            match shape {
+                VectorShape::Path {
+                    commands, width, ..
+                } => {
+                    for command in commands {
+                        match command {
+                            PathCommand::MoveTo(p) | PathCommand::LineTo(p) => {
+                                extend(*p, *width);
+                            }
+                            PathCommand::CubicTo {
+                                control_1,
+                                control_2,
+                                to,
+                            } => {
+                                // The curve is contained in the convex hull of its control points.
+                                extend(*control_1, *width);
+                                extend(*control_2, *width);
+                                extend(*to, *width);
+                            }
+                            PathCommand::Close => (),
+                        }
+                    }
+                }
+                VectorShape::Disk { center, radius, .. } => extend(*center, *radius),
+                VectorShape::Text {
+                    center, text, size, ..
+                } => {
+                    let half_width = text.chars().count() as f32 * size * PDF_CHAR_WIDTH / 2.;
+                    extend(*center, half_width.max(*size));
+                }
+            }
+        }
+        if min.x > max.x {
+            (Vec2::zero(), Vec2::zero())
+        } else {
+            (min - Vec2::broadcast(MARGIN), max + Vec2::broadcast(MARGIN))
+        }
+    }
+
+    /// Write the drawing as an SVG document.
+    pub(crate) fn to_svg(&self) -> String {
+        use svg::node::element::{Circle, Group, Path, Text, path::Data};
+
+        let (min, max) = self.bounds();
+        let size = max - min;
+        let mut document = svg::Document::new()
+            .set(
+                "xmlns:inkscape",
+                "http://www.inkscape.org/namespaces/inkscape",
+            )
+            .set("viewBox", (min.x, min.y, size.x, size.y))
+            .set("width", size.x * POINTS_PER_UNIT)
+            .set("height", size.y * POINTS_PER_UNIT);
+        for layer in &self.layers {
+            let mut group = Group::new()
+                .set("inkscape:groupmode", "layer")
+                .set("inkscape:label", layer.name.as_str());
+            for shape in &layer.shapes {
+                group = match shape {
+                    VectorShape::Path {
+                        commands,
+                        color,
+                        width,
+                    } => {
+                        let data =
+                            commands
+                                .iter()
+                                .fold(Data::new(), |data, command| match *command {
+                                    PathCommand::MoveTo(p) => data.move_to((p.x, p.y)),
+                                    PathCommand::LineTo(p) => data.line_to((p.x, p.y)),
+                                    PathCommand::CubicTo {
+                                        control_1: c1,
+                                        control_2: c2,
+                                        to,
+                                    } => data.cubic_curve_to((c1.x, c1.y, c2.x, c2.y, to.x, to.y)),
+                                    PathCommand::Close => data.close(),
+                                });
+                        group.add(
+                            Path::new()
+                                .set("d", data)
+                                .set("fill", "none")
+                                .set("stroke", svg_color(*color))
+                                .set("stroke-width", *width)
+                                .set("stroke-linecap", "round")
+                                .set("stroke-linejoin", "round"),
+                        )
+                    }
+                    VectorShape::Disk {
+                        center,
+                        radius,
+                        color,
+                    } => group.add(
+                        Circle::new()
+                            .set("cx", center.x)
+                            .set("cy", center.y)
+                            .set("r", *radius)
+                            .set("fill", svg_color(*color)),
+                    ),
+                    VectorShape::Text {
+                        center,
+                        text,
+                        size,
+                        color,
+                    } => group.add(
+                        Text::new(text.as_str())
+                            .set("x", center.x)
+                            .set("y", center.y)
+                            .set("font-family", "Helvetica, Arial, sans-serif")
+                            .set("font-size", *size)
+                            .set("text-anchor", "middle")
+                            .set("dominant-baseline", "central")
+                            .set("fill", svg_color(*color)),
+                    ),
+                };
+            }
+            document = document.add(group);
+        }
+        document.to_string()
+    }
+
+    /// Write the drawing as a single page PDF document.
+    pub(crate) fn to_pdf(&self) -> Vec<u8> {
+        let (min, max) = self.bounds();
+        let size = (max - min) * POINTS_PER_UNIT;
+        // PDF coordinates are in points with the y axis pointing up.
+        let to_page = |p: Vec2| {
+            (
+                (p.x - min.x) * POINTS_PER_UNIT,
+                (max.y - p.y) * POINTS_PER_UNIT,
+            )
+        };
+
+        let catalog_id = Ref::new(1);
+        let page_tree_id = Ref::new(2);
+        let page_id = Ref::new(3);
+        let content_id = Ref::new(4);
+        let font_id = Ref::new(5);
+        let layer_ids: Vec<Ref> = (0..self.layers.len())
+            .map(|i| Ref::new(6 + i as i32))
+            .collect();
+        let layer_names: Vec<String> = (0..self.layers.len()).map(|i| format!("L{i}")).collect();
+        let font_name = Name(b"F1");
+
+        let mut pdf = Pdf::new();
+        {
+            let mut catalog = pdf.catalog(catalog_id);
+            catalog.pages(page_tree_id);
+            let mut properties = catalog.insert(Name(b"OCProperties")).dict();
+            properties
+                .insert(Name(b"OCGs"))
+                .array()
+                .items(layer_ids.iter().copied());
+            properties
+                .insert(Name(b"D"))
+                .dict()
+                .insert(Name(b"Order"))
+                .array()
+                .items(layer_ids.iter().copied());
+        }
+        pdf.pages(page_tree_id).kids([page_id]).count(1);
+        {
+            let mut page = pdf.page(page_id);
+            page.media_box(Rect::new(0., 0., size.x, size.y));
+            page.parent(page_tree_id);
+            page.contents(content_id);
+            let mut resources = page.resources();
+            resources.fonts().pair(font_name, font_id);
+            let mut properties = resources.insert(Name(b"Properties")).dict();
+            for (name, id) in layer_names.iter().zip(&layer_ids) {
+                properties.pair(Name(name.as_bytes()), *id);
+            }
+        }
+        pdf.type1_font(font_id).base_font(Name(b"Helvetica"));
+        for (layer, id) in self.layers.iter().zip(&layer_ids) {
+            let mut group = pdf.indirect(*id).dict();
+            group.pair(Name(b"Type"), Name(b"OCG"));
+            group.pair(Name(b"Name"), TextStr(&layer.name));
+        }
+
+        let mut content = Content::new();
+        content
+            .set_line_cap(pdf_writer::types::LineCapStyle::RoundCap)
+            .set_line_join(pdf_writer::types::LineJoinStyle::RoundJoin);
+        for (layer, name) in self.layers.iter().zip(&layer_names) {
+            content
+                .begin_marked_content_with_properties(Name(b"OC"))
+                .properties_named(Name(name.as_bytes()));
+            for shape in &layer.shapes {
+                match shape {
+                    VectorShape::Path {
+                        commands,
+                        color,
+                        width,
+                    } => {
+                        let (r, g, b) = pdf_color(*color);
+                        content.set_stroke_rgb(r, g, b);
+                        content.set_line_width(width * POINTS_PER_UNIT);
+                        for command in commands {
+                            match *command {
+                                PathCommand::MoveTo(p) => {
+                                    let (x, y) = to_page(p);
+                                    content.move_to(x, y);
+                                }
+                                PathCommand::LineTo(p) => {
+                                    let (x, y) = to_page(p);
+                                    content.line_to(x, y);
+                                }
+                                PathCommand::CubicTo {
+                                    control_1,
+                                    control_2,
+                                    to,
+                                } => {
+                                    let (x1, y1) = to_page(control_1);
+                                    let (x2, y2) = to_page(control_2);
+                                    let (x3, y3) = to_page(to);
+                                    content.cubic_to(x1, y1, x2, y2, x3, y3);
+                                }
+                                PathCommand::Close => {
+                                    content.close_path();
+                                }
+                            }
+                        }
+                        content.stroke();
+                    }
+                    VectorShape::Disk {
+                        center,
+                        radius,
+                        color,
+                    } => {
+                        let (r, g, b) = pdf_color(*color);
+                        content.set_fill_rgb(r, g, b);
+                        add_pdf_circle(&mut content, to_page(*center), radius * POINTS_PER_UNIT);
+                        content.fill_nonzero();
+                    }
+                    VectorShape::Text {
+                        center,
+                        text,
+                        size,
+                        color,
+                    } => {
+                        let (r, g, b) = pdf_color(*color);
+                        let font_size = size * POINTS_PER_UNIT;
+                        let (x, y) = to_page(*center);
+                        let width = text.chars().count() as f32 * font_size * PDF_CHAR_WIDTH;
+                        // The standard fonts only cover latin characters.
+                        let text: String = text
+                            .chars()
+                            .map(|c| if c.is_ascii() { c } else { '?' })
+                            .collect();
+                        content.set_fill_rgb(r, g, b);
+                        content
+                            .begin_text()
+                            .set_font(font_name, font_size)
+                            .next_line(x - width / 2., y - 0.35 * font_size)
+                            .show(Str(text.as_bytes()))
+                            .end_text();
+                    }
+                }
+            }
+            content.end_marked_content();
+        }
+        pdf.stream(content_id, &content.finish());
+        pdf.finish()
+    }
+}
+
+fn svg_color(color: u32) -> String {
+    let mut ret = String::from("#");
+    let _ = write!(ret, "{:06X}", color & 0xFF_FF_FF);
+    ret
+}
+
+fn pdf_color(color: u32) -> (f32, f32, f32) {
+    let channel = |shift: u32| ((color >> shift) & 0xFF) as f32 / 255.;
+    (channel(16), channel(8), channel(0))
+}
+
+/// Add a circle made of four Bézier curves to the current path of `content`.
+fn add_pdf_circle(content: &mut Content, (x, y): (f32, f32), radius: f32) {
+    // The distance of the control points for a quarter circle approximation.
+    let k = 0.552_284_8 * radius;
+    content.move_to(x + radius, y);
+    content.cubic_to(x + radius, y + k, x + k, y + radius, x, y + radius);
+    content.cubic_to(x - k, y + radius, x - radius, y + k, x - radius, y);
+    content.cubic_to(x - radius, y - k, x - k, y - radius, x, y - radius);
+    content.cubic_to(x + k, y - radius, x + radius, y - k, x + radius, y);
+    content.close_path();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawing() -> VectorDrawing {
+        let mut drawing = VectorDrawing::default();
+        drawing.add_shapes(
+            "Helices",
+            [VectorShape::Disk {
+                center: Vec2::new(-2., 1.),
+                radius: 1.5,
+                color: 0xFF_03_69_92,
+            }],
+        );
+        drawing.add_shapes(
+            "Staples & co",
+            [VectorShape::Path {
+                commands: vec![
+                    PathCommand::MoveTo(Vec2::new(0.3, 0.6)),
+                    PathCommand::LineTo(Vec2::new(10.7, 0.6)),
+                    PathCommand::CubicTo {
+                        control_1: Vec2::new(11., 2.),
+                        control_2: Vec2::new(11., 3.),
+                        to: Vec2::new(10.7, 4.6),
+                    },
+                ],
+                color: 0xFF_F7_00_C8,
+                width: 0.25,
+            }],
+        );
+        drawing.add_shapes(
+            "Helices",
+            [VectorShape::Text {
+                center: Vec2::new(-2., 1.),
+                text: "12".to_owned(),
+                size: 1.5,
+                color: 0xFF_00_00_00,
+            }],
+        );
+        drawing
+    }
+
+    #[test]
+    fn shapes_are_added_to_existing_layers() {
+        let drawing = drawing();
+        let names: Vec<_> = drawing.layers.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["Helices", "Staples & co"]);
+        assert_eq!(drawing.layers[0].shapes.len(), 2);
+    }
+
+    #[test]
+    fn svg_export_has_one_layer_per_group() {
+        let svg = drawing().to_svg();
+        assert!(svg.contains(r#"inkscape:label="Helices""#));
+        assert!(svg.contains(r#"inkscape:label="Staples &amp; co""#));
+        assert!(svg.contains("#F700C8"));
+        assert!(svg.contains("12\n</text>"));
+        assert!(svg.contains("C11,2,11,3,10.7,4.6"));
+    }
+
+    #[test]
+    fn pdf_export_declares_the_layers() {
+        let pdf = drawing().to_pdf();
+        let pdf = String::from_utf8_lossy(&pdf);
+        assert!(pdf.starts_with("%PDF-"));
+        assert!(pdf.contains("/OCProperties"));
+        assert!(pdf.contains("(Staples & co)"));
+        assert!(pdf.contains("/Helvetica"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::vector_export::PathCommand;
 use ensnano_utils::{
     bindgroup_manager::DynamicBindGroup, consts::SAMPLE_COUNT, instance::Instance,
 };
@@ -137,16 +138,32 @@ impl InsertionInstance {
             color: Instance::color_from_u32(desc.color).into(),
         }
     }
+
+    /// The loop representing the insertion, for vector exports.
+    pub(crate) fn vector_path(&self) -> Vec<PathCommand> {
+        let [control_1, control_2] =
+            LOOP_CONTROL_POINTS.map(|p| self.orientation * Vec2::from(p) + self.position);
+        vec![
+            PathCommand::MoveTo(self.position),
+            PathCommand::CubicTo {
+                control_1,
+                control_2,
+                to: self.position,
+            },
+        ]
+    }
 }
 
+/// The control points of the loop representing an insertion, which starts and ends at the origin.
+const LOOP_CONTROL_POINTS: [[f32; 2]; 2] = [[-1., 1.], [1., 1.]];
+
 type Vertices = VertexBuffers<InsertionVertex, u16>;
 
 fn make_vertices() -> Vertices {
     let mut vertices = Vertices::new();
     let mut builder = Path::builder();
     let origin = point(0., 0.);
-    let left = point(-1., 1.);
-    let right = point(1., 1.);
+    let [left, right] = LOOP_CONTROL_POINTS.map(|[x, y]| point(x, y));
 
     builder.begin(origin);
     builder.cubic_bezier_to(left, right, origin);
@@ -263,6 +263,10 @@ impl View {
         self.was_updated = true;
     }
 
+    pub fn get_show_sec(&self) -> bool {
+        self.show_sec
+    }
+
     pub fn set_show_torsion(&mut self, show: bool) {
         self.show_torsion = show;
         self.was_updated = true;
@@ -0,0 +1,380 @@
+//! A searchable list of the commands that can be triggered from the GUI.
+//!
+//! The commands of the palette do not have their own implementation: each of them is dispatched
+//! through the same [Requests] method or [LeftPanelMessage] as the corresponding keyboard shortcut
+//! or button, so that the palette stays in sync with the rest of the interface.
+
+use crate::helpers::{section, text_button};
+use ensnano_design::grid::{GridTypeDescr, lattice::Lattice};
+use ensnano_state::{gui::messages::LeftPanelMessage, requests::Requests};
+use ensnano_utils::{
+    export::VectorExportFormat,
+    graphics::SplitMode,
+    keyboard_priority::{KeyboardPriorityId, keyboard_priority},
+    keymap::{Keymap, KeymapAction, KeymapScope},
+    ui_size::UiSize,
+};
+use iced::{
+    Element, Length,
+    widget::{Column, button, column, container, row, scrollable, text, text_input},
+};
+use std::collections::VecDeque;
+
+/// The id of the text input of the palette, used to take the keyboard priority.
+const PRIORITY_ID: &str = "Command palette";
+
+/// Number of recently run commands remembered by the palette.
+const NB_RECENT_COMMANDS: usize = 5;
+
+/// The way a [PaletteCommand] is dispatched.
+#[derive(Clone)]
+pub(super) enum CommandKind {
+    /// Dispatched like the keyboard shortcut bound to the action.
+    Shortcut(KeymapAction),
+    /// Dispatched like the corresponding button of the top bar.
+    Request(fn(&mut Requests)),
+    /// Dispatched like the corresponding button of the left panel.
+    Message(LeftPanelMessage),
+}
+
+pub(super) struct PaletteCommand {
+    name: &'static str,
+    pub(super) kind: CommandKind,
+}
+
+impl PaletteCommand {
+    fn request(name: &'static str, request: fn(&mut Requests)) -> Self {
+        Self {
+            name,
+            kind: CommandKind::Request(request),
+        }
+    }
+
+    fn message(name: &'static str, message: LeftPanelMessage) -> Self {
+        Self {
+            name,
+            kind: CommandKind::Message(message),
+        }
+    }
+
+    fn shortcut(&self, keymap: &Keymap) -> Option<String> {
+        if let CommandKind::Shortcut(action) = self.kind {
+            keymap.shortcut(action)
+        } else {
+            None
+        }
+    }
+}
+
+pub(super) struct CommandPalette {
+    commands: Vec<PaletteCommand>,
+    query: String,
+    /// Indices of the most recently run commands, most recent first.
+    recent: VecDeque<usize>,
+    /// Position of the highlighted command in the list of matches.
+    highlighted: usize,
+    input_id: text_input::Id,
+    pub(super) is_open: bool,
+}
+
+impl CommandPalette {
+    pub(super) fn new() -> Self {
+        Self {
+            commands: all_commands(),
+            query: String::new(),
+            recent: VecDeque::new(),
+            highlighted: 0,
+            input_id: text_input::Id::unique(),
+            is_open: false,
+        }
+    }
+
+    pub(super) fn input_id(&self) -> text_input::Id {
+        self.input_id.clone()
+    }
+
+    pub(super) fn priority_id() -> KeyboardPriorityId {
+        KeyboardPriorityId::new(PRIORITY_ID)
+    }
+
+    pub(super) fn open(&mut self) {
+        self.is_open = true;
+        self.query.clear();
+        self.highlighted = 0;
+    }
+
+    pub(super) fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub(super) fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.highlighted = 0;
+    }
+
+    pub(super) fn move_highlight(&mut self, offset: isize) {
+        let nb_matches = self.matches().len();
+        if nb_matches > 0 {
+            self.highlighted = self
+                .highlighted
+                .saturating_add_signed(offset)
+                .min(nb_matches - 1);
+        }
+    }
+
+    /// The index of the highlighted command, if any command matches the query.
+    pub(super) fn highlighted_command(&self) -> Option<usize> {
+        self.matches().get(self.highlighted).copied()
+    }
+
+    /// Close the palette, remember that command `idx` was run and return the way to dispatch it.
+    pub(super) fn run(&mut self, idx: usize) -> Option<CommandKind> {
+        let kind = self.commands.get(idx)?.kind.clone();
+        self.recent.retain(|i| *i != idx);
+        self.recent.push_front(idx);
+        self.recent.truncate(NB_RECENT_COMMANDS);
+        self.close();
+        Some(kind)
+    }
+
+    /// The indices of the commands matching the query, best matches first.
+    ///
+    /// When the query is empty, recently run commands come first.
+    fn matches(&self) -> Vec<usize> {
+        if self.query.trim().is_empty() {
+            let mut ret: Vec<usize> = self.recent.iter().copied().collect();
+            ret.extend((0..self.commands.len()).filter(|i| !self.recent.contains(i)));
+            ret
+        } else {
+            let mut scored: Vec<(i32, usize)> = self
+                .commands
+                .iter()
+                .enumerate()
+                .filter_map(|(i, c)| fuzzy_score(self.query.trim(), c.name).map(|s| (s, i)))
+                .collect();
+            // The sort is stable so commands with the same score stay in their original order.
+            scored.sort_by_key(|(score, _)| -score);
+            scored.into_iter().map(|(_, i)| i).collect()
+        }
+    }
+
+    pub(super) fn view(&self, ui_size: UiSize, keymap: &Keymap) -> Element<'_, LeftPanelMessage> {
+        let input = keyboard_priority(
+            PRIORITY_ID,
+            LeftPanelMessage::SetKeyboardPriority,
+            text_input("Type a command…", &self.query)
+                .id(self.input_id.clone())
+                .on_input(LeftPanelMessage::CommandPaletteInput)
+                .on_submit(LeftPanelMessage::CommandPaletteSubmit),
+        );
+
+        let mut entries = Column::new().spacing(2);
+        for (pos, idx) in self.matches().into_iter().enumerate() {
+            let command = &self.commands[idx];
+            let mut label = row![text(command.name).width(Length::Fill)];
+            if let Some(shortcut) = command.shortcut(keymap) {
+                label = label.push(text(shortcut));
+            }
+            if self.query.trim().is_empty() && self.recent.contains(&idx) {
+                label = label.push(text(" (recent)"));
+            }
+            let style = if pos == self.highlighted {
+                iced::theme::Button::Primary
+            } else {
+                iced::theme::Button::Text
+            };
+            entries = entries.push(
+                button(label)
+                    .width(Length::Fill)
+                    .style(style)
+                    .on_press(LeftPanelMessage::CommandPaletteRun(idx)),
+            );
+        }
+
+        container(
+            column![
+                row![
+                    section("Commands", ui_size).width(Length::Fill),
+                    text_button("Close", ui_size).on_press(LeftPanelMessage::CloseCommandPalette),
+                ],
+                input,
+                scrollable(entries),
+            ]
+            .spacing(5),
+        )
+        .padding(5)
+        .into()
+    }
+}
+
+/// Score how well `candidate` matches `query`, or return `None` if it does not match at all.
+///
+/// The characters of `query` must appear in `candidate` in the same order, ignoring case.
+/// Consecutive matches and matches at the start of a word increase the score, while skipped
+/// characters decrease it.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous_match: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = pos + candidate[pos..].iter().position(|c| *c == q)?;
+        if previous_match.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - pos) as i32;
+        previous_match = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+fn all_commands() -> Vec<PaletteCommand> {
+    let shortcuts = KeymapAction::ALL
+        .into_iter()
+        .filter(|a| a.scope() == KeymapScope::Global && *a != KeymapAction::CommandPalette)
+        .map(|action| PaletteCommand {
+            name: action.description(),
+            kind: CommandKind::Shortcut(action),
+        });
+
+    shortcuts
+        .chain([
+            PaletteCommand::request("New empty design", Requests::new_design),
+            PaletteCommand::request("Save as…", Requests::save_as),
+            PaletteCommand::request("Reload file", Requests::reload_file),
+            PaletteCommand::request("Export…", |r| r.set_exporting(true)),
+            PaletteCommand::request("Import 3D object", Requests::import_3d_object),
+            PaletteCommand::request("Insert design from file…", Requests::insert_design),
+            PaletteCommand::request("Fit design in view", Requests::fit_design_in_scenes),
+            PaletteCommand::request("Show 3D view only", |r| {
+                r.change_split_mode(SplitMode::Scene3D);
+            }),
+            PaletteCommand::request("Show 2D view only", |r| {
+                r.change_split_mode(SplitMode::Flat);
+            }),
+            PaletteCommand::request("Show 2D and 3D views", |r| {
+                r.change_split_mode(SplitMode::Both);
+            }),
+            PaletteCommand::request("Toggle 2D/3D view", Requests::toggle_2d),
+            PaletteCommand::request("Split 2D view", Requests::toggle_2d_view_split),
+            PaletteCommand::request("Flip split views", Requests::flip_split_views),
+            PaletteCommand::request("Align camera with horizon", Requests::align_horizon),
+            PaletteCommand::request("Help", Requests::force_help),
+            PaletteCommand::request("Tutorials", Requests::show_tutorial),
+            PaletteCommand::message(
+                "New square grid",
+                LeftPanelMessage::NewGrid(GridTypeDescr::Square { twist: None }),
+            ),
+            PaletteCommand::message(
+                "New honeycomb grid",
+                LeftPanelMessage::NewGrid(GridTypeDescr::Honeycomb { twist: None }),
+            ),
+            PaletteCommand::message(
+                "New rotated honeycomb grid",
+                LeftPanelMessage::NewGrid(GridTypeDescr::RotatedHoneycomb { twist: None }),
+            ),
+            PaletteCommand::message(
+                "New hexagonal close-packed grid",
+                LeftPanelMessage::NewGrid(GridTypeDescr::Lattice {
+                    lattice: Lattice::hexagonal_close_packed(),
+                    twist: None,
+                }),
+            ),
+            PaletteCommand::message(
+                "New hybrid lattice grid",
+                LeftPanelMessage::NewGrid(GridTypeDescr::Lattice {
+                    lattice: Lattice::hybrid(),
+                    twist: None,
+                }),
+            ),
+            PaletteCommand::message(
+                "New gridiron lattice grid",
+                LeftPanelMessage::NewGrid(GridTypeDescr::Lattice {
+                    lattice: Lattice::gridiron(),
+                    twist: None,
+                }),
+            ),
+            PaletteCommand::message(
+                "New grid from lattice file",
+                LeftPanelMessage::ImportLattice,
+            ),
+            PaletteCommand::message("New nanotube", LeftPanelMessage::NewHyperboloid),
+            PaletteCommand::message("Edit selected nanotube", LeftPanelMessage::EditHyperboloid),
+            PaletteCommand::message("Finalize nanotube", LeftPanelMessage::FinalizeHyperboloid),
+            PaletteCommand::message("Cancel nanotube", LeftPanelMessage::CancelHyperboloid),
+            PaletteCommand::message("New parametric helix", LeftPanelMessage::NewParametricHelix),
+            PaletteCommand::message(
+                "Finish parametric helix",
+                LeftPanelMessage::FinishParametricHelix,
+            ),
+            PaletteCommand::message(
+                "Make grids from selected helices",
+                LeftPanelMessage::MakeGrids,
+            ),
+            PaletteCommand::message("New Bézier plane", LeftPanelMessage::NewBezierPlane),
+            PaletteCommand::message("Start Bézier path", LeftPanelMessage::StartBezierPath),
+            PaletteCommand::message("Load SVG file", LeftPanelMessage::LoadSvgFile),
+            PaletteCommand::message("Import helix axes", LeftPanelMessage::ImportHelixAxes),
+            PaletteCommand::message(
+                "Make wireframe from 3D object",
+                LeftPanelMessage::MakeWireframe,
+            ),
+            PaletteCommand::message(
+                "Fill 3D object with helices",
+                LeftPanelMessage::VoxelizeMesh,
+            ),
+            PaletteCommand::message("Open script console", LeftPanelMessage::OpenScriptConsole),
+            PaletteCommand::message("Show undo history", LeftPanelMessage::OpenUndoHistory),
+            PaletteCommand::message("Run script file", LeftPanelMessage::RunScriptFile),
+            PaletteCommand::message(
+                "Set scaffold from selection",
+                LeftPanelMessage::SelectScaffold,
+            ),
+            PaletteCommand::message(
+                "Set scaffold sequence",
+                LeftPanelMessage::SetScaffoldSeqButtonPressed,
+            ),
+            PaletteCommand::message(
+                "Optimize scaffold starting position",
+                LeftPanelMessage::OptimizeScaffoldShiftPressed,
+            ),
+            PaletteCommand::message("Export staples", LeftPanelMessage::StaplesRequested),
+            PaletteCommand::message("Export origamis", LeftPanelMessage::OrigamisRequested),
+            PaletteCommand::message("Export STL", LeftPanelMessage::StlExport),
+            PaletteCommand::message(
+                "Save nucleotides positions",
+                LeftPanelMessage::SaveNucleotidesPositions,
+            ),
+            PaletteCommand::message("2D screenshot", LeftPanelMessage::ScreenShot2D),
+            PaletteCommand::message(
+                "Export 2D view as SVG",
+                LeftPanelMessage::VectorExport2D(VectorExportFormat::Svg),
+            ),
+            PaletteCommand::message(
+                "Export 2D view as PDF",
+                LeftPanelMessage::VectorExport2D(VectorExportFormat::Pdf),
+            ),
+            PaletteCommand::message("3D screenshot", LeftPanelMessage::ScreenShot3D),
+            PaletteCommand::message("Make all elements visible", LeftPanelMessage::AllVisible),
+            PaletteCommand::message(
+                "Toggle external objects visibility",
+                LeftPanelMessage::ToggleExternalObjectsVisibility,
+            ),
+            PaletteCommand::message(
+                "Start rigid helices simulation",
+                LeftPanelMessage::RigidHelicesSimulation(true),
+            ),
+            PaletteCommand::message(
+                "Start rigid grids simulation",
+                LeftPanelMessage::RigidGridSimulation(true),
+            ),
+            PaletteCommand::message("Start twist simulation", LeftPanelMessage::StartTwist),
+            PaletteCommand::message("Stop simulation", LeftPanelMessage::StopSimulation),
+            PaletteCommand::message("Reset simulation", LeftPanelMessage::ResetSimulation),
+        ])
+        .collect()
+}
@@ -14,12 +14,9 @@ use ensnano_state::{
 };
 use ensnano_utils::{
     SimulationState,
-    consts::{
-        ALT, BACKSPACE_CHAR, CTRL, HELIX_CHAR, KEY_DOWN, KEY_LEFT, KEY_RIGHT, KEY_UP, L_CLICK,
-        M_CLICK, MOVE_CHAR, NUCL_CHAR, R_CLICK, ROT_CHAR, SELECT_CHAR, SHIFT, STRAND_CHAR,
-        SUPPR_CHAR,
-    },
+    consts::{ALT, CTRL, L_CLICK, M_CLICK, MOVE_CHAR, R_CLICK, ROT_CHAR, SHIFT},
     keyboard_priority::keyboard_priority,
+    keymap::{Keymap, KeymapScope},
     ui_size::UiSize,
 };
 use iced::{
@@ -289,11 +286,11 @@ impl ContextualPanel {
                 ],
             ]
         } else if self.force_help && xover_len.is_none() {
-            turn_into_help_column(ui_size)
+            turn_into_help_column(ui_size, app_state.get_keymap())
         } else if app_state.get_action_mode().is_build() {
             self.add_strand_menu.view(ui_size, self.width)
         } else if *selection == Selection::Nothing && xover_len.is_none() {
-            turn_into_help_column(ui_size)
+            turn_into_help_column(ui_size, app_state.get_keymap())
         } else if nb_selected > 1 {
             // NOTE: When the number of objects selected is greater than one,
             //       we only print the number of object selected.
@@ -332,9 +329,22 @@ impl ContextualPanel {
                 Selection::Strand(_, _) => {
                     column = column.push(add_strand_content(info_values.clone(), ui_size));
                 }
-                Selection::Nucleotide(_, _) => {
+                Selection::Nucleotide(_, nucl) => {
                     let anchor = info_values[0].clone();
                     column = column.push(text(format!("Anchor {anchor}")));
+                    let nucl = *nucl;
+                    column = column.push(
+                        checkbox(
+                            "Deletion (skip)",
+                            app_state.get_reader().has_deletion_at(&nucl),
+                        )
+                        .on_toggle(move |deleted| LeftPanelMessage::SetDeletion {
+                            helix: nucl.helix,
+                            position: nucl.position,
+                            deleted,
+                        })
+                        .size(ui_size.checkbox()),
+                    );
                 }
                 Selection::Xover(_, _) => {
                     if xover_len.is_none() {
@@ -591,17 +601,55 @@ fn add_help_to_column<'a>(
     ]
 }
 
-fn turn_into_help_column<'a>(ui_size: UiSize) -> Column<'a, LeftPanelMessage> {
-    column![
+fn turn_into_help_column<'a>(ui_size: UiSize, keymap: &Keymap) -> Column<'a, LeftPanelMessage> {
+    let mut ret = column![
         section("Help", ui_size)
             .width(Length::Fill)
             .horizontal_alignment(Horizontal::Center),
-        add_help_to_column("3D view", view_3d_help(), ui_size),
-        Space::with_width(15),
-        add_help_to_column("2D/3D view", view_2d_3d_help(), ui_size),
-        Space::with_width(15),
-        add_help_to_column("2D view", view_2d_help(), ui_size),
-    ]
+    ];
+    let conflicts = keymap.conflicts();
+    if !conflicts.is_empty() {
+        ret = ret.push(add_help_to_column(
+            "Keymap conflicts",
+            conflicts
+                .iter()
+                .map(|c| (c.to_string(), String::new()))
+                .collect(),
+            ui_size,
+        ));
+        ret = ret.push(Space::with_width(15));
+    }
+    ret.push(add_help_to_column(
+        KeymapScope::Scene3D.title(),
+        with_shortcuts(view_3d_help(), keymap, KeymapScope::Scene3D),
+        ui_size,
+    ))
+    .push(Space::with_width(15))
+    .push(add_help_to_column(
+        KeymapScope::Global.title(),
+        with_shortcuts(Vec::new(), keymap, KeymapScope::Global),
+        ui_size,
+    ))
+    .push(Space::with_width(15))
+    .push(add_help_to_column(
+        KeymapScope::Scene2D.title(),
+        with_shortcuts(view_2d_help(), keymap, KeymapScope::Scene2D),
+        ui_size,
+    ))
+}
+
+/// Append the cheat sheet of the keyboard shortcuts of `scope` to the mouse help.
+fn with_shortcuts(
+    mut help: Vec<(String, String)>,
+    keymap: &Keymap,
+    scope: KeymapScope,
+) -> Vec<(String, String)> {
+    if !help.is_empty() {
+        help.push((String::new(), String::new()));
+    }
+    help.push(("Keyboard shortcuts".to_owned(), String::new()));
+    help.extend(keymap.cheat_sheet(scope));
+    help
 }
 
 fn view_3d_help() -> Vec<(String, String)> {
@@ -662,34 +710,6 @@ fn view_3d_help() -> Vec<(String, String)> {
     ]
 }
 
-fn view_2d_3d_help() -> Vec<(String, String)> {
-    vec![
-        (format!("{CTRL} + C"), "Copy selection".to_owned()),
-        (format!("{CTRL} + V"), "Paste".to_owned()),
-        (format!("{CTRL} + J"), "Paste & repeat".to_owned()),
-        (String::new(), String::new()),
-        (
-            format!("{SUPPR_CHAR} or {BACKSPACE_CHAR}"),
-            "Delete selected strands".to_owned(),
-        ),
-        (String::new(), String::new()),
-        (format!("{CTRL} + S"), "Save design".to_owned()),
-        (format!("{CTRL} + O"), "Open design".to_owned()),
-        (format!("{CTRL} + Z"), "Undo".to_owned()),
-        (format!("{CTRL} + R"), "Redo".to_owned()),
-        (String::new(), String::new()),
-        ("Selection mode shortcuts".to_owned(), String::new()),
-        ("'N' key".to_owned(), format!("Nucleotide, ({NUCL_CHAR})")),
-        ("'S' key".to_owned(), format!("Strand ({STRAND_CHAR})")),
-        ("'H' key".to_owned(), format!("Helix ({HELIX_CHAR})")),
-        (String::new(), String::new()),
-        ("Action mode shortcuts".to_owned(), String::new()),
-        ("ESC".to_owned(), format!("Select ({SELECT_CHAR})")),
-        ("'T' key".to_owned(), format!("Translation ({MOVE_CHAR})")),
-        ("'R' key".to_owned(), format!("Rotation ({ROT_CHAR})")),
-    ]
-}
-
 fn view_2d_help() -> Vec<(String, String)> {
     vec![
         (format!("{M_CLICK} Drag"), "Translate camera".to_owned()),
@@ -697,14 +717,6 @@ fn view_2d_help() -> Vec<(String, String)> {
             format!("{ALT} + {L_CLICK} Drag"),
             "Translate camera".to_owned(),
         ),
-        (
-            format!("{ALT} + {KEY_LEFT}/{KEY_RIGHT}"),
-            "Tilt camera".to_owned(),
-        ),
-        (
-            format!("{CTRL} + {KEY_LEFT}/{KEY_RIGHT}/{KEY_UP}/{KEY_DOWN}",),
-            "Apply symmetry to view".to_owned(),
-        ),
         (String::new(), String::new()),
         (format!("{L_CLICK}"), "Select".to_owned()),
         (format!("{SHIFT} + {L_CLICK}"), "Multiple Select".to_owned()),
@@ -98,6 +98,13 @@ impl<R: Requestable> RequestFactory<R> {
         self.requestable.request_from_values(&values)
     }
 
+    /// Set all the values at once, in the order of their ids.
+    pub(super) fn set_values(&mut self, values: &[f32]) {
+        for (value, new_val) in self.values.values_mut().zip(values) {
+            value.update_value(*new_val);
+        }
+    }
+
     pub(super) fn make_request(&self, request: &mut Option<R::Request>) {
         let values: Vec<f32> = self.values.values().map(DiscreteValue::get_value).collect();
         self.requestable.make_request(&values, request);
@@ -14,6 +14,7 @@ impl ExportMenu {
             button("Oxdna").on_press(LeftPanelMessage::Export(ExportType::Oxdna)),
             button("Pdb").on_press(LeftPanelMessage::Export(ExportType::Pdb)),
             button("Cadnano").on_press(LeftPanelMessage::Export(ExportType::Cadnano)),
+            button("Scadnano").on_press(LeftPanelMessage::Export(ExportType::Scadnano)),
         ];
 
         scrollable(content).into()
@@ -0,0 +1,80 @@
+//! A list of the operations that can be undone or redone.
+//!
+//! Clicking on an entry of the list undoes or redoes operations until the state that followed
+//! that entry is reached.
+
+use crate::helpers::{section, text_button};
+use ensnano_state::{app_state::transitions::UndoHistory, gui::messages::LeftPanelMessage};
+use ensnano_utils::ui_size::UiSize;
+use iced::{
+    Element, Length,
+    widget::{Column, button, column, container, row, scrollable, text},
+};
+use std::cmp::Ordering;
+
+const BYTES_PER_MB: usize = 1 << 20;
+
+#[derive(Default)]
+pub(super) struct HistoryPanel {
+    history: UndoHistory,
+    pub(super) is_open: bool,
+}
+
+impl HistoryPanel {
+    pub(super) fn set_history(&mut self, history: UndoHistory) {
+        self.history = history;
+    }
+
+    pub(super) fn view(&self, ui_size: UiSize) -> Element<'_, LeftPanelMessage> {
+        let first_label = if self.history.nb_evicted > 0 {
+            "Oldest kept state"
+        } else {
+            "Initial state"
+        };
+        let labels =
+            std::iter::once(first_label).chain(self.history.labels.iter().map(String::as_str));
+
+        let mut entries = Column::new().spacing(2);
+        for (position, label) in labels.enumerate() {
+            let style = match position.cmp(&self.history.position) {
+                Ordering::Less => iced::theme::Button::Text,
+                Ordering::Equal => iced::theme::Button::Primary,
+                // Operations that have been undone.
+                Ordering::Greater => iced::theme::Button::Secondary,
+            };
+            entries = entries.push(
+                button(text(label).size(ui_size.main_text()))
+                    .width(Length::Fill)
+                    .style(style)
+                    .on_press(LeftPanelMessage::JumpInHistory(position)),
+            );
+        }
+
+        let mut content = column![
+            row![
+                section("History", ui_size).width(Length::Fill),
+                text_button("Close", ui_size).on_press(LeftPanelMessage::CloseUndoHistory),
+            ],
+            text(format!(
+                "Memory used: {} MB / {} MB",
+                self.history.memory_usage.div_ceil(BYTES_PER_MB),
+                self.history.memory_budget / BYTES_PER_MB,
+            ))
+            .size(ui_size.main_text()),
+        ]
+        .spacing(5);
+        if self.history.nb_evicted > 0 {
+            content = content.push(
+                text(format!(
+                    "{} older operations were dropped to save memory",
+                    self.history.nb_evicted
+                ))
+                .size(ui_size.main_text()),
+            );
+        }
+
+        container(content.push(scrollable(entries)))
+            .padding(5)
+            .into()
+    }
+}
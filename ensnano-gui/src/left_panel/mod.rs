@@ -1,16 +1,22 @@
 mod color_picker;
+mod command_palette;
 mod contextual_panel;
 mod discrete_value;
 mod export_menu;
+mod history_panel;
 mod organizer;
+mod script_console;
 pub mod tabs;
 
 use self::{
     color_picker::HueColorPicker,
+    command_palette::{CommandKind, CommandPalette},
     contextual_panel::ContextualPanel,
     discrete_value::Requestable,
     export_menu::ExportMenu,
+    history_panel::HistoryPanel,
     organizer::Organizer,
+    script_console::ScriptConsole,
     tabs::{
         GuiTab as _, camera_shortcut::CameraShortcutPanel, camera_tab::CameraTab,
         edition_tab::EditionTab, grids_tab::GridTab, parameters_tab::ParametersTab,
@@ -23,7 +29,13 @@ use crate::{
     theme::GuiBackground,
 };
 use ensnano_design::{
-    design_element::DesignElementKey, interaction_modes::ActionMode, organizer_tree::OrganizerTree,
+    bezier_plane::import_from_svg::SvgImportParameters,
+    curves::import_from_points::AxisImportParameters,
+    design_element::DesignElementKey,
+    interaction_modes::ActionMode,
+    organizer_tree::OrganizerTree,
+    voxelization::VoxelizationParameters,
+    wireframe::{MIN_EDGE_LENGTH, WireframeParameters},
 };
 use ensnano_state::{
     app_state::AppState,
@@ -37,9 +49,11 @@ use ensnano_state::{
         state::RevolutionParameterId,
     },
     requests::Requests,
+    simulation_presets::SimulationPreset,
 };
 use ensnano_utils::{
-    app_state_parameters::AppStateParameters, overlay::OverlayType, ui_size::UiSize,
+    app_state_parameters::AppStateParameters, keyboard_priority::PriorityRequest,
+    overlay::OverlayType, ui_size::UiSize,
 };
 use iced::{
     Command, Element, Length,
@@ -50,8 +64,10 @@ use iced_runtime::Program;
 use std::{
     collections::BTreeSet,
     f32::consts::PI,
+    num::NonZeroUsize,
     sync::{Arc, Mutex},
 };
+use ultraviolet::Vec2;
 use winit::dpi::{LogicalPosition, LogicalSize};
 
 pub struct LeftPanelState {
@@ -76,6 +92,9 @@ pub struct LeftPanelState {
     // mutated somewhere, there is no synchronization.
     application_state: AppState,
     exports_menu: ExportMenu,
+    command_palette: CommandPalette,
+    script_console: ScriptConsole,
+    history_panel: HistoryPanel,
 }
 
 impl LeftPanelState {
@@ -113,6 +132,9 @@ impl LeftPanelState {
             camera_shortcut: CameraShortcutPanel::new(logical_size.width as u16),
             application_state: state.clone(),
             exports_menu: Default::default(),
+            command_palette: CommandPalette::new(),
+            script_console: Default::default(),
+            history_panel: Default::default(),
         }
     }
 
@@ -129,6 +151,47 @@ impl LeftPanelState {
         self.organizer.set_width(logical_size.width as u16);
     }
 
+    fn close_command_palette(&mut self) {
+        self.command_palette.close();
+        self.requests
+            .lock()
+            .unwrap()
+            .set_keyboard_priority(PriorityRequest {
+                id: CommandPalette::priority_id(),
+                taking: false,
+            });
+    }
+
+    fn close_script_console(&mut self) {
+        self.script_console.is_open = false;
+        self.requests
+            .lock()
+            .unwrap()
+            .set_keyboard_priority(PriorityRequest {
+                id: ScriptConsole::priority_id(),
+                taking: false,
+            });
+    }
+
+    /// Run the command of the palette with index `idx`, the same way as the corresponding button
+    /// or keyboard shortcut.
+    fn run_palette_command(&mut self, idx: usize) -> Command<LeftPanelMessage> {
+        let kind = self.command_palette.run(idx);
+        self.close_command_palette();
+        match kind {
+            Some(CommandKind::Shortcut(action)) => {
+                self.requests.lock().unwrap().apply_keymap_action(action);
+                Command::none()
+            }
+            Some(CommandKind::Request(request)) => {
+                request(&mut self.requests.lock().unwrap());
+                Command::none()
+            }
+            Some(CommandKind::Message(message)) => self.update(message),
+            None => Command::none(),
+        }
+    }
+
     /// Convert an [OrganizerMessage] into a LeftPanel [Message].
     fn organizer_message(&mut self, m: OrganizerMessage) -> Option<LeftPanelMessage> {
         match m {
@@ -184,6 +247,11 @@ impl LeftPanelState {
                 .unwrap()
                 .set_keyboard_priority(priority),
             OrganizerMessage::SetFocus(id) => return Some(LeftPanelMessage::SetFocus(id)),
+            OrganizerMessage::NewDrawingStyle(group_id, style) => self
+                .requests
+                .lock()
+                .unwrap()
+                .set_group_drawing_style(group_id, style),
             OrganizerMessage::ElementUpdate(_) => (),
         }
         None
@@ -210,6 +278,7 @@ impl Program for LeftPanelState {
                     children: vec![],
                     expanded: true,
                     id: None,
+                    rule: None,
                 })
             };
         if notify_new_tree {
@@ -388,6 +457,22 @@ impl Program for LeftPanelState {
                     }
                     Command::none()
                 }
+                FactoryId::SvgImport => {
+                    self.pen_tab.update_svg_import_value(value_id, value);
+                    Command::none()
+                }
+                FactoryId::AxisImport => {
+                    self.grid_tab.update_axis_import_value(value_id, value);
+                    Command::none()
+                }
+                FactoryId::Wireframe => {
+                    self.grid_tab.update_wireframe_value(value_id, value);
+                    Command::none()
+                }
+                FactoryId::Voxelization => {
+                    self.grid_tab.update_voxelization_value(value_id, value);
+                    Command::none()
+                }
                 FactoryId::Brownian => {
                     let mut request = None;
                     self.simulation_tab
@@ -436,10 +521,105 @@ impl Program for LeftPanelState {
                 }
                 Command::none()
             }
+            LeftPanelMessage::EditHyperboloid => {
+                let mut request = None;
+                self.grid_tab
+                    .edit_hyperboloid(&self.application_state, &mut request);
+                if let Some((grid_id, request)) = request {
+                    self.requests
+                        .lock()
+                        .unwrap()
+                        .edit_hyperboloid(grid_id, request);
+                }
+                Command::none()
+            }
             LeftPanelMessage::FinalizeHyperboloid => {
                 self.requests.lock().unwrap().finalize_hyperboloid();
                 Command::none()
             }
+            LeftPanelMessage::ParametricHelixInput(field, text) => {
+                if let Some(operation) = self.grid_tab.update_parametric_helix_input(
+                    field,
+                    text,
+                    &self.application_state,
+                ) {
+                    self.requests
+                        .lock()
+                        .unwrap()
+                        .update_current_operation(Arc::new(operation));
+                }
+                Command::none()
+            }
+            LeftPanelMessage::NewParametricHelix => {
+                if let Some(operation) = self.grid_tab.new_parametric_helix() {
+                    self.requests
+                        .lock()
+                        .unwrap()
+                        .update_current_operation(Arc::new(operation));
+                }
+                Command::none()
+            }
+            LeftPanelMessage::FinishParametricHelix => {
+                self.grid_tab.finish_parametric_helix();
+                self.requests.lock().unwrap().finish_pending_operation();
+                Command::none()
+            }
+            LeftPanelMessage::ImportLattice => {
+                self.requests.lock().unwrap().import_lattice();
+                Command::none()
+            }
+            LeftPanelMessage::ImportHelixAxes => {
+                let parameters = self.grid_tab.axis_import_parameters();
+                self.requests.lock().unwrap().import_helix_axes(parameters);
+                Command::none()
+            }
+            LeftPanelMessage::SetAxisResampling(resample) => {
+                self.grid_tab.set_axis_resampling(resample);
+                Command::none()
+            }
+            LeftPanelMessage::SetMeshObject(object_id) => {
+                self.grid_tab.set_mesh_object(object_id);
+                Command::none()
+            }
+            LeftPanelMessage::SetWireframeTwoHelices(two_helices) => {
+                self.grid_tab.set_wireframe_two_helices(two_helices);
+                Command::none()
+            }
+            LeftPanelMessage::MakeWireframe => {
+                if let Some((object_id, path)) = self.grid_tab.mesh_object(&self.application_state)
+                {
+                    let parameters = self.grid_tab.wireframe_parameters();
+                    self.requests
+                        .lock()
+                        .unwrap()
+                        .make_wireframe(object_id, path, parameters);
+                }
+                Command::none()
+            }
+            LeftPanelMessage::SetVoxelizationGridType(grid_type) => {
+                self.grid_tab.set_voxelization_grid_type(grid_type);
+                Command::none()
+            }
+            LeftPanelMessage::SetVoxelizationAxis(axis) => {
+                self.grid_tab.set_voxelization_axis(axis);
+                Command::none()
+            }
+            LeftPanelMessage::SetVoxelizationScaffoldRouting(route_scaffold) => {
+                self.grid_tab
+                    .set_voxelization_scaffold_routing(route_scaffold);
+                Command::none()
+            }
+            LeftPanelMessage::VoxelizeMesh => {
+                if let Some((object_id, path)) = self.grid_tab.mesh_object(&self.application_state)
+                {
+                    let parameters = self.grid_tab.voxelization_parameters();
+                    self.requests
+                        .lock()
+                        .unwrap()
+                        .voxelize_mesh(object_id, path, parameters);
+                }
+                Command::none()
+            }
             LeftPanelMessage::RigidGridSimulation(start) => {
                 if start {
                     let mut request: Option<RigidBodyParametersRequest> = None;
@@ -659,6 +839,16 @@ impl Program for LeftPanelState {
                     self.organizer.notify_selection(selected_group);
                     self.contextual_panel.state_updated();
                 }
+                if state.design_was_modified(&self.application_state)
+                    || state.selection_was_updated(&self.application_state)
+                {
+                    let selected_group = state.get_selected_group();
+                    let style = selected_group
+                        .and_then(|g_id| state.get_reader().get_group_attributes(g_id).cloned())
+                        .map(|attributes| attributes.drawing_style)
+                        .unwrap_or_default();
+                    self.organizer.set_style_target(selected_group, style);
+                }
                 if state.get_action_mode() != self.application_state.get_action_mode() {
                     self.contextual_panel.state_updated();
                 }
@@ -757,6 +947,106 @@ impl Program for LeftPanelState {
                 self.requests.lock().unwrap().stop_simulations();
                 Command::none()
             }
+            LeftPanelMessage::RelaxationFinished => {
+                self.simulation_tab.rapier_parameters.is_simulation_running = false;
+                Command::none()
+            }
+            LeftPanelMessage::NewTrajectory(trajectory) => {
+                self.simulation_tab.trajectory = trajectory;
+                Command::none()
+            }
+            LeftPanelMessage::SetTrajectoryRecording(recording) => {
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .set_trajectory_recording(recording);
+                Command::none()
+            }
+            LeftPanelMessage::SetTrajectoryInterval(steps) => {
+                if let Some(interval) = NonZeroUsize::new(steps as usize) {
+                    self.requests
+                        .lock()
+                        .unwrap()
+                        .set_trajectory_interval(interval);
+                }
+                Command::none()
+            }
+            LeftPanelMessage::ShowTrajectoryFrame(frame) => {
+                self.requests.lock().unwrap().show_trajectory_frame(frame);
+                Command::none()
+            }
+            LeftPanelMessage::ClearTrajectory => {
+                self.requests.lock().unwrap().clear_trajectory();
+                Command::none()
+            }
+            LeftPanelMessage::ExportTrajectory(export_type) => {
+                self.requests.lock().unwrap().export_trajectory(export_type);
+                Command::none()
+            }
+            LeftPanelMessage::NewSimulationParameters(parameters) => {
+                if !self.simulation_tab.rapier_parameters.is_simulation_running {
+                    self.simulation_tab.set_simulation_parameters(&parameters);
+                }
+                Command::none()
+            }
+            LeftPanelMessage::NewSimulationPresets(presets) => {
+                self.simulation_tab.presets = presets;
+                Command::none()
+            }
+            LeftPanelMessage::ApplySimulationPreset(name) => {
+                if let Some(preset) = self.simulation_tab.get_preset(&name).cloned() {
+                    self.simulation_tab
+                        .set_simulation_parameters(&preset.parameters);
+                    self.simulation_tab.preset_name = name;
+                    let mut request: Option<RigidBodyParametersRequest> = None;
+                    self.simulation_tab.make_rigid_body_request(&mut request);
+                    let mut requests = self.requests.lock().unwrap();
+                    requests.request_rapier_simulation(self.simulation_tab.rapier_parameters);
+                    if let Some(request) = request {
+                        requests.update_rigid_body_simulation_parameters(request);
+                    }
+                }
+                Command::none()
+            }
+            LeftPanelMessage::SimulationPresetNameInput(name) => {
+                self.simulation_tab.preset_name = name;
+                Command::none()
+            }
+            LeftPanelMessage::SaveSimulationPreset => {
+                let preset = SimulationPreset {
+                    name: self.simulation_tab.preset_name.trim().to_owned(),
+                    parameters: self.simulation_tab.simulation_parameters(),
+                    built_in: false,
+                };
+                self.requests.lock().unwrap().save_simulation_preset(preset);
+                Command::none()
+            }
+            LeftPanelMessage::DeleteSimulationPreset => {
+                let name = self.simulation_tab.preset_name.clone();
+                self.requests.lock().unwrap().delete_simulation_preset(name);
+                Command::none()
+            }
+            LeftPanelMessage::AnalyseRigidity => {
+                self.requests.lock().unwrap().analyse_rigidity();
+                Command::none()
+            }
+            LeftPanelMessage::ClearRigidityAnalysis => {
+                self.requests.lock().unwrap().clear_rigidity_analysis();
+                Command::none()
+            }
+            LeftPanelMessage::ApplyEquilibriumShape => {
+                self.requests.lock().unwrap().apply_equilibrium_shape();
+                Command::none()
+            }
+            LeftPanelMessage::CommitRelaxation => {
+                self.simulation_tab.rapier_parameters.is_simulation_running = false;
+                self.requests.lock().unwrap().commit_relaxation();
+                Command::none()
+            }
+            LeftPanelMessage::ClearRelaxedPositions => {
+                self.requests.lock().unwrap().clear_relaxed_positions();
+                Command::none()
+            }
             LeftPanelMessage::StartTwist => {
                 if let Some(Selection::Grid(_, g_id)) =
                     self.application_state.get_selection().first()
@@ -801,6 +1091,17 @@ impl Program for LeftPanelState {
                 }
                 Command::none()
             }
+            LeftPanelMessage::SetDeletion {
+                helix,
+                position,
+                deleted,
+            } => {
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .set_deletion(helix, position, deleted);
+                Command::none()
+            }
             LeftPanelMessage::NewBezierPlane => {
                 self.requests.lock().unwrap().create_bezier_plane();
                 Command::none()
@@ -901,7 +1202,12 @@ impl Program for LeftPanelState {
                 Command::none()
             }
             LeftPanelMessage::LoadSvgFile => {
-                self.requests.lock().unwrap().load_svg();
+                let parameters = self.pen_tab.svg_import_parameters(&self.application_state);
+                self.requests.lock().unwrap().load_svg(parameters);
+                Command::none()
+            }
+            LeftPanelMessage::SetSvgImportPlane(plane_id) => {
+                self.pen_tab.set_svg_import_plane(plane_id);
                 Command::none()
             }
             LeftPanelMessage::StlExport => {
@@ -912,6 +1218,13 @@ impl Program for LeftPanelState {
                 self.requests.lock().unwrap().request_screenshot_2d();
                 Command::none()
             }
+            LeftPanelMessage::VectorExport2D(format) => {
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .request_vector_export_2d(format);
+                Command::none()
+            }
             LeftPanelMessage::ScreenShot3D => {
                 self.requests.lock().unwrap().request_screenshot_3d();
                 Command::none()
@@ -984,6 +1297,97 @@ impl Program for LeftPanelState {
                     .toggle_external_objects_visibility();
                 Command::none()
             }
+            LeftPanelMessage::OpenCommandPalette => {
+                self.command_palette.open();
+                // The palette is usually opened while the cursor is over one of the views, so the
+                // keyboard priority must be taken without waiting for an event on the text input.
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .set_keyboard_priority(PriorityRequest {
+                        id: CommandPalette::priority_id(),
+                        taking: true,
+                    });
+                text_input::focus(self.command_palette.input_id())
+            }
+            LeftPanelMessage::CloseCommandPalette => {
+                if self.command_palette.is_open {
+                    self.close_command_palette();
+                }
+                Command::none()
+            }
+            LeftPanelMessage::CommandPaletteInput(query) => {
+                self.command_palette.set_query(query);
+                Command::none()
+            }
+            LeftPanelMessage::CommandPaletteMove(offset) => {
+                if self.command_palette.is_open {
+                    self.command_palette.move_highlight(offset);
+                }
+                Command::none()
+            }
+            LeftPanelMessage::CommandPaletteSubmit => {
+                if let Some(idx) = self.command_palette.highlighted_command() {
+                    self.run_palette_command(idx)
+                } else {
+                    Command::none()
+                }
+            }
+            LeftPanelMessage::CommandPaletteRun(idx) => self.run_palette_command(idx),
+            LeftPanelMessage::OpenScriptConsole => {
+                self.script_console.is_open = true;
+                // The text editor does not report its focus, so the keyboard priority is held
+                // for as long as the console is open.
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .set_keyboard_priority(PriorityRequest {
+                        id: ScriptConsole::priority_id(),
+                        taking: true,
+                    });
+                Command::none()
+            }
+            LeftPanelMessage::CloseScriptConsole => {
+                if self.script_console.is_open {
+                    self.close_script_console();
+                }
+                Command::none()
+            }
+            LeftPanelMessage::ScriptEdit(action) => {
+                self.script_console.edit(action);
+                Command::none()
+            }
+            LeftPanelMessage::RunScript => {
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .run_script(self.script_console.source());
+                Command::none()
+            }
+            LeftPanelMessage::RunScriptFile => {
+                self.requests.lock().unwrap().run_script_file();
+                Command::none()
+            }
+            LeftPanelMessage::ScriptOutput(output) => {
+                self.script_console.set_output(output);
+                Command::none()
+            }
+            LeftPanelMessage::NewUndoHistory(history) => {
+                self.history_panel.set_history(history);
+                Command::none()
+            }
+            LeftPanelMessage::OpenUndoHistory => {
+                self.history_panel.is_open = true;
+                Command::none()
+            }
+            LeftPanelMessage::CloseUndoHistory => {
+                self.history_panel.is_open = false;
+                Command::none()
+            }
+            LeftPanelMessage::JumpInHistory(position) => {
+                self.requests.lock().unwrap().jump_in_history(position);
+                Command::none()
+            }
         };
 
         let command = Command::batch(vec![
@@ -1080,7 +1484,16 @@ impl Program for LeftPanelState {
             .view(selection)
             .map(LeftPanelMessage::OrganizerMessage);
 
-        let first_container = if self.application_state.is_exporting() {
+        let first_container = if self.command_palette.is_open {
+            container(
+                self.command_palette
+                    .view(self.ui_size, self.application_state.get_keymap()),
+            )
+        } else if self.history_panel.is_open {
+            container(self.history_panel.view(self.ui_size))
+        } else if self.script_console.is_open {
+            container(self.script_console.view(self.ui_size))
+        } else if self.application_state.is_exporting() {
             container(self.exports_menu.view())
         } else {
             container(tabs)
@@ -1301,6 +1714,253 @@ impl Requestable for ScrollSensitivity {
     }
 }
 
+/// The scale and origin used to import SVG files.
+pub struct SvgImport;
+
+impl Requestable for SvgImport {
+    type Request = SvgImportParameters;
+
+    fn request_from_values(&self, values: &[f32]) -> SvgImportParameters {
+        let &[scale, origin_x, origin_y] = values else {
+            panic!("expected 3 inputs to SvgImport::request_from_values")
+        };
+
+        SvgImportParameters {
+            scale,
+            origin: Vec2::new(origin_x, origin_y),
+            ..Default::default()
+        }
+    }
+
+    fn nb_values(&self) -> usize {
+        3
+    }
+
+    fn initial_value(&self, n: usize) -> f32 {
+        match n {
+            0 => SvgImportParameters::default().scale,
+            1 | 2 => 0f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn min_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 0.01,
+            1 | 2 => -2000f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn max_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 2f32,
+            1 | 2 => 2000f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn step_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 0.01,
+            1 | 2 => 1f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn name_val(&self, n: usize) -> String {
+        match n {
+            0 => String::from("Scale (nm/unit)"),
+            1 => String::from("Origin x"),
+            2 => String::from("Origin y"),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// The unit and smoothing used to import helix axes from files of points.
+pub struct AxisImport;
+
+impl Requestable for AxisImport {
+    type Request = AxisImportParameters;
+
+    fn request_from_values(&self, values: &[f32]) -> AxisImportParameters {
+        let &[unit, smoothing_passes] = values else {
+            panic!("expected 2 inputs to AxisImport::request_from_values")
+        };
+
+        AxisImportParameters {
+            unit,
+            smoothing_passes: smoothing_passes as usize,
+            ..Default::default()
+        }
+    }
+
+    fn nb_values(&self) -> usize {
+        2
+    }
+
+    fn initial_value(&self, n: usize) -> f32 {
+        match n {
+            0 => AxisImportParameters::default().unit,
+            1 => AxisImportParameters::default().smoothing_passes as f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn min_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 0.01,
+            1 => 0f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn max_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 10f32,
+            1 => 20f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn step_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 0.01,
+            1 => 1f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn name_val(&self, n: usize) -> String {
+        match n {
+            0 => String::from("Unit (nm)"),
+            1 => String::from("Smoothing passes"),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// The length of the shortest edge of the wireframes made from 3D objects.
+pub struct WireframeEdges;
+
+impl Requestable for WireframeEdges {
+    type Request = WireframeParameters;
+
+    fn request_from_values(&self, values: &[f32]) -> WireframeParameters {
+        let &[min_edge_length] = values else {
+            panic!("expected 1 input to WireframeEdges::request_from_values")
+        };
+
+        WireframeParameters {
+            min_edge_length: min_edge_length as usize,
+            ..Default::default()
+        }
+    }
+
+    fn nb_values(&self) -> usize {
+        1
+    }
+
+    fn initial_value(&self, n: usize) -> f32 {
+        match n {
+            0 => WireframeParameters::default().min_edge_length as f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn min_val(&self, n: usize) -> f32 {
+        match n {
+            0 => MIN_EDGE_LENGTH as f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn max_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 200f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn step_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 1f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn name_val(&self, n: usize) -> String {
+        match n {
+            0 => String::from("Min edge length (nt)"),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// The scale and the minimum length of the helices filling the volume of 3D objects.
+pub struct Voxelization_;
+
+impl Requestable for Voxelization_ {
+    type Request = VoxelizationParameters;
+
+    fn request_from_values(&self, values: &[f32]) -> VoxelizationParameters {
+        let &[scale, min_length] = values else {
+            panic!("expected 2 inputs to Voxelization_::request_from_values")
+        };
+
+        VoxelizationParameters {
+            scale,
+            min_length: min_length as usize,
+            ..Default::default()
+        }
+    }
+
+    fn nb_values(&self) -> usize {
+        2
+    }
+
+    fn initial_value(&self, n: usize) -> f32 {
+        match n {
+            0 => VoxelizationParameters::default().scale,
+            1 => VoxelizationParameters::default().min_length as f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn min_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 0.01,
+            1 => 1f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn max_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 100f32,
+            1 => 200f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn step_val(&self, n: usize) -> f32 {
+        match n {
+            0 => 0.01,
+            1 => 1f32,
+            _ => unreachable!(),
+        }
+    }
+
+    fn name_val(&self, n: usize) -> String {
+        match n {
+            0 => String::from("Scale (nm/unit)"),
+            1 => String::from("Min length (nt)"),
+            _ => unreachable!(),
+        }
+    }
+}
+
 struct HelixRoll;
 
 impl Requestable for HelixRoll {
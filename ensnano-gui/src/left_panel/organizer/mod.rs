@@ -1,11 +1,13 @@
 mod hoverable_container;
 mod icon;
 mod node_title_bar;
+mod style_editor;
 mod theme;
 
 use self::{
     hoverable_container::HoverableContainer,
     node_title_bar::NodeTitleBar,
+    style_editor::StyleEditor,
     theme::{OrganizerTheme, SelectionType},
 };
 use ensnano_design::{
@@ -13,18 +15,21 @@ use ensnano_design::{
         AttributeDisplay, AttributeWidget, DesignElement, DesignElementKey, DesignElementSection,
         DnaAttribute, DnaAutoGroup,
     },
+    drawing_style::DrawingStyle,
     organizer_tree::{GroupId, OrganizerNodeId, OrganizerTree},
+    smart_groups::GroupRule,
 };
 use ensnano_state::gui::{
     drag_drop_target::{DragDropTarget, DragIdentifier},
     messages::{OrganizerInternalMessage, OrganizerMessage},
 };
+use ensnano_utils::keyboard_priority::keyboard_priority;
 use iced::{
     Element, Length,
     keyboard::Modifiers,
     widget::{
         Column, Container, Space, button, column, container, horizontal_space, row, scrollable,
-        text, tooltip,
+        text, text_input, tooltip,
     },
 };
 use rand::{Rng as _, rngs::ThreadRng};
@@ -62,6 +67,9 @@ pub(super) struct Organizer {
     last_read_tree: *const OrganizerTree,
     must_update_tree: bool,
     group_to_node: HashMap<GroupId, OrganizerNodeId>,
+    /// The rule of the next smart group, as typed by the user.
+    rule_input: String,
+    style_editor: StyleEditor,
 }
 
 impl Organizer {
@@ -97,6 +105,8 @@ impl Organizer {
             last_read_tree: std::ptr::null(),
             must_update_tree: false,
             group_to_node: HashMap::new(),
+            rule_input: String::new(),
+            style_editor: StyleEditor::new(),
         }
     }
 
@@ -119,6 +129,9 @@ impl Organizer {
         //self.hovered_in = None;
         // TODO: This comment may break some functionality. Not observed so far.
         let mut content = Column::new().spacing(5.0f32); // TODO: Find a way to use `ui_size` here.
+        if let Some(style_editor) = self.style_editor.view() {
+            content = content.push(style_editor);
+        }
         for c in &self.groups {
             content = content.push(row![
                 tabulation(),
@@ -147,6 +160,34 @@ impl Organizer {
         if !selection.is_empty() {
             new_group_button = new_group_button.on_press(OrganizerMessage::new_group());
         }
+        let rule = self.rule_input.parse::<GroupRule>();
+        let mut new_smart_group_button = button("New Smart Group");
+        if rule.is_ok() {
+            new_smart_group_button =
+                new_smart_group_button.on_press(OrganizerMessage::new_smart_group());
+        }
+        let mut smart_group_column = column![
+            row![
+                keyboard_priority(
+                    "Smart group rule",
+                    OrganizerMessage::SetKeyboardPriority,
+                    text_input("strands where staple and on helix 12", &self.rule_input)
+                        .on_input(OrganizerMessage::rule_input)
+                ),
+                tooltip(
+                    new_smart_group_button,
+                    "Create a group whose content is defined by the rule",
+                    tooltip::Position::FollowCursor,
+                )
+                .style(iced::theme::Container::Box)
+            ]
+            .spacing(5.0f32)
+        ];
+        if let Err(error) = rule
+            && !self.rule_input.trim().is_empty()
+        {
+            smart_group_column = smart_group_column.push(text(error.to_string()).size(12));
+        }
         container(
             column![
                 // Title row
@@ -158,6 +199,7 @@ impl Organizer {
                     )
                     .style(iced::theme::Container::Box)
                 ],
+                smart_group_column,
                 scrollable(content).width(self.width)
             ]
             .spacing(5.0f32), // TODO: Find a way to use `ui_size` here.
@@ -196,8 +238,17 @@ impl Organizer {
     }
 
     fn push_content(&mut self, content: Vec<DesignElementKey>, group_name: String) -> GroupId {
+        self.push_group(content, group_name, None)
+    }
+
+    fn push_group(
+        &mut self,
+        content: Vec<DesignElementKey>,
+        group_name: String,
+        rule: Option<GroupRule>,
+    ) -> GroupId {
         let id = OrganizerNodeId::Tree(vec![self.groups.len()]);
-        let new_group = GroupContent::new(content, group_name, id, &mut self.rng_thread);
+        let new_group = GroupContent::new(content, group_name, rule, id, &mut self.rng_thread);
         let ret = new_group
             .get_group_id()
             .expect("new group should have an Id");
@@ -281,6 +332,24 @@ impl Organizer {
                     elements_selected: selection.iter().copied().collect(),
                 });
             }
+            OrganizerInternalMessage::RuleInput { rule } => self.rule_input = rule.clone(),
+            OrganizerInternalMessage::NewSmartGroup => {
+                if let Ok(rule) = self.rule_input.parse::<GroupRule>() {
+                    // The content of the group is computed when the design reads the new tree.
+                    self.push_group(vec![], rule.to_string(), Some(rule));
+                    self.rule_input.clear();
+                    return Some(OrganizerMessage::NewTree(self.tree()));
+                }
+            }
+            OrganizerInternalMessage::StyleInput { field, value } => {
+                self.style_editor.input(*field, value.clone());
+            }
+            OrganizerInternalMessage::StyleSubmit { field } => {
+                return self.style_editor.submit(*field);
+            }
+            OrganizerInternalMessage::StyleFlagPicked { flag, value } => {
+                return self.style_editor.set_flag(*flag, *value);
+            }
             OrganizerInternalMessage::Delete { id } => {
                 self.stop_editing();
                 self.pop_id(id);
@@ -339,6 +408,11 @@ impl Organizer {
         }
     }
 
+    /// Set the group whose drawing style is edited.
+    pub(super) fn set_style_target(&mut self, group_id: Option<GroupId>, style: DrawingStyle) {
+        self.style_editor.set_group(group_id, style);
+    }
+
     pub(super) fn add_selection(
         selection: &mut BTreeSet<DesignElementKey>,
         key: &DesignElementKey,
@@ -497,6 +571,7 @@ impl Organizer {
             children: groups,
             expanded: true,
             id: None,
+            rule: None,
         }
     }
 
@@ -693,6 +768,8 @@ enum GroupContent {
         attributes: Vec<Option<DnaAttribute>>,
         elements_below: BTreeSet<DesignElementKey>,
         group_id: GroupId,
+        /// The rule defining the content of the group, if it is a smart group.
+        rule: Option<GroupRule>,
     },
     Placeholder,
 }
@@ -816,6 +893,7 @@ impl GroupContent {
                 children: content,
                 expanded,
                 id,
+                rule,
             } => {
                 let children = content
                     .iter()
@@ -836,6 +914,7 @@ impl GroupContent {
                     attributes: vec![None; DesignElement::all_discriminants().len()],
                     elements_below: BTreeSet::new(),
                     group_id,
+                    rule: rule.clone(),
                 }
             }
         }
@@ -844,6 +923,7 @@ impl GroupContent {
     fn new(
         content: Vec<DesignElementKey>,
         name: String,
+        rule: Option<GroupRule>,
         id: OrganizerNodeId,
         rng: &mut ThreadRng,
     ) -> Self {
@@ -871,6 +951,7 @@ impl GroupContent {
             attributes: vec![None; DesignElement::all_discriminants().len()],
             elements_below: BTreeSet::new(),
             group_id,
+            rule,
         }
     }
 
@@ -1182,6 +1263,7 @@ impl GroupContent {
                 children,
                 expanded,
                 group_id,
+                rule,
                 ..
             } => {
                 let children = children.iter().filter_map(Self::tree).collect();
@@ -1190,6 +1272,7 @@ impl GroupContent {
                     children,
                     expanded: *expanded,
                     id: Some(*group_id),
+                    rule: rule.clone(),
                 })
             }
             Self::Leaf { element, .. } => Some(OrganizerTree::Leaf(*element)),
@@ -0,0 +1,167 @@
+use ensnano_design::{
+    drawing_style::{DrawingStyle, StyleField, StyleFlag},
+    organizer_tree::GroupId,
+};
+use ensnano_state::gui::messages::OrganizerMessage;
+use ensnano_utils::keyboard_priority::keyboard_priority;
+use iced::{
+    Alignment, Element, Length,
+    widget::{Column, column, pick_list, row, text, text_input},
+};
+use std::{collections::BTreeMap, fmt};
+
+const LABEL_WIDTH: f32 = 130.;
+
+/// Editor of the drawing style of the selected group.
+pub(super) struct StyleEditor {
+    group_id: Option<GroupId>,
+    /// The style of the group, as stored in the design.
+    style: DrawingStyle,
+    /// The text typed in the fields that have not been applied yet.
+    inputs: BTreeMap<StyleField, String>,
+    /// The reason why the text typed in a field could not be applied.
+    errors: BTreeMap<StyleField, String>,
+}
+
+impl StyleEditor {
+    pub(super) fn new() -> Self {
+        Self {
+            group_id: None,
+            style: DrawingStyle::default(),
+            inputs: BTreeMap::new(),
+            errors: BTreeMap::new(),
+        }
+    }
+
+    pub(super) fn set_group(&mut self, group_id: Option<GroupId>, style: DrawingStyle) {
+        if group_id != self.group_id {
+            self.inputs.clear();
+            self.errors.clear();
+        }
+        self.group_id = group_id;
+        self.style = style;
+    }
+
+    pub(super) fn input(&mut self, field: StyleField, value: String) {
+        self.inputs.insert(field, value);
+    }
+
+    pub(super) fn submit(&mut self, field: StyleField) -> Option<OrganizerMessage> {
+        let group_id = self.group_id?;
+        let value = self.inputs.get(&field)?;
+        let mut style = self.style;
+        match field.write(&mut style, value) {
+            Ok(()) => {
+                self.inputs.remove(&field);
+                self.errors.remove(&field);
+                self.style = style;
+                Some(OrganizerMessage::NewDrawingStyle(group_id, style))
+            }
+            Err(error) => {
+                self.errors.insert(field, error);
+                None
+            }
+        }
+    }
+
+    pub(super) fn set_flag(
+        &mut self,
+        flag: StyleFlag,
+        value: Option<bool>,
+    ) -> Option<OrganizerMessage> {
+        let group_id = self.group_id?;
+        flag.write(&mut self.style, value);
+        Some(OrganizerMessage::NewDrawingStyle(group_id, self.style))
+    }
+
+    pub(super) fn view(&self) -> Option<Element<'_, OrganizerMessage>> {
+        self.group_id?;
+        let mut content = Column::new()
+            .spacing(2)
+            .push(text("Drawing style of the selected group"));
+        for field in StyleField::ALL {
+            let value = self
+                .inputs
+                .get(&field)
+                .cloned()
+                .unwrap_or_else(|| field.read(&self.style));
+            content = content.push(
+                row![
+                    text(field.label()).size(12).width(LABEL_WIDTH),
+                    keyboard_priority(
+                        field.label(),
+                        OrganizerMessage::SetKeyboardPriority,
+                        text_input(field.placeholder(), &value)
+                            .size(12)
+                            .on_input(move |s| OrganizerMessage::style_input(field, s))
+                            .on_submit(OrganizerMessage::style_submit(field))
+                    ),
+                ]
+                .align_items(Alignment::Center)
+                .spacing(5),
+            );
+            if let Some(error) = self.errors.get(&field) {
+                content = content.push(text(error).size(12));
+            }
+        }
+        for flag in StyleFlag::ALL {
+            content = content.push(
+                row![
+                    text(flag.label()).size(12).width(LABEL_WIDTH),
+                    pick_list(
+                        FlagChoice::ALL,
+                        Some(FlagChoice::from(flag.read(&self.style))),
+                        move |choice| OrganizerMessage::style_flag_picked(flag, choice.into()),
+                    )
+                    .text_size(12)
+                    .width(Length::Fill),
+                ]
+                .align_items(Alignment::Center)
+                .spacing(5),
+            );
+        }
+        Some(column![content].padding(5).into())
+    }
+}
+
+/// The value of a [StyleFlag], as shown to the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagChoice {
+    Inherited,
+    On,
+    Off,
+}
+
+impl FlagChoice {
+    const ALL: &'static [Self] = &[Self::Inherited, Self::On, Self::Off];
+}
+
+impl From<Option<bool>> for FlagChoice {
+    fn from(value: Option<bool>) -> Self {
+        match value {
+            None => Self::Inherited,
+            Some(true) => Self::On,
+            Some(false) => Self::Off,
+        }
+    }
+}
+
+impl From<FlagChoice> for Option<bool> {
+    fn from(choice: FlagChoice) -> Self {
+        match choice {
+            FlagChoice::Inherited => None,
+            FlagChoice::On => Some(true),
+            FlagChoice::Off => Some(false),
+        }
+    }
+}
+
+impl fmt::Display for FlagChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Inherited => f.write_str("Inherited"),
+            Self::On => f.write_str("On"),
+            Self::Off => f.write_str("Off"),
+        }
+    }
+}
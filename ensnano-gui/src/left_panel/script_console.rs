@@ -0,0 +1,67 @@
+//! A console to write and run scripts on the design.
+//!
+//! The scripts are run by the main event loop, which sends back the lines that they printed.
+
+use crate::helpers::{section, text_button};
+use ensnano_state::gui::messages::LeftPanelMessage;
+use ensnano_utils::{keyboard_priority::KeyboardPriorityId, ui_size::UiSize};
+use iced::{
+    Element, Length,
+    widget::{Column, column, container, row, scrollable, text, text_editor},
+};
+
+/// The id used to take the keyboard priority while the console is open.
+const PRIORITY_ID: &str = "Script console";
+
+#[derive(Default)]
+pub(super) struct ScriptConsole {
+    content: text_editor::Content,
+    output: Vec<String>,
+    pub(super) is_open: bool,
+}
+
+impl ScriptConsole {
+    pub(super) fn priority_id() -> KeyboardPriorityId {
+        KeyboardPriorityId::new(PRIORITY_ID)
+    }
+
+    pub(super) fn edit(&mut self, action: text_editor::Action) {
+        self.content.perform(action);
+    }
+
+    pub(super) fn source(&self) -> String {
+        self.content.text()
+    }
+
+    pub(super) fn set_output(&mut self, output: Vec<String>) {
+        self.output = output;
+    }
+
+    pub(super) fn view(&self, ui_size: UiSize) -> Element<'_, LeftPanelMessage> {
+        let output = self.output.iter().fold(Column::new(), |column, line| {
+            column.push(text(line).size(ui_size.main_text()))
+        });
+
+        container(
+            column![
+                row![
+                    section("Script", ui_size).width(Length::Fill),
+                    text_button("Close", ui_size).on_press(LeftPanelMessage::CloseScriptConsole),
+                ],
+                text_editor(&self.content)
+                    .on_action(LeftPanelMessage::ScriptEdit)
+                    .height(Length::FillPortion(2)),
+                row![
+                    text_button("Run", ui_size).on_press(LeftPanelMessage::RunScript),
+                    text_button("Run script file", ui_size)
+                        .on_press(LeftPanelMessage::RunScriptFile),
+                ]
+                .spacing(5),
+                scrollable(output).height(Length::FillPortion(1)),
+            ]
+            .spacing(5),
+        )
+        .padding(5)
+        .into()
+    }
+}
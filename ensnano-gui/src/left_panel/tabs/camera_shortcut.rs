@@ -8,7 +8,9 @@ use crate::{
 };
 use ensnano_design::CameraId;
 use ensnano_state::app_state::AppState;
-use ensnano_utils::{keyboard_priority::keyboard_priority, ui_size::UiSize};
+use ensnano_utils::{
+    export::VectorExportFormat, keyboard_priority::keyboard_priority, ui_size::UiSize,
+};
 use iced::{
     Alignment, Command, Length,
     alignment::Horizontal,
@@ -329,6 +331,16 @@ impl CameraShortcutPanel {
                                 "Export a JSON file containing the coordinates of the nucleotides in the design file directory", 
                                 tooltip::Position::FollowCursor,                    
                             ).style(iced::theme::Container::Box),
+                            tooltip(fixed_text_button("SVG", 2.0, ui_size)
+                                .on_press(LeftPanelMessage::VectorExport2D(VectorExportFormat::Svg)),
+                                "Export the 2D view as a SVG file with one layer per group in the design file directory",
+                                tooltip::Position::FollowCursor,
+                            ).style(iced::theme::Container::Box),
+                            tooltip(fixed_text_button("PDF", 2.0, ui_size)
+                                .on_press(LeftPanelMessage::VectorExport2D(VectorExportFormat::Pdf)),
+                                "Export the 2D view as a PDF file with one layer per group in the design file directory",
+                                tooltip::Position::FollowCursor,
+                            ).style(iced::theme::Container::Box),
                         ]
                         .spacing(ui_size.button_spacing()),
                     ]
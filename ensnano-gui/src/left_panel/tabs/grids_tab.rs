@@ -1,32 +1,77 @@
 use crate::{
     fonts::material_icons::{MaterialIcon, icon_to_char},
-    helpers::{extra_jump, icon_button, section, subsection, text_button},
-    left_panel::{Hyperboloid_, LeftPanelMessage, discrete_value::RequestFactory, tabs::GuiTab},
+    helpers::{extra_jump, icon_button, right_checkbox, section, subsection, text_button},
+    left_panel::{
+        AxisImport, Hyperboloid_, LeftPanelMessage, Voxelization_, WireframeEdges,
+        discrete_value::RequestFactory, tabs::GuiTab,
+    },
+    theme,
+};
+use ensnano_design::{
+    curves::{
+        import_from_points::AxisImportParameters,
+        parametric::{ParametricCurveDescriptor, check_expression},
+    },
+    external_3d_objects::External3DObjectId,
+    grid::{GridId, GridTypeDescr, lattice::Lattice},
+    voxelization::{VoxelizationAxis, VoxelizationParameters},
+    wireframe::WireframeParameters,
 };
-use ensnano_design::grid::GridTypeDescr;
 use ensnano_state::{
     app_state::AppState,
-    design::operation::HyperboloidRequest,
+    design::{operation::HyperboloidRequest, selection::Selection},
     gui::messages::{FactoryId, ValueId},
+    utils::operation::{ParametricHelixCreation, SimpleOperation as _},
 };
 use ensnano_utils::{
     consts::{ICON_HONEYCOMB_GRID, ICON_NANOTUBE, ICON_ROTATED_HONEYCOMB_GRID, ICON_SQUARE_GRID},
+    keyboard_priority::keyboard_priority,
     ui_size::UiSize,
 };
 use iced::{
-    Length,
-    widget::{Column, column, row, scrollable, text, tooltip},
+    Alignment, Length,
+    widget::{Column, Row, column, pick_list, row, scrollable, text, text_input, tooltip},
 };
 use iced_aw::TabLabel;
+use std::{fmt, path::PathBuf};
 
 pub struct GridTab {
     hyperboloid_factory: RequestFactory<Hyperboloid_>,
+    /// The texts of the inputs of the parametric helix, in the order of
+    /// `ParametricHelixCreation::PARAMETERS`.
+    parametric_helix_inputs: Vec<String>,
+    editing_parametric_helix: bool,
+    axis_import_factory: RequestFactory<AxisImport>,
+    resample_imported_axes: bool,
+    /// The 3D object from which wireframes are made and whose volume is filled.
+    mesh_object: Option<External3DObjectId>,
+    wireframe_factory: RequestFactory<WireframeEdges>,
+    wireframe_two_helices: bool,
+    voxelization_factory: RequestFactory<Voxelization_>,
+    voxelization_grid_type: GridTypeDescr,
+    voxelization_axis: VoxelizationAxis,
+    route_voxelization_scaffold: bool,
 }
 
 impl GridTab {
     pub fn new() -> Self {
+        let parametric_helix = ParametricHelixCreation {
+            descriptor: ParametricCurveDescriptor::default(),
+            replace: false,
+        };
         Self {
             hyperboloid_factory: RequestFactory::new(FactoryId::Hyperboloid, Hyperboloid_),
+            parametric_helix_inputs: parametric_helix.values(),
+            editing_parametric_helix: false,
+            axis_import_factory: RequestFactory::new(FactoryId::AxisImport, AxisImport),
+            resample_imported_axes: AxisImportParameters::default().resample,
+            mesh_object: None,
+            wireframe_factory: RequestFactory::new(FactoryId::Wireframe, WireframeEdges),
+            wireframe_two_helices: WireframeParameters::default().two_helices_per_edge,
+            voxelization_factory: RequestFactory::new(FactoryId::Voxelization, Voxelization_),
+            voxelization_grid_type: VoxelizationParameters::default().grid_type,
+            voxelization_axis: VoxelizationParameters::default().axis,
+            route_voxelization_scaffold: VoxelizationParameters::default().route_scaffold,
         }
     }
 
@@ -35,6 +80,24 @@ impl GridTab {
         self.hyperboloid_factory.make_request(requests);
     }
 
+    /// Load the parameters of the selected hyperboloid grid in the sliders.
+    pub fn edit_hyperboloid(
+        &mut self,
+        app_state: &AppState,
+        request: &mut Option<(GridId, HyperboloidRequest)>,
+    ) {
+        if let Some((grid_id, hyperboloid)) = selected_hyperboloid(app_state) {
+            self.hyperboloid_factory.set_values(&[
+                hyperboloid.radius as f32,
+                hyperboloid.length,
+                hyperboloid.shift,
+                hyperboloid.radius_shift,
+                hyperboloid.nb_turn as f32,
+            ]);
+            *request = Some((grid_id, hyperboloid));
+        }
+    }
+
     pub fn update_hyperboloid_request(
         &mut self,
         value_id: ValueId,
@@ -44,6 +107,269 @@ impl GridTab {
         self.hyperboloid_factory
             .update_request(value_id, value, request);
     }
+
+    /// Whether the last parametric helix created from this tab is still the pending operation,
+    /// in which case editing the inputs replaces it.
+    fn is_editing_parametric_helix(&self, app_state: &AppState) -> bool {
+        self.editing_parametric_helix
+            && app_state
+                .get_pending_operation()
+                .is_some_and(|op| op.is::<ParametricHelixCreation>())
+    }
+
+    fn parametric_helix_descriptor(&self) -> Option<ParametricCurveDescriptor> {
+        let [x, y, z, t_min, t_max] = self.parametric_helix_inputs.as_slice() else {
+            return None;
+        };
+        let descriptor = ParametricCurveDescriptor {
+            x: x.clone(),
+            y: y.clone(),
+            z: z.clone(),
+            t_min: t_min.parse().ok()?,
+            t_max: t_max.parse().ok()?,
+        };
+        descriptor.check().ok().map(|()| descriptor)
+    }
+
+    fn parametric_helix_input_is_valid(&self, field: usize) -> bool {
+        let bound = |i: usize| {
+            self.parametric_helix_inputs
+                .get(i)
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|x| x.is_finite())
+        };
+        match field {
+            0..=2 => check_expression(&self.parametric_helix_inputs[field]).is_ok(),
+            3 | 4 => {
+                bound(field).is_some()
+                    && bound(3)
+                        .zip(bound(4))
+                        .is_none_or(|(t_min, t_max)| t_min < t_max)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn new_parametric_helix(&mut self) -> Option<ParametricHelixCreation> {
+        let descriptor = self.parametric_helix_descriptor()?;
+        self.editing_parametric_helix = true;
+        Some(ParametricHelixCreation {
+            descriptor,
+            replace: false,
+        })
+    }
+
+    pub fn finish_parametric_helix(&mut self) {
+        self.editing_parametric_helix = false;
+    }
+
+    /// Set the text of an input and return the operation that updates the parametric helix being
+    /// edited, if any.
+    pub fn update_parametric_helix_input(
+        &mut self,
+        field: usize,
+        text: String,
+        app_state: &AppState,
+    ) -> Option<ParametricHelixCreation> {
+        if let Some(input) = self.parametric_helix_inputs.get_mut(field) {
+            *input = text;
+        }
+        if !self.is_editing_parametric_helix(app_state) {
+            return None;
+        }
+        Some(ParametricHelixCreation {
+            descriptor: self.parametric_helix_descriptor()?,
+            replace: true,
+        })
+    }
+
+    pub fn update_axis_import_value(&mut self, value_id: ValueId, value: f32) {
+        self.axis_import_factory.update_value(value_id, value);
+    }
+
+    pub fn set_axis_resampling(&mut self, resample: bool) {
+        self.resample_imported_axes = resample;
+    }
+
+    pub fn axis_import_parameters(&self) -> AxisImportParameters {
+        let mut request = None;
+        self.axis_import_factory.make_request(&mut request);
+        AxisImportParameters {
+            resample: self.resample_imported_axes,
+            ..request.unwrap_or_default()
+        }
+    }
+
+    pub fn update_wireframe_value(&mut self, value_id: ValueId, value: f32) {
+        self.wireframe_factory.update_value(value_id, value);
+    }
+
+    pub fn update_voxelization_value(&mut self, value_id: ValueId, value: f32) {
+        self.voxelization_factory.update_value(value_id, value);
+    }
+
+    pub fn set_mesh_object(&mut self, object_id: External3DObjectId) {
+        self.mesh_object = Some(object_id);
+    }
+
+    pub fn set_wireframe_two_helices(&mut self, two_helices: bool) {
+        self.wireframe_two_helices = two_helices;
+    }
+
+    pub fn set_voxelization_grid_type(&mut self, grid_type: GridTypeDescr) {
+        self.voxelization_grid_type = grid_type;
+    }
+
+    pub fn set_voxelization_axis(&mut self, axis: VoxelizationAxis) {
+        self.voxelization_axis = axis;
+    }
+
+    pub fn set_voxelization_scaffold_routing(&mut self, route_scaffold: bool) {
+        self.route_voxelization_scaffold = route_scaffold;
+    }
+
+    /// The chosen 3D object from which helices are made, or the first one if none is chosen.
+    pub fn mesh_object(&self, app_state: &AppState) -> Option<(External3DObjectId, PathBuf)> {
+        let objects = app_state.get_external_3d_objects();
+        let chosen = self
+            .mesh_object
+            .and_then(|id| objects.iter().position(|(o, _)| *o == id))
+            .unwrap_or_default();
+        objects.into_iter().nth(chosen)
+    }
+
+    pub fn wireframe_parameters(&self) -> WireframeParameters {
+        let mut request = None;
+        self.wireframe_factory.make_request(&mut request);
+        WireframeParameters {
+            two_helices_per_edge: self.wireframe_two_helices,
+            ..request.unwrap_or_default()
+        }
+    }
+
+    pub fn voxelization_parameters(&self) -> VoxelizationParameters {
+        let mut request = None;
+        self.voxelization_factory.make_request(&mut request);
+        VoxelizationParameters {
+            grid_type: self.voxelization_grid_type.clone(),
+            axis: self.voxelization_axis,
+            route_scaffold: self.route_voxelization_scaffold,
+            ..request.unwrap_or_default()
+        }
+    }
+
+    fn mesh_object_view(
+        &self,
+        ui_size: UiSize,
+        app_state: &AppState,
+    ) -> Column<'_, LeftPanelMessage> {
+        let objects: Vec<_> = app_state
+            .get_external_3d_objects()
+            .into_iter()
+            .map(|(id, path)| ObjectChoice(id, path))
+            .collect();
+        let has_object = !objects.is_empty();
+        let lattice = match self.voxelization_grid_type {
+            GridTypeDescr::Square { .. } => LatticeChoice::Square,
+            _ => LatticeChoice::Honeycomb,
+        };
+        column![
+            row![
+                text("Object").size(ui_size.main_text()),
+                pick_list(
+                    objects,
+                    self.mesh_object(app_state)
+                        .map(|(id, path)| ObjectChoice(id, path)),
+                    |choice| LeftPanelMessage::SetMeshObject(choice.0),
+                ),
+            ]
+            .spacing(ui_size.button_spacing()),
+            text("Wireframe").size(ui_size.main_text()),
+            Column::with_children(self.wireframe_factory.view(true, ui_size.main_text())),
+            right_checkbox(
+                self.wireframe_two_helices,
+                "Two helices per edge",
+                LeftPanelMessage::SetWireframeTwoHelices,
+                ui_size,
+                true,
+            ),
+            tooltip(
+                text_button("Make wireframe", ui_size)
+                    .on_press_maybe(has_object.then_some(LeftPanelMessage::MakeWireframe)),
+                text("Route a scaffold along the edges of the object").size(ui_size.main_text()),
+                tooltip::Position::FollowCursor,
+            )
+            .style(iced::theme::Container::Box),
+            text("Filled volume").size(ui_size.main_text()),
+            row![
+                pick_list(LatticeChoice::ALL, Some(lattice), |choice| {
+                    LeftPanelMessage::SetVoxelizationGridType(choice.grid_type())
+                }),
+                text("along").size(ui_size.main_text()),
+                pick_list(
+                    VoxelizationAxis::ALL,
+                    Some(self.voxelization_axis),
+                    LeftPanelMessage::SetVoxelizationAxis,
+                ),
+            ]
+            .spacing(ui_size.button_spacing())
+            .align_items(Alignment::Center),
+            Column::with_children(self.voxelization_factory.view(true, ui_size.main_text())),
+            right_checkbox(
+                self.route_voxelization_scaffold,
+                "Route scaffold",
+                LeftPanelMessage::SetVoxelizationScaffoldRouting,
+                ui_size,
+                true,
+            ),
+            tooltip(
+                text_button("Fill with helices", ui_size)
+                    .on_press_maybe(has_object.then_some(LeftPanelMessage::VoxelizeMesh)),
+                text("Fill the volume of the object with helices on a new grid")
+                    .size(ui_size.main_text()),
+                tooltip::Position::FollowCursor,
+            )
+            .style(iced::theme::Container::Box),
+        ]
+        .spacing(5)
+    }
+
+    fn parametric_helix_view(
+        &self,
+        ui_size: UiSize,
+        app_state: &AppState,
+    ) -> Column<'_, LeftPanelMessage> {
+        let inputs = ParametricHelixCreation::PARAMETERS
+            .iter()
+            .zip(self.parametric_helix_inputs.iter())
+            .enumerate()
+            .map(|(field, (name, input))| {
+                row![
+                    text(*name).size(ui_size.main_text()).width(50),
+                    keyboard_priority(
+                        format!("Parametric helix {name}"),
+                        LeftPanelMessage::SetKeyboardPriority,
+                        text_input("", input)
+                            .on_input(move |s| LeftPanelMessage::ParametricHelixInput(field, s))
+                            .size(ui_size.main_text())
+                            .style(theme::BadValue(self.parametric_helix_input_is_valid(field))),
+                    ),
+                ]
+                .align_items(Alignment::Center)
+                .into()
+            });
+        let button = if self.is_editing_parametric_helix(app_state) {
+            text_button("Finish", ui_size)
+                .on_press(LeftPanelMessage::FinishParametricHelix)
+                .style(iced::theme::Button::Positive)
+        } else {
+            text_button("Create", ui_size).on_press_maybe(
+                self.parametric_helix_descriptor()
+                    .map(|_| LeftPanelMessage::NewParametricHelix),
+            )
+        };
+        Column::with_children(inputs).push(button).spacing(5)
+    }
 }
 
 impl GuiTab for GridTab {
@@ -70,6 +396,22 @@ impl GuiTab for GridTab {
                 ),
             ]
             .spacing(ui_size.button_spacing()),
+            Row::with_children(lattice_presets().map(|(name, lattice)| {
+                text_button(name, ui_size)
+                    .on_press(LeftPanelMessage::NewGrid(GridTypeDescr::Lattice {
+                        lattice,
+                        twist: None,
+                    }))
+                    .into()
+            }))
+            .spacing(ui_size.button_spacing()),
+            tooltip(
+                text_button("Lattice from file", ui_size).on_press(LeftPanelMessage::ImportLattice),
+                text("JSON file with two basis vectors and a motif of sites")
+                    .size(ui_size.main_text()),
+                tooltip::Position::FollowCursor,
+            )
+            .style(iced::theme::Container::Box),
             extra_jump(),
             subsection("New nanotube", ui_size),
             // add_start_cancel_hyperboloid_button!
@@ -85,10 +427,12 @@ impl GuiTab for GridTab {
                 .spacing(ui_size.button_spacing())
             } else {
                 row![
-                        icon_button(ICON_NANOTUBE, ui_size)
-                            .on_press(LeftPanelMessage::NewHyperboloid),
-                    ]
-                    .spacing(ui_size.button_spacing())
+                    icon_button(ICON_NANOTUBE, ui_size).on_press(LeftPanelMessage::NewHyperboloid),
+                    text_button("Edit selected", ui_size).on_press_maybe(
+                        selected_hyperboloid(app_state).map(|_| LeftPanelMessage::EditHyperboloid)
+                    ),
+                ]
+                .spacing(ui_size.button_spacing())
             },
             // add hyperboloid sliders!
             Column::with_children(
@@ -96,6 +440,28 @@ impl GuiTab for GridTab {
                     .view(app_state.is_building_hyperboloid(), ui_size.main_text()),
             ),
             extra_jump(),
+            subsection("New parametric helix", ui_size),
+            self.parametric_helix_view(ui_size, app_state),
+            extra_jump(),
+            subsection("Import helix axes", ui_size),
+            Column::with_children(self.axis_import_factory.view(true, ui_size.main_text())),
+            right_checkbox(
+                self.resample_imported_axes,
+                "Fit whole number of nucleotides",
+                LeftPanelMessage::SetAxisResampling,
+                ui_size,
+                true,
+            ),
+            tooltip(
+                text_button("From files", ui_size).on_press(LeftPanelMessage::ImportHelixAxes),
+                text("CSV or XYZ files of 3D points, one helix per file").size(ui_size.main_text()),
+                tooltip::Position::FollowCursor,
+            )
+            .style(iced::theme::Container::Box),
+            extra_jump(),
+            subsection("From 3D object", ui_size),
+            self.mesh_object_view(ui_size, app_state),
+            extra_jump(),
             subsection("Guess grid", ui_size),
             // add_guess_grid_button!
             tooltip(
@@ -113,3 +479,63 @@ impl GuiTab for GridTab {
         scrollable(content).width(Length::Fill).into()
     }
 }
+
+/// A 3D object in the list of objects from which helices can be made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ObjectChoice(External3DObjectId, PathBuf);
+
+impl fmt::Display for ObjectChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1.file_name() {
+            Some(name) => write!(f, "{}", name.to_string_lossy()),
+            None => write!(f, "Object {}", self.0.0),
+        }
+    }
+}
+
+/// The predefined lattices on which new grids can be made.
+fn lattice_presets() -> [(&'static str, Lattice); 3] {
+    [
+        ("HCP", Lattice::hexagonal_close_packed()),
+        ("Hybrid", Lattice::hybrid()),
+        ("Gridiron", Lattice::gridiron()),
+    ]
+}
+
+/// The selected grid and its parameters, if it is a hyperboloid.
+fn selected_hyperboloid(app_state: &AppState) -> Option<(GridId, HyperboloidRequest)> {
+    let Some(Selection::Grid(_, grid_id)) = app_state.get_selection().first() else {
+        return None;
+    };
+    app_state
+        .get_reader()
+        .get_hyperboloid_request(*grid_id)
+        .map(|request| (*grid_id, request))
+}
+
+/// The lattices on which the volume of a 3D object can be filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LatticeChoice {
+    Square,
+    Honeycomb,
+}
+
+impl LatticeChoice {
+    const ALL: [Self; 2] = [Self::Square, Self::Honeycomb];
+
+    fn grid_type(self) -> GridTypeDescr {
+        match self {
+            Self::Square => GridTypeDescr::Square { twist: None },
+            Self::Honeycomb => GridTypeDescr::Honeycomb { twist: None },
+        }
+    }
+}
+
+impl fmt::Display for LatticeChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Square => write!(f, "Square"),
+            Self::Honeycomb => write!(f, "Honeycomb"),
+        }
+    }
+}
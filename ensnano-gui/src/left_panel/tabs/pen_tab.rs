@@ -1,22 +1,79 @@
 use crate::{
     fonts::material_icons::{MaterialIcon, MaterialIconStyle, icon_to_char},
-    helpers::{extra_jump, icon_button, material_icon_button, section},
-    left_panel::{LeftPanelMessage, tabs::GuiTab},
+    helpers::{extra_jump, icon_button, material_icon_button, section, subsection},
+    left_panel::{LeftPanelMessage, SvgImport, discrete_value::RequestFactory, tabs::GuiTab},
+};
+use ensnano_design::{
+    bezier_plane::{BezierPlaneId, import_from_svg::SvgImportParameters},
+    grid::GridTypeDescr,
+    interaction_modes::ActionMode,
+};
+use ensnano_state::{
+    app_state::AppState,
+    gui::messages::{FactoryId, ValueId},
 };
-use ensnano_design::{grid::GridTypeDescr, interaction_modes::ActionMode};
-use ensnano_state::app_state::AppState;
 use ensnano_utils::{
     consts::{ICON_HONEYCOMB_GRID, ICON_ROTATED_HONEYCOMB_GRID, ICON_SQUARE_GRID},
     ui_size::UiSize,
 };
-use iced::widget::{checkbox, column, row, text, tooltip};
+use iced::widget::{Column, checkbox, column, pick_list, row, text, tooltip};
 use iced_aw::TabLabel;
+use std::fmt;
 
 const NEW_BEZIER_PLANE_ICON: MaterialIcon = MaterialIcon::HistoryEdu;
 const EDIT_BEZIER_PATH_ICON: MaterialIcon = MaterialIcon::LinearScale;
 
-#[derive(Default)]
-pub struct PenTab;
+pub struct PenTab {
+    svg_import_factory: RequestFactory<SvgImport>,
+    /// The plane chosen for SVG imports. The first plane of the design is used when it is not
+    /// set or does not exist anymore.
+    svg_import_plane: Option<BezierPlaneId>,
+}
+
+impl Default for PenTab {
+    fn default() -> Self {
+        Self {
+            svg_import_factory: RequestFactory::new(FactoryId::SvgImport, SvgImport),
+            svg_import_plane: None,
+        }
+    }
+}
+
+impl PenTab {
+    pub fn update_svg_import_value(&mut self, value_id: ValueId, value: f32) {
+        self.svg_import_factory.update_value(value_id, value);
+    }
+
+    pub fn set_svg_import_plane(&mut self, plane_id: BezierPlaneId) {
+        self.svg_import_plane = Some(plane_id);
+    }
+
+    fn svg_import_plane(&self, app_state: &AppState) -> Option<BezierPlaneId> {
+        let planes = app_state.get_reader().get_bezier_plane_ids();
+        self.svg_import_plane
+            .filter(|p| planes.contains(p))
+            .or_else(|| planes.first().copied())
+    }
+
+    pub fn svg_import_parameters(&self, app_state: &AppState) -> SvgImportParameters {
+        let mut request = None;
+        self.svg_import_factory.make_request(&mut request);
+        SvgImportParameters {
+            plane_id: self.svg_import_plane(app_state).unwrap_or_default(),
+            ..request.unwrap_or_default()
+        }
+    }
+}
+
+/// A bezier plane in the list of planes on which SVG files can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PlaneChoice(BezierPlaneId);
+
+impl fmt::Display for PlaneChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Plane {}", self.0.0)
+    }
+}
 
 impl GuiTab for PenTab {
     type Message = LeftPanelMessage;
@@ -44,7 +101,7 @@ impl GuiTab for PenTab {
                             ui_size
                         )
                         .on_press(LeftPanelMessage::LoadSvgFile),
-                        "Open bezier file",
+                        "Import the paths of an SVG file",
                         tooltip::Position::FollowCursor,
                     )
                     .style(iced::theme::Container::Box),
@@ -82,6 +139,23 @@ impl GuiTab for PenTab {
                 .spacing(ui_size.button_spacing()),
             ]
             .spacing(ui_size.button_spacing()),
+            subsection("SVG import", ui_size),
+            row![
+                text("Plane").size(ui_size.main_text()),
+                pick_list(
+                    app_state
+                        .get_reader()
+                        .get_bezier_plane_ids()
+                        .into_iter()
+                        .map(PlaneChoice)
+                        .collect::<Vec<_>>(),
+                    self.svg_import_plane(app_state).map(PlaneChoice),
+                    |choice| LeftPanelMessage::SetSvgImportPlane(choice.0),
+                ),
+            ]
+            .spacing(ui_size.button_spacing()),
+            Column::with_children(self.svg_import_factory.view(true, ui_size.main_text())),
+            extra_jump(),
             // add_grid_buttons!
             if let Some(path_id) = app_state.get_selected_bezier_path() {
                 row![
@@ -6,19 +6,24 @@ use crate::{
         tabs::{GuiTab, gostop::GoStop},
     },
 };
-use ensnano_physics::parameters::{RapierFloatParameter, RapierParameters};
+use ensnano_physics::{
+    parameters::{RapierFloatParameter, RapierParameters, SimulationSetupKind},
+    relaxation::RelaxationCriteria,
+};
 use ensnano_state::{
     app_state::AppState,
     gui::messages::{FactoryId, ValueId},
     requests::Requests,
+    simulation_presets::{self, SimulationParameters, SimulationPreset},
+    trajectory::TrajectorySummary,
 };
 use ensnano_utils::{
-    RollRequest, SimulationState, consts::ICON_PHYSICAL_ENGINE,
+    RollRequest, SimulationState, consts::ICON_PHYSICAL_ENGINE, export::ExportType,
     keyboard_priority::keyboard_priority, ui_size::UiSize,
 };
 use iced::{
     Alignment, Length,
-    widget::{Column, Space, column, row, scrollable, slider, text, text_input},
+    widget::{Column, Space, column, pick_list, row, scrollable, slider, text, text_input},
 };
 use iced_aw::TabLabel;
 use std::{
@@ -35,6 +40,10 @@ pub struct SimulationTab {
     pub rapier_parameters: RapierParameters,
     // holds the value of the string fields
     pub rapier_parameter_fields: HashMap<String, String>,
+    pub trajectory: TrajectorySummary,
+    pub presets: Vec<SimulationPreset>,
+    /// The name under which the current parameters are saved as a preset.
+    pub preset_name: String,
 }
 
 impl SimulationTab {
@@ -60,6 +69,9 @@ impl SimulationTab {
             physical_simulation: Default::default(),
             rapier_parameters: Default::default(),
             rapier_parameter_fields: Default::default(),
+            trajectory: Default::default(),
+            presets: simulation_presets::all_presets(),
+            preset_name: String::new(),
         }
     }
 
@@ -140,6 +152,47 @@ impl SimulationTab {
         }
     }
 
+    pub fn simulation_parameters(&self) -> SimulationParameters {
+        let mut rigid_body_request = None;
+        self.make_rigid_body_request(&mut rigid_body_request);
+        SimulationParameters {
+            rapier: self.rapier_parameters,
+            rigid_body: rigid_body_request
+                .map(|request| request.to_constants())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Replace all the parameters of the simulations, without starting or stopping any of them.
+    pub fn set_simulation_parameters(&mut self, parameters: &SimulationParameters) {
+        self.rapier_parameters = RapierParameters {
+            is_simulation_running: self.rapier_parameters.is_simulation_running,
+            ..parameters.rapier
+        };
+        self.update_parameters_fields();
+
+        let request = RigidBodyParametersRequest::from_constants(&parameters.rigid_body);
+        let brownian = BrownianParametersFactory {
+            rate: request.brownian_rate,
+            amplitude: request.brownian_amplitude,
+        };
+        self.brownian_factory
+            .set_values(&[brownian.rate, brownian.amplitude]);
+        self.rigid_body_factory.set_values(&[
+            request.k_springs,
+            request.k_friction,
+            request.mass_factor,
+        ]);
+        let requestable = &mut self.rigid_body_factory.requestable;
+        requestable.volume_exclusion = request.volume_exclusion;
+        requestable.brownian_motion = request.brownian_motion;
+        requestable.brownian_parameters = brownian;
+    }
+
+    pub fn get_preset(&self, name: &str) -> Option<&SimulationPreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
     /// Updates the fields using the parameters.
     ///
     /// Used when modifications to the parameters are made by
@@ -204,48 +257,82 @@ impl GuiTab for SimulationTab {
                     .view(brownian_motion, ui_size.main_text())
             ),
             section("Relaxation (beta)", ui_size),
-            column![row![
-                text_button("Start", ui_size)
-                    .style(iced::theme::Button::Positive)
-                    .on_press_maybe(if self.rapier_parameters.is_simulation_running {
-                        None
-                    } else {
-                        Some(LeftPanelMessage::UpdateRapierParameters(
-                            apply_parameter_fields(
-                                &self.rapier_parameter_fields,
-                                &RapierParameters {
-                                    is_simulation_running: true,
-                                    ..self.rapier_parameters
-                                },
-                            ),
-                        ))
-                    }),
-                Space::with_width(ui_size.button_spacing()),
-                text_button("Stop", ui_size)
-                    .style(iced::theme::Button::Destructive)
-                    .on_press_maybe(
-                        if !self.rapier_parameters.is_simulation_running || sim_state.is_paused() {
+            column![
+                row![
+                    text_button("Start", ui_size)
+                        .style(iced::theme::Button::Positive)
+                        .on_press_maybe(if self.rapier_parameters.is_simulation_running {
                             None
                         } else {
-                            Some(LeftPanelMessage::StopSimulation)
-                        }
+                            Some(LeftPanelMessage::UpdateRapierParameters(
+                                apply_parameter_fields(
+                                    &self.rapier_parameter_fields,
+                                    &RapierParameters {
+                                        is_simulation_running: true,
+                                        ..self.rapier_parameters
+                                    },
+                                ),
+                            ))
+                        }),
+                    Space::with_width(ui_size.button_spacing()),
+                    text_button("Stop", ui_size)
+                        .style(iced::theme::Button::Destructive)
+                        .on_press_maybe(
+                            if !self.rapier_parameters.is_simulation_running
+                                || sim_state.is_paused()
+                            {
+                                None
+                            } else {
+                                Some(LeftPanelMessage::StopSimulation)
+                            }
+                        ),
+                    Space::with_width(ui_size.button_spacing()),
+                    text_button("Reset", ui_size)
+                        .style(iced::theme::Button::Primary)
+                        .on_press_maybe(
+                            sim_state
+                                .is_paused()
+                                .then(|| LeftPanelMessage::ResetSimulation)
+                        ),
+                ],
+                row![
+                    text_button("Commit", ui_size).on_press_maybe(
+                        (self.rapier_parameters.is_simulation_running || sim_state.is_paused())
+                            .then_some(LeftPanelMessage::CommitRelaxation)
                     ),
-                Space::with_width(ui_size.button_spacing()),
-                text_button("Reset", ui_size)
-                    .style(iced::theme::Button::Primary)
-                    .on_press_maybe(
-                        sim_state
-                            .is_paused()
-                            .then(|| LeftPanelMessage::ResetSimulation)
+                    Space::with_width(ui_size.button_spacing()),
+                    text_button("Discard committed positions", ui_size).on_press_maybe(
+                        (!app_state.0.design.design.relaxed_positions.is_empty()
+                            && !self.rapier_parameters.is_simulation_running)
+                            .then_some(LeftPanelMessage::ClearRelaxedPositions)
                     ),
-            ],]
+                ],
+            ]
             .spacing(ui_size.button_spacing()),
+            presets_view(
+                &self.presets,
+                &self.preset_name,
+                self.rapier_parameters.is_simulation_running || sim_state.is_running(),
+                ui_size,
+            ),
             ignore_local_parameters_checkbox(&self.rapier_parameters, ui_size),
+            convergence_checkbox(&self.rapier_parameters, ui_size),
+            setup_picker(&self.rapier_parameters, ui_size),
             view_rapier_parameters(
                 self.rapier_parameters,
                 &self.rapier_parameter_fields,
                 ui_size,
             ),
+            trajectory_view(
+                &self.trajectory,
+                self.rapier_parameters.is_simulation_running || sim_state.is_running(),
+                ui_size,
+            ),
+            rigidity_view(
+                app_state,
+                self.rapier_parameters.is_simulation_running || sim_state.is_running(),
+                ui_size,
+            ),
         ]
         .spacing(5);
 
@@ -273,6 +360,239 @@ fn ignore_local_parameters_checkbox(
     .into()
 }
 
+/// When checked, the relaxation stops by itself once the nucleotides stop moving.
+fn convergence_checkbox(
+    parameters: &RapierParameters,
+    ui_size: UiSize,
+) -> iced::Element<'static, LeftPanelMessage> {
+    let parameters = *parameters;
+    right_checkbox(
+        parameters.convergence.is_some(),
+        "Stop when converged",
+        move |value| {
+            LeftPanelMessage::UpdateRapierParameters(RapierParameters {
+                convergence: value.then(RelaxationCriteria::default),
+                ..parameters
+            })
+        },
+        ui_size,
+        !parameters.is_simulation_running,
+    )
+    .into()
+}
+
+/// Chooses how nucleotides are grouped into rigid bodies. Coarser setups are much faster on
+/// large designs.
+fn setup_picker(
+    parameters: &RapierParameters,
+    ui_size: UiSize,
+) -> iced::Element<'static, LeftPanelMessage> {
+    let parameters = *parameters;
+    row![
+        text("Rigid bodies"),
+        Space::with_width(ui_size.checkbox_spacing()),
+        if parameters.is_simulation_running {
+            pick_list(SimulationSetupKind::ALL, Some(parameters.setup), |_| {
+                LeftPanelMessage::Nothing
+            })
+        } else {
+            pick_list(
+                SimulationSetupKind::ALL,
+                Some(parameters.setup),
+                move |setup| {
+                    LeftPanelMessage::UpdateRapierParameters(RapierParameters {
+                        setup,
+                        ..parameters
+                    })
+                },
+            )
+        },
+    ]
+    .align_items(Alignment::Center)
+    .into()
+}
+
+/// Loading and saving of named sets of parameters. Built-in presets cannot be overwritten.
+fn presets_view(
+    presets: &[SimulationPreset],
+    preset_name: &str,
+    simulating: bool,
+    ui_size: UiSize,
+) -> iced::Element<'static, LeftPanelMessage> {
+    let names: Vec<String> = presets.iter().map(|preset| preset.name.clone()).collect();
+    let existing = presets.iter().find(|preset| preset.name == preset_name);
+    let can_save = !preset_name.trim().is_empty() && existing.is_none_or(|p| !p.built_in);
+    let can_delete = existing.is_some_and(|p| !p.built_in);
+
+    column![
+        subsection("Presets", ui_size),
+        row![
+            text("Load"),
+            Space::with_width(ui_size.checkbox_spacing()),
+            pick_list(names, None::<String>, move |name| {
+                if simulating {
+                    LeftPanelMessage::Nothing
+                } else {
+                    LeftPanelMessage::ApplySimulationPreset(name)
+                }
+            })
+            .placeholder("Choose a preset"),
+        ]
+        .align_items(Alignment::Center),
+        row![
+            keyboard_priority(
+                "Simulation preset name",
+                LeftPanelMessage::SetKeyboardPriority,
+                text_input("Preset name", preset_name)
+                    .on_input(LeftPanelMessage::SimulationPresetNameInput)
+                    .width(Length::FillPortion(2)),
+            ),
+            Space::with_width(ui_size.button_spacing()),
+            text_button("Save", ui_size)
+                .on_press_maybe(can_save.then_some(LeftPanelMessage::SaveSimulationPreset)),
+            Space::with_width(ui_size.button_spacing()),
+            text_button("Delete", ui_size)
+                .on_press_maybe(can_delete.then_some(LeftPanelMessage::DeleteSimulationPreset)),
+        ]
+        .align_items(Alignment::Center),
+    ]
+    .spacing(ui_size.button_spacing())
+    .into()
+}
+
+/// Recording, scrubbing and export of the trajectory of the simulations.
+///
+/// Frames can only be shown or exported while no simulation is running.
+fn trajectory_view(
+    trajectory: &TrajectorySummary,
+    simulating: bool,
+    ui_size: UiSize,
+) -> iced::Element<'static, LeftPanelMessage> {
+    let can_use_frames = trajectory.nb_frames > 0 && !simulating;
+    let interval = trajectory.interval;
+    let mut content = column![
+        section("Trajectory", ui_size),
+        right_checkbox(
+            trajectory.recording,
+            "Record trajectory",
+            LeftPanelMessage::SetTrajectoryRecording,
+            ui_size,
+            true,
+        ),
+        row![
+            text(format!("Frame every {interval} steps")).width(Length::FillPortion(1)),
+            slider(
+                10.0..=1000.0,
+                interval as f32,
+                LeftPanelMessage::SetTrajectoryInterval
+            )
+            .step(10.0)
+            .width(Length::FillPortion(1)),
+        ]
+        .align_items(Alignment::Center),
+        text(format!(
+            "{} frames, last at step {}",
+            trajectory.nb_frames, trajectory.last_step
+        )),
+    ]
+    .spacing(ui_size.button_spacing());
+
+    if trajectory.nb_frames > 1 {
+        let last_frame = (trajectory.nb_frames - 1) as f32;
+        let shown_frame = trajectory.shown_frame.unwrap_or(trajectory.nb_frames - 1);
+        content = content.push(
+            row![
+                text(format!(
+                    "Frame {}/{}",
+                    shown_frame + 1,
+                    trajectory.nb_frames
+                ))
+                .width(Length::FillPortion(1)),
+                slider(0.0..=last_frame, shown_frame as f32, move |frame| {
+                    if simulating {
+                        LeftPanelMessage::Nothing
+                    } else {
+                        LeftPanelMessage::ShowTrajectoryFrame(frame as usize)
+                    }
+                })
+                .step(1.0)
+                .width(Length::FillPortion(1)),
+            ]
+            .align_items(Alignment::Center),
+        );
+    }
+
+    content
+        .push(row![
+            text_button("Export oxDNA", ui_size).on_press_maybe(
+                can_use_frames.then_some(LeftPanelMessage::ExportTrajectory(ExportType::Oxdna))
+            ),
+            Space::with_width(ui_size.button_spacing()),
+            text_button("Export PDB", ui_size).on_press_maybe(
+                can_use_frames.then_some(LeftPanelMessage::ExportTrajectory(ExportType::Pdb))
+            ),
+            Space::with_width(ui_size.button_spacing()),
+            text_button("Clear", ui_size)
+                .on_press_maybe(can_use_frames.then_some(LeftPanelMessage::ClearTrajectory)),
+        ])
+        .into()
+}
+
+/// Elastic network analysis of the design. The fluctuations of the nucleotides are shown as a
+/// heat map in the 3D scene, which is hidden as soon as the design is modified.
+fn rigidity_view(
+    app_state: &AppState,
+    simulating: bool,
+    ui_size: UiSize,
+) -> iced::Element<'static, LeftPanelMessage> {
+    let analysis = app_state.get_rigidity_analysis();
+    let mut content = column![
+        section("Rigidity (beta)", ui_size),
+        row![
+            text_button("Analyse", ui_size)
+                .on_press_maybe((!simulating).then_some(LeftPanelMessage::AnalyseRigidity)),
+            Space::with_width(ui_size.button_spacing()),
+            text_button("Hide", ui_size).on_press_maybe(
+                analysis
+                    .is_some()
+                    .then_some(LeftPanelMessage::ClearRigidityAnalysis)
+            ),
+            Space::with_width(ui_size.button_spacing()),
+            text_button("Apply equilibrium shape", ui_size).on_press_maybe(
+                (analysis.is_some() && !simulating)
+                    .then_some(LeftPanelMessage::ApplyEquilibriumShape)
+            ),
+        ],
+    ]
+    .spacing(ui_size.button_spacing());
+
+    if let Some(analysis) = analysis {
+        if let Some((nucl, fluctuation)) = analysis.most_flexible() {
+            content = content.push(text(format!(
+                "Largest fluctuation: {fluctuation:.2} nm (helix {}, position {})",
+                nucl.helix, nucl.position
+            )));
+        }
+        content = content
+            .push(text(format!(
+                "Equilibrium shape within {:.2} nm of the design",
+                analysis.max_displacement()
+            )))
+            .push(text(format!(
+                "{} nodes of {} bp",
+                analysis.nb_nodes, analysis.resolution
+            )));
+        if analysis.nb_floppy_modes > 0 {
+            content = content.push(text(format!(
+                "{} unconstrained motions are ignored",
+                analysis.nb_floppy_modes
+            )));
+        }
+    }
+
+    content.into()
+}
+
 /// Updates the parameters using the fields.
 fn apply_parameter_fields(
     fields: &HashMap<String, String>,
@@ -315,7 +635,7 @@ fn rapier_parameters_field_editor(
         Space::with_width(ui_size.checkbox_spacing()),
         row![
             keyboard_priority(
-                "Rapier parameters ".to_owned() + &description,
+                format!("Rapier parameters {description}"),
                 LeftPanelMessage::SetKeyboardPriority,
                 // if parameters.is_simulation_running {
                 //     text_input(current_value, current_value)
@@ -43,7 +43,7 @@ use iced::{
     keyboard,
     mouse::Cursor,
 };
-use iced_runtime::{Debug, program};
+use iced_runtime::{Command, Debug, command, program};
 use iced_wgpu::Backend;
 use std::{
     collections::HashMap,
@@ -73,6 +73,26 @@ impl GuiState {
                 }
                 Self::TopBar(_) | Self::LeftPanel(_) => (),
             }
+        } else if let (
+            Self::LeftPanel(state),
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(named),
+                ..
+            }),
+        ) = (&mut *self, &event)
+        {
+            // NOTE: The command palette is navigated with the keyboard while its text input has
+            //       the focus.
+            let message = match named {
+                keyboard::key::Named::Escape => Some(LeftPanelMessage::CloseCommandPalette),
+                keyboard::key::Named::ArrowUp => Some(LeftPanelMessage::CommandPaletteMove(-1)),
+                keyboard::key::Named::ArrowDown => Some(LeftPanelMessage::CommandPaletteMove(1)),
+                _ => None,
+            };
+            state.queue_event(event);
+            if let Some(message) = message {
+                state.queue_message(message);
+            }
         } else {
             match self {
                 Self::TopBar(state) => state.queue_event(event),
@@ -147,7 +167,9 @@ impl GuiState {
                 let _ = state.update(size, cursor, renderer, theme, style, &mut clipboard, debug);
             }
             Self::LeftPanel(state) => {
-                let _ = state.update(size, cursor, renderer, theme, style, &mut clipboard, debug);
+                let (_, command) =
+                    state.update(size, cursor, renderer, theme, style, &mut clipboard, debug);
+                run_widget_operations(state, command, size, renderer, debug);
             }
             Self::StatusBar(state) => {
                 let _ = state.update(size, cursor, renderer, theme, style, &mut clipboard, debug);
@@ -205,6 +227,29 @@ impl GuiState {
     }
 }
 
+/// Apply the widget operations (such as giving focus to a text input) of `command`.
+///
+/// The other actions of the command are ignored, since the GUI is not run by an iced runtime.
+fn run_widget_operations<P>(
+    state: &mut program::State<P>,
+    command: Option<Command<P::Message>>,
+    size: Size,
+    renderer: &mut iced::Renderer,
+    debug: &mut Debug,
+) where
+    P: iced_runtime::Program<Renderer = iced::Renderer> + 'static,
+{
+    let operations =
+        command
+            .into_iter()
+            .flat_map(Command::actions)
+            .filter_map(|action| match action {
+                command::Action::Widget(operation) => Some(operation),
+                _ => None,
+            });
+    state.operate(renderer, operations, size, debug);
+}
+
 /// A Gui component.
 struct GuiComponent {
     state: GuiState,
@@ -135,6 +135,7 @@ impl Program for TopBarState {
             // TODO: Consider rename message ThickHelices → AllHelicesOnAxis
             TopBarMessage::AlignHorizon => self.requests.lock().unwrap().align_horizon(),
             TopBarMessage::Import3D => self.requests.lock().unwrap().import_3d_object(),
+            TopBarMessage::InsertDesign => self.requests.lock().unwrap().insert_design(),
         }
         Command::none()
     }
@@ -232,6 +233,18 @@ impl Program for TopBarState {
         )
         .style(theme::Container::Box);
 
+        let button_insert_design = tooltip(
+            material_icon_button(
+                MaterialIcon::LibraryAdd,
+                MaterialIconStyle::Light,
+                self.ui_size,
+            )
+            .on_press(TopBarMessage::InsertDesign),
+            "Insert design from file…",
+            tooltip::Position::FollowCursor,
+        )
+        .style(theme::Container::Box);
+
         let button_2d = tooltip(
             fixed_text_button("2D", 1.0, self.ui_size)
                 .on_press(TopBarMessage::ToggleView(SplitMode::Flat)),
@@ -418,6 +431,7 @@ impl Program for TopBarState {
                 button_save_as,
                 oxdna_tooltip,
                 button_3d_import,
+                button_insert_design,
             ]
             .spacing(self.ui_size.button_spacing()),
             // “View” group
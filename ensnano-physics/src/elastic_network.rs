@@ -0,0 +1,813 @@
+//! This module defines an elastic network analysis of the rigidity of a design.
+//!
+//! The double stranded portions of the helices are coarse grained into nodes placed on their
+//! axis. Consecutive nodes of a helix are linked by stretching springs and bending terms, and
+//! crossovers link the nodes of different helices. Nicks weaken the bending stiffness of the
+//! duplex, and helices are cut where neither strand continues.
+//!
+//! The equilibrium shape of the network is found by minimizing its energy, and the root mean
+//! square fluctuations of the nodes are computed from the normal modes of the network around
+//! this equilibrium.
+
+use ahash::{HashMap, HashSet};
+use ensnano_design::{Design, domains::Domain, nucl::Nucl};
+use rapier3d::na::{DMatrix, DVector, Matrix3, SymmetricEigen, Vector3};
+use std::collections::BTreeSet;
+use ultraviolet::Vec3;
+
+/// The Boltzmann constant, in pN.nm/K.
+const BOLTZMANN_CONSTANT: f64 = 0.013_806_49;
+
+/// Eigenvalues smaller than this fraction of the largest one are zero modes.
+const ZERO_MODE_THRESHOLD: f64 = 1e-9;
+
+const MAX_MINIMIZATION_STEPS: usize = 50;
+
+/// The minimization stops once no node moves by more than this distance (in nm) in a step.
+const EQUILIBRIUM_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElasticNetworkParameters {
+    /// The stretch modulus of the duplex, in pN.
+    pub stretch_modulus: f64,
+    /// The bending persistence length of the duplex, in nm.
+    pub persistence_length: f64,
+    /// The factor applied to the bending stiffness of the duplex across a nick.
+    pub nick_factor: f64,
+    /// The temperature, in K.
+    pub temperature: f64,
+    /// Base pairs are grouped into coarser nodes until the network has at most this many nodes.
+    pub max_nodes: usize,
+}
+
+impl Default for ElasticNetworkParameters {
+    fn default() -> Self {
+        Self {
+            stretch_modulus: 1100.,
+            persistence_length: 50.,
+            nick_factor: 0.1,
+            temperature: 298.15,
+            max_nodes: 400,
+        }
+    }
+}
+
+/// The result of the analysis of a design.
+#[derive(Debug, Clone)]
+pub struct RigidityAnalysis {
+    /// The number of base pairs grouped in each node of the network.
+    pub resolution: usize,
+    pub nb_nodes: usize,
+    /// The number of motions that the network does not constrain, besides the rigid motions of
+    /// its connected parts. The fluctuations along these motions are not accounted for.
+    pub nb_floppy_modes: usize,
+    /// The root mean square fluctuation of the paired nucleotides, in nm.
+    pub fluctuations: HashMap<Nucl, f32>,
+    /// The displacement of the paired nucleotides from the design to the equilibrium shape.
+    pub displacements: HashMap<Nucl, Vec3>,
+}
+
+impl RigidityAnalysis {
+    /// The nucleotide with the largest fluctuation.
+    pub fn most_flexible(&self) -> Option<(Nucl, f32)> {
+        self.fluctuations
+            .iter()
+            .max_by(|(n1, f1), (n2, f2)| {
+                f1.total_cmp(f2)
+                    .then_with(|| (n2.helix, n2.position).cmp(&(n1.helix, n1.position)))
+            })
+            .map(|(nucl, fluctuation)| (*nucl, *fluctuation))
+    }
+
+    /// The largest distance between the design and its equilibrium shape, in nm.
+    pub fn max_displacement(&self) -> f32 {
+        self.displacements
+            .values()
+            .map(Vec3::mag)
+            .fold(0., f32::max)
+    }
+}
+
+impl std::fmt::Display for RigidityAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} nodes of {} bp", self.nb_nodes, self.resolution)?;
+        if let Some((nucl, fluctuation)) = self.most_flexible() {
+            write!(
+                f,
+                ", largest fluctuation {fluctuation:.2} nm on helix {} at position {}",
+                nucl.helix, nucl.position
+            )?;
+        }
+        write!(
+            f,
+            ", equilibrium shape within {:.2} nm of the design",
+            self.max_displacement()
+        )?;
+        if self.nb_floppy_modes > 0 {
+            write!(f, ", {} unconstrained motions", self.nb_floppy_modes)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElasticNetworkError {
+    NoDoubleStrand,
+    /// The eigenvalue decomposition of the stiffness matrix did not converge.
+    NoConvergence,
+}
+
+impl std::fmt::Display for ElasticNetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoDoubleStrand => write!(f, "The design has no double stranded portion."),
+            Self::NoConvergence => {
+                write!(f, "The normal modes of the design could not be computed.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ElasticNetworkError {}
+
+/// Compute the equilibrium shape of `design` and the fluctuations of its nucleotides around it.
+pub fn analyse_rigidity(
+    design: &Design,
+    parameters: &ElasticNetworkParameters,
+) -> Result<RigidityAnalysis, ElasticNetworkError> {
+    let network = ElasticNetwork::new(design, parameters)?;
+    let equilibrium = network.equilibrium();
+    let (mean_square_fluctuations, nb_floppy_modes) =
+        network.mean_square_fluctuations(&equilibrium, parameters)?;
+
+    let mut fluctuations = HashMap::default();
+    let mut displacements = HashMap::default();
+    for (nucl, node) in &network.nucleotides {
+        fluctuations.insert(*nucl, mean_square_fluctuations[*node].sqrt() as f32);
+        let displacement = equilibrium[*node] - network.initial_positions[*node];
+        displacements.insert(
+            *nucl,
+            Vec3::new(
+                displacement.x as f32,
+                displacement.y as f32,
+                displacement.z as f32,
+            ),
+        );
+    }
+
+    Ok(RigidityAnalysis {
+        resolution: network.resolution,
+        nb_nodes: network.initial_positions.len(),
+        nb_floppy_modes,
+        fluctuations,
+        displacements,
+    })
+}
+
+/// The base pairs and backbone bonds of a design.
+#[derive(Default)]
+struct Topology {
+    /// The positions of the helices where both strands are present.
+    paired: BTreeSet<(usize, isize)>,
+    /// `(helix, position, forward)` such that the nucleotides at `position` and `position + 1`
+    /// are bonded.
+    helix_bonds: HashSet<(usize, isize, bool)>,
+    crossovers: Vec<(Nucl, Nucl)>,
+}
+
+impl Topology {
+    fn read(design: &Design) -> Self {
+        let mut ret = Self::default();
+        let mut present = HashSet::default();
+        for strand in design.strands.values() {
+            let mut nucls = vec![];
+            for domain in &strand.domains {
+                match domain {
+                    Domain::HelixDomain(interval) => {
+                        nucls.extend(interval.iter_nucls(&design.helices).map(|position| {
+                            Some(Nucl {
+                                helix: interval.helix,
+                                position,
+                                forward: interval.forward,
+                            })
+                        }));
+                    }
+                    // Insertions are loops that do not hold the helices together.
+                    Domain::Insertion { .. } => nucls.push(None),
+                }
+            }
+            present.extend(nucls.iter().flatten().copied());
+
+            let closing_bond = strand.is_cyclic.then(|| {
+                (
+                    nucls.last().copied().flatten(),
+                    nucls.first().copied().flatten(),
+                )
+            });
+            let bonds = nucls
+                .windows(2)
+                .map(|pair| {
+                    let [prime5, prime3] = pair else {
+                        unreachable!()
+                    };
+                    (*prime5, *prime3)
+                })
+                .chain(closing_bond);
+            for (prime5, prime3) in bonds {
+                let (Some(prime5), Some(prime3)) = (prime5, prime3) else {
+                    continue;
+                };
+                if prime5.helix != prime3.helix {
+                    ret.crossovers.push((prime5, prime3));
+                } else if prime5.forward == prime3.forward
+                    && (prime5.position - prime3.position).abs() == 1
+                {
+                    ret.helix_bonds.insert((
+                        prime5.helix,
+                        prime5.position.min(prime3.position),
+                        prime5.forward,
+                    ));
+                }
+            }
+        }
+        ret.paired = present
+            .iter()
+            .filter(|nucl| nucl.forward && present.contains(&nucl.compl()))
+            .map(|nucl| (nucl.helix, nucl.position))
+            .collect();
+        ret
+    }
+
+    fn nb_nodes(&self, resolution: usize) -> usize {
+        self.paired
+            .iter()
+            .map(|(helix, position)| (*helix, position.div_euclid(resolution as isize)))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// The smallest number of base pairs per node that keeps the network under `max_nodes`
+    /// nodes.
+    fn resolution(&self, max_nodes: usize) -> usize {
+        let span = {
+            let positions = self.paired.iter().map(|(_, position)| *position);
+            let min = positions.clone().min().unwrap_or_default();
+            let max = positions.max().unwrap_or_default();
+            (max - min + 1) as usize
+        };
+        let mut resolution = self.paired.len().div_ceil(max_nodes.max(1)).max(1);
+        while resolution < span && self.nb_nodes(resolution) > max_nodes {
+            resolution += 1;
+        }
+        resolution
+    }
+
+    /// The number of strands (0, 1 or 2) holding the duplex together between `position` and
+    /// `position + 1`.
+    fn continuity(&self, helix: usize, position: isize) -> usize {
+        if !self.paired.contains(&(helix, position))
+            || !self.paired.contains(&(helix, position + 1))
+        {
+            return 0;
+        }
+        [true, false]
+            .into_iter()
+            .filter(|forward| self.helix_bonds.contains(&(helix, position, *forward)))
+            .count()
+    }
+}
+
+/// A harmonic spring between two nodes.
+struct Spring {
+    nodes: [usize; 2],
+    stiffness: f64,
+    rest_length: f64,
+}
+
+/// A harmonic term on the component, orthogonal to `axis`, of a linear combination of the
+/// positions of some nodes. It gives its bending stiffness to a helix, and couples the
+/// orientations of helices at crossovers.
+struct AngularTerm {
+    coefficients: Vec<(usize, f64)>,
+    stiffness: f64,
+    /// The projection orthogonal to the axis of the term.
+    projection: Matrix3<f64>,
+    rest: Vector3<f64>,
+}
+
+impl AngularTerm {
+    fn new(
+        coefficients: Vec<(usize, f64)>,
+        stiffness: f64,
+        axis: Vector3<f64>,
+        positions: &[Vector3<f64>],
+    ) -> Self {
+        let axis = axis.try_normalize(f64::EPSILON).unwrap_or_default();
+        let mut ret = Self {
+            coefficients,
+            stiffness,
+            projection: Matrix3::identity() - axis * axis.transpose(),
+            rest: Vector3::zeros(),
+        };
+        ret.rest = ret.deformation(positions);
+        ret
+    }
+
+    fn deformation(&self, positions: &[Vector3<f64>]) -> Vector3<f64> {
+        self.projection
+            * self
+                .coefficients
+                .iter()
+                .map(|(node, coefficient)| positions[*node] * *coefficient)
+                .sum::<Vector3<f64>>()
+    }
+}
+
+struct ElasticNetwork {
+    resolution: usize,
+    /// The positions of the nodes in the design.
+    initial_positions: Vec<Vector3<f64>>,
+    springs: Vec<Spring>,
+    angular_terms: Vec<AngularTerm>,
+    /// The node of each paired nucleotide.
+    nucleotides: Vec<(Nucl, usize)>,
+}
+
+impl ElasticNetwork {
+    fn new(
+        design: &Design,
+        parameters: &ElasticNetworkParameters,
+    ) -> Result<Self, ElasticNetworkError> {
+        let topology = Topology::read(design);
+        let helix_parameters = design.helix_parameters.unwrap_or_default();
+        let resolution = topology.resolution(parameters.max_nodes);
+        let block_of = |position: isize| position.div_euclid(resolution as isize);
+
+        // The nodes, with the first and last paired positions that they contain.
+        let mut node_of = HashMap::default();
+        let mut bounds: Vec<(isize, isize)> = vec![];
+        let mut sums: Vec<(Vector3<f64>, f64)> = vec![];
+        let mut nucleotides = vec![];
+        for &(helix_id, position) in &topology.paired {
+            let Some(helix) = design.helices.get(&helix_id) else {
+                continue;
+            };
+            let node = *node_of
+                .entry((helix_id, block_of(position)))
+                .or_insert_with(|| {
+                    bounds.push((position, position));
+                    sums.push((Vector3::zeros(), 0.));
+                    bounds.len() - 1
+                });
+            bounds[node].1 = position;
+            let axis = helix.axis_position(&helix_parameters, position, true);
+            sums[node].0 += Vector3::new(axis.x as f64, axis.y as f64, axis.z as f64);
+            sums[node].1 += 1.;
+            for forward in [true, false] {
+                nucleotides.push((
+                    Nucl {
+                        helix: helix_id,
+                        position,
+                        forward,
+                    },
+                    node,
+                ));
+            }
+        }
+        if nucleotides.is_empty() {
+            return Err(ElasticNetworkError::NoDoubleStrand);
+        }
+        let positions: Vec<Vector3<f64>> = sums.iter().map(|(sum, n)| sum / *n).collect();
+
+        let mut springs = vec![];
+        let mut angular_terms = vec![];
+
+        // Consecutive nodes of a helix, with the number of strands linking them.
+        let mut next_node: HashMap<usize, (usize, usize)> = HashMap::default();
+        let mut previous_node: HashMap<usize, (usize, usize)> = HashMap::default();
+        for (&(helix, block), &node) in &node_of {
+            let Some(&next) = node_of.get(&(helix, block + 1)) else {
+                continue;
+            };
+            let continuity = (bounds[node].0..bounds[next].1)
+                .map(|position| topology.continuity(helix, position))
+                .min()
+                .unwrap_or(0);
+            if continuity == 0 {
+                continue;
+            }
+            next_node.insert(node, (next, continuity));
+            previous_node.insert(next, (node, continuity));
+            let rest_length = (positions[next] - positions[node]).norm();
+            springs.push(Spring {
+                nodes: [node, next],
+                stiffness: parameters.stretch_modulus / rest_length,
+                rest_length,
+            });
+        }
+
+        let bending_rigidity =
+            BOLTZMANN_CONSTANT * parameters.temperature * parameters.persistence_length;
+        for (&node, &(previous, continuity_previous)) in &previous_node {
+            let Some(&(next, continuity_next)) = next_node.get(&node) else {
+                continue;
+            };
+            let length = ((positions[node] - positions[previous]).norm()
+                + (positions[next] - positions[node]).norm())
+                / 2.;
+            let nick_factor = if continuity_previous.min(continuity_next) < 2 {
+                parameters.nick_factor
+            } else {
+                1.
+            };
+            angular_terms.push(AngularTerm::new(
+                vec![(previous, 1.), (node, -2.), (next, 1.)],
+                nick_factor * bending_rigidity / length.powi(3),
+                positions[next] - positions[previous],
+                &positions,
+            ));
+        }
+
+        let crossover_length =
+            (2. * helix_parameters.helix_radius + helix_parameters.inter_helix_gap) as f64;
+        let neighbours = |node: usize| {
+            [previous_node.get(&node), next_node.get(&node)]
+                .into_iter()
+                .flatten()
+                .map(|(neighbour, _)| *neighbour)
+                .collect::<Vec<_>>()
+        };
+        for (prime5, prime3) in &topology.crossovers {
+            let (Some(&a), Some(&b)) = (
+                node_of.get(&(prime5.helix, block_of(prime5.position))),
+                node_of.get(&(prime3.helix, block_of(prime3.position))),
+            ) else {
+                continue;
+            };
+            if !topology.paired.contains(&(prime5.helix, prime5.position))
+                || !topology.paired.contains(&(prime3.helix, prime3.position))
+            {
+                continue;
+            }
+            springs.push(Spring {
+                nodes: [a, b],
+                stiffness: parameters.stretch_modulus / crossover_length,
+                rest_length: crossover_length,
+            });
+            // Diagonal springs prevent the helices from sliding along each other.
+            for (node, other) in [(a, b), (b, a)] {
+                for neighbour in neighbours(node) {
+                    let along_helix = (positions[neighbour] - positions[node]).norm();
+                    let rest_length = crossover_length.hypot(along_helix);
+                    springs.push(Spring {
+                        nodes: [neighbour, other],
+                        stiffness: parameters.stretch_modulus / rest_length,
+                        rest_length,
+                    });
+                }
+            }
+            // The crossover holds the helices parallel to each other.
+            if let (
+                Some(&(a_minus, _)),
+                Some(&(a_plus, _)),
+                Some(&(b_minus, _)),
+                Some(&(b_plus, _)),
+            ) = (
+                previous_node.get(&a),
+                next_node.get(&a),
+                previous_node.get(&b),
+                next_node.get(&b),
+            ) {
+                let tangent_a = positions[a_plus] - positions[a_minus];
+                let tangent_b = positions[b_plus] - positions[b_minus];
+                let sign = tangent_a.dot(&tangent_b).signum();
+                let length = (tangent_a.norm() + tangent_b.norm()) / 4.;
+                angular_terms.push(AngularTerm::new(
+                    vec![
+                        (a_plus, 1.),
+                        (a_minus, -1.),
+                        (b_plus, -sign),
+                        (b_minus, sign),
+                    ],
+                    bending_rigidity / (4. * length.powi(3)),
+                    tangent_a + tangent_b * sign,
+                    &positions,
+                ));
+            }
+        }
+
+        Ok(Self {
+            resolution,
+            initial_positions: positions,
+            springs,
+            angular_terms,
+            nucleotides,
+        })
+    }
+
+    fn energy(&self, positions: &[Vector3<f64>]) -> f64 {
+        let springs = self.springs.iter().map(|spring| {
+            let [a, b] = spring.nodes;
+            let stretch = (positions[a] - positions[b]).norm() - spring.rest_length;
+            0.5 * spring.stiffness * stretch * stretch
+        });
+        let angular = self.angular_terms.iter().map(|term| {
+            0.5 * term.stiffness * (term.deformation(positions) - term.rest).norm_squared()
+        });
+        springs.chain(angular).sum()
+    }
+
+    fn gradient(&self, positions: &[Vector3<f64>]) -> DVector<f64> {
+        let mut gradient = DVector::zeros(3 * positions.len());
+        let mut add = |node: usize, force: Vector3<f64>| {
+            let mut block = gradient.fixed_rows_mut::<3>(3 * node);
+            block += force;
+        };
+        for spring in &self.springs {
+            let [a, b] = spring.nodes;
+            let delta = positions[a] - positions[b];
+            let length = delta.norm();
+            if length > f64::EPSILON {
+                let force = delta * (spring.stiffness * (length - spring.rest_length) / length);
+                add(a, force);
+                add(b, -force);
+            }
+        }
+        for term in &self.angular_terms {
+            let force = (term.deformation(positions) - term.rest) * term.stiffness;
+            for (node, coefficient) in &term.coefficients {
+                add(*node, force * *coefficient);
+            }
+        }
+        gradient
+    }
+
+    /// The stiffness matrix of the network around `positions`, ignoring the tension of the
+    /// springs so that it is positive semi-definite.
+    fn stiffness_matrix(&self, positions: &[Vector3<f64>]) -> DMatrix<f64> {
+        let mut matrix = DMatrix::zeros(3 * positions.len(), 3 * positions.len());
+        let mut add = |i: usize, j: usize, block: Matrix3<f64>| {
+            let mut view = matrix.fixed_view_mut::<3, 3>(3 * i, 3 * j);
+            view += block;
+        };
+        for spring in &self.springs {
+            let [a, b] = spring.nodes;
+            let Some(direction) = (positions[a] - positions[b]).try_normalize(f64::EPSILON) else {
+                continue;
+            };
+            let block = direction * direction.transpose() * spring.stiffness;
+            add(a, a, block);
+            add(b, b, block);
+            add(a, b, -block);
+            add(b, a, -block);
+        }
+        for term in &self.angular_terms {
+            for (i, c_i) in &term.coefficients {
+                for (j, c_j) in &term.coefficients {
+                    add(*i, *j, term.projection * (term.stiffness * c_i * c_j));
+                }
+            }
+        }
+        matrix
+    }
+
+    /// The nodes linked to each other, directly or indirectly.
+    fn connected_components(&self, nb_nodes: usize) -> Vec<Vec<usize>> {
+        fn root(parent: &mut [usize], mut node: usize) -> usize {
+            while parent[node] != node {
+                parent[node] = parent[parent[node]];
+                node = parent[node];
+            }
+            node
+        }
+        let mut parent: Vec<usize> = (0..nb_nodes).collect();
+        let links = self
+            .springs
+            .iter()
+            .map(|spring| spring.nodes.to_vec())
+            .chain(
+                self.angular_terms
+                    .iter()
+                    .map(|term| term.coefficients.iter().map(|(node, _)| *node).collect()),
+            );
+        for nodes in links {
+            for pair in nodes.windows(2) {
+                let [a, b] = pair else { unreachable!() };
+                let (a, b) = (root(&mut parent, *a), root(&mut parent, *b));
+                parent[a] = b;
+            }
+        }
+        let mut components: HashMap<usize, Vec<usize>> = HashMap::default();
+        for node in 0..nb_nodes {
+            components
+                .entry(root(&mut parent, node))
+                .or_default()
+                .push(node);
+        }
+        components.into_values().collect()
+    }
+
+    /// An orthonormal basis of the rigid motions of the connected components of the network.
+    fn rigid_motions(&self, positions: &[Vector3<f64>]) -> Vec<DVector<f64>> {
+        let mut basis: Vec<DVector<f64>> = vec![];
+        for component in self.connected_components(positions.len()) {
+            let center = component
+                .iter()
+                .map(|node| positions[*node])
+                .sum::<Vector3<f64>>()
+                / component.len() as f64;
+            let mut motions = vec![];
+            for axis in [Vector3::x(), Vector3::y(), Vector3::z()] {
+                let mut translation = DVector::zeros(3 * positions.len());
+                let mut rotation = DVector::zeros(3 * positions.len());
+                for node in &component {
+                    translation.fixed_rows_mut::<3>(3 * node).copy_from(&axis);
+                    rotation
+                        .fixed_rows_mut::<3>(3 * node)
+                        .copy_from(&axis.cross(&(positions[*node] - center)));
+                }
+                motions.push(translation);
+                motions.push(rotation);
+            }
+            // Gram-Schmidt orthonormalization. The components are disjoint, so only the motions
+            // of the same component need to be orthogonalized.
+            let first = basis.len();
+            for mut motion in motions {
+                for vector in &basis[first..] {
+                    motion -= vector * vector.dot(&motion);
+                }
+                let norm = motion.norm();
+                if norm > 1e-6 {
+                    basis.push(motion / norm);
+                }
+            }
+        }
+        basis
+    }
+
+    /// Find the equilibrium positions of the nodes with a damped Newton method.
+    fn equilibrium(&self) -> Vec<Vector3<f64>> {
+        let mut positions = self.initial_positions.clone();
+        let mut energy = self.energy(&positions);
+        for _ in 0..MAX_MINIMIZATION_STEPS {
+            let gradient = self.gradient(&positions);
+            let mut stiffness = self.stiffness_matrix(&positions);
+            // Rigid motions and floppy modes make the stiffness matrix singular. They are given
+            // a small stiffness so that the step along them stays small.
+            let regularization = stiffness.diagonal().max().max(1.);
+            for motion in self.rigid_motions(&positions) {
+                stiffness += &motion * motion.transpose() * regularization;
+            }
+            for i in 0..stiffness.nrows() {
+                stiffness[(i, i)] += 1e-9 * regularization;
+            }
+            let Some(cholesky) = stiffness.cholesky() else {
+                break;
+            };
+            let step = cholesky.solve(&-gradient);
+
+            let mut scale = 1.;
+            let (trial, trial_energy) = loop {
+                let trial: Vec<Vector3<f64>> = positions
+                    .iter()
+                    .enumerate()
+                    .map(|(node, position)| position + step.fixed_rows::<3>(3 * node) * scale)
+                    .collect();
+                let trial_energy = self.energy(&trial);
+                if trial_energy <= energy || scale < 1e-4 {
+                    break (trial, trial_energy);
+                }
+                scale /= 2.;
+            };
+            if trial_energy > energy {
+                break;
+            }
+            positions = trial;
+            energy = trial_energy;
+            let max_displacement = (0..positions.len())
+                .map(|node| step.fixed_rows::<3>(3 * node).norm() * scale)
+                .fold(0., f64::max);
+            if max_displacement < EQUILIBRIUM_TOLERANCE {
+                break;
+            }
+        }
+        positions
+    }
+
+    /// The mean square fluctuations of the nodes around `positions` (in nm²), and the number of
+    /// floppy modes that were ignored.
+    fn mean_square_fluctuations(
+        &self,
+        positions: &[Vector3<f64>],
+        parameters: &ElasticNetworkParameters,
+    ) -> Result<(Vec<f64>, usize), ElasticNetworkError> {
+        let stiffness = self.stiffness_matrix(positions);
+        let rigid_motions = self.rigid_motions(positions);
+
+        // Project out the rigid motions, so that they are exactly zero modes even when the
+        // angular terms slightly constrain them.
+        let rigid = DMatrix::from_columns(&rigid_motions);
+        let left = &stiffness - &rigid * (rigid.transpose() * &stiffness);
+        let projected = &left - (&left * &rigid) * rigid.transpose();
+        let projected = (&projected + projected.transpose()) * 0.5;
+
+        let eigen = SymmetricEigen::try_new(projected, 1e-12, 0)
+            .ok_or(ElasticNetworkError::NoConvergence)?;
+        let max_eigenvalue = eigen.eigenvalues.max();
+        let thermal_energy = BOLTZMANN_CONSTANT * parameters.temperature;
+
+        let mut fluctuations = vec![0.; positions.len()];
+        let mut nb_zero_modes = 0usize;
+        for (mode, eigenvalue) in eigen.eigenvalues.iter().enumerate() {
+            if *eigenvalue <= ZERO_MODE_THRESHOLD * max_eigenvalue {
+                nb_zero_modes += 1;
+                continue;
+            }
+            let vector = eigen.eigenvectors.column(mode);
+            for (node, fluctuation) in fluctuations.iter_mut().enumerate() {
+                *fluctuation +=
+                    thermal_energy * vector.fixed_rows::<3>(3 * node).norm_squared() / eigenvalue;
+            }
+        }
+        Ok((
+            fluctuations,
+            nb_zero_modes.saturating_sub(rigid_motions.len()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ensnano_design::{domains::helix_interval::HelixInterval, helices::Helix, strands::Strand};
+    use ultraviolet::Rotor3;
+
+    const LENGTH: isize = 40;
+
+    /// A straight duplex, whose forward strand is nicked after `nick`.
+    fn duplex(nick: Option<isize>) -> Design {
+        let mut design = Design::new();
+        design
+            .helices
+            .make_mut()
+            .insert(0, Helix::new(Vec3::zero(), Rotor3::identity()));
+        let strand = |start, end, forward| Strand {
+            domains: vec![Domain::HelixDomain(HelixInterval {
+                helix: 0,
+                start,
+                end,
+                forward,
+                sequence: None,
+            })],
+            junctions: vec![],
+            sequence: None,
+            is_cyclic: false,
+            color: 0,
+            name: None,
+        };
+        match nick {
+            Some(nick) => {
+                design.strands.push(strand(0, nick + 1, true));
+                design.strands.push(strand(nick + 1, LENGTH, true));
+            }
+            None => design.strands.push(strand(0, LENGTH, true)),
+        }
+        design.strands.push(strand(0, LENGTH, false));
+        design
+    }
+
+    fn fluctuation(analysis: &RigidityAnalysis, position: isize) -> f32 {
+        analysis.fluctuations[&Nucl {
+            helix: 0,
+            position,
+            forward: true,
+        }]
+    }
+
+    #[test]
+    fn duplex_ends_fluctuate_more_than_middle() {
+        let analysis = analyse_rigidity(&duplex(None), &Default::default()).unwrap();
+        assert_eq!(analysis.nb_nodes, LENGTH as usize);
+        assert_eq!(analysis.nb_floppy_modes, 0);
+        assert!(analysis.max_displacement() < 1e-3);
+        assert!(fluctuation(&analysis, 0) > fluctuation(&analysis, LENGTH / 2));
+    }
+
+    #[test]
+    fn nicks_make_duplex_more_flexible() {
+        let intact = analyse_rigidity(&duplex(None), &Default::default()).unwrap();
+        let nicked = analyse_rigidity(&duplex(Some(LENGTH / 2)), &Default::default()).unwrap();
+        assert!(fluctuation(&nicked, LENGTH / 2) > fluctuation(&intact, LENGTH / 2));
+    }
+
+    #[test]
+    fn nodes_are_coarsened_to_fit_the_budget() {
+        let parameters = ElasticNetworkParameters {
+            max_nodes: 10,
+            ..Default::default()
+        };
+        let analysis = analyse_rigidity(&duplex(None), &parameters).unwrap();
+        assert!(analysis.nb_nodes <= 10);
+        assert_eq!(analysis.resolution, 4);
+    }
+}
@@ -3,8 +3,10 @@
 
 mod anchors;
 mod brown;
+pub mod elastic_network;
 mod helices;
 pub mod parameters;
+pub mod relaxation;
 mod repulsion;
 mod setup;
 pub mod simulation;
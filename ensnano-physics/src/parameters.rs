@@ -2,8 +2,13 @@
 //! so that the interface part of the program can directly construct
 //! the relevant data types.
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::relaxation::RelaxationCriteria;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RapierParameters {
+    #[serde(skip)]
     pub is_simulation_running: bool,
     pub speed: f32,
     pub ignore_local_parameters: bool,
@@ -25,6 +30,40 @@ pub struct RapierParameters {
     pub squish_strength: f32,
     pub squish_damping: f32,
     pub squish_soft_cutoff: f32,
+    pub setup: SimulationSetupKind,
+    /// When set, the simulation stops by itself once these criteria are met.
+    pub convergence: Option<RelaxationCriteria>,
+}
+
+/// How the nucleotides are grouped into rigid bodies. Coarser setups
+/// trade accuracy for speed and are meant for large designs.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SimulationSetupKind {
+    /// Every pair of nucleotides is its own rigid body.
+    #[default]
+    Full,
+    /// Each continuous double stranded portion of an helix is a rigid body.
+    RigidHelices,
+    /// Like `RigidHelices`, but the rigid bodies are also cut at crossovers.
+    SlicedRigidHelices,
+}
+
+impl SimulationSetupKind {
+    pub const ALL: [Self; 3] = [Self::Full, Self::RigidHelices, Self::SlicedRigidHelices];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Full => "Full",
+            Self::RigidHelices => "Rigid helices",
+            Self::SlicedRigidHelices => "Sliced rigid helices",
+        }
+    }
+}
+
+impl std::fmt::Display for SimulationSetupKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -248,6 +287,8 @@ impl RapierParameters {
         squish_strength: 0.0,
         squish_damping: 1.0,
         squish_soft_cutoff: 3.0,
+        setup: SimulationSetupKind::Full,
+        convergence: None,
     };
 
     fn parameters_array(&self) -> [f32; RAPIER_FLOAT_PARAMETERS_COUNT] {
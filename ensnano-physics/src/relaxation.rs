@@ -0,0 +1,249 @@
+//! This module defines the relaxation mode of the simulation.
+//!
+//! The system is stepped until the nucleotides stop moving or until a step budget is exhausted,
+//! while the energy of the system is tracked so that a diverging computation is reported instead
+//! of running away.
+
+use crate::{parameters::RapierParameters, simulation::RapierPhysicsSystem};
+use rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Past this displacement in a single step (in nm) the simulation is considered to have
+/// diverged.
+pub const DIVERGENCE_DISPLACEMENT: f32 = 10.0;
+
+/// When to stop a relaxation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RelaxationCriteria {
+    /// The relaxation has converged once no nucleotide moves by more than this distance (in nm)
+    /// during a step.
+    pub tolerance: f32,
+    /// The maximum number of steps of the relaxation.
+    pub max_steps: usize,
+}
+
+impl Default for RelaxationCriteria {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-4,
+            max_steps: 20_000,
+        }
+    }
+}
+
+impl RelaxationCriteria {
+    /// Return the outcome of the relaxation if it should stop after `progress`.
+    pub fn outcome(&self, progress: &RelaxationProgress) -> Option<RelaxationOutcome> {
+        if progress.max_displacement <= self.tolerance {
+            Some(RelaxationOutcome::Converged)
+        } else if progress.step >= self.max_steps {
+            Some(RelaxationOutcome::StepBudgetExhausted)
+        } else {
+            None
+        }
+    }
+
+    /// The fraction of the step budget that has been used.
+    pub fn completion(&self, progress: &RelaxationProgress) -> f32 {
+        if self.max_steps == 0 {
+            1.0
+        } else {
+            (progress.step as f32 / self.max_steps as f32).min(1.0)
+        }
+    }
+}
+
+/// Measures taken after a step of the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RelaxationProgress {
+    /// The number of steps done so far.
+    pub step: usize,
+    /// The energy stored in the springs between nucleotides.
+    pub spring_energy: f32,
+    /// The electrostatic repulsion energy between helices.
+    pub repulsion_energy: f32,
+    /// The largest distance (in nm) travelled by a nucleotide during the last step.
+    pub max_displacement: f32,
+}
+
+impl RelaxationProgress {
+    pub fn total_energy(&self) -> f32 {
+        self.spring_energy + self.repulsion_energy
+    }
+}
+
+impl std::fmt::Display for RelaxationProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "step {}, energy {:.3} (springs {:.3}, repulsion {:.3}), max displacement {:.2e} nm",
+            self.step,
+            self.total_energy(),
+            self.spring_energy,
+            self.repulsion_energy,
+            self.max_displacement
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelaxationOutcome {
+    /// The displacement fell under the tolerance.
+    Converged,
+    /// The maximum number of steps was reached before convergence.
+    StepBudgetExhausted,
+}
+
+/// The result of a relaxation that did not diverge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelaxationReport {
+    pub outcome: RelaxationOutcome,
+    /// The measures taken after the last step.
+    pub progress: RelaxationProgress,
+}
+
+impl std::fmt::Display for RelaxationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.outcome {
+            RelaxationOutcome::Converged => write!(f, "Relaxation converged: {}", self.progress),
+            RelaxationOutcome::StepBudgetExhausted => {
+                write!(
+                    f,
+                    "Relaxation stopped without converging: {}",
+                    self.progress
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelaxationError {
+    /// The positions or the energy of the system became non finite or exploded.
+    Diverged { step: usize },
+}
+
+impl std::fmt::Display for RelaxationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Diverged { step } => write!(
+                f,
+                "The relaxation diverged at step {step}. Try lowering the speed or the stiffness of the springs."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RelaxationError {}
+
+impl RapierPhysicsSystem {
+    /// Do one step of the simulation and measure how the system evolved.
+    ///
+    /// `step` is the number of steps done so far, including this one.
+    pub fn measured_step(
+        &mut self,
+        parameters: &RapierParameters,
+        step: usize,
+    ) -> Result<RelaxationProgress, RelaxationError> {
+        let before = self.nucleotide_translations();
+        self.step(parameters);
+        let after = self.nucleotide_translations();
+
+        // `f32::max` ignores NaN, so non finite displacements are looked for separately.
+        let displacements = before.iter().zip(after.iter()).map(|(a, b)| (a - b).norm());
+        let max_displacement = if displacements.clone().all(f32::is_finite) {
+            displacements.fold(0.0, f32::max)
+        } else {
+            f32::NAN
+        };
+
+        let progress = RelaxationProgress {
+            step,
+            spring_energy: self.spring_energy(),
+            repulsion_energy: self.repulsion_energy,
+            max_displacement,
+        };
+
+        if !max_displacement.is_finite()
+            || max_displacement > DIVERGENCE_DISPLACEMENT
+            || !progress.total_energy().is_finite()
+        {
+            return Err(RelaxationError::Diverged { step });
+        }
+        Ok(progress)
+    }
+
+    /// Step the simulation until `criteria` are met, calling `on_progress` after each step.
+    pub fn relax(
+        &mut self,
+        parameters: &RapierParameters,
+        criteria: &RelaxationCriteria,
+        mut on_progress: impl FnMut(&RelaxationProgress),
+    ) -> Result<RelaxationReport, RelaxationError> {
+        let mut step = 0;
+        loop {
+            step += 1;
+            let progress = self.measured_step(parameters, step)?;
+            on_progress(&progress);
+            if let Some(outcome) = criteria.outcome(&progress) {
+                return Ok(RelaxationReport { outcome, progress });
+            }
+        }
+    }
+
+    /// The translations of the nucleotides, in the iteration order of `nucleotide_body_map`.
+    fn nucleotide_translations(&self) -> Vec<Vector<Real>> {
+        self.nucleotide_body_map
+            .values()
+            .filter_map(|handle| self.collider_set.get(*handle))
+            .map(|collider| collider.position().translation.vector)
+            .collect()
+    }
+
+    /// The energy stored in the spring joints of the system.
+    fn spring_energy(&self) -> f32 {
+        self.impulse_joint_set
+            .iter()
+            .filter_map(|(_, joint)| {
+                let motor = joint.data.motor(JointAxis::LinX)?;
+                let body1 = self.rigid_body_set.get(joint.body1)?;
+                let body2 = self.rigid_body_set.get(joint.body2)?;
+                let anchor1 = body1.position() * joint.data.local_anchor1();
+                let anchor2 = body2.position() * joint.data.local_anchor2();
+                let stretch = (anchor1 - anchor2).norm() - motor.target_pos;
+                Some(0.5 * motor.stiffness * stretch * stretch)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn criteria_stop_on_tolerance_or_budget() {
+        let criteria = RelaxationCriteria {
+            tolerance: 0.1,
+            max_steps: 10,
+        };
+        let moving = RelaxationProgress {
+            step: 3,
+            max_displacement: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(criteria.outcome(&moving), None);
+        assert_eq!(
+            criteria.outcome(&RelaxationProgress {
+                max_displacement: 0.01,
+                ..moving
+            }),
+            Some(RelaxationOutcome::Converged)
+        );
+        assert_eq!(
+            criteria.outcome(&RelaxationProgress { step: 10, ..moving }),
+            Some(RelaxationOutcome::StepBudgetExhausted)
+        );
+    }
+}
@@ -18,8 +18,14 @@ fn simple_kernel_2(r: f32, h: f32) -> f32 {
     v.max(0.0)
 }
 
+/// The potential whose derivative is `-simple_kernel_2`, vanishing at `h`.
+fn simple_potential_2(r: f32, h: f32) -> f32 {
+    let v = (1.0 / r - 1.0 / h) - (h - r) / h.powi(2);
+    v.max(0.0)
+}
+
 /// Operates a repulsion between all rigid bodies
-/// based on colliders at proximity.
+/// based on colliders at proximity, and records the corresponding potential energy.
 fn repulsion_step(system: &mut RapierPhysicsSystem, parameters: &RapierParameters) {
     let handles = system.nucleotide_body_map.values().collect::<Vec<_>>();
 
@@ -28,7 +34,7 @@ fn repulsion_step(system: &mut RapierPhysicsSystem, parameters: &RapierParameter
     let dt0 = RapierParameters::default().speed;
     let virtual_seconds = parameters.speed / dt0;
 
-    let forces = handles
+    let forces_and_energies = handles
         .clone()
         .par_iter()
         .map(|handle| {
@@ -76,15 +82,21 @@ fn repulsion_step(system: &mut RapierPhysicsSystem, parameters: &RapierParameter
                 .map(|v| (v.normalize(), v.norm()))
                 // which we then multiply by that square, and some other constants
                 .map(|(v, d)| {
-                    v * simple_kernel_2(d, force_range) * force_strength * constant_factor
-                        / virtual_seconds
+                    (
+                        v * simple_kernel_2(d, force_range) * force_strength * constant_factor
+                            / virtual_seconds,
+                        simple_potential_2(d, force_range) * force_strength * constant_factor,
+                    )
                 })
-                // and we then sum all these forces
-                .sum()
+                // and we then sum all these forces and energies
+                .fold((Vector::zeros(), 0.0), |(f, e), (df, de)| (f + df, e + de))
         })
-        .collect::<Vec<Vector<Real>>>();
+        .collect::<Vec<(Vector<Real>, f32)>>();
+
+    // every pair of nucleotides has been seen twice
+    system.repulsion_energy = forces_and_energies.iter().map(|(_, e)| e).sum::<f32>() / 2.0;
 
-    for (force, handle) in forces.into_iter().zip(handles.into_iter()) {
+    for ((force, _), handle) in forces_and_energies.into_iter().zip(handles) {
         let Some(collider) = system.collider_set.get(*handle) else {
             continue;
         };
@@ -79,10 +79,119 @@ impl SimulationSetup for FullSimulationSetup {
     }
 }
 
+/// Each continuous double stranded portion of an helix becomes a single rigid body.
+/// Free nucleotides keep their own bodies so that single strands stay flexible.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct RigidHelicesSetup;
+
+impl SimulationSetup for RigidHelicesSetup {
+    fn build_bodies(
+        &self,
+        rigid_body_set: &mut RigidBodySet,
+        collider_set: &mut ColliderSet,
+        collider_map: &HashMap<(usize, isize), Vec<ColliderHandle>>,
+        intermediary_representation: &HashMap<usize, IntermediaryHelix>,
+        rapier_parameters: &RapierParameters,
+    ) {
+        for (helix_index, helix) in intermediary_representation {
+            build_segment_bodies(
+                rigid_body_set,
+                collider_set,
+                collider_map,
+                *helix_index,
+                rigid_segments(helix, &[]),
+                rapier_parameters,
+            );
+        }
+    }
+}
+
+/// Same as [`RigidHelicesSetup`], but the double stranded portions are
+/// also cut at crossovers, so that helices can bend where they are connected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct SlicedRigidHelicesSetup;
+
+impl SimulationSetup for SlicedRigidHelicesSetup {
+    fn build_bodies(
+        &self,
+        rigid_body_set: &mut RigidBodySet,
+        collider_set: &mut ColliderSet,
+        collider_map: &HashMap<(usize, isize), Vec<ColliderHandle>>,
+        intermediary_representation: &HashMap<usize, IntermediaryHelix>,
+        rapier_parameters: &RapierParameters,
+    ) {
+        for (helix_index, helix) in intermediary_representation {
+            build_segment_bodies(
+                rigid_body_set,
+                collider_set,
+                collider_map,
+                *helix_index,
+                rigid_segments(helix, &helix.crossover_cuts),
+                rapier_parameters,
+            );
+        }
+    }
+}
+
+/// Groups the positions of an helix into segments that each make one rigid body.
+/// Continuous double ranges form a segment, which is split before each of
+/// the sorted `cuts`. Single stranded positions are segments on their own.
+fn rigid_segments(helix: &IntermediaryHelix, cuts: &[isize]) -> Vec<Vec<isize>> {
+    let mut result = vec![];
+
+    for range in &helix.double_ranges {
+        let mut segment = vec![];
+        for position in range.clone() {
+            if !segment.is_empty() && cuts.binary_search(&position).is_ok() {
+                result.push(std::mem::take(&mut segment));
+            }
+            segment.push(position);
+        }
+        result.push(segment);
+    }
+
+    for range in &helix.single_ranges {
+        result.extend(range.clone().map(|position| vec![position]));
+    }
+
+    result
+}
+
+/// Creates one rigid body per segment and assigns it the colliders of the segment.
+///
+/// The bodies are created at the origin, so that the positions of the colliders
+/// relative to their body are also their positions in the world, which is what
+/// the springs expect when they are built.
+fn build_segment_bodies(
+    rigid_body_set: &mut RigidBodySet,
+    collider_set: &mut ColliderSet,
+    collider_map: &HashMap<(usize, isize), Vec<ColliderHandle>>,
+    helix_index: usize,
+    segments: Vec<Vec<isize>>,
+    rapier_parameters: &RapierParameters,
+) {
+    for segment in segments {
+        let rigid_body = RigidBodyBuilder::dynamic()
+            .linear_damping(rapier_parameters.linear_damping)
+            .angular_damping(rapier_parameters.angular_damping);
+
+        let rigid_body_handle = rigid_body_set.insert(rigid_body);
+
+        for position in segment {
+            for collider_handle in collider_map
+                .get(&(helix_index, position))
+                .unwrap_or(&vec![])
+            {
+                collider_set.set_parent(*collider_handle, Some(rigid_body_handle), rigid_body_set);
+            }
+        }
+    }
+}
+
 /// Builds the entire simulation, taking a generic parameter setup
 /// that indicates how to regroup the colliders in rigid bodies.
-pub(crate) fn build_simulation<S: SimulationSetup>(
-    setup: S,
+pub(crate) fn build_simulation<S: SimulationSetup + ?Sized>(
+    setup: &S,
     intermediary_representation: &HashMap<usize, IntermediaryHelix>,
     nucl_collection: &NuclCollection,
     elements: &Vec<DesignElement>,
@@ -760,3 +869,39 @@ pub(crate) fn add_crossover_springs(
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_are_cut_at_crossovers() {
+        let mut helix = IntermediaryHelix::default();
+        for position in 0..6 {
+            for forward in [true, false] {
+                // position 4 only has a forward nucleotide
+                if position == 4 && !forward {
+                    continue;
+                }
+                helix.push_nucleotide(
+                    0,
+                    Nucl {
+                        helix: 0,
+                        position,
+                        forward,
+                    },
+                );
+            }
+        }
+        helix.compute_ranges();
+
+        assert_eq!(
+            rigid_segments(&helix, &[]),
+            vec![vec![0, 1, 2, 3], vec![5], vec![4]]
+        );
+        assert_eq!(
+            rigid_segments(&helix, &[0, 2, 3]),
+            vec![vec![0, 1], vec![2], vec![3], vec![5], vec![4]]
+        );
+    }
+}
@@ -2,8 +2,11 @@
 
 use crate::{
     helices::build_helices,
-    parameters::RapierParameters,
-    setup::{FullSimulationSetup, build_simulation},
+    parameters::{RapierParameters, SimulationSetupKind},
+    setup::{
+        FullSimulationSetup, RigidHelicesSetup, SimulationSetup, SlicedRigidHelicesSetup,
+        build_simulation,
+    },
 };
 use ahash::HashMap;
 use ensnano_design::{
@@ -31,10 +34,14 @@ pub struct RapierPhysicsSystem {
     // pub rapier_parameters: RapierParameters,
     pub crossovers: Vec<(ColliderHandle, ColliderHandle)>,
     pub nucleotide_body_map: HashMap<u32, ColliderHandle>,
+    /// The electrostatic repulsion energy computed during the last step.
+    pub repulsion_energy: f32,
 }
 
 impl RapierPhysicsSystem {
-    pub fn full_simulation(
+    /// Builds the simulation of the design, grouping the nucleotides in rigid bodies
+    /// according to `rapier_parameters.setup`.
+    pub fn new(
         parameters: HelixParameters,
         rapier_parameters: RapierParameters,
         nucl_collection: &NuclCollection,
@@ -46,8 +53,14 @@ impl RapierPhysicsSystem {
     ) -> Self {
         let intermediary = build_helices(elements, nucleotide, is_clone_map);
 
+        let setup: &dyn SimulationSetup = match rapier_parameters.setup {
+            SimulationSetupKind::Full => &FullSimulationSetup,
+            SimulationSetupKind::RigidHelices => &RigidHelicesSetup,
+            SimulationSetupKind::SlicedRigidHelices => &SlicedRigidHelicesSetup,
+        };
+
         build_simulation(
-            FullSimulationSetup,
+            setup,
             &intermediary,
             nucl_collection,
             elements,
@@ -92,6 +105,11 @@ impl RapierPhysicsSystem {
         );
     }
 
+    /// The simulated time elapsed during the last step.
+    pub fn time_step(&self) -> f32 {
+        self.integration_parameters.dt
+    }
+
     pub fn get_positions(&self) -> Vec<(u32, [f32; 3])> {
         let mut result = vec![];
 
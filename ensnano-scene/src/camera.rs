@@ -270,31 +270,23 @@ impl CameraController {
             true
         };
 
-        let mut process_rotation = |xz: f32, yz: f32| {
-            const ROTATE_AMOUNT: f32 = FRAC_PI_2 / 20.;
-            if state.is_pressed() {
-                let pivot = self.pivot_point.unwrap_or_else(FiniteVec3::zero);
-                self.rotate_camera_around(xz * ROTATE_AMOUNT, yz * ROTATE_AMOUNT, pivot);
-                self.cam0 = self.camera.borrow().clone();
-                true
-            } else {
-                false
-            }
-        };
-
         match key {
             KeyCode::ArrowUp => process_translation(&mut self.amount_up),
             KeyCode::ArrowRight => process_translation(&mut self.amount_right),
             KeyCode::ArrowDown => process_translation(&mut self.amount_down),
             KeyCode::ArrowLeft => process_translation(&mut self.amount_left),
-            KeyCode::KeyH => process_rotation(1., 0.),
-            KeyCode::KeyL => process_rotation(-1., 0.),
-            KeyCode::KeyJ => process_rotation(0., 1.),
-            KeyCode::KeyK => process_rotation(0., -1.),
             _ => false,
         }
     }
 
+    /// Rotate the camera around the pivot point by a fixed step in the given directions.
+    pub(crate) fn rotate_by_step(&mut self, xz: f32, yz: f32) {
+        const ROTATE_AMOUNT: f32 = FRAC_PI_2 / 20.;
+        let pivot = self.pivot_point.unwrap_or_else(FiniteVec3::zero);
+        self.rotate_camera_around(xz * ROTATE_AMOUNT, yz * ROTATE_AMOUNT, pivot);
+        self.cam0 = self.camera.borrow().clone();
+    }
+
     pub(crate) fn is_moving(&self) -> bool {
         self.amount_down != 0.
             || self.amount_up != 0.
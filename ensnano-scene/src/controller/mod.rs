@@ -21,12 +21,15 @@ use ensnano_design::{
     nucl::Nucl,
 };
 use ensnano_state::state::MainState;
-use ensnano_utils::graphics::PhySize;
+use ensnano_utils::{
+    graphics::PhySize,
+    keymap::{KeymapAction, KeymapScope},
+};
 use std::{cell::RefCell, ops::Deref as _, rc::Rc, time::Duration};
 use ultraviolet::{Rotor3, Vec2, Vec3};
 use winit::{
-    event::{ElementState, KeyEvent, Modifiers},
-    keyboard::{Key, ModifiersState, NamedKey, PhysicalKey},
+    event::{KeyEvent, Modifiers},
+    keyboard::{ModifiersState, PhysicalKey},
     window::CursorIcon,
 };
 
@@ -70,8 +73,6 @@ pub(crate) enum Consequence {
     ToggleWidget,
     BuildEnded,
     Building(isize),
-    Undo,
-    Redo,
     Candidate(Option<SceneElement>),
     PivotElement(Option<SceneElement>),
     ElementSelected(Option<SceneElement>, bool),
@@ -281,31 +282,41 @@ impl SceneController {
                     },
                 ..
             } => {
-                let csq = match logical_key.as_ref() {
-                    Key::Character("a") if *state == ElementState::Pressed => {
+                let action = state
+                    .is_pressed()
+                    .then(|| {
+                        main_state.app_state.get_keymap().action_for_key(
+                            KeymapScope::Scene3D,
+                            logical_key,
+                            &self.current_modifiers_state,
+                        )
+                    })
+                    .flatten();
+                let csq = match action {
+                    Some(KeymapAction::AlignWithStereographicCamera) => {
                         Consequence::AlignWithStereo
                     }
-                    Key::Character("c") if *state == ElementState::Pressed => {
-                        Consequence::CheckXovers
+                    Some(KeymapAction::CheckXovers) => Consequence::CheckXovers,
+                    Some(KeymapAction::PivotAtOrigin) => Consequence::PivotCenter,
+                    Some(KeymapAction::ReverseSurfaceDirection) => {
+                        Consequence::ReverseSurfaceDirection
                     }
-                    Key::Character("z")
-                        if ctrl(&self.current_modifiers_state)
-                            && *state == ElementState::Pressed =>
-                    {
-                        Consequence::Undo
+                    Some(KeymapAction::ToggleWidgetBasis) => Consequence::ToggleWidget,
+                    Some(KeymapAction::RotateCameraLeft) => {
+                        self.camera_controller.rotate_by_step(1., 0.);
+                        Consequence::CameraMoved
                     }
-                    Key::Character("r")
-                        if ctrl(&self.current_modifiers_state)
-                            && *state == ElementState::Pressed =>
-                    {
-                        Consequence::Redo
+                    Some(KeymapAction::RotateCameraRight) => {
+                        self.camera_controller.rotate_by_step(-1., 0.);
+                        Consequence::CameraMoved
                     }
-                    Key::Character("q") => Consequence::PivotCenter,
-                    Key::Character("w") if *state == ElementState::Pressed => {
-                        Consequence::ReverseSurfaceDirection
+                    Some(KeymapAction::RotateCameraUp) => {
+                        self.camera_controller.rotate_by_step(0., 1.);
+                        Consequence::CameraMoved
                     }
-                    Key::Named(NamedKey::Space) if *state == ElementState::Pressed => {
-                        Consequence::ToggleWidget
+                    Some(KeymapAction::RotateCameraDown) => {
+                        self.camera_controller.rotate_by_step(0., -1.);
+                        Consequence::CameraMoved
                     }
                     _ => {
                         if let PhysicalKey::Code(key_code) = physical_key
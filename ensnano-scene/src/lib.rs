@@ -339,8 +339,6 @@ impl Scene {
             | Consequence::ReleaseBezierVertex
             | Consequence::ReleaseBezierCorner
             | Consequence::ReleaseBezierTangent => self.requests.lock().unwrap().suspend_op(),
-            Consequence::Undo => self.requests.lock().unwrap().undo(),
-            Consequence::Redo => self.requests.lock().unwrap().redo(),
             Consequence::Building(position) => {
                 self.requests
                     .lock()
@@ -1231,7 +1229,8 @@ impl Application for Scene {
             | Notification::Split2d
             | Notification::Redim2dHelices(_)
             | Notification::FlipSplitViews
-            | Notification::ScreenShot2D(_) => (),
+            | Notification::ScreenShot2D(_)
+            | Notification::VectorExport2D(_, _) => (),
         }
     }
 
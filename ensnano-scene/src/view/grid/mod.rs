@@ -10,7 +10,9 @@ use crate::{
         letter::LetterInstance,
     },
 };
-use ensnano_design::grid::{GridDivision as _, GridId, GridInstance, GridPosition, GridType};
+use ensnano_design::grid::{
+    GridDivision as _, GridId, GridInstance, GridPosition, GridType, HYPERBOLOID_GRID_TYPE,
+};
 use ensnano_utils::instance::Instance;
 use std::collections::BTreeMap;
 use ultraviolet::{Mat4, Vec2, Vec3, Vec4};
@@ -40,6 +42,10 @@ pub trait GridInstanceExt {
     fn convert_coord(&self, x: f32, y: f32) -> (f32, f32);
 
     fn contains_point(&self, x: f32, y: f32) -> bool;
+
+    /// The corners of the rectangle of the grid's plane that contains the sites of a user-defined
+    /// lattice.
+    fn lattice_bounds(&self) -> (Vec2, Vec2);
 }
 
 impl GridInstanceExt for GridInstance {
@@ -110,6 +116,11 @@ impl GridInstanceExt for GridInstance {
                 min_y = -h.grid_radius(&self.grid.helix_parameters);
                 max_y = h.grid_radius(&self.grid.helix_parameters);
             }
+            GridType::Lattice(_) => {
+                // The shader flips the y coordinate.
+                let (min, max) = self.lattice_bounds();
+                (min_x, max_x, min_y, max_y) = (min.x, max.x, -max.y, -min.y);
+            }
             GridType::RotatedHoneycomb(_) => {
                 min_x = self.min_y as f32;
                 max_x = self.max_y as f32;
@@ -123,10 +134,15 @@ impl GridInstanceExt for GridInstance {
                 max_y = self.max_y as f32;
             }
         }
+        // User-defined lattices are drawn as plain sheets, like hyperboloids.
+        let grid_type = match self.grid.grid_type {
+            GridType::Lattice(_) => HYPERBOLOID_GRID_TYPE,
+            _ => self.grid.grid_type.descr().to_u32(),
+        };
         let grid_type = if self.fake {
-            self.grid.grid_type.descr().to_u32() + 1000
+            grid_type + 1000
         } else {
-            self.grid.grid_type.descr().to_u32()
+            grid_type
         };
         GridInstanceRaw {
             model: Mat4::from_translation(self.grid.position)
@@ -188,13 +204,16 @@ impl GridInstanceExt for GridInstance {
                     + self.grid.helix_parameters.inter_helix_gap;
                 ((x - r / 2.) * 2. / (3. * r), y * 2. / (3f32.sqrt() * r))
             }
-            GridType::Hyperboloid(_) => unreachable!(),
+            GridType::Hyperboloid(_) | GridType::Lattice(_) => unreachable!(),
         }
     }
 
     fn contains_point(&self, x: f32, y: f32) -> bool {
         if let GridType::Hyperboloid(h) = &self.grid.grid_type {
             h.contains_point(&self.grid.helix_parameters, x, y)
+        } else if let GridType::Lattice(_) = &self.grid.grid_type {
+            let (min, max) = self.lattice_bounds();
+            (min.x..=max.x).contains(&x) && (min.y..=max.y).contains(&y)
         } else {
             let (x, y) = self.convert_coord(x, y);
 
@@ -219,6 +238,24 @@ impl GridInstanceExt for GridInstance {
             // ret
         }
     }
+
+    fn lattice_bounds(&self) -> (Vec2, Vec2) {
+        let parameters = &self.grid.helix_parameters;
+        let mut min = Vec2::broadcast(f32::INFINITY);
+        let mut max = Vec2::broadcast(f32::NEG_INFINITY);
+        for x in self.min_x..=self.max_x {
+            for y in self.min_y..=self.max_y {
+                let position = self
+                    .grid
+                    .grid_type
+                    .origin_helix(parameters, x as isize, y as isize);
+                min = min.min_by_component(position);
+                max = max.max_by_component(position);
+            }
+        }
+        let margin = parameters.helix_radius + parameters.inter_helix_gap / 2.;
+        (min - Vec2::broadcast(margin), max + Vec2::broadcast(margin))
+    }
 }
 
 #[repr(C)]
@@ -1,10 +1,13 @@
 use crate::{
     app_state::design_interactor::controller::clipboard::PastePosition,
     design::operation::{DesignOperation, HyperboloidRequest},
+    simulation_presets::SimulationPreset,
     utils::application::Notification,
 };
 use ensnano_design::{
     CameraId,
+    bezier_plane::import_from_svg::SvgImportParameters,
+    curves::import_from_points::AxisImportParameters,
     grid::{GridId, GridTypeDescr},
     group_attributes::GroupPivot,
     parameters::HelixParameters,
@@ -19,6 +22,7 @@ use ensnano_utils::{
     ui_size::UiSize,
 };
 use std::{
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -48,6 +52,8 @@ pub enum Action {
     SilentDesignOperation(DesignOperation),
     Undo,
     Redo,
+    /// Undo or redo operations until the given number of operations lead to the current state.
+    JumpInHistory(usize),
     NotifyApps(Notification),
     TurnSelectionIntoGrid,
     AddGrid(GridTypeDescr),
@@ -58,6 +64,22 @@ pub enum Action {
     ToggleSmallSphere(bool),
     RollRequest(RollRequest),
     UpdateRapierParameters(RapierParameters),
+    /// Store the positions reached by the relaxation in the design.
+    CommitRelaxation,
+    SetTrajectoryRecording(bool),
+    /// The number of solver steps between two recorded frames.
+    SetTrajectoryInterval(NonZeroUsize),
+    /// Move the nucleotides to their positions in a recorded frame.
+    ShowTrajectoryFrame(usize),
+    ClearTrajectory,
+    ExportTrajectory(ExportType),
+    AnalyseRigidity,
+    ClearRigidityAnalysis,
+    /// Move the nucleotides to the equilibrium shape given by the analysis of the rigidity.
+    ApplyEquilibriumShape,
+    /// Save a preset of simulation parameters in the preferences of the user.
+    SaveSimulationPreset(SimulationPreset),
+    DeleteSimulationPreset(String),
     StopSimulation,
     RollHelices(f32),
     Copy,
@@ -87,6 +109,8 @@ pub enum Action {
     /// Save the nucleotides 3D positions by strand as a json file in the design directory.
     GetDesignPathAndNotify(fn(Option<Arc<Path>>) -> Notification),
     SuspendOp,
+    /// Finish the operation whose parameters are being edited.
+    FinishPendingOp,
     Fog(FogParameters),
     Split2D,
     ReloadFile,
@@ -108,6 +132,17 @@ pub enum Action {
     AddBezierPlane,
     SetExporting(bool),
     Import3DObject,
-    ImportSvg,
+    /// Import the paths of an SVG file chosen by the user.
+    ImportSvg(SvgImportParameters),
+    /// Create a grid on a lattice read from a file chosen by the user.
+    ImportLattice,
+    /// Import helix axes from files of points chosen by the user.
+    ImportHelixAxes(AxisImportParameters),
+    /// Insert a design chosen by the user in the current design.
+    InsertDesign,
     OptimizeShift,
+    /// Run a script on the design.
+    RunScript(String),
+    /// Ask for a script file and run it on the design.
+    RunScriptFile,
 }
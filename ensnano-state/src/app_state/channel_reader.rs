@@ -1,9 +1,15 @@
 //! This module defines the [ChannelReader] struct which is in charge of communication with
 //! computation threads that can be spawned by the program.
 
-use crate::app_state::design_interactor::{
-    controller::{shift_optimization::ShiftOptimizationResult, simulations::SimulationInterface},
-    presenter::SimulationUpdate,
+use crate::{
+    app_state::design_interactor::{
+        controller::{
+            shift_optimization::ShiftOptimizationResult,
+            simulations::{SimulationFrame, SimulationInterface},
+        },
+        presenter::SimulationUpdate,
+    },
+    rigidity::RigidityAnalysisResult,
 };
 use std::sync::{Arc, Mutex, Weak, mpsc};
 
@@ -11,11 +17,14 @@ use std::sync::{Arc, Mutex, Weak, mpsc};
 pub struct ScaffoldShiftReader {
     pub scaffold_shift_optimization_progress: Option<Arc<Mutex<mpsc::Receiver<f32>>>>,
     scaffold_shift_optimization_result: Option<Arc<Mutex<mpsc::Receiver<ShiftOptimizationResult>>>>,
+    rigidity_analysis_result: Option<Arc<Mutex<mpsc::Receiver<RigidityAnalysisResult>>>>,
 }
 
 #[derive(Default, Clone)]
 pub struct SimulationInterfaceHandle {
     simulation_interface: Option<Weak<Mutex<dyn SimulationInterface>>>,
+    /// True if the progress of the current simulation is shown to the user.
+    reporting_progress: bool,
 }
 
 pub enum ChannelReaderUpdate {
@@ -23,11 +32,20 @@ pub enum ChannelReaderUpdate {
     ScaffoldShiftOptimizationProgress(f32),
     /// The optimum scaffold position has been found.
     ScaffoldShiftOptimizationResult(ShiftOptimizationResult),
+    /// The analysis of the rigidity of the design is over.
+    RigidityAnalysisResult(RigidityAnalysisResult),
 }
 
 pub enum SimulationInterfaceUpdate {
+    /// The frames sampled by the simulation since the last update, which all precede the
+    /// following `SimulationUpdate`.
+    Frames(Vec<SimulationFrame>),
     SimulationUpdate(Box<dyn SimulationUpdate>),
     SimulationExpired,
+    /// The advancement of the simulation, or `None` once it should not be shown anymore.
+    Progress(Option<(String, f32)>),
+    /// The simulation stopped by itself, with a conclusion or an error.
+    Finished(Result<String, String>),
 }
 
 impl ScaffoldShiftReader {
@@ -41,6 +59,10 @@ impl ScaffoldShiftReader {
         if let Some(result) = self.get_scaffold_shift_optimization_result() {
             updates.push(ChannelReaderUpdate::ScaffoldShiftOptimizationResult(result));
         }
+        if let Some(result) = self.get_rigidity_analysis_result() {
+            self.rigidity_analysis_result = None;
+            updates.push(ChannelReaderUpdate::RigidityAnalysisResult(result));
+        }
         updates
     }
 
@@ -56,6 +78,12 @@ impl ScaffoldShiftReader {
             .and_then(|channel| channel.lock().unwrap().try_recv().ok())
     }
 
+    fn get_rigidity_analysis_result(&self) -> Option<RigidityAnalysisResult> {
+        self.rigidity_analysis_result
+            .as_ref()
+            .and_then(|channel| channel.lock().unwrap().try_recv().ok())
+    }
+
     pub fn attach_result_chanel(&mut self, channel: mpsc::Receiver<ShiftOptimizationResult>) {
         self.scaffold_shift_optimization_result = Some(Arc::new(Mutex::new(channel)));
     }
@@ -63,6 +91,13 @@ impl ScaffoldShiftReader {
     pub fn attach_progress_chanel(&mut self, channel: mpsc::Receiver<f32>) {
         self.scaffold_shift_optimization_progress = Some(Arc::new(Mutex::new(channel)));
     }
+
+    pub fn attach_rigidity_analysis_chanel(
+        &mut self,
+        channel: mpsc::Receiver<RigidityAnalysisResult>,
+    ) {
+        self.rigidity_analysis_result = Some(Arc::new(Mutex::new(channel)));
+    }
 }
 
 impl SimulationInterfaceHandle {
@@ -71,19 +106,35 @@ impl SimulationInterfaceHandle {
         let mut invalidated = false;
         if let Some(interface_ptr) = self.simulation_interface.as_ref() {
             if let Some(interface) = interface_ptr.upgrade() {
-                if !interface.lock().unwrap().still_valid() {
+                let mut interface = interface.lock().unwrap();
+                if !interface.still_valid() {
                     invalidated = true;
                     updates.push(SimulationInterfaceUpdate::SimulationExpired);
                 }
-                if let Some(new_state) = interface.lock().unwrap().get_simulation_state() {
+                let frames = interface.take_frames();
+                if !frames.is_empty() {
+                    updates.push(SimulationInterfaceUpdate::Frames(frames));
+                }
+                if let Some(new_state) = interface.get_simulation_state() {
                     updates.push(SimulationInterfaceUpdate::SimulationUpdate(new_state));
                 }
+                if let Some(progress) = interface.progress() {
+                    self.reporting_progress = true;
+                    updates.push(SimulationInterfaceUpdate::Progress(Some(progress)));
+                }
+                if let Some(report) = interface.take_report() {
+                    updates.push(SimulationInterfaceUpdate::Finished(report));
+                }
             } else {
                 invalidated = true;
             }
         }
         if invalidated {
             self.simulation_interface = None;
+            if self.reporting_progress {
+                self.reporting_progress = false;
+                updates.push(SimulationInterfaceUpdate::Progress(None));
+            }
         }
         updates
     }
@@ -13,7 +13,7 @@ use ensnano_design::{
     Design, MutStrandAndData,
     domains::{Domain, helix_interval::HelixInterval},
     grid::{Edge, GridData, GridId, GridPosition, HelixGridPosition, grid_collection::FreeGridId},
-    helices::Helices,
+    helices::{Helices, Helix},
     nucl::Nucl,
     parameters::HelixParameters,
     strands::{Strand, Strands, read_junctions},
@@ -487,7 +487,7 @@ impl Controller {
             for dom in &domains {
                 if let Domain::HelixDomain(dom) = dom {
                     let helix = helices.get(&dom.helix).unwrap();
-                    for position in dom.iter() {
+                    for position in dom.iter_nucls(helices) {
                         nucl_position.push(helix.space_pos(
                             helix_parameters,
                             position,
@@ -727,15 +727,11 @@ impl Controller {
                         data.get_edge(source, &dest)
                     })
                     .ok_or(OperationError::CannotPasteHere)?;
+                let copies = Self::translated_helices(design, &helices, edge, &data)?;
                 let mut helices_mut = design.helices.make_mut();
-                for h_id in &helices {
-                    let h = helices_mut
-                        .get(h_id)
-                        .ok_or(OperationError::HelixDoesNotExists(*h_id))?;
-                    if let Some(copy) = h.translated_by(edge, &data) {
-                        log::info!("adding helix");
-                        helices_mut.push_helix(copy);
-                    }
+                for copy in copies {
+                    log::info!("adding helix");
+                    helices_mut.push_helix(copy);
                 }
                 self.state = ControllerState::WithPendingHelicesDuplication {
                     last_pasting_point: new_duplication_point.light(),
@@ -885,20 +881,37 @@ impl Controller {
             .update_helices_pasting_position(position, edge, design)?;
         if let Some(edge) = edge {
             log::info!("edge is some");
+            let copies = Self::translated_helices(design, &helices, edge, &grid_data)?;
             let mut helices_mut = design.helices.make_mut();
-            for h_id in helices {
-                let h = helices_mut
-                    .get(&h_id)
-                    .ok_or(OperationError::HelixDoesNotExists(h_id))?;
-                if let Some(copy) = h.translated_by(edge, &grid_data) {
-                    log::info!("adding helix");
-                    helices_mut.push_helix(copy);
-                }
+            for copy in copies {
+                log::info!("adding helix");
+                helices_mut.push_helix(copy);
             }
         }
         Ok(())
     }
 
+    /// Copies of `helices` translated by `edge`. Fails if one of the helices cannot be
+    /// translated, so that helices are never pasted partially.
+    fn translated_helices(
+        design: &Design,
+        helices: &[usize],
+        edge: Edge,
+        grid_data: &GridData,
+    ) -> Result<Vec<Helix>, OperationError> {
+        helices
+            .iter()
+            .map(|h_id| {
+                design
+                    .helices
+                    .get(h_id)
+                    .ok_or(OperationError::HelixDoesNotExists(*h_id))?
+                    .translated_by(edge, grid_data)
+                    .ok_or(OperationError::CannotPasteHere)
+            })
+            .collect()
+    }
+
     fn position_xover_copies(
         &mut self,
         design: &mut Design,
@@ -6,8 +6,8 @@ pub mod update_insertion_length;
 use self::{
     clipboard::{Clipboard, CopyOperation, PastePosition, PastedStrand, StrandClipboard},
     simulations::{
-        GridSystemInterface, GridsSystemThread, HelixSystemInterface, HelixSystemThread,
-        SimulationOperation,
+        FrameSampler, GridSystemInterface, GridsSystemThread, HelixSystemInterface,
+        HelixSystemThread, SimulationOperation,
         rapier::{RapierInterface, RapierPhysicalSystem},
         revolutions::{RevolutionSystemInterface, RevolutionSystemThread},
         roller::{PhysicalSystem, RollInterface},
@@ -16,13 +16,16 @@ use self::{
 };
 use crate::{
     app_state::{
-        AddressPointer, channel_reader::ScaffoldShiftReader,
-        design_interactor::controller::simulations::SimulationInterface,
+        AddressPointer, LoadDesignError,
+        channel_reader::ScaffoldShiftReader,
+        design_interactor::{
+            controller::simulations::SimulationInterface, file_parsing::read_file,
+        },
     },
     design::{
         operation::{
             BezierPlaneHomothethy, DesignOperation, DesignRotation, DesignTranslation,
-            HyperboloidOperation, IsometryTarget, NewBezierTangentVector,
+            HyperboloidOperation, HyperboloidRequest, IsometryTarget, NewBezierTangentVector,
         },
         selection::{Selection, list_of_helices},
     },
@@ -33,11 +36,13 @@ use ensnano_design::{
     CameraId, Design,
     bezier_plane::{
         BezierPathId, BezierPlaneDescriptor, BezierPlaneId, BezierVertex, BezierVertexId,
-        import_from_svg::{SvgImportError, read_first_svg_path},
+        import_from_svg::{SvgImportError, SvgImportParameters, read_svg_paths},
     },
     curves::{
         CurveDescriptor,
         bezier::{BezierControlPoint, BezierEnd},
+        import_from_points::{AxisImportError, AxisImportParameters, read_axis},
+        parametric::{ExpressionError, ParametricCurveDescriptor},
     },
     design_element::{DesignElementKey, DnaAttribute},
     design_operations::{
@@ -45,8 +50,8 @@ use ensnano_design::{
         translate_helices,
     },
     domains::{Domain, helix_interval::HelixInterval},
-    drawing_style::{DrawingAttribute, DrawingStyle},
-    external_3d_objects::{External3DObject, External3DObjectDescriptor},
+    drawing_style::DrawingStyle,
+    external_3d_objects::{External3DObject, External3DObjectDescriptor, External3DObjectId},
     grid::{
         Edge, GridDescriptor, GridDivision as _, GridId, GridObject, GridPosition, GridTypeDescr,
         HelixGridPosition, copy_grid::GridCopyError, grid_collection::FreeGridId,
@@ -57,20 +62,22 @@ use ensnano_design::{
     mutate_in_arc, mutate_one_helix,
     nucl::Nucl,
     organizer_tree::GroupId,
-    strands::{DomainJunction, Strand, Strands},
+    strands::{DomainJunction, Strand, Strands, read_junctions},
+    voxelization::{VoxelizationError, VoxelizationParameters, voxelize},
+    wireframe::{WireframeError, WireframeParameters, make_wireframe},
 };
 use ensnano_utils::{
     PastingStatus, SimulationState,
     clipboard::ClipboardContent,
     colors::{new_color, random_color_with_shade},
+    obj_loader::{ErrMesh, load_triangles},
     strand_builder::{DomainIdentifier, NeighborDescriptor, StrandBuilder, get_neighbor_nucl},
 };
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, HashMap},
+    collections::BTreeMap,
     f32::consts::PI,
-    path::PathBuf,
-    str::FromStr as _,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 use ultraviolet::{Isometry2, Rotor2, Rotor3, Vec2, Vec3};
@@ -121,7 +128,19 @@ impl Controller {
         }
         log::debug!("applicable");
 
-        operation.apply(self, design)
+        let keeps_relaxed_positions = operation.keeps_relaxed_positions();
+        let outcome = if operation.may_modify_locked_elements() {
+            operation.apply(self, design)?
+        } else {
+            self.apply_preserving_locked_elements(design, |controller, design| {
+                operation.apply(controller, design)
+            })?
+        };
+        if !keeps_relaxed_positions {
+            // The relaxed positions were computed for the previous helices and strands.
+            design.relaxed_positions.clear();
+        }
+        Ok(outcome)
     }
 
     pub fn update_pending_operation(
@@ -132,9 +151,15 @@ impl Controller {
         let effect = operation.effect();
 
         if operation.replace_previous()
-            && let ControllerState::WithPendingOp { design, .. } = &self.state
+            && let ControllerState::WithPendingOp {
+                design: initial_design,
+                ..
+            } = &self.state
         {
-            let result = self.apply_operation(design.clone().make_mut(), effect)?;
+            // The edited operation replaces the previous one, so it is applied to the design as
+            // it was before the previous one.
+            *design = initial_design.clone_inner();
+            let result = self.apply_operation(design, effect)?;
             self.state.update_operation(operation);
 
             return Ok(result);
@@ -151,7 +176,41 @@ impl Controller {
         design: &mut Design,
         operation: CopyOperation,
     ) -> AppStateOperationResult {
-        operation.apply(self, design)
+        let only_copies = matches!(
+            operation,
+            CopyOperation::CopyGrids(_)
+                | CopyOperation::CopyStrands(_)
+                | CopyOperation::CopyXovers(_)
+                | CopyOperation::CopyHelices(_)
+        );
+        let outcome = self.apply_preserving_locked_elements(design, |controller, design| {
+            operation.apply(controller, design)
+        })?;
+        if !only_copies {
+            design.relaxed_positions.clear();
+        }
+        Ok(outcome)
+    }
+
+    /// Apply `apply` to copies of `self` and `design`, and keep the result only if no locked
+    /// element was modified.
+    fn apply_preserving_locked_elements(
+        &mut self,
+        design: &mut Design,
+        apply: impl FnOnce(&mut Self, &mut Design) -> AppStateOperationResult,
+    ) -> AppStateOperationResult {
+        if design.locked_elements.is_empty() {
+            return apply(self, design);
+        }
+        let mut new_controller = self.clone();
+        let mut new_design = design.clone();
+        let outcome = apply(&mut new_controller, &mut new_design)?;
+        if let Some(key) = design.first_modified_locked_element(&new_design) {
+            return Err(OperationError::ElementIsLocked(key));
+        }
+        *self = new_controller;
+        *design = new_design;
+        Ok(outcome)
     }
 
     fn check_state_compatible_with_simulation(&self) -> Result<(), OperationError> {
@@ -169,6 +228,7 @@ impl Controller {
         &mut self,
         design: &mut Design,
         operation: SimulationOperation,
+        sampler: FrameSampler,
     ) -> Result<
         (
             AppStateOperationOutcome,
@@ -195,7 +255,7 @@ impl Controller {
                 parameters,
             } => {
                 self.check_state_compatible_with_simulation()?;
-                let interface = HelixSystemThread::start_new(presenter, parameters)?;
+                let interface = HelixSystemThread::start_new(presenter, parameters, sampler)?;
                 let dyn_interface: Arc<Mutex<dyn SimulationInterface>> = interface.clone();
                 returned_interface = Some(dyn_interface);
                 self.state = ControllerState::Simulating {
@@ -208,7 +268,7 @@ impl Controller {
                 parameters,
             } => {
                 self.check_state_compatible_with_simulation()?;
-                let interface = GridsSystemThread::start_new(presenter, parameters)?;
+                let interface = GridsSystemThread::start_new(presenter, parameters, sampler)?;
                 let dyn_interface: Arc<Mutex<dyn SimulationInterface>> = interface.clone();
                 returned_interface = Some(dyn_interface);
                 self.state = ControllerState::SimulatingGrids {
@@ -232,7 +292,8 @@ impl Controller {
                         }
                     } else {
                         // the simulation is starting, we save the initial design
-                        let interface = RapierPhysicalSystem::start_new(presenter, parameters);
+                        let interface =
+                            RapierPhysicalSystem::start_new(presenter, parameters, sampler);
                         let dyn_interface: Arc<Mutex<dyn SimulationInterface>> = interface.clone();
                         returned_interface = Some(dyn_interface);
 
@@ -248,7 +309,7 @@ impl Controller {
                 target_helices,
             } => {
                 self.check_state_compatible_with_simulation()?;
-                let interface = PhysicalSystem::start_new(presenter, target_helices);
+                let interface = PhysicalSystem::start_new(presenter, target_helices, sampler);
                 let dyn_interface: Arc<Mutex<dyn SimulationInterface>> = interface.clone();
                 returned_interface = Some(dyn_interface);
                 self.state = ControllerState::Rolling {
@@ -317,6 +378,27 @@ impl Controller {
                     interface.lock().unwrap().finish();
                 }
             }
+            SimulationOperation::CommitRelaxation { presenter } => {
+                match &self.state {
+                    ControllerState::RapierSimulating { interface, .. } => {
+                        interface.lock().unwrap().kill();
+                    }
+                    ControllerState::WithPausedSimulation { .. } => (),
+                    _ => {
+                        return Err(OperationError::IncompatibleState(
+                            "There is no relaxation to commit".into(),
+                        ));
+                    }
+                }
+                design.relaxed_positions = presenter.nucleotide_positions();
+                self.state = ControllerState::Normal;
+                return Ok((
+                    AppStateOperationOutcome::Push {
+                        label: "Commit relaxation".into(),
+                    },
+                    None,
+                ));
+            }
         }
 
         Ok((
@@ -386,6 +468,12 @@ impl Controller {
             keys.push(key);
         }
         drop(helices_mut);
+        self.add_hyperboloid_strands(design, keys, nb_nucl);
+        Ok(())
+    }
+
+    /// Add a forward and a backward strand spanning `nb_nucl` nucleotides on each helix.
+    fn add_hyperboloid_strands(&mut self, design: &mut Design, keys: Vec<usize>, nb_nucl: usize) {
         for key in keys {
             for b in &[true, false] {
                 //let new_key = self.add_strand(design, key, -(nb_nucl as isize) / 2, *b);
@@ -397,6 +485,75 @@ impl Controller {
                 }
             }
         }
+    }
+
+    /// Change the parameters of an existing hyperboloid grid and regenerate its helices.
+    ///
+    /// The helices that are still on the grid keep their identifier and the domains on them are
+    /// stretched to the new helix length. The domains on the helices that no longer fit on the
+    /// grid are removed, cutting the strands that go through them, and the new helices are given
+    /// a strand on each side, as when the grid is created.
+    fn edit_hyperboloid(
+        &mut self,
+        design: &mut Design,
+        grid_id: GridId,
+        request: HyperboloidRequest,
+    ) -> Result<(), OperationError> {
+        let hyperboloid = request.to_grid();
+        let mut grids_mut = design.free_grids.make_mut();
+        let grid = grids_mut
+            .get_mut_g_id(&grid_id)
+            .ok_or(OperationError::GridDoesNotExist(grid_id))?;
+        let GridTypeDescr::Hyperboloid {
+            length: old_length, ..
+        } = grid.grid_type
+        else {
+            return Err(OperationError::GridIsNotHyperboloid(grid_id));
+        };
+        grid.grid_type = hyperboloid.desc();
+        drop(grids_mut);
+
+        let helix_parameters = design.helix_parameters.unwrap_or_default();
+        let (helices, nb_nucl) = hyperboloid.make_helices(&helix_parameters);
+        let nb_nucl = nb_nucl.min(5000);
+        let old_nb_nucl = (old_length as usize).min(5000);
+
+        let mut kept = BTreeMap::new();
+        let mut removed = Vec::new();
+        for (h_id, h) in design.helices.iter() {
+            if let Some(position) = h.grid_position.filter(|p| p.grid == grid_id) {
+                if (0..helices.len() as isize).contains(&position.x) && position.y == 0 {
+                    kept.insert(position.x, *h_id);
+                } else {
+                    removed.push(*h_id);
+                }
+            }
+        }
+
+        remove_domains_on_helices(&mut design.strands, &removed, &mut self.color_idx);
+        let kept_helices: Vec<usize> = kept.values().copied().collect();
+        stretch_domains_on_helices(&mut design.strands, &kept_helices, old_nb_nucl, nb_nucl);
+
+        let mut helices_mut = design.helices.make_mut();
+        for h_id in &removed {
+            helices_mut.remove(h_id);
+        }
+        let mut keys = Vec::new();
+        for (i, mut h) in helices.into_iter().enumerate() {
+            if kept.contains_key(&(i as isize)) {
+                continue;
+            }
+            h.grid_position = Some(HelixGridPosition {
+                grid: grid_id,
+                x: i as isize,
+                y: 0,
+                axis_pos: 0,
+                roll: 0.,
+            });
+            keys.push(helices_mut.push_helix(h));
+        }
+        drop(helices_mut);
+        self.add_hyperboloid_strands(design, keys, nb_nucl);
         Ok(())
     }
 
@@ -463,6 +620,20 @@ impl Controller {
         Ok(())
     }
 
+    pub fn set_group_drawing_style(
+        &self,
+        design: &mut Design,
+        group_id: GroupId,
+        style: DrawingStyle,
+    ) -> Result<(), OperationError> {
+        design
+            .group_attributes
+            .entry(group_id)
+            .or_default()
+            .drawing_style = style;
+        Ok(())
+    }
+
     pub fn update_attribute(
         &self,
         design: &mut Design,
@@ -477,6 +648,20 @@ impl Controller {
                 DnaAttribute::LockedForSimulations(locked) => {
                     self.set_lock_during_simulation(design, elt, locked)?;
                 }
+                DnaAttribute::LockedForEdition(locked) => {
+                    if matches!(
+                        elt,
+                        DesignElementKey::Helix(_)
+                            | DesignElementKey::Strand(_)
+                            | DesignElementKey::Grid(_)
+                    ) {
+                        if locked {
+                            design.locked_elements.insert(*elt);
+                        } else {
+                            design.locked_elements.remove(elt);
+                        }
+                    }
+                }
             }
         }
         Ok(())
@@ -546,6 +731,26 @@ impl Controller {
         Ok(())
     }
 
+    pub fn set_deletion(
+        &self,
+        design: &mut Design,
+        h_id: usize,
+        position: isize,
+        deleted: bool,
+    ) -> Result<(), OperationError> {
+        if !design.helices.contains_key(&h_id) {
+            return Err(OperationError::HelixDoesNotExists(h_id));
+        }
+        mutate_one_helix(design, h_id, |h| {
+            if deleted {
+                h.deletions.insert(position);
+            } else {
+                h.deletions.remove(&position);
+            }
+        });
+        Ok(())
+    }
+
     fn set_lock_during_simulation(
         &self,
         design: &mut Design,
@@ -578,6 +783,7 @@ impl Controller {
                     position,
                     orientation,
                     initial_design: AddressPointer::new(design.clone()),
+                    edited_grid: None,
                 };
                 let hyperboloid = request.to_grid();
                 let grid_descriptor =
@@ -586,16 +792,37 @@ impl Controller {
                 self.add_hyperboloid_helices(design, &hyperboloid, position, orientation)?;
                 Ok(())
             }
+            HyperboloidOperation::Edit { grid_id, request } => {
+                let grid = design
+                    .free_grids
+                    .get_from_g_id(&grid_id)
+                    .ok_or(OperationError::GridDoesNotExist(grid_id))?;
+                let (position, orientation) = (grid.position, grid.orientation);
+                let initial_design = AddressPointer::new(design.clone());
+                self.edit_hyperboloid(design, grid_id, request)?;
+                self.state = ControllerState::MakingHyperboloid {
+                    position,
+                    orientation,
+                    initial_design,
+                    edited_grid: Some(grid_id),
+                };
+                Ok(())
+            }
             HyperboloidOperation::Update(request) => {
                 if let ControllerState::MakingHyperboloid {
                     position,
                     orientation,
                     initial_design,
+                    edited_grid,
                 } = &self.state
                 {
                     let position = *position;
                     let orientation = *orientation;
+                    let edited_grid = *edited_grid;
                     *design = initial_design.clone_inner();
+                    if let Some(grid_id) = edited_grid {
+                        return self.edit_hyperboloid(design, grid_id, request);
+                    }
                     let hyperboloid = request.to_grid();
                     let grid_descriptor =
                         GridDescriptor::hyperboloid(position, orientation, hyperboloid.clone());
@@ -636,6 +863,18 @@ impl Controller {
         matches!(&self.state, ControllerState::MakingHyperboloid { .. })
     }
 
+    /// The operation whose parameters can still be edited, if any.
+    pub fn get_pending_operation(&self) -> Option<Arc<dyn SimpleOperation>> {
+        match &self.state {
+            ControllerState::WithPendingOp { operation, .. }
+            | ControllerState::ApplyingOperation {
+                operation: Some(operation),
+                ..
+            } => Some(operation.clone()),
+            _ => None,
+        }
+    }
+
     pub fn can_iterate_duplication(&self) -> bool {
         matches!(
             self.state,
@@ -702,17 +941,29 @@ impl Controller {
     pub fn notify(&mut self, notification: InteractorNotification) {
         match notification {
             InteractorNotification::FinishOperation => self.state.finish(),
+            InteractorNotification::FinishPendingOperation => self.state.finish_pending(),
             InteractorNotification::NewSelection => {
                 self.state.acknowledge_new_selection();
             }
         }
     }
 
+    /// Return true if applying `operation` would continue the operation in progress instead of
+    /// starting a new one.
+    pub fn continues_current_operation(&self, operation: &DesignOperation) -> bool {
+        !self.is_in_persistent_state().is_persistent()
+            && matches!(
+                self.check_compatibility(operation),
+                OperationCompatibility::Compatible
+            )
+    }
+
     fn check_compatibility(&self, operation: &DesignOperation) -> OperationCompatibility {
         match self.state {
             ControllerState::MakingHyperboloid { .. } => {
                 if let DesignOperation::HyperboloidOperation(op) = operation {
-                    if let HyperboloidOperation::New { .. } = op {
+                    if let HyperboloidOperation::New { .. } | HyperboloidOperation::Edit { .. } = op
+                    {
                         OperationCompatibility::Incompatible
                     } else {
                         OperationCompatibility::Compatible
@@ -1450,30 +1701,18 @@ impl Controller {
         drop(new_grids);
     }
 
+    /// Give a new color to the staples. The strands that are locked for edition keep their color,
+    /// so that recoloring a design with some locked strands does not fail.
     pub fn fancy_recolor_staples(&mut self, design: &mut Design) {
-        let mut drawing_styles = HashMap::<DesignElementKey, DrawingStyle>::default();
-
-        if let Some(t) = &design.organizer_tree {
-            // Read drawing style -> this should be a function on its own, the exact same code is used in design-content
-            let prefix = "style:"; // PREFIX SHOULD BELONG TO CONST.RS
-            let h = t.get_hashmap_to_all_group_names_with_prefix(prefix);
-            for (e, names) in h {
-                let drawing_attributes = names
-                    .iter()
-                    .flat_map(|x| {
-                        x.split(&[' ', ':'])
-                            .flat_map(DrawingAttribute::from_str)
-                            .collect::<Vec<DrawingAttribute>>()
-                    })
-                    .collect::<Vec<DrawingAttribute>>();
-                let style = DrawingStyle::from(drawing_attributes);
-                drawing_styles.insert(e, style);
-            }
-        }
+        let drawing_styles = design.drawing_styles();
 
         for (s_id, strand) in design.strands.iter_mut() {
-            // recoloring only concerns the non-scaffold strands
-            if Some(*s_id) != design.scaffold_id {
+            // recoloring only concerns the non-scaffold strands that are not locked
+            if Some(*s_id) != design.scaffold_id
+                && !design
+                    .locked_elements
+                    .contains(&DesignElementKey::Strand(*s_id))
+            {
                 // Compute strand drawing style
                 let strand_style = drawing_styles
                     .get(&DesignElementKey::Strand(*s_id))
@@ -2141,6 +2380,188 @@ impl Controller {
         Ok(())
     }
 
+    pub fn add_parametric_helix(
+        &mut self,
+        design: &mut Design,
+        descriptor: ParametricCurveDescriptor,
+    ) -> Result<(), OperationError> {
+        descriptor.check()?;
+        self.update_state_and_design(design);
+        let curve = CurveDescriptor::Parametric(descriptor);
+        let rise = design.helix_parameters.unwrap_or_default().rise as f64;
+        let length = curve
+            .compute_length()
+            .map_or(0, |l| (l / rise).floor() as isize);
+        self.add_curved_helix(design, curve, length);
+        Ok(())
+    }
+
+    /// Add an helix whose axis is read from each file of `paths`. Nothing is added if one of the
+    /// files cannot be read.
+    pub fn import_helix_axes(
+        &mut self,
+        design: &mut Design,
+        paths: &[PathBuf],
+        parameters: &AxisImportParameters,
+    ) -> Result<(), OperationError> {
+        let rise = design.helix_parameters.unwrap_or_default().rise;
+        let axes = paths
+            .iter()
+            .map(|path| read_axis(path, parameters, rise))
+            .collect::<Result<Vec<_>, _>>()?;
+        for axis in axes {
+            self.add_curved_helix(design, axis.curve, axis.nb_nucl as isize);
+        }
+        Ok(())
+    }
+
+    /// Insert the design in the file at `path`, in any format that can be loaded, as a new group
+    /// named after the file.
+    pub fn insert_design(
+        &mut self,
+        design: &mut Design,
+        path: &Path,
+    ) -> Result<(), OperationError> {
+        let mut inserted = read_file(path).map_err(OperationError::CouldNotLoadDesign)?;
+        inserted.strands.remove_empty_domains();
+        let name = path.file_stem().map_or_else(
+            || "Inserted design".to_owned(),
+            |s| s.to_string_lossy().into_owned(),
+        );
+        design.insert_design(inserted, name);
+        Ok(())
+    }
+
+    /// The triangles of the mesh of a 3D object, read from the file at `mesh_path` and placed at
+    /// the position and orientation of the object in the design.
+    fn placed_mesh(
+        design: &Design,
+        object_id: External3DObjectId,
+        mesh_path: &Path,
+    ) -> Result<Vec<[Vec3; 3]>, OperationError> {
+        let object = design
+            .external_3d_objects
+            .get(&object_id)
+            .ok_or(OperationError::External3DObjectDoesNotExist(object_id))?;
+        let triangles = load_triangles(mesh_path).map_err(OperationError::CouldNotReadMesh)?;
+        Ok(object.place_triangles(&triangles))
+    }
+
+    /// Add the helices and strands of a wireframe made from the mesh of a 3D object, whose source
+    /// file is at `mesh_path`. The mesh is rescaled to nanometers as when it is displayed, and
+    /// moved to the position of the object. The scaffold of the wireframe becomes the scaffold
+    /// of the design if it has none.
+    pub fn make_wireframe(
+        &mut self,
+        design: &mut Design,
+        object_id: External3DObjectId,
+        mesh_path: &Path,
+        parameters: &WireframeParameters,
+    ) -> Result<(), OperationError> {
+        let triangles = Self::placed_mesh(design, object_id, mesh_path)?;
+        let wireframe = make_wireframe(
+            &triangles,
+            parameters,
+            &design.helix_parameters.unwrap_or_default(),
+        )?;
+
+        let mut new_helices = design.helices.make_mut();
+        let helix_ids: Vec<usize> = wireframe
+            .helices
+            .into_iter()
+            .map(|h| new_helices.push_helix(h))
+            .collect();
+        drop(new_helices);
+
+        self.add_generated_strands(
+            design,
+            &helix_ids,
+            Some(wireframe.scaffold),
+            wireframe.staples,
+        );
+        Ok(())
+    }
+
+    /// Fill the volume of the mesh of a 3D object, whose source file is at `mesh_path`, with
+    /// helices on a new grid. The mesh is rescaled to nanometers and placed as when it is
+    /// displayed, and then scaled about its center by the scale of the parameters.
+    pub fn voxelize_mesh(
+        &mut self,
+        design: &mut Design,
+        object_id: External3DObjectId,
+        mesh_path: &Path,
+        parameters: &VoxelizationParameters,
+    ) -> Result<(), OperationError> {
+        let triangles = Self::placed_mesh(design, object_id, mesh_path)?;
+        let helix_parameters = design.helix_parameters.unwrap_or_default();
+        let voxelization = voxelize(&triangles, parameters, &helix_parameters)?;
+
+        let mut new_grids = design.free_grids.make_mut();
+        let grid = voxelization.grid.to_grid(helix_parameters);
+        let grid_id = new_grids.push(voxelization.grid);
+        drop(new_grids);
+        let mut new_helices = design.helices.make_mut();
+        let helix_ids: Vec<usize> = voxelization
+            .helices
+            .iter()
+            .map(|(x, y)| new_helices.push_helix(Helix::new_on_grid(&grid, *x, *y, grid_id)))
+            .collect();
+        drop(new_helices);
+
+        self.add_generated_strands(
+            design,
+            &helix_ids,
+            voxelization.scaffold,
+            voxelization.strands,
+        );
+        Ok(())
+    }
+
+    /// Add strands whose domains refer to the helices of `helix_ids` by their index in it. The
+    /// scaffold becomes the scaffold of the design if it has none.
+    fn add_generated_strands(
+        &mut self,
+        design: &mut Design,
+        helix_ids: &[usize],
+        scaffold: Option<Strand>,
+        strands: Vec<Strand>,
+    ) {
+        let mut add_strand = |mut strand: Strand| {
+            for domain in &mut strand.domains {
+                if let Domain::HelixDomain(interval) = domain {
+                    interval.helix = helix_ids[interval.helix];
+                }
+            }
+            strand.color = new_color(&mut self.color_idx);
+            design.strands.push(strand);
+            design.strands.keys().max().copied()
+        };
+        let scaffold_id = scaffold.and_then(&mut add_strand);
+        for strand in strands {
+            add_strand(strand);
+        }
+        if design.scaffold_id.is_none() {
+            design.scaffold_id = scaffold_id;
+        }
+    }
+
+    /// Add an helix following `curve` with a strand of `length` nucleotides on each side.
+    fn add_curved_helix(&mut self, design: &mut Design, curve: CurveDescriptor, length: isize) {
+        let mut new_helices = design.helices.make_mut();
+        let helix_id = new_helices.push_helix(Helix::new_with_curve(curve));
+        drop(new_helices);
+        if length > 0 {
+            for forward in [false, true] {
+                let new_key = self.add_strand(design, helix_id, 0, forward);
+                if let Domain::HelixDomain(ref mut dom) =
+                    design.strands.get_mut(&new_key).unwrap().domains[0]
+                {
+                    dom.end = length;
+                }
+            }
+        }
+    }
+
     pub fn add_two_points_bezier(
         &mut self,
         design: &mut Design,
@@ -2309,7 +2730,7 @@ impl Controller {
                 .clone()
                 .zip(strand3prime.sequence.clone())
             {
-                let new_seq = seq5.into_owned() + &seq3;
+                let new_seq = seq5.into_owned() + seq3.as_ref();
                 Some(Cow::Owned(new_seq))
             } else if let Some(seq5) = &strand5prime.sequence {
                 Some(seq5.clone())
@@ -2852,15 +3273,21 @@ impl Controller {
         &self,
         design: &mut Design,
         path: PathBuf,
+        mut parameters: SvgImportParameters,
     ) -> Result<(), OperationError> {
-        // The imported bezier path will be attached to plane 0 so we need to ensure that it exists
-        if design.bezier_planes.get(&BezierPlaneId(0)).is_none() {
+        // If the design has no plane yet, the paths are attached to a new one.
+        if design.bezier_planes.is_empty() {
             self.add_bezier_plane(design, Default::default());
+            parameters.plane_id = BezierPlaneId(0);
+        } else if design.bezier_planes.get(&parameters.plane_id).is_none() {
+            return Err(OperationError::BezierPlaneDoesNotExist(parameters.plane_id));
         }
 
+        let imported_paths = read_svg_paths(&path, &parameters)?;
         let mut paths = design.bezier_paths.make_mut();
-        let path = read_first_svg_path(&path)?;
-        paths.push(path);
+        for path in imported_paths {
+            paths.push(path);
+        }
 
         drop(paths);
 
@@ -2906,11 +3333,126 @@ pub enum OperationError {
     GridCopyError(GridCopyError),
     CouldNotGetPrime3of(usize),
     PathDoesNotExist(BezierPathId),
+    BezierPlaneDoesNotExist(BezierPlaneId),
+    InvalidParametricCurve(ExpressionError),
     VertexDoesNotExist(BezierPathId, usize),
     GridIsNotEmpty(GridId),
     CouldNotMake3DObject,
+    External3DObjectDoesNotExist(External3DObjectId),
     SvgImportError(SvgImportError),
+    AxisImportError(AxisImportError),
+    CouldNotReadMesh(ErrMesh),
+    WireframeError(WireframeError),
+    VoxelizationError(VoxelizationError),
+    CouldNotLoadDesign(LoadDesignError),
     ShiftOptimizationInterrupted,
+    /// The operation would modify an element that is locked for edition.
+    ElementIsLocked(DesignElementKey),
+}
+
+impl std::fmt::Display for OperationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::GroupHasNoPivot(g_id) => write!(f, "Group {g_id:?} has no pivot"),
+            Self::NotImplemented => write!(f, "This operation is not implemented"),
+            Self::BadSelection => {
+                write!(
+                    f,
+                    "This operation cannot be applied on the current selection"
+                )
+            }
+            Self::IncompatibleState(state) => write!(f, "Incompatible state: {state}"),
+            Self::CannotBuildOn(nucl) => write!(f, "Cannot build on nucleotide {nucl}"),
+            Self::CutNonExistentStrand => write!(f, "There is no strand to cut here"),
+            Self::GridDoesNotExist(g_id) => write!(f, "Grid {g_id:?} does not exist"),
+            Self::GridPositionAlreadyUsed => write!(f, "This grid position is already used"),
+            Self::StrandDoesNotExist(s_id) => write!(f, "Strand {s_id} does not exist"),
+            Self::HelixDoesNotExists(h_id) => write!(f, "Helix {h_id} does not exist"),
+            Self::HelixHasNoGridPosition(h_id) => {
+                write!(f, "Helix {h_id} is not on a grid")
+            }
+            Self::CouldNotMakeEdge(from, to) => {
+                write!(f, "Could not make an edge from {from:?} to {to:?}")
+            }
+            Self::MergingSameStrand => write!(f, "Cannot merge a strand with itself"),
+            Self::NuclDoesNotExist(nucl) => write!(f, "Nucleotide {nucl} does not exist"),
+            Self::XoverBetweenTwoPrime5 => {
+                write!(f, "Cannot make a cross-over between two 5' ends")
+            }
+            Self::XoverBetweenTwoPrime3 => {
+                write!(f, "Cannot make a cross-over between two 3' ends")
+            }
+            Self::CouldNotCreateEdges => write!(f, "Could not create the edges"),
+            Self::EmptyOrigin => write!(f, "Nothing to copy"),
+            Self::EmptyClipboard => write!(f, "The clipboard is empty"),
+            Self::WrongClipboard => {
+                write!(f, "The clipboard does not contain this kind of elements")
+            }
+            Self::CannotPasteHere => write!(f, "Cannot paste here"),
+            Self::HelixNotEmpty(h_id) => write!(f, "Helix {h_id} is not empty"),
+            Self::EmptyScaffoldSequence => write!(f, "The scaffold sequence is empty"),
+            Self::NoScaffoldSet => write!(f, "No scaffold is set"),
+            Self::NoGrids => write!(f, "There is no grid"),
+            Self::FinishFirst => write!(f, "The current operation must be finished first"),
+            Self::CameraDoesNotExist(c_id) => write!(f, "Camera {c_id:?} does not exist"),
+            Self::GridIsNotHyperboloid(g_id) => {
+                write!(f, "Grid {g_id:?} is not a nanotube")
+            }
+            Self::DesignOperationError(e) => write!(f, "{e:?}"),
+            Self::NotPiecewiseBezier(h_id) => {
+                write!(f, "Helix {h_id} is not a piecewise Bezier curve")
+            }
+            Self::GridCopyError(e) => write!(f, "Could not copy the grid: {e:?}"),
+            Self::CouldNotGetPrime3of(s_id) => {
+                write!(f, "Could not get the 3' end of strand {s_id}")
+            }
+            Self::PathDoesNotExist(p_id) => write!(f, "Path {p_id:?} does not exist"),
+            Self::BezierPlaneDoesNotExist(p_id) => {
+                write!(f, "Bezier plane {p_id:?} does not exist")
+            }
+            Self::InvalidParametricCurve(e) => write!(f, "Invalid parametric curve: {e:?}"),
+            Self::VertexDoesNotExist(p_id, vertex) => {
+                write!(f, "Vertex {vertex} of path {p_id:?} does not exist")
+            }
+            Self::GridIsNotEmpty(g_id) => write!(f, "Grid {g_id:?} is not empty"),
+            Self::CouldNotMake3DObject => write!(f, "Could not make the 3D object"),
+            Self::External3DObjectDoesNotExist(id) => {
+                write!(f, "3D object {id:?} does not exist")
+            }
+            Self::SvgImportError(e) => write!(f, "Could not import the SVG file: {e:?}"),
+            Self::AxisImportError(e) => write!(f, "Could not import the helix axes: {e:?}"),
+            Self::CouldNotReadMesh(e) => write!(f, "Could not read the mesh: {e:?}"),
+            Self::WireframeError(e) => write!(f, "Could not make the wireframe: {e:?}"),
+            Self::VoxelizationError(e) => write!(f, "Could not voxelize the mesh: {e:?}"),
+            Self::CouldNotLoadDesign(e) => write!(f, "Could not load the design: {e}"),
+            Self::ShiftOptimizationInterrupted => {
+                write!(
+                    f,
+                    "The optimization of the scaffold position was interrupted"
+                )
+            }
+            Self::ElementIsLocked(key) => match key {
+                DesignElementKey::Helix(h_id) => write!(f, "Helix {h_id} is locked"),
+                DesignElementKey::Strand(s_id) => write!(f, "Strand {s_id} is locked"),
+                DesignElementKey::Grid(g_id) => write!(f, "Grid {g_id} is locked"),
+                key => write!(f, "{key:?} is locked"),
+            },
+        }
+    }
+}
+
+impl OperationError {
+    /// True if the error should be shown to the user.
+    ///
+    /// The errors raised because an operation is sent while the controller cannot accept it, like
+    /// the operations sent while dragging during another operation, or because the user
+    /// interrupted an operation, are expected and are only logged.
+    pub fn is_worth_reporting(&self) -> bool {
+        !matches!(
+            self,
+            Self::IncompatibleState(_) | Self::FinishFirst | Self::ShiftOptimizationInterrupted
+        )
+    }
 }
 
 impl From<DesignOperationError> for OperationError {
@@ -2925,6 +3467,30 @@ impl From<SvgImportError> for OperationError {
     }
 }
 
+impl From<AxisImportError> for OperationError {
+    fn from(e: AxisImportError) -> Self {
+        Self::AxisImportError(e)
+    }
+}
+
+impl From<WireframeError> for OperationError {
+    fn from(e: WireframeError) -> Self {
+        Self::WireframeError(e)
+    }
+}
+
+impl From<VoxelizationError> for OperationError {
+    fn from(e: VoxelizationError) -> Self {
+        Self::VoxelizationError(e)
+    }
+}
+
+impl From<ExpressionError> for OperationError {
+    fn from(e: ExpressionError) -> Self {
+        Self::InvalidParametricCurve(e)
+    }
+}
+
 fn nucl_pos_2d(helices: &Helices, nucl: &Nucl, segment: usize) -> Option<Vec2> {
     let isometry = helices.get(&nucl.helix).and_then(|h| {
         if segment > 0 {
@@ -2953,6 +3519,8 @@ enum ControllerState {
         initial_design: AddressPointer<Design>,
         position: Vec3,
         orientation: Rotor3,
+        /// The existing hyperboloid grid whose parameters are being edited, if any.
+        edited_grid: Option<GridId>,
     },
     BuildingStrand {
         builders: Vec<StrandBuilder>,
@@ -3283,6 +3851,13 @@ impl ControllerState {
         }
     }
 
+    fn finish_pending(&mut self) {
+        self.finish();
+        if let Self::WithPendingOp { .. } = self {
+            *self = Self::Normal;
+        }
+    }
+
     fn acknowledge_new_selection(&mut self) {
         let value = self.clone();
         if matches!(
@@ -3308,6 +3883,9 @@ impl ControllerState {
 #[derive(Copy, Clone)]
 pub enum InteractorNotification {
     FinishOperation,
+    /// Finish the current operation and stop editing the pending operation, whose parameters
+    /// can then no longer be modified.
+    FinishPendingOperation,
     NewSelection,
 }
 
@@ -3344,3 +3922,100 @@ impl StatePersistence {
         matches!(self, Self::Transitory)
     }
 }
+
+/// Remove the domains that lie on one of `helices`, cutting the strands that go through them.
+///
+/// The first remaining piece of a cut strand keeps its identifier, the other pieces are given new
+/// identifiers and colors.
+fn remove_domains_on_helices(strands: &mut Strands, helices: &[usize], color_idx: &mut usize) {
+    let on_helices =
+        |d: &Domain| matches!(d, Domain::HelixDomain(dom) if helices.contains(&dom.helix));
+    let strand_ids: Vec<usize> = strands
+        .iter()
+        .filter(|(_, s)| s.domains.iter().any(on_helices))
+        .map(|(s_id, _)| *s_id)
+        .collect();
+    for s_id in strand_ids {
+        let Some(strand) = strands.remove(&s_id) else {
+            continue;
+        };
+        let mut domains = strand.domains;
+        if strand.is_cyclic
+            && let Some(first) = domains.iter().position(on_helices)
+        {
+            domains.rotate_left(first);
+        }
+        let pieces = domains
+            .split(|d| on_helices(d))
+            .filter(|piece| piece.iter().any(|d| matches!(d, Domain::HelixDomain(_))));
+        let mut piece_id = s_id;
+        let mut color = strand.color;
+        for piece in pieces {
+            strands.insert(
+                piece_id,
+                Strand {
+                    domains: piece.to_vec(),
+                    junctions: read_junctions(piece, false),
+                    sequence: None,
+                    is_cyclic: false,
+                    color,
+                    name: strand.name.clone(),
+                },
+            );
+            piece_id = strands.keys().max().map_or(0, |id| id + 1);
+            color = Controller::new_color(color_idx);
+        }
+    }
+}
+
+/// Stretch the domains that lie on one of `helices` so that the positions `0..old_length` are
+/// mapped onto `0..new_length`.
+///
+/// The domains that become empty are removed, as are the strands that no longer have any domain.
+fn stretch_domains_on_helices(
+    strands: &mut Strands,
+    helices: &[usize],
+    old_length: usize,
+    new_length: usize,
+) {
+    if old_length == new_length || old_length == 0 {
+        return;
+    }
+    let ratio = new_length as f64 / old_length as f64;
+    let stretch = |x: isize| (x as f64 * ratio).round() as isize;
+    let mut emptied = Vec::new();
+    for (s_id, strand) in strands.iter_mut() {
+        let mut stretched = false;
+        for domain in &mut strand.domains {
+            if let Domain::HelixDomain(dom) = domain
+                && helices.contains(&dom.helix)
+            {
+                dom.start = stretch(dom.start);
+                dom.end = stretch(dom.end);
+                dom.sequence = None;
+                stretched = true;
+            }
+        }
+        if !stretched {
+            continue;
+        }
+        strand.sequence = None;
+        let nb_domains = strand.domains.len();
+        strand
+            .domains
+            .retain(|d| !matches!(d, Domain::HelixDomain(dom) if dom.start >= dom.end));
+        if strand.domains.len() != nb_domains {
+            strand.junctions = read_junctions(&strand.domains, strand.is_cyclic);
+        }
+        if !strand
+            .domains
+            .iter()
+            .any(|d| matches!(d, Domain::HelixDomain(_)))
+        {
+            emptied.push(*s_id);
+        }
+    }
+    for s_id in emptied {
+        strands.remove(&s_id);
+    }
+}
@@ -42,7 +42,7 @@ pub fn read_scaffold_seq(
             .ok_or(OperationError::StrandDoesNotExist(s_id))?;
         for domain in &strand.domains {
             if let Domain::HelixDomain(dom) = domain {
-                for nucl_position in dom.iter() {
+                for nucl_position in dom.iter_nucls(&design.helices) {
                     let nucl = Nucl {
                         helix: dom.helix,
                         position: nucl_position,
@@ -151,7 +151,7 @@ fn evaluate_shift(design: &Design, basis_map: &BTreeMap<Nucl, char>) -> (usize,
         let mut sequence = String::with_capacity(10000);
         for domain in &strand.domains {
             if let Domain::HelixDomain(dom) = domain {
-                for position in dom.iter() {
+                for position in dom.iter_nucls(&design.helices) {
                     let nucl = Nucl {
                         position,
                         forward: dom.forward,
@@ -29,6 +29,7 @@ use rand_distr::{Exp, StandardNormal};
 use std::{
     cmp::Reverse,
     collections::{BTreeMap, BinaryHeap, HashMap},
+    num::NonZeroUsize,
     sync::{Arc, Mutex, Weak},
 };
 use ultraviolet::{Bivec3, Mat3, Rotor3, Vec3};
@@ -779,12 +780,14 @@ pub(super) struct HelixSystemThread {
     /// immediately stop when the listener is dropped.
     interface: Weak<Mutex<HelixSystemInterface>>,
     constants: Arc<RigidHelixConstants>,
+    sampler: FrameSampler,
 }
 
 #[derive(Default)]
 pub struct HelixSystemInterface {
     pub new_state: Option<RigidHelixState>,
     pub(super) parameters_update: Option<RigidBodyConstants>,
+    frames: Vec<SimulationFrame>,
 }
 
 #[derive(Debug, Clone)]
@@ -808,12 +811,13 @@ impl HelixSystemThread {
     pub(super) fn start_new(
         presenter: &Presenter,
         rigid_parameters: RigidBodyConstants,
+        sampler: FrameSampler,
     ) -> Result<Arc<Mutex<HelixSystemInterface>>, OperationError> {
         let interval_results = read_intervals(presenter)?;
         let helix_system =
             make_flexible_helices_system((0., 1.), rigid_parameters, presenter, &interval_results)?;
         let ret = Arc::new(Mutex::new(HelixSystemInterface::default()));
-        let helix_system_thread = Self::new(helix_system, &ret, interval_results);
+        let helix_system_thread = Self::new(helix_system, &ret, interval_results, sampler);
         helix_system_thread.run();
         Ok(ret)
     }
@@ -822,25 +826,38 @@ impl HelixSystemThread {
         helix_system: HelixSystem,
         interface: &Arc<Mutex<HelixSystemInterface>>,
         interval_result: IntervalResult,
+        sampler: FrameSampler,
     ) -> Self {
         let constants = helix_system.get_constants(interval_result);
         Self {
             helix_system,
             interface: Arc::downgrade(interface),
             constants: Arc::new(constants),
+            sampler,
         }
     }
 
     /// Spawn a thread to run the physical simulation.
     fn run(mut self) {
         std::thread::spawn(move || {
+            let mut step = 0;
             while let Some(interface_ptr) = self.interface.upgrade() {
                 let mut interface = interface_ptr.lock().unwrap();
                 if let Some(parameters) = interface.parameters_update.take() {
                     self.helix_system.update_parameters(parameters);
                 }
-                interface.new_state = Some(self.get_state());
+                let state = self.get_state();
+                if self.sampler.is_due(step) {
+                    interface.frames.push(SimulationFrame {
+                        step,
+                        // The last solved time span ended at `next_time`.
+                        time: self.helix_system.next_time,
+                        state: Box::new(state.clone()),
+                    });
+                }
+                interface.new_state = Some(state);
                 drop(interface);
+                step += 1;
                 self.helix_system.next_time();
                 let solver = FixedStepper::new(1e-4f32);
                 let method = ExplicitEuler::default();
@@ -883,24 +900,28 @@ pub struct GridSystemState {
 pub(super) struct GridsSystemThread {
     grid_system: GridsSystem,
     interface: Weak<Mutex<GridSystemInterface>>,
+    sampler: FrameSampler,
 }
 
 #[derive(Default)]
 pub(super) struct GridSystemInterface {
     new_state: Option<GridSystemState>,
     pub(super) parameters_update: Option<RigidBodyConstants>,
+    frames: Vec<SimulationFrame>,
 }
 
 impl GridsSystemThread {
     pub(super) fn start_new(
         presenter: &Presenter,
         rigid_parameters: RigidBodyConstants,
+        sampler: FrameSampler,
     ) -> Result<Arc<Mutex<GridSystemInterface>>, OperationError> {
         let grid_system = make_grid_system(presenter, (0., 1.), rigid_parameters)?;
         let ret = Arc::new(Mutex::new(GridSystemInterface::default()));
         let grid_system_thread = Self {
             grid_system,
             interface: Arc::downgrade(&ret),
+            sampler,
         };
         grid_system_thread.run();
         Ok(ret)
@@ -909,11 +930,23 @@ impl GridsSystemThread {
     /// Spawn a thread to run the physical simulation.
     fn run(mut self) {
         std::thread::spawn(move || {
+            let mut step = 0;
             while let Some(interface_ptr) = self.interface.upgrade() {
-                if let Some(parameters) = interface_ptr.lock().unwrap().parameters_update.take() {
+                let mut interface = interface_ptr.lock().unwrap();
+                if let Some(parameters) = interface.parameters_update.take() {
                     self.grid_system.update_parameters(parameters);
                 }
-                interface_ptr.lock().unwrap().new_state = Some(self.get_state());
+                let state = self.get_state();
+                if self.sampler.is_due(step) {
+                    interface.frames.push(SimulationFrame {
+                        step,
+                        time: step as f32 * self.grid_system.time_span.1,
+                        state: Box::new(state.clone()),
+                    });
+                }
+                interface.new_state = Some(state);
+                drop(interface);
+                step += 1;
                 let solver = FixedStepper::new(1e-4f32);
                 let method = Kutta3::default();
                 if let Ok((_, y)) = solver.solve(&self.grid_system, &method) {
@@ -1250,6 +1283,10 @@ pub enum SimulationOperation<'pres> {
         presenter: &'pres Presenter,
         parameters: RapierParameters,
     },
+    /// Stop the relaxation and store the current positions of the nucleotides in the design.
+    CommitRelaxation {
+        presenter: &'pres Presenter,
+    },
 }
 
 pub trait SimulationInterface: Send {
@@ -1260,6 +1297,52 @@ pub trait SimulationInterface: Send {
     fn still_valid(&self) -> bool {
         true
     }
+    /// Return a description of the advancement of the simulation and its completion ratio, if
+    /// the simulation knows when it will stop.
+    fn progress(&self) -> Option<(String, f32)> {
+        None
+    }
+    /// Return, once, the conclusion or the error of a simulation that stopped by itself.
+    fn take_report(&mut self) -> Option<Result<String, String>> {
+        None
+    }
+    /// Return the frames sampled since the last call, in the order in which they were reached.
+    fn take_frames(&mut self) -> Vec<SimulationFrame> {
+        Vec::new()
+    }
+}
+
+/// A state reached by a simulation, with the solver step at which it was reached.
+pub struct SimulationFrame {
+    /// The number of solver steps done since the start of the simulation.
+    pub step: usize,
+    /// The simulated time since the start of the simulation, in the time unit of its solver.
+    pub time: f32,
+    pub state: Box<dyn SimulationUpdate>,
+}
+
+/// Decides which states of a simulation are kept as frames of a trajectory.
+///
+/// Frames are taken every `interval` solver steps by the thread running the simulation, so that
+/// the frames of a simulation do not depend on how often its state is read.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameSampler {
+    interval: Option<NonZeroUsize>,
+}
+
+impl FrameSampler {
+    /// Take a frame every `interval` solver steps, starting with the initial state.
+    pub fn every(interval: NonZeroUsize) -> Self {
+        Self {
+            interval: Some(interval),
+        }
+    }
+
+    /// Return true if a frame must be taken once `step` solver steps are done.
+    pub fn is_due(&self, step: usize) -> bool {
+        self.interval
+            .is_some_and(|interval| step.is_multiple_of(interval.get()))
+    }
 }
 
 impl SimulationInterface for HelixSystemInterface {
@@ -1267,6 +1350,10 @@ impl SimulationInterface for HelixSystemInterface {
         let s = self.new_state.take()?;
         Some(Box::new(s))
     }
+
+    fn take_frames(&mut self) -> Vec<SimulationFrame> {
+        std::mem::take(&mut self.frames)
+    }
 }
 
 impl SimulationUpdate for RigidHelixState {
@@ -1672,6 +1759,9 @@ impl SimulationInterface for GridSystemInterface {
         let s = self.new_state.take()?;
         Some(Box::new(s))
     }
+    fn take_frames(&mut self) -> Vec<SimulationFrame> {
+        std::mem::take(&mut self.frames)
+    }
 }
 
 impl SimulationUpdate for GridSystemState {
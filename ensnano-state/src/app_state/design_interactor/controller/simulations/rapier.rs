@@ -1,10 +1,14 @@
 use crate::app_state::design_interactor::{
-    controller::simulations::SimulationInterface,
+    controller::simulations::{FrameSampler, SimulationFrame, SimulationInterface},
     presenter::{Presenter, SimulationUpdate},
 };
 use ahash::RandomState;
 use ensnano_design::{Design, helices::NuclCollection, parameters::HelixParameters};
-use ensnano_physics::{parameters::RapierParameters, simulation::RapierPhysicsSystem};
+use ensnano_physics::{
+    parameters::RapierParameters,
+    relaxation::{RelaxationError, RelaxationProgress, RelaxationReport},
+    simulation::RapierPhysicsSystem,
+};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex, Weak},
@@ -14,36 +18,40 @@ use std::{
 pub(crate) struct RapierPhysicalSystem {
     system: RapierPhysicsSystem,
     interface: Weak<Mutex<RapierInterface>>,
+    sampler: FrameSampler,
 }
 
 impl RapierPhysicalSystem {
     pub(crate) fn start_new(
         presenter: &Presenter,
         parameters: RapierParameters,
+        sampler: FrameSampler,
     ) -> Arc<Mutex<RapierInterface>> {
-        let system = RapierPhysicsSystem::full_simulation(
-            presenter
-                .get_design()
-                .helix_parameters
-                .unwrap_or(HelixParameters::GEARY_2014_DNA_P_STICK),
-            parameters,
-            presenter.content.nucl_collection.as_ref(),
-            &presenter.content.elements,
-            &presenter.content.nucleotide,
-            &presenter.content.space_position,
-            &presenter.get_design().helices,
-            &presenter.content.is_clone_map,
-        );
+        let system = build_system(presenter, parameters);
+        let space_position = system.get_positions();
+        let frames = if sampler.is_due(0) {
+            vec![SimulationFrame {
+                step: 0,
+                time: 0.,
+                state: Box::new(RapierPositions(space_position.clone())),
+            }]
+        } else {
+            Vec::new()
+        };
 
         let interface = Arc::new(Mutex::new(RapierInterface {
-            space_position: system.get_positions(),
+            space_position,
             force_stop: false,
             parameters,
+            progress: None,
+            report: None,
+            frames,
         }));
 
         let result = Self {
             system,
             interface: Arc::downgrade(&interface),
+            sampler,
         };
 
         result.run();
@@ -52,26 +60,82 @@ impl RapierPhysicalSystem {
 
     pub(crate) fn run(mut self) {
         std::thread::spawn(move || {
+            let mut step = 0;
+            let mut time = 0.;
             while let Some(interface) = self.interface.upgrade() {
                 let Ok(parameters) = interface.try_lock().map(|i| i.parameters) else {
                     continue;
                 };
                 // we update the physics
-                self.system.step(&parameters);
+                step += 1;
+                let progress = self.system.measured_step(&parameters, step);
+                time += self.system.time_step();
+                if self.sampler.is_due(step) {
+                    // Frames must not be missed, so the lock is waited for.
+                    interface.lock().unwrap().frames.push(SimulationFrame {
+                        step,
+                        time,
+                        state: Box::new(RapierPositions(self.system.get_positions())),
+                    });
+                }
+                let report = match progress {
+                    Ok(progress) => parameters
+                        .convergence
+                        .and_then(|criteria| criteria.outcome(&progress))
+                        .map(|outcome| Ok(RelaxationReport { outcome, progress })),
+                    Err(error) => Some(Err(error)),
+                };
+                if let Some(report) = report {
+                    // The simulation stops by itself, the report must not be missed.
+                    let mut guard = interface.lock().unwrap();
+                    if report.is_ok() {
+                        guard.space_position = self.system.get_positions();
+                    }
+                    guard.progress = report.as_ref().ok().map(|report| report.progress);
+                    guard.report = Some(report);
+                    guard.force_stop = true;
+                    drop(guard);
+                    break;
+                }
                 // we get the positions
                 if let Ok(mut guard) = interface.try_lock() {
                     guard.space_position = self.system.get_positions();
+                    guard.progress = progress.ok();
                 }
             }
         });
     }
 }
 
-#[derive(Default, Clone)]
+/// Build the physical system simulating the design held by `presenter`.
+pub(crate) fn build_system(
+    presenter: &Presenter,
+    parameters: RapierParameters,
+) -> RapierPhysicsSystem {
+    RapierPhysicsSystem::new(
+        presenter
+            .get_design()
+            .helix_parameters
+            .unwrap_or(HelixParameters::GEARY_2014_DNA_P_STICK),
+        parameters,
+        presenter.content.nucl_collection.as_ref(),
+        &presenter.content.elements,
+        &presenter.content.nucleotide,
+        &presenter.content.space_position,
+        &presenter.get_design().helices,
+        &presenter.content.is_clone_map,
+    )
+}
+
+#[derive(Default)]
 pub(crate) struct RapierInterface {
     pub parameters: RapierParameters,
     space_position: Vec<(u32, [f32; 3])>,
     pub force_stop: bool,
+    progress: Option<RelaxationProgress>,
+    /// Set when the simulation stopped by itself.
+    report: Option<Result<RelaxationReport, RelaxationError>>,
+    frames: Vec<SimulationFrame>,
 }
 
 impl RapierInterface {
@@ -82,15 +146,41 @@ impl RapierInterface {
 
 impl SimulationInterface for RapierInterface {
     fn get_simulation_state(&mut self) -> Option<Box<dyn SimulationUpdate>> {
-        Some(Box::new(self.clone()))
+        Some(Box::new(RapierPositions(self.space_position.clone())))
     }
 
     fn still_valid(&self) -> bool {
         !self.force_stop
     }
+
+    fn progress(&self) -> Option<(String, f32)> {
+        let criteria = self.parameters.convergence?;
+        let progress = self.progress?;
+        Some((
+            format!(
+                "Relaxing: energy {:.3}, max displacement {:.1e} nm",
+                progress.total_energy(),
+                progress.max_displacement
+            ),
+            criteria.completion(&progress),
+        ))
+    }
+
+    fn take_report(&mut self) -> Option<Result<String, String>> {
+        self.report
+            .take()
+            .map(|report| report.map(|r| r.to_string()).map_err(|e| e.to_string()))
+    }
+
+    fn take_frames(&mut self) -> Vec<SimulationFrame> {
+        std::mem::take(&mut self.frames)
+    }
 }
 
-impl SimulationUpdate for RapierInterface {
+/// The positions of the nucleotides computed by the physical system.
+struct RapierPositions(Vec<(u32, [f32; 3])>);
+
+impl SimulationUpdate for RapierPositions {
     fn update_design(&self, _: &mut Design) {
         // No operations are done here
     }
@@ -101,6 +191,6 @@ impl SimulationUpdate for RapierInterface {
         space_position: &mut HashMap<u32, [f32; 3], RandomState>,
     ) {
         // we extract the physical positions here
-        space_position.extend(self.space_position.iter().map(|(a, b)| (a, b)));
+        space_position.extend(self.0.iter().map(|(a, b)| (a, b)));
     }
 }
@@ -6,7 +6,7 @@
 //! normal distance between two consecutive nucleotides.
 
 use crate::app_state::design_interactor::{
-    controller::simulations::SimulationInterface,
+    controller::simulations::{FrameSampler, SimulationFrame, SimulationInterface},
     presenter::{Presenter, SimulationUpdate},
 };
 use ensnano_design::{Design, helices::Helix, nucl::Nucl, parameters::HelixParameters};
@@ -19,6 +19,8 @@ use std::{
 const MASS_HELIX: f32 = 2.;
 const K_SPRING: f32 = 1000.;
 const FRICTION: f32 = 100.;
+/// The time step of the simulation.
+const TIME_STEP: f32 = 1e-3;
 
 const SYNC_ROLLS_INSTEAD_OF_COPY_ROLLS: bool = false; // false is ENSnano default
 
@@ -29,12 +31,14 @@ pub(crate) struct PhysicalSystem {
     /// The structure that handles the simulation of the rotation springs.
     roller: RollSystem,
     interface: Weak<Mutex<RollInterface>>,
+    sampler: FrameSampler,
 }
 
 impl PhysicalSystem {
     pub(crate) fn start_new(
         presenter: &Presenter,
         target_helices: Option<Vec<usize>>,
+        sampler: FrameSampler,
     ) -> Arc<Mutex<RollInterface>> {
         let helices: Vec<Helix> = presenter.get_helices().values().cloned().collect();
         let keys: Vec<usize> = presenter.get_helices().keys().copied().collect();
@@ -57,6 +61,7 @@ impl PhysicalSystem {
             data,
             roller,
             interface: Arc::downgrade(&interface),
+            sampler,
         };
         system.run();
         interface
@@ -66,8 +71,17 @@ impl PhysicalSystem {
     /// termination of the simulation and one to fetch the current state of the helices.
     pub(crate) fn run(mut self) {
         std::thread::spawn(move || {
+            let mut step = 0;
             while let Some(interface_ptr) = self.interface.upgrade() {
-                let grad = self.roller.solve_one_step(&mut self.data, 1e-3);
+                if self.sampler.is_due(step) {
+                    interface_ptr.lock().unwrap().frames.push(SimulationFrame {
+                        step,
+                        time: step as f32 * TIME_STEP,
+                        state: Box::new(self.data.get_roll_state()),
+                    });
+                }
+                let grad = self.roller.solve_one_step(&mut self.data, TIME_STEP);
+                step += 1;
                 log::trace!("grad {grad}");
                 interface_ptr.lock().unwrap().stabilized = grad < 0.1;
                 interface_ptr.lock().unwrap().new_state = Some(self.data.get_roll_state());
@@ -309,6 +323,7 @@ impl DesignData {
 pub(crate) struct RollInterface {
     pub new_state: Option<RollState>,
     stabilized: bool,
+    frames: Vec<SimulationFrame>,
 }
 
 impl SimulationInterface for RollInterface {
@@ -320,6 +335,10 @@ impl SimulationInterface for RollInterface {
     fn still_valid(&self) -> bool {
         !self.stabilized
     }
+
+    fn take_frames(&mut self) -> Vec<SimulationFrame> {
+        std::mem::take(&mut self.frames)
+    }
 }
 
 pub(crate) struct RollState(HashMap<usize, Helix>);
@@ -82,7 +82,7 @@ impl Twister {
         {
             TwistState {
                 grid_id: target_grid,
-                grid: *grid,
+                grid: grid.clone(),
                 helices: presenter
                     .get_design()
                     .helices
@@ -158,7 +158,10 @@ impl TwistState {
             }
             GridTypeDescr::Square { twist: grid_twist }
             | GridTypeDescr::Honeycomb { twist: grid_twist }
-            | GridTypeDescr::RotatedHoneycomb { twist: grid_twist } => {
+            | GridTypeDescr::RotatedHoneycomb { twist: grid_twist }
+            | GridTypeDescr::Lattice {
+                twist: grid_twist, ..
+            } => {
                 *grid_twist = Some(twist);
                 twist_to_omega(twist, helix_parameters)
             }
@@ -191,7 +194,7 @@ impl SimulationUpdate for TwistState {
         if let Some(grid) =
             FreeGridId::try_from_grid_id(self.grid_id).and_then(|g_id| grids_mut.get_mut(&g_id))
         {
-            *grid = self.grid;
+            *grid = self.grid.clone();
         } else {
             log::error!("COULD NOT UPDATE GRID {:?}", self.grid_id);
         }
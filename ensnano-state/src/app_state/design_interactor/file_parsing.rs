@@ -46,10 +46,8 @@ impl DesignInteractor {
 }
 
 /// Create a design by parsing a file.
-#[expect(clippy::panic_in_result_fn)] // FIXME
-fn read_file<P: AsRef<Path> + std::fmt::Debug>(path: P) -> Result<Design, LoadDesignError> {
-    let json_str =
-        std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("File not found {path:?}"));
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Design, LoadDesignError> {
+    let json_str = std::fs::read_to_string(&path).map_err(LoadDesignError::IoError)?;
 
     let design: Result<Design, _> = serde_json::from_str(&json_str);
     // First try to read icednano format
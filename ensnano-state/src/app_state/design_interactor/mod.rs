@@ -4,8 +4,9 @@ pub mod presenter;
 
 use self::{
     controller::{
-        Controller, InteractorNotification, OperationError, clipboard::CopyOperation,
-        simulations::SimulationOperation,
+        Controller, InteractorNotification, OperationError,
+        clipboard::CopyOperation,
+        simulations::{FrameSampler, SimulationOperation, rapier::build_system},
     },
     presenter::{Presenter, SimulationUpdate, apply_simulation_update, update_presenter},
 };
@@ -16,6 +17,7 @@ use crate::{
     },
     design::{operation::DesignOperation, selection::Selection},
     operation::{AppStateOperationOutcome, AppStateOperationResult},
+    rigidity::RigidityHeatMap,
     utils::operation::{CurrentOpState, SimpleOperation},
 };
 use ensnano_design::{
@@ -24,19 +26,26 @@ use ensnano_design::{
     curves::bezier::InstantiatedPiecewiseBezier,
     domains::Domain,
     group_attributes::GroupAttribute,
+    nucl::Nucl,
     organizer_tree::GroupId,
     parameters::HelixParameters,
 };
+use ensnano_physics::{
+    parameters::RapierParameters,
+    relaxation::{RelaxationCriteria, RelaxationError, RelaxationProgress, RelaxationReport},
+};
 use ensnano_utils::{
     PastingStatus, SimulationState,
     app_state_parameters::suggestion_parameters::SuggestionParameters, clipboard::ClipboardContent,
     consts::UPDATE_VISIBILITY_SIEVE_LABEL, strand_builder::StrandBuilder,
 };
 use std::{
+    collections::HashMap,
     io::Write as _,
     path::PathBuf,
     sync::{Arc, Mutex},
 };
+use ultraviolet::Vec3;
 
 /// The `DesignInteractor` handles all read/write operations on the design. It is a stateful struct
 /// so it is meant to be cheap to clone.
@@ -70,6 +79,10 @@ impl DesignInteractor {
         self.controller.is_building_hyperboloid()
     }
 
+    pub(super) fn get_pending_operation(&self) -> Option<Arc<dyn SimpleOperation>> {
+        self.controller.get_pending_operation()
+    }
+
     pub(super) fn apply_operation(
         &mut self,
         operation: DesignOperation,
@@ -130,6 +143,7 @@ impl DesignInteractor {
     pub(super) fn update_simulation(
         &mut self,
         operation: SimulationOperation,
+        sampler: FrameSampler,
     ) -> Result<
         (
             AppStateOperationOutcome,
@@ -139,7 +153,7 @@ impl DesignInteractor {
     > {
         self.controller
             .make_mut()
-            .apply_simulation_operation(self.design.make_mut(), operation)
+            .apply_simulation_operation(self.design.make_mut(), operation, sampler)
     }
 
     pub(super) fn get_current_operation_state(&self) -> Option<CurrentOpState> {
@@ -193,6 +207,20 @@ impl DesignInteractor {
         self
     }
 
+    pub(super) fn nucleotide_positions_with(
+        &self,
+        update: Box<dyn SimulationUpdate>,
+        suggestion_parameters: &SuggestionParameters,
+    ) -> HashMap<Nucl, Vec3> {
+        let (presenter, _) = apply_simulation_update(
+            &self.presenter,
+            self.design.clone(),
+            update,
+            suggestion_parameters,
+        );
+        presenter.nucleotide_positions()
+    }
+
     fn after_applying_simulation_update(
         mut self,
         update: Arc<dyn SimulationUpdate>,
@@ -210,6 +238,21 @@ impl DesignInteractor {
         self
     }
 
+    /// Relax the design in the current thread and store the relaxed positions of the nucleotides
+    /// in the design.
+    pub(super) fn relax(
+        &mut self,
+        parameters: RapierParameters,
+        criteria: &RelaxationCriteria,
+        on_progress: impl FnMut(&RelaxationProgress),
+    ) -> Result<RelaxationReport, RelaxationError> {
+        let mut system = build_system(&self.presenter, parameters);
+        let report = system.relax(&parameters, criteria, on_progress)?;
+        self.design.make_mut().relaxed_positions =
+            self.presenter.identified_positions(system.get_positions());
+        Ok(report)
+    }
+
     #[cfg(test)]
     pub(super) fn update_design(&mut self, design: Design) {
         *self.design.make_mut() = design;
@@ -219,6 +262,10 @@ impl DesignInteractor {
         self.controller.is_in_persistent_state().is_persistent()
     }
 
+    pub(super) fn continues_current_operation(&self, operation: &DesignOperation) -> bool {
+        self.controller.continues_current_operation(operation)
+    }
+
     pub(super) fn has_different_design_than(&self, other: &Self) -> bool {
         self.design != other.design
     }
@@ -269,6 +316,14 @@ impl DesignInteractor {
         })
     }
 
+    pub(super) fn set_rigidity_heat_map(
+        &mut self,
+        heat_map: Option<RigidityHeatMap>,
+    ) -> AppStateOperationResult {
+        self.presenter.make_mut().rigidity = heat_map;
+        Ok(AppStateOperationOutcome::Replace)
+    }
+
     pub(super) fn get_new_selection(&self) -> Option<Vec<Selection>> {
         self.controller.get_new_selection()
     }
@@ -344,18 +399,33 @@ impl DesignInteractor {
 mod tests {
     use super::*;
     use crate::{
-        app_state::{AppState, design_interactor::controller::clipboard::PastePosition},
-        design::operation::InsertionPoint,
-        utils::operation::GridHelixCreation,
+        app_state::{
+            AppState, LoadDesignError,
+            channel_reader::{ChannelReaderUpdate, SimulationInterfaceUpdate},
+            design_interactor::controller::clipboard::PastePosition,
+        },
+        design::operation::{HyperboloidOperation, HyperboloidRequest, InsertionPoint},
+        utils::operation::{GridHelixCreation, GridTranslation, ParametricHelixCreation},
     };
     use ensnano_design::{
-        grid::{GridDescriptor, GridId, GridTypeDescr, HelixGridPosition},
+        curves::{
+            import_from_points::{AxisImportParameters, read_axis},
+            parametric::ParametricCurveDescriptor,
+        },
+        design_element::{DesignElementKey, DnaAttribute},
+        external_3d_objects::External3DObjectId,
+        grid::{
+            GridDescriptor, GridId, GridPosition, GridTypeDescr, HelixGridPosition,
+            lattice::Lattice,
+        },
         id_generator::IdGenerator,
-        nucl::Nucl,
-        strands::{DomainJunction, Strand},
+        organizer_tree::OrganizerTree,
+        strands::{DomainJunction, Strand, read_junctions},
+        voxelization::VoxelizationParameters,
+        wireframe::WireframeParameters,
     };
     use regex::Regex;
-    use ultraviolet::{Rotor3, Vec3};
+    use ultraviolet::{Isometry2, Rotor2, Rotor3, Vec2};
 
     fn test_path(design_name: &'static str) -> PathBuf {
         let mut ret = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"));
@@ -1170,6 +1240,382 @@ mod tests {
         assert_eq!(app_state.0.design.presenter.current_design.helices.len(), 1);
     }
 
+    #[test]
+    fn editing_a_parametric_helix_replaces_it() {
+        let mut app_state = AppState::default();
+        let creation = ParametricHelixCreation {
+            descriptor: ParametricCurveDescriptor {
+                x: "t".into(),
+                y: "0".into(),
+                z: "0".into(),
+                t_min: 0.,
+                t_max: 10.,
+            },
+            replace: false,
+        };
+        app_state
+            .update_pending_operation(Arc::new(creation.clone()))
+            .unwrap();
+        app_state
+            .notify(InteractorNotification::FinishOperation)
+            .unwrap();
+        app_state.update();
+        let design = &app_state.0.design.presenter.current_design;
+        assert_eq!(design.helices.len(), 1);
+        let initial_length = design.strands.values().next().unwrap().length();
+        assert!(initial_length > 0);
+
+        assert!(creation.with_new_value(0, "t +".into()).is_none());
+        let longer = creation.with_new_value(4, "20".into()).unwrap();
+        app_state.update_pending_operation(longer).unwrap();
+        app_state.update();
+        let design = &app_state.0.design.presenter.current_design;
+        assert_eq!(design.helices.len(), 1);
+        assert_eq!(design.strands.len(), 2);
+        let length = design.strands.values().next().unwrap().length();
+        assert!(length > initial_length);
+        assert!(
+            app_state
+                .get_pending_operation()
+                .is_some_and(|op| op.is::<ParametricHelixCreation>())
+        );
+
+        // Once finished, the helix is no longer edited and a new helix is created instead.
+        app_state
+            .notify(InteractorNotification::FinishPendingOperation)
+            .unwrap();
+        assert!(app_state.get_pending_operation().is_none());
+        assert!(app_state.is_in_stable_state());
+        app_state
+            .update_pending_operation(creation.with_new_value(4, "5".into()).unwrap())
+            .unwrap();
+        app_state.update();
+        assert_eq!(app_state.0.design.presenter.current_design.helices.len(), 2);
+    }
+
+    #[test]
+    fn helix_axes_are_imported_from_files_of_points() {
+        let mut app_state = AppState::default();
+        let parameters = AxisImportParameters::default();
+        let missing_file = DesignOperation::ImportHelixAxes {
+            paths: vec![test_path("helix_axis.csv"), test_path("missing_axis.csv")],
+            parameters,
+        };
+        assert!(app_state.apply_design_op(missing_file).is_err());
+
+        app_state
+            .apply_design_op(DesignOperation::ImportHelixAxes {
+                paths: vec![test_path("helix_axis.csv"), test_path("helix_axis.csv")],
+                parameters,
+            })
+            .unwrap();
+        app_state.update();
+        let design = &app_state.0.design.presenter.current_design;
+        assert_eq!(design.helices.len(), 2);
+        assert_eq!(design.strands.len(), 4);
+        let rise = design.helix_parameters.unwrap_or_default().rise;
+        let axis = read_axis(test_path("helix_axis.csv"), &parameters, rise).unwrap();
+        assert!(design.strands.values().all(|s| s.length() == axis.nb_nucl));
+    }
+
+    /// Add the mesh in the test file `mesh_name` as a 3D object of the design.
+    fn add_mesh_object(app_state: &mut AppState, mesh_name: &'static str) -> External3DObjectId {
+        app_state
+            .apply_design_op(DesignOperation::Add3DObject {
+                file_path: test_path(mesh_name),
+                design_path: test_path(""),
+            })
+            .unwrap();
+        app_state.update();
+        let design = &app_state.0.design.presenter.current_design;
+        design
+            .external_3d_objects
+            .iter()
+            .map(|(id, _)| *id)
+            .max()
+            .unwrap()
+    }
+
+    /// The positions on the axis of their helix of the nucleotides of all the strands.
+    fn nucl_axis_positions(design: &Design) -> Vec<Vec3> {
+        let helix_parameters = design.helix_parameters.unwrap_or_default();
+        design
+            .strands
+            .values()
+            .flat_map(|s| &s.domains)
+            .filter_map(|d| match d {
+                Domain::HelixDomain(interval) => Some(interval),
+                Domain::Insertion { .. } => None,
+            })
+            .flat_map(|interval| {
+                let helix = design.helices.get(&interval.helix).unwrap();
+                interval
+                    .iter()
+                    .map(|n| helix.axis_position(&helix_parameters, n, interval.forward))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wireframe_is_made_from_a_mesh() {
+        let mut app_state = AppState::default();
+        assert!(matches!(
+            app_state.apply_design_op(DesignOperation::MakeWireframe {
+                object_id: External3DObjectId(0),
+                mesh_path: test_path("tetrahedron.stl"),
+                parameters: WireframeParameters::default(),
+            }),
+            Err(OperationError::External3DObjectDoesNotExist(_))
+        ));
+        // The tetrahedron is centered on (50, 0, 0) nm.
+        let object_id = add_mesh_object(&mut app_state, "shifted_tetrahedron.stl");
+        app_state
+            .apply_design_op(DesignOperation::MakeWireframe {
+                object_id,
+                mesh_path: test_path("shifted_tetrahedron.stl"),
+                parameters: WireframeParameters::default(),
+            })
+            .unwrap();
+        app_state.update();
+        let design = &app_state.0.design.presenter.current_design;
+        // Two helices on each of the six edges.
+        assert_eq!(design.helices.len(), 12);
+        // The nucleotides are centered on the mesh.
+        let positions = nucl_axis_positions(design);
+        let center = positions.iter().copied().sum::<Vec3>() / positions.len() as f32;
+        assert!((center - Vec3::new(50., 0., 0.)).mag() < 1e-2, "{center:?}");
+        let scaffold = design
+            .scaffold_id
+            .and_then(|s| design.strands.get(&s))
+            .unwrap();
+        assert!(scaffold.is_cyclic);
+        let nb_nucl: usize = design.strands.values().map(Strand::length).sum();
+        assert_eq!(nb_nucl, 2 * scaffold.length());
+    }
+
+    #[test]
+    fn mesh_is_voxelized_on_a_new_grid() {
+        let mut app_state = AppState::default();
+        // The tetrahedron is centered on (50, 0, 0) nm.
+        let object_id = add_mesh_object(&mut app_state, "shifted_tetrahedron.stl");
+        app_state
+            .apply_design_op(DesignOperation::VoxelizeMesh {
+                object_id,
+                mesh_path: test_path("shifted_tetrahedron.stl"),
+                parameters: VoxelizationParameters {
+                    scale: 1.5,
+                    ..Default::default()
+                },
+            })
+            .unwrap();
+        app_state.update();
+        let design = &app_state.0.design.presenter.current_design;
+        assert_eq!(design.free_grids.len(), 1);
+        assert!(design.helices.len() > 0);
+        assert!(design.helices.values().all(|h| h.grid_position.is_some()));
+        // The mesh is scaled about its center, where the helices stay.
+        let (min, max) = nucl_axis_positions(design).into_iter().fold(
+            (
+                Vec3::broadcast(f32::INFINITY),
+                Vec3::broadcast(f32::NEG_INFINITY),
+            ),
+            |(min, max), p| (min.min_by_component(p), max.max_by_component(p)),
+        );
+        let center = (min + max) / 2.;
+        assert!((center - Vec3::new(50., 0., 0.)).mag() < 2., "{center:?}");
+        assert!((max - min).component_max() > 20.);
+        let scaffold = design
+            .scaffold_id
+            .and_then(|s| design.strands.get(&s))
+            .unwrap();
+        // The scaffold is split where a crossover would be too long, and keeps its longest part.
+        assert!(
+            design
+                .strands
+                .values()
+                .all(|s| s.length() <= scaffold.length())
+        );
+        let nb_nucl: usize = design.strands.values().map(Strand::length).sum();
+        assert!(2 * scaffold.length() <= nb_nucl);
+    }
+
+    fn hyperboloid_request(radius: usize, length: f32) -> HyperboloidRequest {
+        HyperboloidRequest {
+            radius,
+            length,
+            shift: 0.,
+            radius_shift: 0.2,
+            nb_turn: 0.,
+        }
+    }
+
+    /// The identifiers of the helices of a grid, sorted by their position on the grid.
+    fn helices_on_grid(design: &Design, grid_id: GridId) -> Vec<usize> {
+        let mut helices: Vec<(isize, usize)> = design
+            .helices
+            .iter()
+            .filter_map(|(h_id, h)| {
+                h.grid_position
+                    .filter(|p| p.grid == grid_id)
+                    .map(|p| (p.x, *h_id))
+            })
+            .collect();
+        helices.sort_unstable();
+        helices.into_iter().map(|(_, h_id)| h_id).collect()
+    }
+
+    #[test]
+    fn finalized_hyperboloid_can_be_edited() {
+        let mut app_state = AppState::default();
+        let grid_id = GridId::FreeGrid(0);
+        for operation in [
+            HyperboloidOperation::New {
+                request: hyperboloid_request(10, 30.),
+                position: Vec3::zero(),
+                orientation: Rotor3::identity(),
+            },
+            HyperboloidOperation::Finalize,
+        ] {
+            app_state
+                .apply_design_op(DesignOperation::HyperboloidOperation(operation))
+                .unwrap();
+            app_state.update();
+        }
+        let initial_helices =
+            helices_on_grid(&app_state.0.design.presenter.current_design, grid_id);
+        assert_eq!(initial_helices.len(), 10);
+
+        // Join the forward strands of the first and last helices with a crossover.
+        let mut design = app_state.0.design.design.clone_inner();
+        let forward_strand = |design: &Design, h_id: usize| {
+            *design
+                .strands
+                .iter()
+                .find(|(_, s)| s.get_5prime().is_some_and(|n| n.helix == h_id && n.forward))
+                .unwrap()
+                .0
+        };
+        let first = forward_strand(&design, initial_helices[0]);
+        let last = forward_strand(&design, initial_helices[9]);
+        let mut last_strand = design.strands.remove(&last).unwrap();
+        let first_strand = design.strands.get_mut(&first).unwrap();
+        first_strand.domains.append(&mut last_strand.domains);
+        first_strand.junctions = read_junctions(&first_strand.domains, false);
+        _ = app_state.update_design(design);
+        app_state.update();
+
+        app_state
+            .apply_design_op(DesignOperation::HyperboloidOperation(
+                HyperboloidOperation::Edit {
+                    grid_id,
+                    request: hyperboloid_request(6, 30.),
+                },
+            ))
+            .unwrap();
+        assert!(app_state.is_building_hyperboloid());
+        app_state
+            .apply_design_op(DesignOperation::HyperboloidOperation(
+                HyperboloidOperation::Update(hyperboloid_request(6, 60.)),
+            ))
+            .unwrap();
+        app_state
+            .apply_design_op(DesignOperation::HyperboloidOperation(
+                HyperboloidOperation::Finalize,
+            ))
+            .unwrap();
+        app_state.update();
+
+        let design = &app_state.0.design.presenter.current_design;
+        assert!(matches!(
+            design.free_grids.get_from_g_id(&grid_id).unwrap().grid_type,
+            GridTypeDescr::Hyperboloid { radius: 6, .. }
+        ));
+        let helices = helices_on_grid(design, grid_id);
+        assert_eq!(helices, initial_helices[..6]);
+        assert_eq!(design.helices.len(), 6);
+        assert_eq!(design.strands.len(), 12);
+        for strand in design.strands.values() {
+            assert_eq!(strand.length(), 60);
+            test_sane_strand(strand);
+        }
+        let first_strand = design.strands.get(&first).unwrap();
+        assert_eq!(first_strand.get_5prime().unwrap().helix, initial_helices[0]);
+
+        app_state
+            .apply_design_op(DesignOperation::HyperboloidOperation(
+                HyperboloidOperation::Edit {
+                    grid_id,
+                    request: hyperboloid_request(8, 60.),
+                },
+            ))
+            .unwrap();
+        app_state
+            .apply_design_op(DesignOperation::HyperboloidOperation(
+                HyperboloidOperation::Finalize,
+            ))
+            .unwrap();
+        app_state.update();
+        let design = &app_state.0.design.presenter.current_design;
+        assert_eq!(helices_on_grid(design, grid_id)[..6], helices);
+        assert_eq!(design.helices.len(), 8);
+        assert_eq!(design.strands.len(), 16);
+        assert!(design.strands.values().all(|s| s.length() == 60));
+    }
+
+    #[test]
+    fn design_is_inserted_as_a_group() {
+        let mut app_state = pastable_design();
+        let (nb_helices, nb_strands) = {
+            let design = &app_state.0.design.presenter.current_design;
+            (design.helices.len(), design.strands.len())
+        };
+        let inserted = one_xover();
+        let inserted = &inserted.0.design.presenter.current_design;
+        let missing_file = DesignOperation::InsertDesign {
+            path: test_path("missing_design.json"),
+        };
+        assert!(matches!(
+            app_state.apply_design_op(missing_file),
+            Err(OperationError::CouldNotLoadDesign(
+                LoadDesignError::IoError(_)
+            ))
+        ));
+        for _ in 0..2 {
+            app_state
+                .apply_design_op(DesignOperation::InsertDesign {
+                    path: test_path("one_xover.json"),
+                })
+                .unwrap();
+            app_state.update();
+        }
+        let design = &app_state.0.design.presenter.current_design;
+        assert_eq!(
+            design.helices.len(),
+            nb_helices + 2 * inserted.helices.len()
+        );
+        assert_eq!(
+            design.strands.len(),
+            nb_strands + 2 * inserted.strands.len()
+        );
+        let Some(OrganizerTree::Node { children, .. }) = design.organizer_tree.as_deref() else {
+            panic!("no organizer tree");
+        };
+        let group_ids: Vec<_> = children
+            .iter()
+            .filter_map(|c| match c {
+                OrganizerTree::Node { name, id, .. } if name == "one_xover" => *id,
+                _ => None,
+            })
+            .collect();
+        assert_eq!(group_ids.len(), 2);
+        assert_ne!(group_ids[0], group_ids[1]);
+        assert!(
+            group_ids
+                .iter()
+                .all(|id| design.group_attributes[id].pivot.is_some())
+        );
+    }
+
     #[ignore = "need fix"]
     #[test]
     fn copy_creates_clipboard() {
@@ -1271,6 +1717,86 @@ mod tests {
         }
     }
 
+    /// A design with two helices on the two sites of a cell of a lattice.
+    fn lattice_design(lattice: Lattice) -> AppState {
+        let mut app_state = AppState::default();
+        app_state
+            .apply_design_op(DesignOperation::AddGrid(GridDescriptor {
+                position: Vec3::zero(),
+                orientation: Rotor3::identity(),
+                helix_parameters: None,
+                grid_type: GridTypeDescr::Lattice {
+                    lattice,
+                    twist: None,
+                },
+                invisible: false,
+                bezier_vertex: None,
+            }))
+            .unwrap();
+        app_state.update();
+        for x in 0..2 {
+            app_state
+                .apply_design_op(DesignOperation::AddGridHelix {
+                    position: HelixGridPosition::from_grid_id_x_y(GridId::FreeGrid(0), x, 0),
+                    start: 0,
+                    length: 10,
+                })
+                .unwrap();
+            app_state.update();
+        }
+        app_state
+    }
+
+    fn position_helices_copy(
+        app_state: &mut AppState,
+        x: isize,
+        y: isize,
+    ) -> AppStateOperationResult {
+        let helices = app_state.0.design.design.helices.keys().copied().collect();
+        app_state
+            .apply_copy_operation(CopyOperation::CopyHelices(helices))
+            .unwrap();
+        app_state.apply_copy_operation(CopyOperation::PositionPastingPoint(Some(
+            PastePosition::GridPosition(GridPosition {
+                grid: GridId::FreeGrid(0),
+                x,
+                y,
+            }),
+        )))
+    }
+
+    #[test]
+    fn helices_on_several_sites_of_a_lattice_are_pasted() {
+        let mut app_state = lattice_design(Lattice::gridiron());
+        position_helices_copy(&mut app_state, 0, 1).unwrap();
+        app_state
+            .apply_copy_operation(CopyOperation::Paste)
+            .unwrap();
+        app_state.update();
+        let mut positions: Vec<(isize, isize)> = app_state
+            .0
+            .design
+            .design
+            .helices
+            .values()
+            .filter_map(|h| h.grid_position.map(|p| (p.x, p.y)))
+            .collect();
+        positions.sort_unstable();
+        assert_eq!(positions, [(0, 0), (0, 1), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn helices_cannot_be_pasted_where_the_lattice_differs() {
+        let mut app_state = lattice_design(Lattice::hybrid());
+        // The second site of the motif is not below the second site of the cell.
+        assert!(matches!(
+            position_helices_copy(&mut app_state, 1, 1),
+            Err(OperationError::CannotPasteHere)
+        ));
+        app_state.update();
+        assert_eq!(app_state.0.design.design.helices.len(), 2);
+    }
+
     #[test]
     fn not_pasting_after_copy() {
         let mut app_state = pastable_design();
@@ -1759,4 +2285,287 @@ mod tests {
 
         assert_good_strand(strand, "[H1: 0 -> 10] [@20] [H2: 0 <- 10]");
     }
+
+    #[test]
+    fn edited_pending_operation_is_applied_to_the_initial_design() {
+        let mut app_state = two_neighbor_one_helix();
+        let position = |app_state: &AppState| {
+            app_state
+                .0
+                .design
+                .design
+                .free_grids
+                .get_from_g_id(&GridId::FreeGrid(0))
+                .unwrap()
+                .position
+        };
+        let initial_position = position(&app_state);
+        let translation = GridTranslation {
+            design_id: 0,
+            grid_ids: vec![GridId::FreeGrid(0)],
+            right: Vec3::unit_x(),
+            top: Vec3::unit_y(),
+            dir: Vec3::unit_z(),
+            x: 1.,
+            y: 0.,
+            z: 0.,
+            group_id: None,
+            replace: false,
+        };
+        app_state
+            .update_pending_operation(Arc::new(translation.clone()))
+            .unwrap();
+        app_state
+            .notify(InteractorNotification::FinishOperation)
+            .unwrap();
+        app_state.update();
+        assert!((position(&app_state) - initial_position - Vec3::unit_x()).mag() < 1e-5);
+
+        // The new value replaces the previous one instead of being added to it.
+        let edited = translation.with_new_value(0, "3".to_owned()).unwrap();
+        app_state.update_pending_operation(edited).unwrap();
+        app_state.update();
+        assert!((position(&app_state) - initial_position - 3. * Vec3::unit_x()).mag() < 1e-5);
+    }
+
+    #[test]
+    fn locked_helices_cannot_be_moved_in_2d() {
+        let mut app_state = two_neighbor_one_helix();
+        app_state
+            .apply_design_op(DesignOperation::UpdateAttribute {
+                attribute: DnaAttribute::LockedForEdition(true),
+                elements: vec![DesignElementKey::Helix(1)],
+            })
+            .unwrap();
+        app_state.update();
+        let isometry = app_state
+            .0
+            .design
+            .design
+            .helices
+            .get(&1)
+            .unwrap()
+            .isometry2d;
+
+        let result = app_state.apply_design_op(DesignOperation::SetIsometry {
+            helix: 1,
+            segment_idx: 0,
+            isometry: Isometry2::new(Vec2::new(10., 10.), Rotor2::identity()),
+        });
+        assert!(matches!(
+            result,
+            Err(OperationError::ElementIsLocked(DesignElementKey::Helix(1)))
+        ));
+        app_state.update();
+        let helix = app_state.0.design.design.helices.get(&1).unwrap();
+        assert_eq!(helix.isometry2d, isometry);
+    }
+
+    #[test]
+    fn locked_strands_cannot_be_edited() {
+        let mut app_state = two_neighbor_one_helix();
+        let nucl = Nucl {
+            helix: 1,
+            position: 2,
+            forward: true,
+        };
+        let s_id = app_state
+            .get_design_interactor()
+            .get_id_of_strand_containing_nucl(&nucl)
+            .unwrap();
+        let lock = |locked| DesignOperation::UpdateAttribute {
+            attribute: DnaAttribute::LockedForEdition(locked),
+            elements: vec![DesignElementKey::Strand(s_id)],
+        };
+        app_state.apply_design_op(lock(true)).unwrap();
+        app_state.update();
+        let strand = app_state.0.design.design.strands.get(&s_id).cloned();
+
+        for operation in [
+            DesignOperation::Cut { nucl },
+            DesignOperation::ChangeColor {
+                color: 0xFF_00_00_FF,
+                strands: vec![s_id],
+            },
+            DesignOperation::RmStrands {
+                strand_ids: vec![s_id],
+            },
+        ] {
+            let result = app_state.apply_design_op(operation);
+            assert!(
+                matches!(result, Err(OperationError::ElementIsLocked(DesignElementKey::Strand(s))) if s == s_id)
+            );
+            app_state.update();
+            assert_eq!(
+                app_state.0.design.design.strands.get(&s_id),
+                strand.as_ref()
+            );
+            assert!(app_state.is_in_stable_state());
+        }
+
+        app_state.apply_design_op(lock(false)).unwrap();
+        app_state.update();
+        app_state
+            .apply_design_op(DesignOperation::Cut { nucl })
+            .unwrap();
+    }
+
+    #[test]
+    fn recoloring_staples_skips_locked_strands() {
+        let mut app_state = two_neighbor_one_helix();
+        let strand_ids: Vec<usize> = app_state.0.design.design.strands.keys().copied().collect();
+        let (locked_id, other_ids) = strand_ids.split_first().unwrap();
+        app_state
+            .apply_design_op(DesignOperation::UpdateAttribute {
+                attribute: DnaAttribute::LockedForEdition(true),
+                elements: vec![DesignElementKey::Strand(*locked_id)],
+            })
+            .unwrap();
+        app_state.update();
+        let colors = |app_state: &AppState| -> Vec<u32> {
+            strand_ids
+                .iter()
+                .map(|s_id| app_state.0.design.design.strands.get(s_id).unwrap().color)
+                .collect()
+        };
+        let initial_colors = colors(&app_state);
+
+        app_state
+            .apply_design_op(DesignOperation::RecolorStaples)
+            .unwrap();
+        app_state.update();
+        let new_colors = colors(&app_state);
+        assert_eq!(new_colors[0], initial_colors[0]);
+        assert!(!other_ids.is_empty());
+        assert_ne!(new_colors[1..], initial_colors[1..]);
+    }
+
+    #[test]
+    fn relaxation_stores_positions_in_design() {
+        let mut app_state = pastable_design();
+        let criteria = RelaxationCriteria {
+            tolerance: 1e-3,
+            max_steps: 200,
+        };
+        let mut steps = 0;
+        let report = app_state
+            .relax(RapierParameters::default(), &criteria, |_| steps += 1)
+            .unwrap();
+        assert_eq!(report.progress.step, steps);
+        let design = &app_state.0.design.design;
+        assert!(!design.relaxed_positions.is_empty());
+        assert!(
+            design
+                .relaxed_positions
+                .values()
+                .all(|position| position.x.is_finite())
+        );
+    }
+
+    #[test]
+    fn relaxed_positions_are_forgotten_when_strands_change() {
+        let mut app_state = pastable_design();
+        app_state
+            .relax(
+                RapierParameters::default(),
+                &RelaxationCriteria::default(),
+                |_| (),
+            )
+            .unwrap();
+        app_state.update();
+        let nucl = Nucl {
+            helix: 1,
+            position: 2,
+            forward: true,
+        };
+        let s_id = app_state
+            .get_design_interactor()
+            .get_id_of_strand_containing_nucl(&nucl)
+            .unwrap();
+
+        app_state
+            .apply_design_op(DesignOperation::ChangeColor {
+                color: 0xFF_00_00_FF,
+                strands: vec![s_id],
+            })
+            .unwrap();
+        app_state
+            .notify(InteractorNotification::FinishOperation)
+            .unwrap();
+        app_state.update();
+        assert!(!app_state.0.design.design.relaxed_positions.is_empty());
+
+        app_state
+            .apply_design_op(DesignOperation::Cut { nucl })
+            .unwrap();
+        app_state.update();
+        assert!(app_state.0.design.design.relaxed_positions.is_empty());
+    }
+
+    #[test]
+    fn simulations_sample_frames_every_interval() {
+        let mut app_state = pastable_design();
+        app_state.update();
+        let presenter = app_state.0.design.presenter.clone();
+        app_state
+            .update_simulation(
+                SimulationOperation::UpdateRapierParameters {
+                    presenter: presenter.as_ref(),
+                    parameters: RapierParameters {
+                        is_simulation_running: true,
+                        ..Default::default()
+                    },
+                },
+                FrameSampler::every(std::num::NonZeroUsize::new(5).unwrap()),
+            )
+            .unwrap();
+
+        // However often the state of the simulation is read, no frame is missed.
+        let mut frames = Vec::new();
+        let start = std::time::Instant::now();
+        while frames.len() < 4 && start.elapsed() < std::time::Duration::from_secs(30) {
+            for update in app_state
+                .0
+                .make_mut()
+                .simulation_interface_handle
+                .get_updates()
+            {
+                if let SimulationInterfaceUpdate::Frames(new_frames) = update {
+                    frames.extend(new_frames);
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let steps = frames.iter().take(4).map(|f| f.step).collect::<Vec<_>>();
+        assert_eq!(steps, [0, 5, 10, 15]);
+        assert!(frames.is_sorted_by(|a, b| a.time < b.time));
+    }
+
+    #[test]
+    fn rigidity_is_analysed_in_a_separate_thread() {
+        let mut app_state = AppState::import_design(one_helix_path()).ok().unwrap();
+        app_state.start_rigidity_analysis(Default::default());
+        let result = loop {
+            let mut updates = app_state.0.make_mut().channel_reader.get_updates();
+            if let Some(ChannelReaderUpdate::RigidityAnalysisResult(result)) = updates.pop() {
+                break result;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert!(app_state.is_current_design(&result.design));
+        assert!(!result.heat_map.unwrap().analysis().fluctuations.is_empty());
+    }
+
+    #[test]
+    fn diverging_relaxation_is_an_error() {
+        let mut app_state = pastable_design();
+        let parameters = RapierParameters {
+            // nucleotides are thrown hundreds of nanometers away at each step
+            brownian_motion_strength: 1e3,
+            ..Default::default()
+        };
+        let result = app_state.relax(parameters, &RelaxationCriteria::default(), |_| ());
+        assert!(matches!(result, Err(RelaxationError::Diverged { .. })));
+        assert!(app_state.0.design.design.relaxed_positions.is_empty());
+    }
 }
@@ -4,6 +4,7 @@ pub mod xover_suggestions;
 use self::{click_counter::ClickCounter, xover_suggestions::XoverSuggestions};
 use crate::{
     app_state::design_interactor::presenter::{JunctionsIds, Presenter, SimulationUpdate},
+    design::operation::HyperboloidRequest,
     scene::design_reader::Scalebar,
 };
 use ahash::{HashMap, HashSet};
@@ -12,7 +13,7 @@ use ensnano_design::{
     design_element::{DesignElement, DesignElementKey},
     domains::Domain,
     drawing_style::{ColorType, DrawingAttribute, DrawingStyle},
-    grid::{GridData, GridId, GridInstance, GridObject, GridPosition, HelixGridPosition},
+    grid::{GridData, GridId, GridInstance, GridObject, GridPosition, GridType, HelixGridPosition},
     helices::NuclCollection,
     isometry3_descriptor::Isometry3MissingMethods as _,
     nucl::Nucl,
@@ -185,12 +186,22 @@ impl DesignContent {
             .and_then(|g| g.grid_type.get_nb_turn().map(|x| x as f32))
     }
 
+    pub fn get_hyperboloid_request(&self, g_id: GridId) -> Option<HyperboloidRequest> {
+        self.grid_manager
+            .grids
+            .get(&g_id)
+            .and_then(|g| match &g.grid_type {
+                GridType::Hyperboloid(h) => Some(HyperboloidRequest::from_grid(h)),
+                _ => None,
+            })
+    }
+
     pub fn get_staple_mismatch(&self, design: &Design) -> Option<Nucl> {
         let basis_map = self.letter_map.as_ref();
         for strand in design.strands.values() {
             for domain in &strand.domains {
                 if let Domain::HelixDomain(dom) = domain {
-                    for position in dom.iter() {
+                    for position in dom.iter_nucls(&design.helices) {
                         let nucl = Nucl {
                             position,
                             forward: dom.forward,
@@ -236,7 +247,7 @@ impl DesignContent {
                 let helices = &design.helices;
                 first = false;
                 if let Domain::HelixDomain(dom) = domain {
-                    for position in dom.iter() {
+                    for position in dom.iter_nucls(helices) {
                         let nucl = Nucl {
                             position,
                             forward: dom.forward,
@@ -314,7 +325,7 @@ impl DesignContent {
                     sequence,
                     strand_name: strand.name.clone(),
                     domain_decomposition: presenter.decompose_length(*s_id),
-                    length: strand.length(),
+                    length: strand.nb_nucls(&design.helices),
                     color: strand.color & 0xFFFFFF,
                     group_names: presenter.get_name_of_group_having_strand(*s_id),
                     intervals,
@@ -412,6 +423,7 @@ impl DesignContent {
         xover_ids: &JunctionsIds,
         suggestion_parameters: &SuggestionParameters,
     ) -> (Self, Design, JunctionsIds) {
+        design.update_smart_groups();
         let groups = design.groups.clone();
         let mut object_type = HashMap::default();
         let mut space_position = HashMap::default();
@@ -444,31 +456,14 @@ impl DesignContent {
         let mut scalebar: Option<Scalebar> = None;
 
         // Maps identifiers to drawing styles
-        let mut drawing_styles = HashMap::<DesignElementKey, DrawingStyle>::default();
+        let drawing_styles = design.drawing_styles();
         let mut clone_transformations = Vec::<Isometry3>::new();
 
         xover_ids.copy_next_id_to(&mut new_junctions);
         let rainbow_strand = design.scaffold_id.filter(|_| design.rainbow_scaffold);
         let grid_manager = design.get_updated_grid_data().clone();
 
-        // Build drawing style map from organizer tree
         if let Some(t) = &design.organizer_tree {
-            // Read drawing style
-            let prefix = "style:"; // PREFIX SHOULD BELONG TO CONST.RS
-            let h = t.get_hashmap_to_all_group_names_with_prefix(prefix);
-            for (e, names) in h {
-                let drawing_attributes = names
-                    .iter()
-                    .flat_map(|x| {
-                        x.split(&[' ', ':'])
-                            .flat_map(DrawingAttribute::from_str)
-                            .collect::<Vec<DrawingAttribute>>()
-                    })
-                    .collect::<Vec<DrawingAttribute>>();
-                let style = DrawingStyle::from(drawing_attributes);
-                drawing_styles.insert(e, style);
-            }
-
             // collect all the variables defined in the organizer tree - these variables can only be used in the cloning transformations
             let all_group_names = t.get_names_of_all_groups_without_id();
             let clone_variables_declaration = &all_group_names
@@ -502,6 +497,9 @@ impl DesignContent {
                 id: *s_id, // the key in design.strands btreemap
                 length: strand.length(),
                 domain_lengths: strand.domain_lengths(),
+                locked: design
+                    .locked_elements
+                    .contains(&DesignElementKey::Strand(*s_id)),
             });
             let parameters = design.helix_parameters.unwrap_or_default();
             strand.update_insertions(&design.helices, &parameters);
@@ -604,8 +602,10 @@ impl DesignContent {
                     // Get the sequence if any
                     let dom_seq = domain.sequence.as_ref().filter(|s| s.is_ascii());
 
-                    // Iterate along the domain
-                    for (dom_position, nucl_position) in domain.iter().enumerate() {
+                    // Iterate along the domain, skipping the deleted positions
+                    for (dom_position, nucl_position) in
+                        domain.iter_nucls(&design.helices).enumerate()
+                    {
                         let axis_position = {
                             let p = design.helices.get(&domain.helix).unwrap().axis_position(
                                 design.helix_parameters.as_ref().unwrap(),
@@ -614,16 +614,19 @@ impl DesignContent {
                             );
                             [p.x, p.y, p.z]
                         };
-                        let position = design.helices.get(&domain.helix).unwrap().space_pos(
-                            design.helix_parameters.as_ref().unwrap(),
-                            nucl_position,
-                            domain.forward,
-                        );
                         let nucl: Nucl = Nucl {
                             position: nucl_position,
                             forward: domain.forward,
                             helix: domain.helix,
                         };
+                        let position = match design.relaxed_positions.get(&nucl) {
+                            Some(position) => *position,
+                            None => design.helices.get(&domain.helix).unwrap().space_pos(
+                                design.helix_parameters.as_ref().unwrap(),
+                                nucl_position,
+                                domain.forward,
+                            ),
+                        };
                         let virtual_nucl = Nucl::map_to_virtual_nucl(nucl, &design.helices);
                         if let Some(v_nucl) = virtual_nucl {
                             let previous = nucl_collection.insert_virtual(v_nucl, nucl);
@@ -682,7 +685,14 @@ impl DesignContent {
                             bond_ids_sequence.push(bond_id);
                             identifier_bond.insert(bond, bond_id);
                             nucleotides_involved.insert(bond_id, bond);
-                            let bond_color = if prev_nucl.helix != nucl.helix || prev_nucl.forward != nucl.forward || (prev_nucl.position - nucl.position).abs() > 1 {
+                            // Nucleotides on both sides of a deletion are still neighbours.
+                            let far_apart = design.helices.get(&nucl.helix).is_none_or(|h| {
+                                (h.index_without_deletions(prev_nucl.position)
+                                    - h.index_without_deletions(nucl.position))
+                                .abs()
+                                    > 1
+                            });
+                            let bond_color = if prev_nucl.helix != nucl.helix || prev_nucl.forward != nucl.forward || far_apart {
                                 // xover
                                 xover_bond_color
                             } else {
@@ -909,6 +919,9 @@ impl DesignContent {
                 elements.push(DesignElement::Grid {
                     id: *id,
                     visible: grid_manager.get_visibility(*g_id),
+                    locked: design
+                        .locked_elements
+                        .contains(&DesignElementKey::Grid(*id)),
                 });
             }
         }
@@ -918,6 +931,9 @@ impl DesignContent {
                 id: *h_id,
                 group: groups.get(h_id).copied(),
                 locked_for_simulations: h.locked_for_simulations,
+                locked: design
+                    .locked_elements
+                    .contains(&DesignElementKey::Helix(*h_id)),
             });
         }
 
@@ -1282,7 +1298,7 @@ impl DesignContent {
         {
             for d in &s.domains {
                 if let Domain::HelixDomain(interval) = d {
-                    for n in interval.iter() {
+                    for n in interval.iter_nucls(&design.helices) {
                         let nucl = Nucl {
                             helix: interval.helix,
                             position: n,
@@ -90,6 +90,16 @@ impl DesignInteractor {
         self.presenter.current_design.groups.clone()
     }
 
+    /// The name of the first group of the organizer that contains each strand.
+    pub fn get_strand_group_names(&self) -> HashMap<usize, String> {
+        self.presenter
+            .current_design
+            .organizer_tree
+            .as_ref()
+            .map(|tree| tree.strand_group_names())
+            .unwrap_or_default()
+    }
+
     pub fn get_insertions(&self, s_id: usize) -> Option<Vec<Nucl>> {
         self.presenter
             .current_design
@@ -110,6 +120,16 @@ impl DesignInteractor {
             .map(|h| h.visible)
     }
 
+    /// The deleted positions of the helix, in increasing order.
+    pub fn get_deletions(&self, h_id: usize) -> Vec<isize> {
+        self.presenter
+            .current_design
+            .helices
+            .get(&h_id)
+            .map(|h| h.deletions.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
     pub fn get_xovers_list_with_id(&self) -> Vec<(usize, (Nucl, Nucl))> {
         self.presenter.junctions_ids.get_all_elements()
     }
@@ -28,6 +28,15 @@ use ultraviolet::{Mat4, Rotor3, Vec2, Vec3};
 
 impl DesignInteractor {
     pub fn get_color(&self, e_id: u32) -> Option<u32> {
+        if let Some(heat_map) = self.presenter.rigidity.as_ref() {
+            let content = &self.presenter.content;
+            if let Some(nucl) = content.nucleotide.get(&e_id) {
+                return Some(heat_map.color(&[*nucl]));
+            }
+            if let Some((prime5, prime3)) = content.nucleotides_involved.get(&e_id) {
+                return Some(heat_map.color(&[*prime5, *prime3]));
+            }
+        }
         self.presenter.content.color_map.get(&e_id).copied()
     }
 
@@ -543,7 +552,7 @@ impl DesignInteractor {
                 if let Domain::HelixDomain(domain) = domain {
                     // Real helix domain
                     // Iterate along the domain
-                    for nucl_position in domain.iter() {
+                    for nucl_position in domain.iter_nucls(&design.helices) {
                         let nucl: Nucl = Nucl {
                             position: nucl_position,
                             forward: domain.forward,
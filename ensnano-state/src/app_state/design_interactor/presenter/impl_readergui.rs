@@ -1,10 +1,13 @@
 use crate::{
     app_state::design_interactor::DesignInteractor,
-    design::{operation::InsertionPoint, selection::Selection},
+    design::{
+        operation::{HyperboloidRequest, InsertionPoint},
+        selection::Selection,
+    },
 };
 use ensnano_design::{
     CameraId,
-    bezier_plane::{BezierPathId, BezierVertexId},
+    bezier_plane::{BezierPathId, BezierPlaneId, BezierVertexId},
     design_element::DesignElement,
     grid::GridId,
     nucl::Nucl,
@@ -31,6 +34,11 @@ impl DesignInteractor {
         self.presenter.content.get_grid_nb_turn(g_id)
     }
 
+    /// The parameters of a hyperboloid grid, or `None` if the grid is not a hyperboloid.
+    pub fn get_hyperboloid_request(&self, g_id: GridId) -> Option<HyperboloidRequest> {
+        self.presenter.content.get_hyperboloid_request(g_id)
+    }
+
     pub fn get_strand_length(&self, s_id: usize) -> Option<usize> {
         self.presenter
             .current_design
@@ -117,6 +125,15 @@ impl DesignInteractor {
         self.presenter.current_design.rainbow_scaffold
     }
 
+    /// True iff the position of `nucl` is deleted on its helix.
+    pub fn has_deletion_at(&self, nucl: &Nucl) -> bool {
+        self.presenter
+            .current_design
+            .helices
+            .get(&nucl.helix)
+            .is_some_and(|h| h.has_deletion_at(nucl.position))
+    }
+
     pub fn get_insertion_length_in_selection(&self, selection: &Selection) -> Option<usize> {
         match selection {
             Selection::Bond(_, n1, n2) => {
@@ -210,6 +227,15 @@ impl DesignInteractor {
             .map(|p| p.is_cyclic)
     }
 
+    pub fn get_bezier_plane_ids(&self) -> Vec<BezierPlaneId> {
+        self.presenter
+            .current_design
+            .bezier_planes
+            .iter()
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
     pub fn get_bezier_vertex_position(&self, vertex_id: BezierVertexId) -> Option<Vec2> {
         let path = self
             .presenter
@@ -8,6 +8,7 @@ use self::design_content::DesignContent;
 use crate::{
     app_state::{address_pointer::AddressPointer, design_interactor::DesignInteractor},
     design::selection::Selection,
+    rigidity::RigidityHeatMap,
     utils::application::Camera3D,
 };
 use ahash::{HashMap, HashSet};
@@ -54,6 +55,8 @@ pub struct Presenter {
     pub visibility_sieve: Option<VisibilitySieve>,
     invisible_nucls: HashSet<Nucl>,
     h_bonds: AddressPointer<Vec<HBond>>,
+    /// The analysis of the rigidity of the current design, shown as a heat map.
+    pub rigidity: Option<RigidityHeatMap>,
 }
 
 impl Default for Presenter {
@@ -67,6 +70,7 @@ impl Default for Presenter {
             visibility_sieve: None,
             invisible_nucls: Default::default(),
             h_bonds: Default::default(),
+            rigidity: None,
         }
     }
 }
@@ -101,6 +105,10 @@ impl Presenter {
         design: AddressPointer<Design>,
         suggestion_parameters: &SuggestionParameters,
     ) -> Self {
+        if self.current_design != design {
+            // The analysis is not valid for the new design.
+            self.rigidity = None;
+        }
         if self.current_design != design
             || &self.current_suggestion_parameters != suggestion_parameters
         {
@@ -133,6 +141,7 @@ impl Presenter {
             visibility_sieve: None,
             invisible_nucls: Default::default(),
             h_bonds: Default::default(),
+            rigidity: None,
         };
         // Strand sequence are not read
         ret.read_scaffold_seq();
@@ -203,7 +212,7 @@ impl Presenter {
             {
                 for domain in &strand.domains {
                     if let Domain::HelixDomain(dom) = domain {
-                        for nucl_position in dom.iter() {
+                        for nucl_position in dom.iter_nucls(&self.current_design.helices) {
                             let nucl = Nucl {
                                 helix: dom.helix,
                                 position: nucl_position,
@@ -536,6 +545,31 @@ impl Presenter {
             .and_then(|id| self.content.space_position.get(&id).map(Into::into))
     }
 
+    /// The current positions of the nucleotides of the design, ignoring the clones.
+    pub(crate) fn nucleotide_positions(&self) -> std::collections::HashMap<Nucl, Vec3> {
+        self.identified_positions(
+            self.content
+                .space_position
+                .iter()
+                .map(|(id, position)| (*id, *position)),
+        )
+    }
+
+    /// Map positions given by nucleotide identifiers to the nucleotides, ignoring the clones.
+    pub(crate) fn identified_positions(
+        &self,
+        positions: impl IntoIterator<Item = (u32, [f32; 3])>,
+    ) -> std::collections::HashMap<Nucl, Vec3> {
+        positions
+            .into_iter()
+            .filter(|(id, _)| self.content.is_clone_map.get(id) == Some(&false))
+            .filter_map(|(id, position)| {
+                let nucl = self.content.nucleotide.get(&id)?;
+                Some((*nucl, Vec3::from(position)))
+            })
+            .collect()
+    }
+
     pub fn has_nucl(&self, nucl: &Nucl) -> bool {
         self.content.nucl_collection.contains_nucl(nucl)
     }
@@ -630,6 +664,12 @@ pub(super) fn apply_simulation_update(
 ) -> (AddressPointer<Presenter>, AddressPointer<Design>) {
     let mut new_design = design.clone_inner();
     update.as_ref().update_design(&mut new_design);
+    if !new_design.helices.ptr_eq(&design.helices)
+        || !new_design.free_grids.ptr_eq(&design.free_grids)
+    {
+        // The relaxed positions were computed for the previous position of the helices.
+        new_design.relaxed_positions.clear();
+    }
     log::trace!("calling from apply_simulation_update");
     let (new_presenter, returned_design) = update_presenter(
         presenter,
@@ -719,12 +759,11 @@ impl DesignInteractor {
         let id = self.presenter.current_design.scaffold_id?;
         let length = self.get_strand_length(id)?;
         let shift = self.presenter.current_design.scaffold_shift;
-        let starting_nucl = self
-            .presenter
-            .current_design
+        let design = &self.presenter.current_design;
+        let starting_nucl = design
             .strands
             .get(&id)
-            .and_then(|s| s.get_nth_nucl(shift.unwrap_or(0)));
+            .and_then(|s| s.get_nth_nucl(shift.unwrap_or(0), &design.helices));
         Some(ScaffoldInfo {
             id,
             length,
@@ -144,6 +144,10 @@ impl AppState {
         self.0.design.presenter.visibility_sieve != other.0.design.presenter.visibility_sieve
     }
 
+    pub fn rigidity_heat_map_was_updated(&self, other: &Self) -> bool {
+        self.0.design.presenter.rigidity != other.0.design.presenter.rigidity
+    }
+
     pub fn get_current_unrooted_surface(&self) -> Option<UnrootedRevolutionSurfaceDescriptor> {
         self.0.unrooted_surface.descriptor.clone()
     }
@@ -7,15 +7,18 @@ use crate::{
     app_state::{AppState, NewHelixStrand, design_interactor::DesignInteractor},
     design::selection::{DesignElementKeySelection as _, Selection, all_helices_no_grid},
     gui::curve::{CurveDescriptorBuilder, RevolutionScaling},
-    utils::operation::CurrentOpState,
+    rigidity::RigidityHeatMap,
+    utils::operation::{CurrentOpState, SimpleOperation},
 };
 use ensnano_design::{
     bezier_plane::BezierPathId,
     design_element::DesignElementKey,
+    external_3d_objects::External3DObjectId,
     interaction_modes::{ActionMode, SelectionMode},
     organizer_tree::GroupId,
     parameters::HelixParameters,
 };
+use ensnano_physics::elastic_network::RigidityAnalysis;
 use ensnano_utils::{
     ScaffoldInfo,
     app_state_parameters::{
@@ -24,7 +27,7 @@ use ensnano_utils::{
     clipboard::ClipboardContent,
     graphics::HBondDisplay,
 };
-use std::f64::consts::TAU;
+use std::{f64::consts::TAU, path::PathBuf, sync::Arc};
 
 impl AppState {
     pub const POSSIBLE_CURVES: &'static [CurveDescriptorBuilder] = &[
@@ -71,6 +74,24 @@ impl AppState {
         self.get_design_interactor()
     }
 
+    /// The external 3D objects of the design with the path to their source file, sorted by
+    /// identifier. The paths are relative to the design file, so there are none if the design
+    /// has not been saved.
+    pub fn get_external_3d_objects(&self) -> Vec<(External3DObjectId, PathBuf)> {
+        let Some(mut path_base) = self.get_design_path() else {
+            return Vec::new();
+        };
+        path_base.pop();
+        let reader = self.get_design_interactor();
+        let mut ret: Vec<_> = reader
+            .get_external_objects()
+            .iter()
+            .map(|(id, object)| (*id, object.get_path_to_source_file(&path_base)))
+            .collect();
+        ret.sort_by_key(|(id, _)| *id);
+        ret
+    }
+
     pub fn get_build_helix_mode(&self) -> ActionMode {
         if let Some(NewHelixStrand { length, start }) = self.0.strand_on_new_helix.as_ref() {
             ActionMode::BuildHelix {
@@ -89,6 +110,10 @@ impl AppState {
         self.0.design.get_current_operation_state()
     }
 
+    pub fn get_pending_operation(&self) -> Option<Arc<dyn SimpleOperation>> {
+        self.0.design.get_pending_operation()
+    }
+
     pub fn get_selected_group(&self) -> Option<GroupId> {
         self.0.selection.selected_group
     }
@@ -137,6 +162,15 @@ impl AppState {
         }
     }
 
+    pub fn get_rigidity_analysis(&self) -> Option<&RigidityAnalysis> {
+        self.0
+            .design
+            .presenter
+            .rigidity
+            .as_ref()
+            .map(RigidityHeatMap::analysis)
+    }
+
     pub fn is_exporting(&self) -> bool {
         self.0.exporting
     }
@@ -15,9 +15,6 @@ pub mod impl_app3d;
 pub mod impl_gui;
 pub mod transitions;
 
-#[cfg(test)]
-use ensnano_design::Design;
-
 use crate::{
     app_state::{
         address_pointer::AddressPointer,
@@ -26,7 +23,7 @@ use crate::{
             DesignInteractor,
             controller::{
                 InteractorNotification, OperationError, clipboard::CopyOperation,
-                simulations::SimulationOperation,
+                simulations::{FrameSampler, SimulationOperation},
             },
             presenter::SimulationUpdate,
         },
@@ -36,16 +33,24 @@ use crate::{
         selection::{CenterOfSelection, Selection},
     },
     operation::{AppStateOperationOutcome, AppStateOperationResult},
+    rigidity::{RigidityAnalysisResult, RigidityHeatMap},
     utils::operation::SimpleOperation,
 };
 use ensnano_design::{
-    SavingInformation,
+    Design, SavingInformation,
     bezier_plane::BezierPathId,
     domains::Domain,
     group_attributes::GroupPivot,
     interaction_modes::{ActionMode, SelectionMode},
+    nucl::Nucl,
     organizer_tree::GroupId,
     scadnano::ScadnanoImportError,
+    strands::Strand,
+};
+use ensnano_physics::{
+    elastic_network::{ElasticNetworkParameters, analyse_rigidity},
+    parameters::RapierParameters,
+    relaxation::{RelaxationCriteria, RelaxationError, RelaxationProgress, RelaxationReport},
 };
 use ensnano_utils::{
     PastingStatus, SimulationState, StrandBuildingStatus, WidgetBasis,
@@ -55,15 +60,25 @@ use ensnano_utils::{
     },
     consts::{APP_NAME, CANNOT_OPEN_DEFAULT_DIR, ENS_BACKUP_EXTENSION, ENS_EXTENSION},
     graphics::{Background3D, HBondDisplay, RenderingMode},
+    keymap::Keymap,
     surfaces::{RevolutionSurfaceRadius, UnrootedRevolutionSurfaceDescriptor},
     ui_size::UiSize,
 };
 use std::{
+    collections::HashMap,
     path::PathBuf,
-    sync::{Arc, RwLock},
+    sync::{Arc, RwLock, mpsc},
 };
 use ultraviolet::{Rotor3, Vec3};
 
+/// The memory cost of a snapshot on the undo stack that does not depend on its design.
+const SNAPSHOT_BASE_SIZE: usize = 1 << 10;
+/// The approximate memory cost of a nucleotide in a snapshot, including the data derived from it by
+/// the presenter.
+const SNAPSHOT_SIZE_PER_NUCLEOTIDE: usize = 256;
+/// The approximate memory cost of a helix in a snapshot.
+const SNAPSHOT_SIZE_PER_HELIX: usize = 1 << 10;
+
 /// A structure containing the global state of the program.
 ///
 /// At each event loop iteration, a new `AppState` may be created. Successive AppState are stored
@@ -278,6 +293,21 @@ impl AppState {
         self.0.path_to_current_design.as_ref()
     }
 
+    pub fn set_path_to_current_design(&mut self, path: Option<PathBuf>) {
+        self.0.make_mut().path_to_current_design = path;
+    }
+
+    /// Return the positions that the nucleotides would have once `update` is applied, without
+    /// applying it.
+    pub fn nucleotide_positions_with(
+        &self,
+        update: Box<dyn SimulationUpdate>,
+    ) -> HashMap<Nucl, Vec3> {
+        self.0
+            .design
+            .nucleotide_positions_with(update, &self.0.parameters.suggestion_parameters)
+    }
+
     pub fn apply_simulation_update(&mut self, update: Box<dyn SimulationUpdate>) {
         let mut design = self.0.design.clone_inner();
         design = design.with_simulation_update_applied(update);
@@ -309,6 +339,33 @@ impl AppState {
         self.0.make_mut().design.make_mut().apply_operation(op)
     }
 
+    /// Apply a design operation, finishing the current operation first if needed, and select the
+    /// elements that the operation may have created.
+    pub fn apply_design_op_and_select(&mut self, op: DesignOperation) -> AppStateOperationResult {
+        let mut result = self.apply_design_op(op.clone());
+
+        // we ask to finish the current operation, and try again.
+        while matches!(result, Err(OperationError::FinishFirst)) {
+            self.notify(InteractorNotification::FinishOperation)?;
+            result = self.apply_design_op(op.clone());
+        }
+
+        // if a new selection is made, we apply it here
+        // note : this could be done better if the operations did
+        // this directly
+        if let Some(selection) = self
+            .get_design_interactor()
+            .controller
+            .make_mut()
+            .next_selection
+            .take()
+        {
+            self.set_selection(&selection, &None)?;
+        }
+
+        result
+    }
+
     pub fn apply_copy_operation(&mut self, op: CopyOperation) -> AppStateOperationResult {
         self.0.make_mut().design.make_mut().apply_copy_operation(op)
     }
@@ -324,13 +381,19 @@ impl AppState {
             .update_pending_operation(op)
     }
 
-    pub fn update_simulation(&mut self, operation: SimulationOperation) -> AppStateOperationResult {
+    /// Apply a simulation operation. The simulations started by the operation take frames of
+    /// their trajectory as decided by `sampler`.
+    pub fn update_simulation(
+        &mut self,
+        operation: SimulationOperation,
+        sampler: FrameSampler,
+    ) -> AppStateOperationResult {
         let (outcome, interface) = self
             .0
             .make_mut()
             .design
             .make_mut()
-            .update_simulation(operation)?;
+            .update_simulation(operation, sampler)?;
 
         if let Some(interface) = interface {
             self.0
@@ -342,6 +405,64 @@ impl AppState {
         Ok(outcome)
     }
 
+    /// Relax the design without spawning a simulation, until `criteria` are met, and store the
+    /// relaxed positions of the nucleotides in the design.
+    ///
+    /// This blocks the current thread and is meant for batch processing.
+    pub fn relax(
+        &mut self,
+        parameters: RapierParameters,
+        criteria: &RelaxationCriteria,
+        on_progress: impl FnMut(&RelaxationProgress),
+    ) -> Result<RelaxationReport, RelaxationError> {
+        self.update();
+        let report =
+            self.0
+                .make_mut()
+                .design
+                .make_mut()
+                .relax(parameters, criteria, on_progress)?;
+        self.update();
+        Ok(report)
+    }
+
+    /// Analyse the rigidity of the design with an elastic network, in a separate thread.
+    ///
+    /// The result is delivered by the channel reader once the normal modes of the network are
+    /// computed.
+    pub fn start_rigidity_analysis(&mut self, parameters: ElasticNetworkParameters) {
+        let design = self.0.design.design.clone();
+        let (result_snd, result_rcv) = mpsc::channel();
+        self.0
+            .make_mut()
+            .channel_reader
+            .attach_rigidity_analysis_chanel(result_rcv);
+        std::thread::spawn(move || {
+            let heat_map = analyse_rigidity(&design, &parameters).map(RigidityHeatMap::new);
+            if let Err(error) = result_snd.send(RigidityAnalysisResult { design, heat_map }) {
+                log::error!("error in rigidity analysis thread");
+                log::error!("{error:?}");
+            }
+        });
+    }
+
+    /// Whether `design` is the design currently held by the app state.
+    pub fn is_current_design(&self, design: &AddressPointer<Design>) -> bool {
+        self.0.design.design == *design
+    }
+
+    /// Show an analysis of the rigidity of the design in the 3D scene, or hide it.
+    pub fn set_rigidity_heat_map(
+        &mut self,
+        heat_map: Option<RigidityHeatMap>,
+    ) -> AppStateOperationResult {
+        self.0
+            .make_mut()
+            .design
+            .make_mut()
+            .set_rigidity_heat_map(heat_map)
+    }
+
     pub fn notify(&mut self, notification: InteractorNotification) -> AppStateOperationResult {
         self.0.make_mut().design.make_mut().notify(notification);
 
@@ -496,6 +617,12 @@ impl AppState {
         result
     }
 
+    /// Return true if applying `operation` would continue the operation in progress, like the
+    /// successive translations of a drag.
+    pub fn continues_current_operation(&self, operation: &DesignOperation) -> bool {
+        self.0.design.continues_current_operation(operation)
+    }
+
     pub fn is_in_stable_state(&self) -> bool {
         self.0.design.is_in_stable_state()
     }
@@ -512,6 +639,11 @@ impl AppState {
             .set_visibility_sieve(selection, compl)
     }
 
+    /// True if the designs of `self` and `other` differ, even if neither state was updated.
+    pub fn has_different_design_than(&self, other: &Self) -> bool {
+        self.0.design.has_different_design_than(&other.0.design)
+    }
+
     pub fn design_was_modified(&self, other: &Self) -> bool {
         self.0.design.has_different_design_than(&other.0.design)
             && (self.0.updated_once || other.0.updated_once)
@@ -607,6 +739,35 @@ impl AppState {
     pub fn get_new_selection(&self) -> Option<Vec<Selection>> {
         self.0.design.get_new_selection()
     }
+
+    pub fn get_keymap(&self) -> &Keymap {
+        &self.0.parameters.keymap
+    }
+
+    /// The maximum amount of memory, in bytes, that the undo and redo stacks may use.
+    pub fn get_undo_memory_budget(&self) -> usize {
+        self.0
+            .parameters
+            .undo_memory_budget_mb
+            .saturating_mul(1 << 20)
+    }
+
+    /// A rough estimate, in bytes, of the memory that keeping `self` on the undo stack costs,
+    /// knowing that the state that follows it is `next`.
+    ///
+    /// The two states share everything that the transition did not modify, so only the design
+    /// is accounted for, and only if the transition modified it.
+    pub fn estimated_snapshot_size(&self, next: &Self) -> usize {
+        if self.0.design.has_different_design_than(&next.0.design) {
+            let design = &self.0.design.design;
+            let nb_nucl: usize = design.strands.values().map(Strand::length).sum();
+            SNAPSHOT_BASE_SIZE
+                + nb_nucl * SNAPSHOT_SIZE_PER_NUCLEOTIDE
+                + design.helices.len() * SNAPSHOT_SIZE_PER_HELIX
+        } else {
+            SNAPSHOT_BASE_SIZE
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -680,7 +841,10 @@ pub struct NewHelixStrand {
     length: usize,
     start: isize,
 }
+
+#[derive(Debug)]
 pub enum LoadDesignError {
+    IoError(std::io::Error),
     JsonError(serde_json::Error),
     ScadnanoImportError(ScadnanoImportError),
     IncompatibleVersion { current: String, required: String },
@@ -689,6 +853,7 @@ pub enum LoadDesignError {
 impl std::fmt::Display for LoadDesignError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
+            Self::IoError(e) => write!(f, "Could not read the file: {e}"),
             Self::JsonError(e) => write!(f, "Json error: {e}"),
             Self::ScadnanoImportError(e) => {
                 write!(
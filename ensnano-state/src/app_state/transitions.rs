@@ -9,6 +9,24 @@ pub struct AppStateTransition {
     pub label: TransitionLabel,
     /// The position of the 3d scene's camera at the moment the operation was performed.
     pub camera_3d: Camera3D,
+    /// An estimate, in bytes, of the memory that keeping `state` costs.
+    pub memory_size: usize,
+}
+
+/// A description of the undo and redo stacks, meant to be displayed in app.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UndoHistory {
+    /// The labels of all the transitions, from the oldest to the most recent one.
+    pub labels: Vec<String>,
+    /// The number of transitions that lead to the current state. The transitions with an index
+    /// greater or equal to `position` can be redone.
+    pub position: usize,
+    /// The number of old transitions that were dropped to respect the memory budget.
+    pub nb_evicted: usize,
+    /// An estimate, in bytes, of the memory used by the undo and redo stacks.
+    pub memory_usage: usize,
+    /// The maximum amount of memory, in bytes, that the undo and redo stacks may use.
+    pub memory_budget: usize,
 }
 
 /// A label describing an operation.
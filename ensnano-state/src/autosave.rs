@@ -0,0 +1,648 @@
+//! Crash-safe autosaves.
+//!
+//! Each design has its own directory in the local data directory. This directory contains the
+//! last [`NB_AUTOSAVES`] snapshots of the design, and a journal of the design operations that were
+//! applied since the last snapshot, or since the design was last saved. Replaying the journal on
+//! top of the state it refers to recovers the design as it was before the last operation.
+//!
+//! Each running instance of ENSnano has a session, with a session file indicating the directory
+//! of the design that is being edited. The session file is locked while the session runs, and
+//! removed when ENSnano exits normally, so finding an unlocked session file on startup means that
+//! a session ended unexpectedly. Each session has its own journal, so that several instances
+//! editing the same design do not overwrite each other's journal.
+
+use crate::{
+    app_state::{
+        AppState, LoadDesignError, SaveDesignError,
+        design_interactor::controller::InteractorNotification,
+    },
+    design::operation::DesignOperation,
+};
+use ensnano_design::SavingInformation;
+use ensnano_utils::consts::{
+    APP_NAME, CANNOT_OPEN_DEFAULT_DIR, ENS_EXTENSION, ENS_UNNAMED_FILE_NAME, NB_AUTOSAVES,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Seek as _, Write as _},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const AUTOSAVE_DIRECTORY: &str = "autosaves";
+const SESSION_DIRECTORY: &str = "sessions";
+const JOURNAL_PREFIX: &str = "journal-";
+const JOURNAL_EXTENSION: &str = "jsonl";
+const SNAPSHOT_PREFIX: &str = "autosave-";
+
+/// The first line of a journal, indicating the state on which the operations must be replayed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct JournalHeader {
+    /// The path at which the design is saved, if any.
+    design_path: Option<PathBuf>,
+    /// The name of the snapshot on which the operations are applied.
+    ///
+    /// If `None`, the operations are applied on the design saved at `design_path`, or on an
+    /// empty design if the design was never saved.
+    snapshot: Option<String>,
+}
+
+/// An operation of the journal.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct JournalEntry {
+    /// True if the operation continued the operation in progress, like the successive
+    /// translations of a drag. Otherwise, the operation in progress is finished before the
+    /// operation is replayed, so that replaying does not depend on the selection or on the
+    /// operations that were not journaled.
+    continues: bool,
+    operation: DesignOperation,
+}
+
+/// The session of the running instance of ENSnano.
+struct Session {
+    /// An identifier that is unique among the sessions that ran on this computer.
+    id: String,
+    /// The session file, locked while the session runs.
+    file: File,
+}
+
+/// The autosaves of the design that is being edited.
+#[derive(Default)]
+pub struct Autosave {
+    session: Option<Session>,
+    /// The directory of the autosaves of the current design, `None` if autosaving is disabled.
+    directory: Option<PathBuf>,
+    /// The state of the design that can be recovered from the last snapshot and the journal.
+    journaled_state: AppState,
+    /// The header of the journal, `None` if the journal has not been started.
+    header: Option<JournalHeader>,
+    nb_journaled_operations: usize,
+    /// True if an operation could not be journaled, in which case a snapshot must be taken as
+    /// soon as possible.
+    pub snapshot_requested: bool,
+}
+
+impl Autosave {
+    /// Start autosaving the design of `app_state` and mark the session as running.
+    pub fn start_session(&mut self, app_state: &AppState) {
+        let Some(root) = root_directory() else {
+            log::error!("{CANNOT_OPEN_DEFAULT_DIR}");
+            return;
+        };
+        match Session::start(&root) {
+            Ok(session) => self.session = Some(session),
+            Err(e) => {
+                log::error!("Could not start session: {e}");
+                return;
+            }
+        }
+        self.directory = Some(design_directory(&root, app_state.path_to_current_design()));
+        self.journaled_state = app_state.clone();
+        self.header = None;
+        self.nb_journaled_operations = 0;
+        self.write_session_file();
+    }
+
+    /// Mark the session as ended normally and discard the journal.
+    pub fn end_session(&mut self) {
+        self.discard_journal();
+        if let (Some(root), Some(session)) = (root_directory(), self.session.take()) {
+            remove_if_exists(&session_file(&root, &session.id));
+        }
+        self.directory = None;
+    }
+
+    /// Start autosaving a new design, or the current design after it has been saved.
+    ///
+    /// This discards the journal of the previous design.
+    pub fn set_design(&mut self, app_state: &AppState) {
+        let Some(root) = self.directory.as_ref().and_then(|_| root_directory()) else {
+            return;
+        };
+        self.discard_journal();
+        self.directory = Some(design_directory(&root, app_state.path_to_current_design()));
+        self.journaled_state = app_state.clone();
+        self.write_session_file();
+    }
+
+    /// True if the snapshot and the journal allow to recover the design of `app_state`.
+    pub fn is_up_to_date(&self, app_state: &AppState) -> bool {
+        !self.journaled_state.has_different_design_than(app_state)
+    }
+
+    /// Take into account that `old_state` was updated into `new_state`.
+    ///
+    /// Updating a state is deterministic, so replaying the journal recovers `new_state` if it
+    /// recovers `old_state`.
+    pub fn follow_update(&mut self, old_state: &AppState, new_state: &AppState) {
+        if self.is_up_to_date(old_state) {
+            self.journaled_state = new_state.clone();
+        }
+    }
+
+    /// True if a new snapshot would allow to recover more than the current one.
+    pub fn needs_snapshot(&self, app_state: &AppState) -> bool {
+        self.directory.is_some()
+            && (self.nb_journaled_operations > 0 || !self.is_up_to_date(app_state))
+    }
+
+    /// Record that `operation` turned `old_state` into `new_state`.
+    ///
+    /// If the journal cannot recover `old_state`, a snapshot is requested instead.
+    pub fn record_operation(
+        &mut self,
+        old_state: &AppState,
+        operation: &DesignOperation,
+        new_state: &AppState,
+    ) -> Result<(), SaveDesignError> {
+        if self.directory.is_none() || !new_state.has_different_design_than(old_state) {
+            return Ok(());
+        }
+        let entry = JournalEntry {
+            continues: old_state.continues_current_operation(operation),
+            operation: operation.clone(),
+        };
+        // An operation that continues an operation which is not in the journal cannot be
+        // replayed.
+        let continues_unjournaled = entry.continues && self.nb_journaled_operations == 0;
+        if self.is_up_to_date(old_state) && self.can_journal() && !continues_unjournaled {
+            self.append_to_journal(&entry)?;
+            self.journaled_state = new_state.clone();
+        } else {
+            self.snapshot_requested = true;
+        }
+        Ok(())
+    }
+
+    /// Save `app_state` in a new snapshot, and start a new journal on top of it.
+    ///
+    /// Only the last [`NB_AUTOSAVES`] snapshots are kept.
+    pub fn snapshot(
+        &mut self,
+        app_state: &AppState,
+        saving_info: SavingInformation,
+    ) -> Result<(), SaveDesignError> {
+        let Some(directory) = self.directory.clone() else {
+            return Ok(());
+        };
+        let Some(journal_path) = self.journal_path() else {
+            return Ok(());
+        };
+        self.snapshot_requested = false;
+        fs::create_dir_all(&directory)?;
+        let number = claim_snapshot_number(&directory)?;
+        let name = snapshot_name(number);
+        app_state
+            .clone()
+            .save_design(&directory.join(&name), saving_info, false)?;
+
+        let header = JournalHeader {
+            design_path: app_state.path_to_current_design().cloned(),
+            snapshot: Some(name),
+        };
+        // Replace the journal atomically so that it always refers to an existing snapshot.
+        let tmp_journal = journal_path.with_extension("tmp");
+        fs::write(&tmp_journal, serde_json::to_string(&header)? + "\n")?;
+        fs::rename(&tmp_journal, &journal_path)?;
+        self.header = Some(header);
+        self.nb_journaled_operations = 0;
+        self.journaled_state = app_state.clone();
+        log::info!("Autosaved design in {}", directory.display());
+
+        // The snapshots on which the journals of other sessions are replayed are kept.
+        let journaled_snapshots = journaled_snapshots(&directory);
+        let snapshots = list_snapshots(&directory);
+        let nb_obsolete = snapshots.len().saturating_sub(NB_AUTOSAVES);
+        for name in snapshots[..nb_obsolete].iter().map(|n| snapshot_name(*n)) {
+            if !journaled_snapshots.contains(&name) {
+                remove_if_exists(&directory.join(name));
+            }
+        }
+        Ok(())
+    }
+
+    /// The path of the journal of the current session.
+    fn journal_path(&self) -> Option<PathBuf> {
+        let directory = self.directory.as_ref()?;
+        let session = self.session.as_ref()?;
+        Some(journal_path(directory, &session.id))
+    }
+
+    /// True if the journal can be continued, or started on top of the saved design.
+    ///
+    /// Designs imported from other formats are not saved at their path, so a snapshot must be
+    /// taken before journaling their modifications.
+    fn can_journal(&self) -> bool {
+        self.header.is_some()
+            || self
+                .journaled_state
+                .path_to_current_design()
+                .is_none_or(|path| path.is_file())
+    }
+
+    fn append_to_journal(&mut self, entry: &JournalEntry) -> Result<(), SaveDesignError> {
+        let (Some(directory), Some(journal_path)) = (self.directory.as_ref(), self.journal_path())
+        else {
+            return Ok(());
+        };
+        let mut lines = String::new();
+        let new_journal = self.header.is_none();
+        if new_journal {
+            fs::create_dir_all(directory)?;
+            let header = JournalHeader {
+                design_path: self.journaled_state.path_to_current_design().cloned(),
+                snapshot: None,
+            };
+            lines = serde_json::to_string(&header)? + "\n";
+            self.header = Some(header);
+        }
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .append(!new_journal)
+            .write(true)
+            .truncate(new_journal)
+            .open(journal_path)?;
+        journal.write_all(lines.as_bytes())?;
+        self.nb_journaled_operations += 1;
+        Ok(())
+    }
+
+    fn discard_journal(&mut self) {
+        if let Some(journal_path) = self.journal_path() {
+            remove_if_exists(&journal_path);
+        }
+        self.header = None;
+        self.nb_journaled_operations = 0;
+        self.snapshot_requested = false;
+    }
+
+    fn write_session_file(&mut self) {
+        let (Some(session), Some(directory)) = (self.session.as_mut(), self.directory.as_ref())
+        else {
+            return;
+        };
+        let file = &mut session.file;
+        let result = file
+            .set_len(0)
+            .and_then(|()| file.rewind())
+            .and_then(|()| file.write_all(directory.to_string_lossy().as_bytes()));
+        if let Err(e) = result {
+            log::error!("Could not write session file: {e}");
+        }
+    }
+}
+
+impl Session {
+    /// Create and lock the file of a new session.
+    fn start(root: &Path) -> std::io::Result<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let id = format!("{}-{nanos}", std::process::id());
+        let path = session_file(root, &id);
+        fs::create_dir_all(root.join(SESSION_DIRECTORY))?;
+        let file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        file.try_lock()?;
+        Ok(Self { id, file })
+    }
+}
+
+/// Unsaved work left by a session that ended unexpectedly.
+#[derive(Clone, Debug)]
+pub struct Recovery {
+    directory: PathBuf,
+    session_file: PathBuf,
+    journal_path: PathBuf,
+    header: JournalHeader,
+    entries: Vec<JournalEntry>,
+}
+
+/// If a session ended unexpectedly with unsaved work, return that work.
+///
+/// The sessions of the instances of ENSnano that are still running are ignored.
+pub fn unclean_session() -> Option<Recovery> {
+    let sessions = fs::read_dir(root_directory()?.join(SESSION_DIRECTORY)).ok()?;
+    sessions
+        .flatten()
+        .find_map(|entry| Recovery::of_ended_session(entry.path()))
+}
+
+/// Read the header and the entries of a journal, if there is something to recover.
+fn read_journal(journal: &str) -> Option<(JournalHeader, Vec<JournalEntry>)> {
+    let mut lines = journal.lines();
+    let header: JournalHeader = serde_json::from_str(lines.next()?).ok()?;
+    let mut entries = Vec::new();
+    for line in lines {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => {
+                // The last line may be incomplete if the crash happened while it was written.
+                log::warn!("Ignoring the end of the journal: {e}");
+                break;
+            }
+        }
+    }
+    (!entries.is_empty() || header.snapshot.is_some()).then_some((header, entries))
+}
+
+impl Recovery {
+    /// Return the unsaved work of the session whose file is `session_file`, if that session
+    /// ended unexpectedly. A session that ended without unsaved work is forgotten.
+    fn of_ended_session(session_file: PathBuf) -> Option<Self> {
+        let file = OpenOptions::new().write(true).open(&session_file).ok()?;
+        // The session is still running if its file is locked. The lock is released when the
+        // file is closed.
+        file.try_lock().ok()?;
+        let id = session_file.file_name()?.to_str()?;
+        let directory = PathBuf::from(fs::read_to_string(&session_file).ok()?);
+        let journal_path = journal_path(&directory, id);
+        let recovery = fs::read_to_string(&journal_path)
+            .ok()
+            .and_then(|journal| read_journal(&journal))
+            .map(|(header, entries)| Self {
+                directory,
+                session_file: session_file.clone(),
+                journal_path,
+                header,
+                entries,
+            });
+        if recovery.is_none() {
+            remove_if_exists(&session_file);
+        }
+        recovery
+    }
+
+    /// The name of the design that can be recovered.
+    pub fn design_name(&self) -> String {
+        self.header
+            .design_path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map_or_else(
+                || ENS_UNNAMED_FILE_NAME.to_owned(),
+                |name| name.to_string_lossy().into_owned(),
+            )
+    }
+
+    /// Rebuild the design by replaying the journal on top of the state that it refers to.
+    pub fn restore(&self) -> Result<AppState, LoadDesignError> {
+        let mut app_state = if let Some(snapshot) = self.header.snapshot.as_ref() {
+            AppState::import_design(self.directory.join(snapshot))?
+        } else if let Some(path) = self.header.design_path.as_ref() {
+            AppState::import_design(path.clone())?
+        } else {
+            AppState::default()
+        };
+        app_state.set_path_to_current_design(self.header.design_path.clone());
+        for entry in &self.entries {
+            let label = entry.operation.label();
+            if !entry.continues
+                && let Err(e) = app_state.notify(InteractorNotification::FinishOperation)
+            {
+                log::warn!("Could not finish the operation before {label}: {e:?}");
+            }
+            if let Err(e) = app_state.apply_design_op(entry.operation.clone()) {
+                log::warn!("Could not replay {label}: {e:?}");
+            }
+            app_state.update();
+        }
+        Ok(app_state)
+    }
+
+    /// Forget the unsaved work and its session. The snapshots are kept.
+    pub fn discard(&self) {
+        remove_if_exists(&self.journal_path);
+        remove_if_exists(&self.session_file);
+    }
+}
+
+fn session_file(root: &Path, session_id: &str) -> PathBuf {
+    root.join(SESSION_DIRECTORY).join(session_id)
+}
+
+fn journal_path(directory: &Path, session_id: &str) -> PathBuf {
+    directory.join(format!("{JOURNAL_PREFIX}{session_id}.{JOURNAL_EXTENSION}"))
+}
+
+fn root_directory() -> Option<PathBuf> {
+    let mut ret = dirs::data_local_dir()?;
+    ret.push(APP_NAME);
+    ret.push(AUTOSAVE_DIRECTORY);
+    Some(ret)
+}
+
+/// The directory of the autosaves of the design saved at `design_path`.
+///
+/// The name of the directory is made of the name of the design and of a hash of its path, so
+/// that designs with the same name in different directories do not share their autosaves.
+fn design_directory(root: &Path, design_path: Option<&PathBuf>) -> PathBuf {
+    let name = match design_path {
+        Some(path) => {
+            let stem = path
+                .file_stem()
+                .map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+            format!("{stem}-{:016x}", fnv1a(path.to_string_lossy().as_bytes()))
+        }
+        None => ENS_UNNAMED_FILE_NAME.to_owned(),
+    };
+    root.join(name)
+}
+
+/// A hash function whose output does not depend on the version of the compiler.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn snapshot_name(number: usize) -> String {
+    format!("{SNAPSHOT_PREFIX}{number}.{ENS_EXTENSION}")
+}
+
+/// The numbers of the snapshots in `directory`, in increasing order.
+fn list_snapshots(directory: &Path) -> Vec<usize> {
+    let mut ret: Vec<usize> = fs::read_dir(directory)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_prefix(SNAPSHOT_PREFIX)?
+                .strip_suffix(ENS_EXTENSION)?
+                .strip_suffix('.')?
+                .parse()
+                .ok()
+        })
+        .collect();
+    ret.sort_unstable();
+    ret
+}
+
+/// Reserve the number of a new snapshot in `directory`, by creating an empty snapshot.
+///
+/// The snapshot is created atomically, so that sessions editing the same design do not write the
+/// same snapshot.
+fn claim_snapshot_number(directory: &Path) -> std::io::Result<usize> {
+    let mut number = list_snapshots(directory).last().map_or(0, |n| n + 1);
+    loop {
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(directory.join(snapshot_name(number)))
+        {
+            Ok(_) => return Ok(number),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => number += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// The names of the snapshots referred to by the journals in `directory`.
+fn journaled_snapshots(directory: &Path) -> Vec<String> {
+    fs::read_dir(directory)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            if !name.starts_with(JOURNAL_PREFIX) || path.extension()?.to_str()? != JOURNAL_EXTENSION
+            {
+                return None;
+            }
+            let journal = fs::read_to_string(&path).ok()?;
+            let header: JournalHeader = serde_json::from_str(journal.lines().next()?).ok()?;
+            header.snapshot
+        })
+        .collect()
+}
+
+fn remove_if_exists(path: &Path) {
+    if let Err(e) = fs::remove_file(path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        log::error!("Could not remove {}: {e}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::design::operation::{DesignTranslation, IsometryTarget};
+    use ensnano_design::grid::GridId;
+    use ultraviolet::Vec3;
+
+    #[test]
+    fn design_directories_depend_on_the_whole_path() {
+        let root = PathBuf::from("autosaves");
+        let a = design_directory(&root, Some(&PathBuf::from("/a/origami.ens")));
+        let b = design_directory(&root, Some(&PathBuf::from("/b/origami.ens")));
+        assert_ne!(a, b);
+        assert!(
+            a.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("origami-")
+        );
+        assert_eq!(
+            design_directory(&root, None),
+            root.join(ENS_UNNAMED_FILE_NAME)
+        );
+    }
+
+    #[test]
+    fn entries_round_trip_through_the_journal() {
+        let header = JournalHeader {
+            design_path: None,
+            snapshot: Some(snapshot_name(2)),
+        };
+        let entry = JournalEntry {
+            continues: true,
+            operation: DesignOperation::SetStrandName {
+                s_id: 3,
+                name: "staple".to_owned(),
+            },
+        };
+        let journal = format!(
+            "{}\n{}\n{{\"continues\"",
+            serde_json::to_string(&header).unwrap(),
+            serde_json::to_string(&entry).unwrap()
+        );
+        // The incomplete last line is ignored.
+        let (read_header, entries) = read_journal(&journal).unwrap();
+        assert_eq!(read_header.snapshot, header.snapshot);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].continues);
+        assert_eq!(
+            format!("{:?}", entries[0].operation),
+            format!("{:?}", entry.operation)
+        );
+    }
+
+    fn grid_translation(x: f32) -> DesignOperation {
+        DesignOperation::Translation(DesignTranslation {
+            translation: x * Vec3::unit_x(),
+            target: IsometryTarget::Grids(vec![GridId::FreeGrid(0)]),
+            group_id: None,
+        })
+    }
+
+    fn grid_position(app_state: &AppState) -> Vec3 {
+        app_state
+            .0
+            .design
+            .design
+            .free_grids
+            .get_from_g_id(&GridId::FreeGrid(0))
+            .unwrap()
+            .position
+    }
+
+    #[test]
+    fn replaying_the_journal_finishes_the_operations_that_were_finished() {
+        let mut path = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"));
+        path.pop();
+        path.push("tests");
+        path.push("two_neighbor_strands.ens");
+        let mut app_state = AppState::import_design(path.clone()).unwrap();
+        let initial_position = grid_position(&app_state);
+
+        // Two drags of the grid. The translations of a drag are relative to its start.
+        let mut entries = Vec::new();
+        for (x, finish) in [(1., false), (2., true), (5., false)] {
+            let operation = grid_translation(x);
+            entries.push(JournalEntry {
+                continues: app_state.continues_current_operation(&operation),
+                operation: operation.clone(),
+            });
+            app_state.apply_design_op_and_select(operation).unwrap();
+            app_state.update();
+            if finish {
+                app_state
+                    .notify(InteractorNotification::FinishOperation)
+                    .unwrap();
+            }
+        }
+        assert_eq!(
+            entries.iter().map(|e| e.continues).collect::<Vec<_>>(),
+            [false, true, false]
+        );
+        let expected = initial_position + 7. * Vec3::unit_x();
+        assert!((grid_position(&app_state) - expected).mag() < 1e-5);
+
+        let recovery = Recovery {
+            directory: PathBuf::new(),
+            session_file: PathBuf::new(),
+            journal_path: PathBuf::new(),
+            header: JournalHeader {
+                design_path: Some(path),
+                snapshot: None,
+            },
+            entries,
+        };
+        let restored = recovery.restore().unwrap();
+        assert!((grid_position(&restored) - expected).mag() < 1e-5);
+    }
+}
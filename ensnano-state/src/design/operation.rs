@@ -7,9 +7,15 @@ use ensnano_design::{
     CameraId, Design,
     bezier_plane::{
         BezierPathId, BezierPlaneDescriptor, BezierPlaneId, BezierVertex, BezierVertexId,
+        import_from_svg::SvgImportParameters,
+    },
+    curves::{
+        bezier::BezierControlPoint, import_from_points::AxisImportParameters,
+        parametric::ParametricCurveDescriptor,
     },
-    curves::bezier::BezierControlPoint,
     design_element::{DesignElementKey, DnaAttribute},
+    drawing_style::DrawingStyle,
+    external_3d_objects::External3DObjectId,
     grid::{
         GridDescriptor, GridId, GridObject, GridTypeDescr, HelixGridPosition,
         hyperboloid::Hyperboloid,
@@ -18,12 +24,15 @@ use ensnano_design::{
     nucl::Nucl,
     organizer_tree::{GroupId, OrganizerTree},
     parameters::HelixParameters,
+    voxelization::VoxelizationParameters,
+    wireframe::WireframeParameters,
 };
+use serde::{Deserialize, Serialize};
 use std::{path::PathBuf, sync::Arc};
 use ultraviolet::{Isometry2, Rotor3, Vec2, Vec3};
 
 /// An operation that can be performed on a design.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DesignOperation {
     /// Rotate an element of the design.
     Rotation(DesignRotation),
@@ -39,6 +48,31 @@ pub enum DesignOperation {
         start: HelixGridPosition,
         end: HelixGridPosition,
     },
+    /// Add an helix whose axis follows a parametric curve, with a strand on each side.
+    AddParametricHelix(ParametricCurveDescriptor),
+    /// Add one curved helix, with a strand on each side, per file of points describing its axis.
+    ImportHelixAxes {
+        paths: Vec<PathBuf>,
+        parameters: AxisImportParameters,
+    },
+    /// Add a wireframe made from the mesh of a 3D object, whose stl or gltf file is at
+    /// `mesh_path`.
+    MakeWireframe {
+        object_id: External3DObjectId,
+        mesh_path: PathBuf,
+        parameters: WireframeParameters,
+    },
+    /// Fill the volume of the mesh of a 3D object, whose stl or gltf file is at `mesh_path`, with
+    /// helices on a new grid.
+    VoxelizeMesh {
+        object_id: External3DObjectId,
+        mesh_path: PathBuf,
+        parameters: VoxelizationParameters,
+    },
+    /// Insert the design in a file as a new group, with new identifiers for all its elements.
+    InsertDesign {
+        path: PathBuf,
+    },
     RmHelices {
         h_ids: Vec<usize>,
     },
@@ -158,6 +192,10 @@ pub enum DesignOperation {
         group_id: GroupId,
         pivot: GroupPivot,
     },
+    SetGroupDrawingStyle {
+        group_id: GroupId,
+        style: DrawingStyle,
+    },
     DeleteCamera(CameraId),
     CreateNewCamera {
         position: Vec3,
@@ -195,6 +233,12 @@ pub enum DesignOperation {
         length: usize,
         insertion_point: InsertionPoint,
     },
+    /// Add or remove a deletion (a skip in cadnano) at a position of an helix.
+    SetDeletion {
+        helix: usize,
+        position: isize,
+        deleted: bool,
+    },
     AddBezierPlane {
         desc: BezierPlaneDescriptor,
     },
@@ -236,8 +280,17 @@ pub enum DesignOperation {
         file_path: PathBuf,
         design_path: PathBuf,
     },
+    /// Import the paths of an SVG file on a bezier plane.
     ImportSvgPath {
         path: PathBuf,
+        parameters: SvgImportParameters,
+    },
+    /// Forget the positions obtained by committing a relaxation.
+    ClearRelaxedPositions,
+    /// Override the positions of the nucleotides given by their helix, for example with the
+    /// equilibrium shape given by an analysis of the rigidity of the design.
+    SetRelaxedPositions {
+        positions: Vec<(Nucl, Vec3)>,
     },
 }
 
@@ -250,6 +303,11 @@ impl DesignOperation {
             }
             Self::AddGridHelix { .. } => "Helix creation".into(),
             Self::AddTwoPointsBezier { .. } => "Bezier curve creation".into(),
+            Self::AddParametricHelix(_) => "Parametric helix creation".into(),
+            Self::ImportHelixAxes { .. } => "Helix axes import".into(),
+            Self::MakeWireframe { .. } => "Wireframe generation".into(),
+            Self::VoxelizeMesh { .. } => "Mesh voxelization".into(),
+            Self::InsertDesign { .. } => "Design insertion".into(),
             Self::RmHelices { .. } => "Helix deletion".into(),
             Self::RmXovers { .. } => "Xover deletion".into(),
             Self::Cut { nucl, .. } => format!("Cut on {nucl:?}").into(),
@@ -290,15 +348,65 @@ impl DesignOperation {
             Self::SetOrganizerTree(_) => "Update organizer tree".into(),
             Self::SetStrandName { .. } => "Update name of strand".into(),
             Self::SetGroupPivot { .. } => "Set group pivot".into(),
+            Self::SetGroupDrawingStyle { .. } => "Set group drawing style".into(),
             Self::DeleteCamera(_) => "Delete camera".into(),
             Self::CreateNewCamera { .. } => "Create camera shortcut".into(),
             Self::SetGridPosition { .. } => "Set grid position".into(),
             Self::SetGridOrientation { .. } => "Set grid orientation".into(),
             Self::MakeSeveralXovers { .. } => "Multiple xovers".into(),
+            Self::SetDeletion { deleted: true, .. } => "Add deletion".into(),
+            Self::SetDeletion { deleted: false, .. } => "Remove deletion".into(),
+            Self::ClearRelaxedPositions => "Clear relaxed positions".into(),
+            Self::SetRelaxedPositions { .. } => "Set relaxed positions".into(),
             _ => "Unnamed operation".into(),
         }
     }
 
+    /// Whether the operation may modify elements that are locked for edition. This is the case of
+    /// the operations that only change how the elements are displayed, or their lock.
+    pub fn may_modify_locked_elements(&self) -> bool {
+        matches!(
+            self,
+            Self::UpdateAttribute { .. } | Self::SetVisibilityHelix { .. }
+        )
+    }
+
+    /// Whether the relaxed positions of the nucleotides are still valid after the operation. This
+    /// is the case of the operations that change neither the helices nor the strands, and of the
+    /// operations that set the relaxed positions themselves.
+    pub fn keeps_relaxed_positions(&self) -> bool {
+        matches!(
+            self,
+            Self::RecolorStaples
+                | Self::ChangeColor { .. }
+                | Self::SetScaffoldId(_)
+                | Self::SetScaffoldShift(_)
+                | Self::SetScaffoldSequence { .. }
+                | Self::SetHelicesPersistence { .. }
+                | Self::UpdateAttribute { .. }
+                | Self::SetSmallSpheres { .. }
+                | Self::SetIsometry { .. }
+                | Self::SetVisibilityHelix { .. }
+                | Self::FlipHelixGroup { .. }
+                | Self::FlipAnchors { .. }
+                | Self::SetOrganizerTree(_)
+                | Self::SetStrandName { .. }
+                | Self::SetGroupPivot { .. }
+                | Self::SetGroupDrawingStyle { .. }
+                | Self::DeleteCamera(_)
+                | Self::CreateNewCamera { .. }
+                | Self::SetCameraName { .. }
+                | Self::CheckXovers { .. }
+                | Self::SetRainbowScaffold(_)
+                | Self::AddGrid(_)
+                | Self::AddBezierPlane { .. }
+                | Self::Add3DObject { .. }
+                | Self::InsertDesign { .. }
+                | Self::ClearRelaxedPositions
+                | Self::SetRelaxedPositions { .. }
+        )
+    }
+
     fn outcome(&self) -> AppStateOperationOutcome {
         let label = self.label();
 
@@ -392,6 +500,29 @@ impl DesignOperation {
             Self::AddTwoPointsBezier { start, end } => {
                 controller.add_two_points_bezier(design, start, end)?;
             }
+            Self::AddParametricHelix(descriptor) => {
+                controller.add_parametric_helix(design, descriptor)?;
+            }
+            Self::ImportHelixAxes { paths, parameters } => {
+                controller.import_helix_axes(design, &paths, &parameters)?;
+            }
+            Self::MakeWireframe {
+                object_id,
+                mesh_path,
+                parameters,
+            } => {
+                controller.make_wireframe(design, object_id, &mesh_path, &parameters)?;
+            }
+            Self::VoxelizeMesh {
+                object_id,
+                mesh_path,
+                parameters,
+            } => {
+                controller.voxelize_mesh(design, object_id, &mesh_path, &parameters)?;
+            }
+            Self::InsertDesign { path } => {
+                controller.insert_design(design, &path)?;
+            }
             Self::CrossCut {
                 target_3prime,
                 source_id,
@@ -458,6 +589,9 @@ impl DesignOperation {
             Self::SetGroupPivot { group_id, pivot } => {
                 controller.set_group_pivot(design, group_id, pivot)?;
             }
+            Self::SetGroupDrawingStyle { group_id, style } => {
+                controller.set_group_drawing_style(design, group_id, style)?;
+            }
             Self::CreateNewCamera {
                 position,
                 orientation,
@@ -493,6 +627,12 @@ impl DesignOperation {
             Self::SetRainbowScaffold(b) => {
                 design.rainbow_scaffold = b;
             }
+            Self::ClearRelaxedPositions => {
+                design.relaxed_positions.clear();
+            }
+            Self::SetRelaxedPositions { positions } => {
+                design.relaxed_positions = positions.into_iter().collect();
+            }
             Self::SetGlobalHelixParameters {
                 helix_parameters: parameters,
             } => {
@@ -504,6 +644,13 @@ impl DesignOperation {
             } => {
                 controller.update_insertion_length(design, insertion_point, length)?;
             }
+            Self::SetDeletion {
+                helix,
+                position,
+                deleted,
+            } => {
+                controller.set_deletion(design, helix, position, deleted)?;
+            }
             Self::AddBezierPlane { desc } => {
                 controller.add_bezier_plane(design, desc);
             }
@@ -545,8 +692,8 @@ impl DesignOperation {
                 file_path,
                 design_path,
             } => controller.add_3d_object(design, file_path, design_path)?,
-            Self::ImportSvgPath { path } => {
-                controller.import_svg_path(design, path)?;
+            Self::ImportSvgPath { path, parameters } => {
+                controller.import_svg_path(design, path, parameters)?;
             }
         }
 
@@ -555,7 +702,7 @@ impl DesignOperation {
 }
 
 /// A rotation on an element of a design.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DesignRotation {
     pub origin: Vec3,
     pub rotation: Rotor3,
@@ -565,26 +712,32 @@ pub struct DesignRotation {
 }
 
 /// A translation of an element of a design.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DesignTranslation {
     pub translation: Vec3,
     pub target: IsometryTarget,
     pub group_id: Option<GroupId>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum HyperboloidOperation {
     New {
         request: HyperboloidRequest,
         position: Vec3,
         orientation: Rotor3,
     },
+    /// Start editing the parameters of an existing hyperboloid grid. Its helices are regenerated
+    /// and the strands on them are remapped onto the new helix lengths.
+    Edit {
+        grid_id: GridId,
+        request: HyperboloidRequest,
+    },
     Update(HyperboloidRequest),
     Finalize,
     Cancel,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct HyperboloidRequest {
     pub radius: usize,
     pub length: f32,
@@ -604,15 +757,25 @@ impl HyperboloidRequest {
             nb_turn_per_100_nt: self.nb_turn,
         }
     }
+
+    pub fn from_grid(hyperboloid: &Hyperboloid) -> Self {
+        Self {
+            radius: hyperboloid.radius,
+            length: hyperboloid.length,
+            shift: hyperboloid.shift,
+            radius_shift: hyperboloid.radius_shift,
+            nb_turn: hyperboloid.nb_turn_per_100_nt,
+        }
+    }
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, Serialize, Deserialize)]
 pub struct InsertionPoint {
     pub nucl: Nucl,
     pub nucl_is_prime5_of_insertion: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BezierPlaneHomothethy {
     pub plane_id: BezierPlaneId,
     pub fixed_corner: Vec2,
@@ -621,7 +784,7 @@ pub struct BezierPlaneHomothethy {
 }
 
 /// A element on which an isometry must be applied.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum IsometryTarget {
     /// An helix of the design.
     Helices(Vec<usize>, bool),
@@ -633,7 +796,7 @@ pub enum IsometryTarget {
     ControlPoint(Vec<(usize, BezierControlPoint)>),
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, Serialize, Deserialize)]
 pub struct NewBezierTangentVector {
     pub vertex_id: BezierVertexId,
     /// Whether `new_vector` is the vector of the inward or outward tangent.
@@ -8,9 +8,10 @@ use ensnano_design::{
     nucl::Nucl,
     phantom_element::PhantomElement,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeSet;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Selection {
     Nucleotide(u32, Nucl),
     Bond(u32, Nucl, Nucl),
@@ -1,31 +1,39 @@
 use crate::{
-    app_state::AppState,
+    app_state::{AppState, transitions::UndoHistory},
     gui::{
         curve::CurveDescriptorBuilder, drag_drop_target::DragIdentifier,
         state::RevolutionParameterId,
     },
+    simulation_presets::{SimulationParameters, SimulationPreset},
+    trajectory::TrajectorySummary,
 };
 use ensnano_design::{
     CameraId,
-    bezier_plane::BezierPathId,
+    bezier_plane::{BezierPathId, BezierPlaneId},
     design_element::{DesignElement, DesignElementKey, DnaAttribute},
+    drawing_style::{DrawingStyle, StyleField, StyleFlag},
+    external_3d_objects::External3DObjectId,
     grid::GridTypeDescr,
     interaction_modes::{ActionMode, SelectionMode},
     organizer_tree::{GroupId, OrganizerNodeId, OrganizerTree},
     parameters::NamedParameter,
+    voxelization::VoxelizationAxis,
 };
 use ensnano_physics::parameters::RapierParameters;
 use ensnano_utils::{
     app_state_parameters::{
         check_xovers_parameter::CheckXoversParameter, suggestion_parameters::SuggestionParameters,
     },
-    export::ExportType,
+    export::{ExportType, VectorExportFormat},
     graphics::{Background3D, HBondDisplay, RenderingMode, SplitMode, fog_kind},
     keyboard_priority::PriorityRequest,
     surfaces::EquadiffSolvingMethod,
     ui_size::UiSize,
 };
-use iced::{Color, widget::text_input::Id};
+use iced::{
+    Color,
+    widget::{text_editor, text_input::Id},
+};
 use std::collections::{BTreeMap, VecDeque};
 use ultraviolet::{Rotor3, Vec2, Vec3};
 use winit::{
@@ -254,6 +262,41 @@ impl GuiMessages {
         self.left_panel.push_back(LeftPanelMessage::ForceHelp);
     }
 
+    pub fn open_command_palette(&mut self) {
+        self.left_panel
+            .push_back(LeftPanelMessage::OpenCommandPalette);
+    }
+
+    pub fn update_undo_history(&mut self, history: UndoHistory) {
+        self.left_panel
+            .push_back(LeftPanelMessage::NewUndoHistory(history));
+    }
+
+    pub fn relaxation_finished(&mut self) {
+        self.left_panel
+            .push_back(LeftPanelMessage::RelaxationFinished);
+    }
+
+    pub fn update_trajectory(&mut self, trajectory: TrajectorySummary) {
+        self.left_panel
+            .push_back(LeftPanelMessage::NewTrajectory(trajectory));
+    }
+
+    pub fn update_simulation_parameters(&mut self, parameters: SimulationParameters) {
+        self.left_panel
+            .push_back(LeftPanelMessage::NewSimulationParameters(parameters));
+    }
+
+    pub fn update_simulation_presets(&mut self, presets: Vec<SimulationPreset>) {
+        self.left_panel
+            .push_back(LeftPanelMessage::NewSimulationPresets(presets));
+    }
+
+    pub fn script_output(&mut self, output: Vec<String>) {
+        self.left_panel
+            .push_back(LeftPanelMessage::ScriptOutput(output));
+    }
+
     pub fn push_application_state(&mut self, state: AppState, top_bar_state: TopBarStateFlags) {
         log::trace!("Old ptr {:p}, new ptr {:p}", state, self.application_state);
         self.application_state = state.clone();
@@ -313,7 +356,31 @@ pub enum LeftPanelMessage {
         value: f32,
     },
     NewHyperboloid,
+    /// Edit the parameters of the selected hyperboloid grid.
+    EditHyperboloid,
     FinalizeHyperboloid,
+    /// Set the text of an input of the parametric helix, given in the order of
+    /// `ParametricHelixCreation::PARAMETERS`.
+    ParametricHelixInput(usize, String),
+    NewParametricHelix,
+    FinishParametricHelix,
+    /// Create a grid on a lattice read from a file.
+    ImportLattice,
+    /// Import helix axes from files of points.
+    ImportHelixAxes,
+    /// Whether imported helix axes are resampled to fit a whole number of nucleotides.
+    SetAxisResampling(bool),
+    /// Choose the 3D object from which wireframes are made and whose volume is filled.
+    SetMeshObject(External3DObjectId),
+    /// Whether wireframes have two helices per edge instead of one.
+    SetWireframeTwoHelices(bool),
+    MakeWireframe,
+    /// Choose the lattice on which the volume of 3D objects is filled.
+    SetVoxelizationGridType(GridTypeDescr),
+    SetVoxelizationAxis(VoxelizationAxis),
+    /// Whether a scaffold is routed through the helices filling the volume of 3D objects.
+    SetVoxelizationScaffoldRouting(bool),
+    VoxelizeMesh,
     RollTargeted(bool),
     /// Start or Stop Rigid Grid simulation.
     RigidGridSimulation(bool),
@@ -366,12 +433,38 @@ pub enum LeftPanelMessage {
     ShowHBonds(HBondDisplay),
     RainbowScaffold(bool),
     StopSimulation,
+    /// The relaxation stopped by itself because it converged or diverged.
+    RelaxationFinished,
+    NewTrajectory(TrajectorySummary),
+    SetTrajectoryRecording(bool),
+    /// The number of solver steps between two recorded frames.
+    SetTrajectoryInterval(f32),
+    ShowTrajectoryFrame(usize),
+    ClearTrajectory,
+    ExportTrajectory(ExportType),
+    AnalyseRigidity,
+    ClearRigidityAnalysis,
+    ApplyEquilibriumShape,
+    /// The simulation parameters saved in a newly opened design.
+    NewSimulationParameters(SimulationParameters),
+    NewSimulationPresets(Vec<SimulationPreset>),
+    ApplySimulationPreset(String),
+    SimulationPresetNameInput(String),
+    SaveSimulationPreset,
+    DeleteSimulationPreset,
+    CommitRelaxation,
+    ClearRelaxedPositions,
     FinishRelaxation,
     StartTwist,
     NewDnaParameters(NamedParameter),
     SetExpandInsertions(bool),
     InsertionLengthInput(String),
     InsertionLengthSubmitted,
+    SetDeletion {
+        helix: usize,
+        position: isize,
+        deleted: bool,
+    },
     NewBezierPlane,
     StartBezierPath,
     TurnPathIntoGrid {
@@ -394,7 +487,10 @@ pub enum LeftPanelMessage {
     InitRevolutionRelaxation,
     CancelExport,
     LoadSvgFile,
+    /// Choose the bezier plane on which SVG files are imported.
+    SetSvgImportPlane(BezierPlaneId),
     ScreenShot2D,
+    VectorExport2D(VectorExportFormat),
     ScreenShot3D,
     SaveNucleotidesPositions,
     IncrWinding,
@@ -408,6 +504,28 @@ pub enum LeftPanelMessage {
     SetKeyboardPriority(PriorityRequest),
     SetFocus(Id),
     ToggleExternalObjectsVisibility,
+    OpenCommandPalette,
+    CloseCommandPalette,
+    CommandPaletteInput(String),
+    /// Move the highlighted entry of the command palette by the given offset.
+    CommandPaletteMove(isize),
+    /// Run the highlighted entry of the command palette.
+    CommandPaletteSubmit,
+    /// Run the command of the palette with the given index.
+    CommandPaletteRun(usize),
+    OpenScriptConsole,
+    CloseScriptConsole,
+    ScriptEdit(text_editor::Action),
+    /// Run the content of the script console.
+    RunScript,
+    RunScriptFile,
+    /// The lines printed by the last script run.
+    ScriptOutput(Vec<String>),
+    NewUndoHistory(UndoHistory),
+    OpenUndoHistory,
+    CloseUndoHistory,
+    /// Undo or redo operations until the given number of operations lead to the current state.
+    JumpInHistory(usize),
 }
 
 /// Public messages generated by an Organizer.
@@ -430,6 +548,8 @@ pub enum OrganizerMessage {
     SetKeyboardPriority(PriorityRequest),
     /// Ask Iced application to focus on this element.
     SetFocus(Id),
+    /// Set the drawing style of a group.
+    NewDrawingStyle(GroupId, DrawingStyle),
 }
 
 #[derive(Clone, Debug)]
@@ -461,6 +581,23 @@ pub enum OrganizerInternalMessage {
     },
     /// Create a new group.
     NewGroup,
+    RuleInput {
+        rule: String,
+    },
+    /// Create a new smart group from the rule being typed.
+    NewSmartGroup,
+    StyleInput {
+        field: StyleField,
+        value: String,
+    },
+    /// Apply the value typed in a field of the drawing style of the selected group.
+    StyleSubmit {
+        field: StyleField,
+    },
+    StyleFlagPicked {
+        flag: StyleFlag,
+        value: Option<bool>,
+    },
     AddSelectionToGroup {
         id: OrganizerNodeId,
     },
@@ -521,6 +658,26 @@ impl OrganizerMessage {
         Self::InternalMessage(OrganizerInternalMessage::NewGroup)
     }
 
+    pub fn rule_input(rule: String) -> Self {
+        Self::InternalMessage(OrganizerInternalMessage::RuleInput { rule })
+    }
+
+    pub fn new_smart_group() -> Self {
+        Self::InternalMessage(OrganizerInternalMessage::NewSmartGroup)
+    }
+
+    pub fn style_input(field: StyleField, value: String) -> Self {
+        Self::InternalMessage(OrganizerInternalMessage::StyleInput { field, value })
+    }
+
+    pub fn style_submit(field: StyleField) -> Self {
+        Self::InternalMessage(OrganizerInternalMessage::StyleSubmit { field })
+    }
+
+    pub fn style_flag_picked(flag: StyleFlag, value: Option<bool>) -> Self {
+        Self::InternalMessage(OrganizerInternalMessage::StyleFlagPicked { flag, value })
+    }
+
     pub fn dragging(key: DragIdentifier) -> Self {
         Self::InternalMessage(OrganizerInternalMessage::Dragging(key))
     }
@@ -562,6 +719,7 @@ pub enum TopBarMessage {
     FlipSplitViews,
     ThickHelices(bool),
     Import3D,
+    InsertDesign,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -586,6 +744,10 @@ pub enum FactoryId {
     Scroll,
     RigidBody,
     Brownian,
+    SvgImport,
+    AxisImport,
+    Wireframe,
+    Voxelization,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
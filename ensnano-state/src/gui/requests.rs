@@ -1,3 +1,5 @@
+use ensnano_utils::RigidBodyConstants;
+
 #[derive(Clone)]
 pub struct RigidBodyParametersRequest {
     pub k_springs: f32,
@@ -8,3 +10,31 @@ pub struct RigidBodyParametersRequest {
     pub brownian_rate: f32,
     pub brownian_amplitude: f32,
 }
+
+impl RigidBodyParametersRequest {
+    /// The constants of the simulation. The stiffness, friction, mass and brownian rate of the
+    /// request are in log scale.
+    pub fn to_constants(&self) -> RigidBodyConstants {
+        RigidBodyConstants {
+            k_spring: 10f32.powf(self.k_springs),
+            k_friction: 10f32.powf(self.k_friction),
+            mass: 10f32.powf(self.mass_factor),
+            volume_exclusion: self.volume_exclusion,
+            brownian_motion: self.brownian_motion,
+            brownian_rate: 10f32.powf(self.brownian_rate),
+            brownian_amplitude: self.brownian_amplitude,
+        }
+    }
+
+    pub fn from_constants(constants: &RigidBodyConstants) -> Self {
+        Self {
+            k_springs: constants.k_spring.log10(),
+            k_friction: constants.k_friction.log10(),
+            mass_factor: constants.mass.log10(),
+            volume_exclusion: constants.volume_exclusion,
+            brownian_motion: constants.brownian_motion,
+            brownian_rate: constants.brownian_rate.log10(),
+            brownian_amplitude: constants.brownian_amplitude,
+        }
+    }
+}
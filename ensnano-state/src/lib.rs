@@ -1,9 +1,14 @@
 pub mod app_state;
+pub mod autosave;
 pub mod design;
 pub mod gui;
 pub mod multiplexer;
 pub mod operation;
 pub mod requests;
+pub mod rigidity;
 pub mod scene;
+pub mod scripting;
+pub mod simulation_presets;
 pub mod state;
+pub mod trajectory;
 pub mod utils;
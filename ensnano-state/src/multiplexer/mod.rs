@@ -16,9 +16,9 @@ mod layout_manager;
 
 use crate::multiplexer::layout_manager::{LayoutTree, PixelRegion};
 use crate::{app_state::action::Action, requests::Requests};
-use ensnano_design::interaction_modes::{ActionMode, SelectionMode};
 use ensnano_utils::{
     graphics::{DrawArea, GuiComponentType, PhySize, SplitMode},
+    keymap::{Keymap, KeymapScope},
     texture::SampledTexture,
     ui_size::UiSize,
 };
@@ -30,7 +30,7 @@ use std::{
 use winit::{
     dpi::{PhysicalPosition, PhysicalSize},
     event::{ElementState, KeyEvent, Modifiers, WindowEvent},
-    keyboard::{Key, KeyLocation, ModifiersState, NamedKey},
+    keyboard::{Key, KeyLocation, ModifiersState},
     window::{CursorIcon, Window},
 };
 
@@ -364,6 +364,7 @@ impl Multiplexer {
         mut event: WindowEvent,
         resized: &mut bool,
         scale_factor_changed: &mut bool,
+        keymap: &Keymap,
     ) -> Option<(WindowEvent, GuiComponentType)> {
         let mut captured = false;
         match &mut event {
@@ -498,99 +499,22 @@ impl Multiplexer {
                     },
                 ..
             } => {
-                // NOTE: Gui keyboard shortcuts are defined and handled here.
+                // NOTE: Gui keyboard shortcuts are defined in the keymap and handled here.
                 captured = true;
-                match logical_key.as_ref() {
-                    Key::Named(NamedKey::Escape) => {
-                        self.requests.lock().unwrap().action_mode = Some(ActionMode::Normal);
-                    }
-                    Key::Character("X" | "x") if self.modifiers_state.alt_key() => {
-                        self.requests.lock().unwrap().keep_proceed.push_back(
-                            Action::MakeAllSuggestedXover {
-                                doubled: self.modifiers_state.shift_key(),
-                            },
-                        );
-                    }
-                    Key::Character("X" | "x") => {
-                        self.requests.lock().unwrap().toggle_all_helices_on_axis = Some(());
-                    }
-                    Key::Character("Z" | "z") if control_key(&self.modifiers_state) => {
-                        if self.modifiers_state.shift_key() {
-                            self.requests.lock().unwrap().redo = Some(());
-                        } else {
-                            self.requests.lock().unwrap().undo = Some(());
-                        }
-                    }
-                    Key::Character("R" | "r") if control_key(&self.modifiers_state) => {
-                        self.requests.lock().unwrap().redo = Some(());
-                    }
-                    Key::Character("C" | "c") if control_key(&self.modifiers_state) => {
-                        self.requests.lock().unwrap().copy = Some(());
-                    }
-                    Key::Character("V" | "v") if control_key(&self.modifiers_state) => {
-                        self.requests.lock().unwrap().paste = Some(());
-                    }
-                    Key::Character("J" | "j") if control_key(&self.modifiers_state) => {
-                        self.requests.lock().unwrap().duplication = Some(());
-                    }
-                    Key::Character("L" | "l") if control_key(&self.modifiers_state) => {
-                        self.requests.lock().unwrap().anchor = Some(());
-                    }
-                    Key::Character("R" | "r") if !control_key(&self.modifiers_state) => {
-                        self.requests.lock().unwrap().action_mode = Some(ActionMode::Rotate);
-                    }
-                    Key::Character("T" | "t") => {
-                        self.requests.lock().unwrap().action_mode = Some(ActionMode::Translate);
-                    }
-                    Key::Character("N" | "n") => {
-                        self.requests.lock().unwrap().selection_mode =
-                            Some(SelectionMode::Nucleotide);
-                    }
-                    Key::Character("H" | "h") => {
-                        self.requests.lock().unwrap().selection_mode = Some(SelectionMode::Helix);
-                    }
-                    Key::Character("S" | "s") if control_key(&self.modifiers_state) => {
-                        self.requests.lock().unwrap().save_shortcut = Some(());
-                    }
-                    Key::Character("O" | "o") if control_key(&self.modifiers_state) => {
-                        self.requests
-                            .lock()
-                            .unwrap()
-                            .keep_proceed
-                            .push_back(Action::LoadDesign(None));
-                    }
-                    Key::Character("Q" | "q")
-                        if control_key(&self.modifiers_state) && cfg!(target_os = "macos") =>
-                    {
-                        self.requests
-                            .lock()
-                            .unwrap()
-                            .keep_proceed
-                            .push_back(Action::Exit);
-                    }
-                    Key::Character("S" | "s") => {
-                        self.requests.lock().unwrap().selection_mode = Some(SelectionMode::Strand);
-                    }
-                    Key::Character("K" | "k") => {
-                        self.requests.lock().unwrap().recolor_staples = Some(());
-                    }
-                    Key::Named(NamedKey::Delete | NamedKey::Backspace) => {
-                        self.requests.lock().unwrap().delete_selection = Some(());
-                    }
-                    Key::Character("0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9")
-                        if location == &KeyLocation::Standard =>
-                    {
-                        if let Some(num) = keycode_to_num(logical_key, location) {
-                            self.requests
-                                .lock()
-                                .unwrap()
-                                .keep_proceed
-                                .push_back(Action::SelectFavoriteCamera(num));
-                        } else {
-                            captured = false;
-                        }
-                    }
-                    _ => captured = false,
+                if let Some(action) =
+                    keymap.action_for_key(KeymapScope::Global, logical_key, &self.modifiers_state)
+                {
+                    self.requests.lock().unwrap().apply_keymap_action(action);
+                } else if location == &KeyLocation::Standard
+                    && let Some(num) = keycode_to_num(logical_key, location)
+                {
+                    self.requests
+                        .lock()
+                        .unwrap()
+                        .keep_proceed
+                        .push_back(Action::SelectFavoriteCamera(num));
+                } else {
+                    captured = false;
                 }
             }
             _ => {}
@@ -842,15 +766,6 @@ impl State {
     }
 }
 
-/// MaxOS, Windows and Linux compatible modifier key.
-fn control_key(modifiers: &ModifiersState) -> bool {
-    if cfg!(target_os = "macos") {
-        modifiers.super_key() // ❖ or ⌘
-    } else {
-        modifiers.control_key() // Ctrl
-    }
-}
-
 // impl MultiplexerExt for Multiplexer {
 //     fn get_draw_area(&self, element_type: GuiComponentType) -> Option<DrawArea> {
 //         self.get_draw_area(element_type)
@@ -20,6 +20,11 @@ impl Requests {
         self.operation_update = Some(operation);
     }
 
+    /// Show an error message to the user.
+    pub fn show_error(&mut self, message: String) {
+        self.keep_proceed.push_back(Action::ErrorMsg(message));
+    }
+
     pub fn set_isometry(&mut self, helix: usize, segment_idx: usize, isometry: Isometry2) {
         self.keep_proceed.push_back(Action::SilentDesignOperation(
             DesignOperation::SetIsometry {
@@ -1,18 +1,26 @@
 use crate::{
     app_state::action::Action,
-    design::operation::{DesignOperation, HyperboloidRequest, InsertionPoint},
+    design::operation::{
+        DesignOperation, HyperboloidOperation, HyperboloidRequest, InsertionPoint,
+    },
     gui::requests::RigidBodyParametersRequest,
     requests::Requests,
+    simulation_presets::SimulationPreset,
     utils::{application::Notification, operation::SimpleOperation},
 };
 use ensnano_design::{
     CameraId,
-    bezier_plane::{BezierPathId, BezierVertexId},
+    bezier_plane::{BezierPathId, BezierVertexId, import_from_svg::SvgImportParameters},
+    curves::import_from_points::AxisImportParameters,
     design_element::{DesignElementKey, DnaAttribute},
+    drawing_style::DrawingStyle,
+    external_3d_objects::External3DObjectId,
     grid::{GridId, GridTypeDescr},
     interaction_modes::{ActionMode, SelectionMode},
     organizer_tree::{GroupId, OrganizerTree},
     parameters::HelixParameters,
+    voxelization::VoxelizationParameters,
+    wireframe::WireframeParameters,
 };
 use ensnano_physics::parameters::RapierParameters;
 use ensnano_utils::{
@@ -20,14 +28,15 @@ use ensnano_utils::{
     app_state_parameters::{
         check_xovers_parameter::CheckXoversParameter, suggestion_parameters::SuggestionParameters,
     },
-    export::ExportType,
+    export::{ExportType, VectorExportFormat},
     graphics::{Background3D, FogParameters, HBondDisplay, RenderingMode, SplitMode},
     keyboard_priority::PriorityRequest,
+    keymap::KeymapAction,
     overlay::OverlayType,
     surfaces::{RevolutionSurfaceSystemDescriptor, UnrootedRevolutionSurfaceDescriptor},
     ui_size::UiSize,
 };
-use std::{collections::BTreeSet, sync::Arc};
+use std::{collections::BTreeSet, num::NonZeroUsize, path::PathBuf, sync::Arc};
 use ultraviolet::{Rotor3, Vec2, Vec3};
 
 impl Requests {
@@ -151,6 +160,15 @@ impl Requests {
         self.hyperboloid_update = Some(parameters);
     }
 
+    pub fn edit_hyperboloid(&mut self, grid_id: GridId, parameters: HyperboloidRequest) {
+        self.keep_proceed.push_back(Action::DesignOperation(
+            DesignOperation::HyperboloidOperation(HyperboloidOperation::Edit {
+                grid_id,
+                request: parameters,
+            }),
+        ));
+    }
+
     pub fn update_roll_of_selected_helices(&mut self, roll: f32) {
         self.helix_roll = Some(roll);
     }
@@ -196,6 +214,10 @@ impl Requests {
         self.new_tree = Some(tree);
     }
 
+    pub fn set_group_drawing_style(&mut self, group_id: GroupId, style: DrawingStyle) {
+        self.new_group_drawing_style = Some((group_id, style));
+    }
+
     pub fn update_attribute_of_elements(
         &mut self,
         attribute: DnaAttribute,
@@ -232,6 +254,52 @@ impl Requests {
         self.show_tutorial = Some(());
     }
 
+    pub fn open_command_palette(&mut self) {
+        self.open_command_palette = Some(());
+    }
+
+    /// Forward the request associated to a global keyboard shortcut.
+    pub fn apply_keymap_action(&mut self, action: KeymapAction) {
+        match action {
+            KeymapAction::ActionModeSelect => self.action_mode = Some(ActionMode::Normal),
+            KeymapAction::ActionModeRotate => self.action_mode = Some(ActionMode::Rotate),
+            KeymapAction::ActionModeTranslate => {
+                self.action_mode = Some(ActionMode::Translate);
+            }
+            KeymapAction::SelectionModeNucleotide => {
+                self.selection_mode = Some(SelectionMode::Nucleotide);
+            }
+            KeymapAction::SelectionModeStrand => {
+                self.selection_mode = Some(SelectionMode::Strand);
+            }
+            KeymapAction::SelectionModeHelix => {
+                self.selection_mode = Some(SelectionMode::Helix);
+            }
+            KeymapAction::MakeAllSuggestedXovers => self
+                .keep_proceed
+                .push_back(Action::MakeAllSuggestedXover { doubled: false }),
+            KeymapAction::MakeAllSuggestedDoubleXovers => self
+                .keep_proceed
+                .push_back(Action::MakeAllSuggestedXover { doubled: true }),
+            KeymapAction::ToggleAllHelicesOnAxis => {
+                self.toggle_all_helices_on_axis = Some(());
+            }
+            KeymapAction::Undo => self.undo = Some(()),
+            KeymapAction::Redo => self.redo = Some(()),
+            KeymapAction::Copy => self.copy = Some(()),
+            KeymapAction::Paste => self.paste = Some(()),
+            KeymapAction::Duplicate => self.duplication = Some(()),
+            KeymapAction::TurnIntoAnchor => self.anchor = Some(()),
+            KeymapAction::Save => self.save_shortcut = Some(()),
+            KeymapAction::Open => self.keep_proceed.push_back(Action::LoadDesign(None)),
+            KeymapAction::Quit => self.keep_proceed.push_back(Action::Exit),
+            KeymapAction::RecolorStaples => self.recolor_staples = Some(()),
+            KeymapAction::DeleteSelection => self.delete_selection = Some(()),
+            KeymapAction::CommandPalette => self.open_command_palette = Some(()),
+            _ => unreachable!("{action:?} is not a global action"),
+        }
+    }
+
     pub fn new_design(&mut self) {
         self.keep_proceed.push_back(Action::NewDesign);
     }
@@ -257,6 +325,10 @@ impl Requests {
         self.suspend_op = Some(());
     }
 
+    pub fn finish_pending_operation(&mut self) {
+        self.finish_pending_op = Some(());
+    }
+
     pub fn set_scaffold_id(&mut self, s_id: Option<usize>) {
         self.set_scaffold_id = Some(s_id);
     }
@@ -281,6 +353,62 @@ impl Requests {
         self.keep_proceed.push_back(Action::ResetSimulation);
     }
 
+    pub fn commit_relaxation(&mut self) {
+        self.keep_proceed.push_back(Action::CommitRelaxation);
+    }
+
+    pub fn clear_relaxed_positions(&mut self) {
+        self.keep_proceed.push_back(Action::DesignOperation(
+            DesignOperation::ClearRelaxedPositions,
+        ));
+    }
+
+    pub fn set_trajectory_recording(&mut self, recording: bool) {
+        self.keep_proceed
+            .push_back(Action::SetTrajectoryRecording(recording));
+    }
+
+    pub fn set_trajectory_interval(&mut self, interval: NonZeroUsize) {
+        self.keep_proceed
+            .push_back(Action::SetTrajectoryInterval(interval));
+    }
+
+    pub fn show_trajectory_frame(&mut self, frame: usize) {
+        self.keep_proceed
+            .push_back(Action::ShowTrajectoryFrame(frame));
+    }
+
+    pub fn clear_trajectory(&mut self) {
+        self.keep_proceed.push_back(Action::ClearTrajectory);
+    }
+
+    pub fn export_trajectory(&mut self, export_type: ExportType) {
+        self.keep_proceed
+            .push_back(Action::ExportTrajectory(export_type));
+    }
+
+    pub fn save_simulation_preset(&mut self, preset: SimulationPreset) {
+        self.keep_proceed
+            .push_back(Action::SaveSimulationPreset(preset));
+    }
+
+    pub fn delete_simulation_preset(&mut self, name: String) {
+        self.keep_proceed
+            .push_back(Action::DeleteSimulationPreset(name));
+    }
+
+    pub fn analyse_rigidity(&mut self) {
+        self.keep_proceed.push_back(Action::AnalyseRigidity);
+    }
+
+    pub fn clear_rigidity_analysis(&mut self) {
+        self.keep_proceed.push_back(Action::ClearRigidityAnalysis);
+    }
+
+    pub fn apply_equilibrium_shape(&mut self) {
+        self.keep_proceed.push_back(Action::ApplyEquilibriumShape);
+    }
+
     pub fn reload_file(&mut self) {
         self.keep_proceed.push_back(Action::ReloadFile);
     }
@@ -418,6 +546,15 @@ impl Requests {
         ));
     }
 
+    pub fn set_deletion(&mut self, helix: usize, position: isize, deleted: bool) {
+        self.keep_proceed
+            .push_back(Action::DesignOperation(DesignOperation::SetDeletion {
+                helix,
+                position,
+                deleted,
+            }));
+    }
+
     pub fn turn_path_into_grid(&mut self, path_id: BezierPathId, grid_type: GridTypeDescr) {
         self.keep_proceed.push_back(Action::DesignOperation(
             DesignOperation::TurnPathVerticesIntoGrid { path_id, grid_type },
@@ -461,8 +598,61 @@ impl Requests {
             .push_back(Action::FinishRelaxationSimulation);
     }
 
-    pub fn load_svg(&mut self) {
-        self.keep_proceed.push_back(Action::ImportSvg);
+    pub fn load_svg(&mut self, parameters: SvgImportParameters) {
+        self.keep_proceed.push_back(Action::ImportSvg(parameters));
+    }
+
+    pub fn import_lattice(&mut self) {
+        self.keep_proceed.push_back(Action::ImportLattice);
+    }
+
+    pub fn import_helix_axes(&mut self, parameters: AxisImportParameters) {
+        self.keep_proceed
+            .push_back(Action::ImportHelixAxes(parameters));
+    }
+
+    pub fn insert_design(&mut self) {
+        self.keep_proceed.push_back(Action::InsertDesign);
+    }
+
+    pub fn make_wireframe(
+        &mut self,
+        object_id: External3DObjectId,
+        mesh_path: PathBuf,
+        parameters: WireframeParameters,
+    ) {
+        self.keep_proceed
+            .push_back(Action::DesignOperation(DesignOperation::MakeWireframe {
+                object_id,
+                mesh_path,
+                parameters,
+            }));
+    }
+
+    pub fn voxelize_mesh(
+        &mut self,
+        object_id: External3DObjectId,
+        mesh_path: PathBuf,
+        parameters: VoxelizationParameters,
+    ) {
+        self.keep_proceed
+            .push_back(Action::DesignOperation(DesignOperation::VoxelizeMesh {
+                object_id,
+                mesh_path,
+                parameters,
+            }));
+    }
+
+    pub fn jump_in_history(&mut self, position: usize) {
+        self.keep_proceed.push_back(Action::JumpInHistory(position));
+    }
+
+    pub fn run_script(&mut self, source: String) {
+        self.keep_proceed.push_back(Action::RunScript(source));
+    }
+
+    pub fn run_script_file(&mut self) {
+        self.keep_proceed.push_back(Action::RunScriptFile);
     }
 
     pub fn set_bezier_revolution_id(&mut self, id: Option<usize>) {
@@ -480,6 +670,19 @@ impl Requests {
             }));
     }
 
+    pub fn request_vector_export_2d(&mut self, format: VectorExportFormat) {
+        let notification: fn(_) -> _ = match format {
+            VectorExportFormat::Svg => {
+                |path| Notification::VectorExport2D(path, VectorExportFormat::Svg)
+            }
+            VectorExportFormat::Pdf => {
+                |path| Notification::VectorExport2D(path, VectorExportFormat::Pdf)
+            }
+        };
+        self.keep_proceed
+            .push_back(Action::GetDesignPathAndNotify(notification));
+    }
+
     pub fn request_screenshot_3d(&mut self) {
         self.keep_proceed
             .push_back(Action::GetDesignPathAndNotify(|path| {
@@ -521,15 +724,7 @@ impl Requests {
 }
 
 fn rigid_parameters(parameters: RigidBodyParametersRequest) -> RigidBodyConstants {
-    let ret = RigidBodyConstants {
-        k_spring: 10f32.powf(parameters.k_springs),
-        k_friction: 10f32.powf(parameters.k_friction),
-        mass: 10f32.powf(parameters.mass_factor),
-        volume_exclusion: parameters.volume_exclusion,
-        brownian_motion: parameters.brownian_motion,
-        brownian_rate: 10f32.powf(parameters.brownian_rate),
-        brownian_amplitude: parameters.brownian_amplitude,
-    };
+    let ret = parameters.to_constants();
     log::info!("rigid parameters {ret:?}");
     ret
 }
@@ -17,6 +17,7 @@ use crate::{
 };
 use ensnano_design::{
     design_element::{DesignElementKey, DnaAttribute},
+    drawing_style::DrawingStyle,
     grid::{GridId, GridPosition, GridTypeDescr},
     interaction_modes::{ActionMode, SelectionMode},
     nucl::Nucl,
@@ -85,6 +86,7 @@ pub struct Requests {
     pub organizer_candidates: Option<Vec<DesignElementKey>>,
     pub new_attribute: Option<(DnaAttribute, Vec<DesignElementKey>)>,
     pub new_tree: Option<OrganizerTree>,
+    pub new_group_drawing_style: Option<(GroupId, DrawingStyle)>,
     pub split2d: Option<()>,
     pub toggle_visibility: Option<bool>,
     pub all_visible: Option<()>,
@@ -98,11 +100,13 @@ pub struct Requests {
     pub redo: Option<()>,
     pub save_shortcut: Option<()>,
     pub force_help: Option<()>,
+    pub open_command_palette: Option<()>,
     pub show_tutorial: Option<()>,
     pub clean_requests: Option<()>,
     pub new_candidates: Option<Vec<Selection>>,
     pub new_selection: Option<Vec<Selection>>,
     pub suspend_op: Option<()>,
+    pub finish_pending_op: Option<()>,
     pub center_selection: Option<(Selection, AppId)>,
     pub toggle_widget_basis: Option<()>,
     pub stop_roll: Option<()>,
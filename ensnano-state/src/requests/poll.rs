@@ -184,6 +184,12 @@ pub fn poll_all(requests: &mut Requests, main_state: &mut MainState) {
         )));
     }
 
+    if let Some((group_id, style)) = requests.new_group_drawing_style.take() {
+        main_state.push_action(Action::DesignOperation(
+            DesignOperation::SetGroupDrawingStyle { group_id, style },
+        ));
+    }
+
     if requests.clean_requests.take().is_some() {
         main_state.push_action(Action::DesignOperation(DesignOperation::CleanDesign));
     }
@@ -247,6 +253,10 @@ pub fn poll_all(requests: &mut Requests, main_state: &mut MainState) {
         main_state.messages.lock().unwrap().show_help();
     }
 
+    if requests.open_command_palette.take().is_some() {
+        main_state.messages.lock().unwrap().open_command_palette();
+    }
+
     if let Some(candidates) = requests.new_candidates.take() {
         main_state.update_candidates(candidates);
     }
@@ -270,6 +280,10 @@ pub fn poll_all(requests: &mut Requests, main_state: &mut MainState) {
         requests.keep_proceed.push_back(Action::SuspendOp);
     }
 
+    if requests.finish_pending_op.take().is_some() {
+        requests.keep_proceed.push_back(Action::FinishPendingOp);
+    }
+
     if requests.horizon_targeted.take().is_some() {
         main_state
             .pending_actions
@@ -0,0 +1,108 @@
+//! Display of the elastic network analysis of a design.
+//!
+//! While an analysis is shown, the nucleotides of the 3D scene are colored according to their
+//! fluctuations, from blue for the most rigid ones to red for the most flexible ones. The analysis
+//! is forgotten as soon as the design is modified.
+
+use crate::app_state::address_pointer::AddressPointer;
+use ensnano_design::{Design, nucl::Nucl};
+use ensnano_physics::elastic_network::{ElasticNetworkError, RigidityAnalysis};
+use std::{collections::HashMap, sync::Arc};
+use ultraviolet::Vec3;
+
+/// The color of the nucleotides that are not part of the analysis, such as single strands.
+const UNANALYSED_COLOR: u32 = 0xFF_A0_A0_A0;
+
+/// The outcome of an analysis run in a separate thread, with the design that was analysed.
+pub struct RigidityAnalysisResult {
+    pub design: AddressPointer<Design>,
+    pub heat_map: Result<RigidityHeatMap, ElasticNetworkError>,
+}
+
+#[derive(Clone)]
+pub struct RigidityHeatMap {
+    analysis: Arc<RigidityAnalysis>,
+    min: f32,
+    max: f32,
+}
+
+impl PartialEq for RigidityHeatMap {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.analysis, &other.analysis)
+    }
+}
+
+impl RigidityHeatMap {
+    pub fn new(analysis: RigidityAnalysis) -> Self {
+        let (min, max) = analysis
+            .fluctuations
+            .values()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), f| {
+                (min.min(*f), max.max(*f))
+            });
+        Self {
+            analysis: Arc::new(analysis),
+            min,
+            max,
+        }
+    }
+
+    pub fn analysis(&self) -> &RigidityAnalysis {
+        &self.analysis
+    }
+
+    /// The color of an element made of `nucls`, according to their mean fluctuation.
+    pub fn color(&self, nucls: &[Nucl]) -> u32 {
+        let fluctuations = nucls
+            .iter()
+            .filter_map(|nucl| self.analysis.fluctuations.get(nucl))
+            .collect::<Vec<_>>();
+        if fluctuations.is_empty() {
+            return UNANALYSED_COLOR;
+        }
+        let mean = fluctuations.iter().copied().sum::<f32>() / fluctuations.len() as f32;
+        let range = self.max - self.min;
+        let t = if range > f32::EPSILON {
+            (mean - self.min) / range
+        } else {
+            0.
+        };
+        heat_color(t)
+    }
+
+    /// The positions of the nucleotides in the equilibrium shape, given their current
+    /// `positions`.
+    pub fn equilibrium_positions(&self, positions: &HashMap<Nucl, Vec3>) -> Vec<(Nucl, Vec3)> {
+        positions
+            .iter()
+            .map(|(nucl, position)| {
+                let displacement = self
+                    .analysis
+                    .displacements
+                    .get(nucl)
+                    .copied()
+                    .unwrap_or_default();
+                (*nucl, *position + displacement)
+            })
+            .collect()
+    }
+}
+
+/// Map `t` in [0, 1] to a color going from blue to red.
+fn heat_color(t: f32) -> u32 {
+    let hue = 240. * (1. - t.clamp(0., 1.) as f64);
+    let rgb = color_space::Rgb::from(color_space::Hsv::new(hue, 1., 1.));
+    (0xFF << 24) | ((rgb.r as u32) << 16) | ((rgb.g as u32) << 8) | (rgb.b as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heat_colors_go_from_blue_to_red() {
+        assert_eq!(heat_color(0.), 0xFF_00_00_FF);
+        assert_eq!(heat_color(1.), 0xFF_FF_00_00);
+        assert_eq!(heat_color(2.), heat_color(1.));
+    }
+}
@@ -0,0 +1,654 @@
+//! This module defines the scripting engine used to automate design edits.
+//!
+//! Scripts are written in [Rhai](https://rhai.rs). They can read the helices, strands and grids of
+//! the design, and modify it through [DesignOperation]s, exactly as the graphical components do.
+//! The whole script is run on a working copy of the [AppState], so that a script run can be
+//! reverted or undone as a single operation. Scripts can take a long time, for example when they
+//! relax the design, so the application runs them in a separate thread with [ScriptThread].
+//!
+//! Nucleotides are represented in scripts by maps of the form
+//! `#{helix: 0, position: 12, forward: true}`, which can be built with the `nucl` function.
+//!
+//! The functions available to scripts are:
+//! * reading the design: `helices()`, `helix(id)`, `strands()`, `strand(id)`, `strand_of(nucl)`,
+//!   `scaffold_id()`, `grids()`;
+//! * selection: `selected_strands()`, `selected_helices()`, `select_strands(ids)`,
+//!   `select_helices(ids)`, `clear_selection()`;
+//! * editing the design: `recolor_staples()`, `set_color(strand, color)`,
+//!   `set_strand_name(strand, name)`, `delete_strands(ids)`, `delete_helices(ids)`,
+//!   `add_helix(grid, x, y, start, length)`, `set_roll(helices, roll)`,
+//!   `set_helix_visibility(helix, visible)`, `cut(nucl)`, `xover(source, target)`,
+//!   `insert_after(nucl, length)`, `insert_before(nucl, length)`,
+//!   `set_deletion(helix, position, deleted)`, `set_scaffold(strand)`,
+//!   `unset_scaffold()`, `set_scaffold_sequence(sequence, shift)`;
+//! * simulation: `relax(tolerance, max_steps)` relaxes the design with the default simulation
+//!   parameters, stores the relaxed positions in the design and returns
+//!   `#{converged, steps, energy}`;
+//! * exports: `export_design(format, path)` where format is `"oxdna"`, `"pdb"`, `"cadnano"` or `"scadnano"`.
+
+use crate::{
+    app_state::AppState,
+    design::{
+        operation::{DesignOperation, InsertionPoint},
+        selection::{Selection, extract_strands_from_selection, list_of_helices},
+    },
+};
+use ensnano_design::{
+    Design,
+    domains::Domain,
+    grid::{GridId, GridTypeDescr, HelixGridPosition},
+    nucl::Nucl,
+};
+use ensnano_physics::{
+    parameters::RapierParameters,
+    relaxation::{RelaxationCriteria, RelaxationOutcome},
+};
+use ensnano_utils::export::ExportType;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FLOAT, INT, Map};
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    rc::Rc,
+    sync::mpsc::{self, TryRecvError},
+    thread,
+};
+
+/// The maximum number of operations that a script can perform, to prevent an infinite loop from
+/// freezing the application.
+const MAX_SCRIPT_OPERATIONS: u64 = 100_000_000;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// The outcome of a successful script run.
+#[derive(Debug, Default)]
+pub struct ScriptReport {
+    /// The lines printed by the script.
+    pub output: Vec<String>,
+    /// The exports requested by the script, in the order in which they were requested.
+    ///
+    /// Exports need access to the rendered design, so they are performed by the caller once the
+    /// script has been applied.
+    pub exports: Vec<(ExportType, PathBuf)>,
+}
+
+/// The error returned when a script could not be run until the end.
+#[derive(Debug)]
+pub struct ScriptError {
+    pub message: String,
+    /// The lines printed by the script before the error occurred.
+    pub output: Vec<String>,
+}
+
+/// The state modified by a successful script run, and the report of the run.
+pub type ScriptOutcome = Result<(AppState, ScriptReport), ScriptError>;
+
+/// A script running in a separate thread.
+pub struct ScriptThread(mpsc::Receiver<ScriptOutcome>);
+
+impl ScriptThread {
+    /// Run `source` on a copy of `app_state` in a new thread.
+    pub fn spawn(app_state: &AppState, source: String) -> Self {
+        let mut app_state = app_state.clone();
+        let (snd, rcv) = mpsc::channel();
+        thread::spawn(move || {
+            let outcome = run_script(&mut app_state, &source).map(|report| (app_state, report));
+            if snd.send(outcome).is_err() {
+                log::warn!("The result of the script was not received");
+            }
+        });
+        Self(rcv)
+    }
+
+    /// The outcome of the script if it has finished.
+    pub fn outcome(&self) -> Option<ScriptOutcome> {
+        match self.0.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(ScriptError {
+                message: "The script stopped unexpectedly".to_owned(),
+                output: Vec::new(),
+            })),
+        }
+    }
+}
+
+/// The state shared by all the functions available to scripts.
+struct ScriptContext {
+    app_state: AppState,
+    report: ScriptReport,
+}
+
+/// Run `source` on `app_state`.
+///
+/// If the script fails, `app_state` is left untouched.
+pub fn run_script(app_state: &mut AppState, source: &str) -> Result<ScriptReport, ScriptError> {
+    let context = Rc::new(RefCell::new(ScriptContext {
+        app_state: app_state.clone(),
+        report: ScriptReport::default(),
+    }));
+
+    let result = make_engine(&context).run(source);
+
+    let mut context = context.borrow_mut();
+    let report = std::mem::take(&mut context.report);
+    match result {
+        Ok(()) => {
+            *app_state = context.app_state.clone();
+            Ok(report)
+        }
+        Err(e) => Err(ScriptError {
+            message: e.to_string(),
+            output: report.output,
+        }),
+    }
+}
+
+impl ScriptContext {
+    fn design(&self) -> &Design {
+        &self.app_state.0.design.design
+    }
+
+    fn apply(&mut self, operation: DesignOperation) -> ScriptResult<()> {
+        let label = operation.label();
+        self.app_state
+            .apply_design_op_and_select(operation)
+            .map_err(|e| format!("{label}: {e:?}"))?;
+        self.app_state.update();
+        Ok(())
+    }
+
+    fn select(&mut self, selection: &[Selection]) -> ScriptResult<()> {
+        self.app_state
+            .set_selection(selection, &None)
+            .map_err(|e| format!("{e:?}"))?;
+        Ok(())
+    }
+
+    fn helix(&self, id: INT) -> ScriptResult<Map> {
+        let helix = self
+            .design()
+            .helices
+            .get(&to_usize(id)?)
+            .ok_or_else(|| format!("Helix {id} does not exist"))?;
+        let mut ret = Map::new();
+        ret.insert("id".into(), id.into());
+        ret.insert("visible".into(), helix.visible.into());
+        ret.insert("roll".into(), FLOAT::from(helix.roll).into());
+        let grid_position = helix.grid_position.map_or(Dynamic::UNIT, |p| {
+            let mut position = Map::new();
+            position.insert("grid".into(), grid_to_dynamic(p.grid));
+            position.insert("x".into(), (p.x as INT).into());
+            position.insert("y".into(), (p.y as INT).into());
+            position.insert("axis_pos".into(), (p.axis_pos as INT).into());
+            position.into()
+        });
+        ret.insert("grid_position".into(), grid_position);
+        let deletions: Array = helix.deletions.iter().map(|d| (*d as INT).into()).collect();
+        ret.insert("deletions".into(), deletions.into());
+        Ok(ret)
+    }
+
+    fn strand(&self, id: INT) -> ScriptResult<Map> {
+        let s_id = to_usize(id)?;
+        let design = self.design();
+        let strand = design
+            .strands
+            .get(&s_id)
+            .ok_or_else(|| format!("Strand {id} does not exist"))?;
+        let domains: Array = strand
+            .domains
+            .iter()
+            .map(|d| {
+                let mut domain = Map::new();
+                match d {
+                    Domain::HelixDomain(interval) => {
+                        domain.insert("helix".into(), (interval.helix as INT).into());
+                        domain.insert("start".into(), (interval.start as INT).into());
+                        domain.insert("end".into(), (interval.end as INT).into());
+                        domain.insert("forward".into(), interval.forward.into());
+                    }
+                    Domain::Insertion { nb_nucl, .. } => {
+                        domain.insert("insertion".into(), (*nb_nucl as INT).into());
+                    }
+                }
+                domain.into()
+            })
+            .collect();
+        let mut ret = Map::new();
+        ret.insert("id".into(), id.into());
+        ret.insert(
+            "name".into(),
+            strand
+                .name
+                .as_ref()
+                .map_or(Dynamic::UNIT, |n| n.to_string().into()),
+        );
+        ret.insert("color".into(), INT::from(strand.color).into());
+        ret.insert("is_cyclic".into(), strand.is_cyclic.into());
+        ret.insert(
+            "is_scaffold".into(),
+            (design.scaffold_id == Some(s_id)).into(),
+        );
+        ret.insert("length".into(), (strand.length() as INT).into());
+        ret.insert("domains".into(), domains.into());
+        ret.insert(
+            "prime5".into(),
+            strand.get_5prime().map_or(Dynamic::UNIT, nucl_to_dynamic),
+        );
+        ret.insert(
+            "prime3".into(),
+            strand.get_3prime().map_or(Dynamic::UNIT, nucl_to_dynamic),
+        );
+        Ok(ret)
+    }
+
+    fn grids(&self) -> Array {
+        self.design()
+            .free_grids
+            .iter()
+            .map(|(id, grid)| {
+                let kind = match &grid.grid_type {
+                    GridTypeDescr::Square { .. } => "square",
+                    GridTypeDescr::Honeycomb { .. } => "honeycomb",
+                    GridTypeDescr::RotatedHoneycomb { .. } => "rotated_honeycomb",
+                    GridTypeDescr::Hyperboloid { .. } => "nanotube",
+                    GridTypeDescr::Lattice { .. } => "lattice",
+                };
+                let mut ret = Map::new();
+                ret.insert("id".into(), (id.0 as INT).into());
+                ret.insert("kind".into(), kind.into());
+                ret.insert("visible".into(), (!grid.invisible).into());
+                ret.into()
+            })
+            .collect()
+    }
+}
+
+fn make_engine(context: &Rc<RefCell<ScriptContext>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+
+    let ctx = Rc::clone(context);
+    engine.on_print(move |line| ctx.borrow_mut().report.output.push(line.to_owned()));
+    let ctx = Rc::clone(context);
+    engine.on_debug(move |line, _, _| ctx.borrow_mut().report.output.push(line.to_owned()));
+
+    engine.register_fn("nucl", |helix: INT, position: INT, forward: bool| {
+        let mut ret = Map::new();
+        ret.insert("helix".into(), helix.into());
+        ret.insert("position".into(), position.into());
+        ret.insert("forward".into(), forward.into());
+        ret
+    });
+
+    // Read access to the design.
+    let ctx = Rc::clone(context);
+    engine.register_fn("helices", move || -> Array {
+        ctx.borrow()
+            .design()
+            .helices
+            .keys()
+            .map(|h| (*h as INT).into())
+            .collect()
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("helix", move |id: INT| ctx.borrow().helix(id));
+    let ctx = Rc::clone(context);
+    engine.register_fn("strands", move || -> Array {
+        ctx.borrow()
+            .design()
+            .strands
+            .keys()
+            .map(|s| (*s as INT).into())
+            .collect()
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("strand", move |id: INT| ctx.borrow().strand(id));
+    let ctx = Rc::clone(context);
+    engine.register_fn("scaffold_id", move || {
+        ctx.borrow()
+            .design()
+            .scaffold_id
+            .map_or(Dynamic::UNIT, |s| (s as INT).into())
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("grids", move || ctx.borrow().grids());
+    let ctx = Rc::clone(context);
+    engine.register_fn("strand_of", move |nucl: Map| -> ScriptResult<Dynamic> {
+        let nucl = nucl_from_map(&nucl)?;
+        Ok(ctx
+            .borrow()
+            .design()
+            .strands
+            .get_strand_nucl(&nucl)
+            .map_or(Dynamic::UNIT, |s| (s as INT).into()))
+    });
+
+    // Selection.
+    let ctx = Rc::clone(context);
+    engine.register_fn("selected_strands", move || -> Array {
+        extract_strands_from_selection(ctx.borrow().app_state.get_selection())
+            .into_iter()
+            .map(|s| (s as INT).into())
+            .collect()
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("selected_helices", move || -> Array {
+        list_of_helices(ctx.borrow().app_state.get_selection())
+            .map(|(_, helices)| helices.into_iter().map(|h| (h as INT).into()).collect())
+            .unwrap_or_default()
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("select_strands", move |ids: Array| -> ScriptResult<()> {
+        let selection: Vec<Selection> = to_ids(ids)?
+            .into_iter()
+            .map(|s| Selection::Strand(0, s as u32))
+            .collect();
+        ctx.borrow_mut().select(&selection)
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("select_helices", move |ids: Array| -> ScriptResult<()> {
+        let selection: Vec<Selection> = to_ids(ids)?
+            .into_iter()
+            .map(|helix_id| Selection::Helix {
+                design_id: 0,
+                helix_id,
+                segment_id: 0,
+            })
+            .collect();
+        ctx.borrow_mut().select(&selection)
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("clear_selection", move || ctx.borrow_mut().select(&[]));
+
+    // Design operations.
+    let ctx = Rc::clone(context);
+    engine.register_fn("recolor_staples", move || {
+        ctx.borrow_mut().apply(DesignOperation::RecolorStaples)
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("set_color", move |s_id: INT, color: INT| {
+        let color = u32::try_from(color).map_err(|e| format!("Invalid color {color}: {e}"))?;
+        ctx.borrow_mut().apply(DesignOperation::ChangeColor {
+            color,
+            strands: vec![to_usize(s_id)?],
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("set_strand_name", move |s_id: INT, name: &str| {
+        ctx.borrow_mut().apply(DesignOperation::SetStrandName {
+            s_id: to_usize(s_id)?,
+            name: name.to_owned(),
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("delete_strands", move |ids: Array| {
+        ctx.borrow_mut().apply(DesignOperation::RmStrands {
+            strand_ids: to_ids(ids)?,
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("delete_helices", move |ids: Array| {
+        ctx.borrow_mut().apply(DesignOperation::RmHelices {
+            h_ids: to_ids(ids)?,
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn(
+        "add_helix",
+        move |grid: INT, x: INT, y: INT, start: INT, length: INT| {
+            let position = HelixGridPosition::from_grid_id_x_y(
+                GridId::FreeGrid(to_usize(grid)?),
+                x as isize,
+                y as isize,
+            );
+            ctx.borrow_mut().apply(DesignOperation::AddGridHelix {
+                position,
+                start: start as isize,
+                length: to_usize(length)?,
+            })
+        },
+    );
+    let ctx = Rc::clone(context);
+    engine.register_fn("set_roll", move |ids: Array, roll: FLOAT| {
+        ctx.borrow_mut().apply(DesignOperation::SetRollHelices {
+            helices: to_ids(ids)?,
+            roll: roll as f32,
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("set_helix_visibility", move |helix: INT, visible: bool| {
+        ctx.borrow_mut().apply(DesignOperation::SetVisibilityHelix {
+            helix: to_usize(helix)?,
+            visible,
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("cut", move |nucl: Map| {
+        ctx.borrow_mut().apply(DesignOperation::Cut {
+            nucl: nucl_from_map(&nucl)?,
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("xover", move |source: Map, target: Map| {
+        ctx.borrow_mut().apply(DesignOperation::GeneralXover {
+            source: nucl_from_map(&source)?,
+            target: nucl_from_map(&target)?,
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("insert_after", move |nucl: Map, length: INT| {
+        ctx.borrow_mut().apply(DesignOperation::SetInsertionLength {
+            length: to_usize(length)?,
+            insertion_point: InsertionPoint {
+                nucl: nucl_from_map(&nucl)?,
+                nucl_is_prime5_of_insertion: true,
+            },
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("insert_before", move |nucl: Map, length: INT| {
+        ctx.borrow_mut().apply(DesignOperation::SetInsertionLength {
+            length: to_usize(length)?,
+            insertion_point: InsertionPoint {
+                nucl: nucl_from_map(&nucl)?,
+                nucl_is_prime5_of_insertion: false,
+            },
+        })
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn(
+        "set_deletion",
+        move |helix: INT, position: INT, deleted: bool| {
+            ctx.borrow_mut().apply(DesignOperation::SetDeletion {
+                helix: to_usize(helix)?,
+                position: position as isize,
+                deleted,
+            })
+        },
+    );
+    let ctx = Rc::clone(context);
+    engine.register_fn("set_scaffold", move |s_id: INT| {
+        ctx.borrow_mut()
+            .apply(DesignOperation::SetScaffoldId(Some(to_usize(s_id)?)))
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn("unset_scaffold", move || {
+        ctx.borrow_mut().apply(DesignOperation::SetScaffoldId(None))
+    });
+    let ctx = Rc::clone(context);
+    engine.register_fn(
+        "set_scaffold_sequence",
+        move |sequence: &str, shift: INT| {
+            ctx.borrow_mut()
+                .apply(DesignOperation::SetScaffoldSequence {
+                    sequence: sequence.to_owned(),
+                    shift: to_usize(shift)?,
+                })
+        },
+    );
+
+    // Simulation.
+    let ctx = Rc::clone(context);
+    engine.register_fn(
+        "relax",
+        move |tolerance: FLOAT, max_steps: INT| -> ScriptResult<Map> {
+            let criteria = RelaxationCriteria {
+                tolerance: tolerance as f32,
+                max_steps: to_usize(max_steps)?,
+            };
+            let mut ctx = ctx.borrow_mut();
+            let report = ctx
+                .app_state
+                .relax(RapierParameters::default(), &criteria, |_| ())
+                .map_err(|e| e.to_string())?;
+            ctx.report.output.push(report.to_string());
+            let mut ret = Map::new();
+            ret.insert(
+                "converged".into(),
+                (report.outcome == RelaxationOutcome::Converged).into(),
+            );
+            ret.insert("steps".into(), (report.progress.step as INT).into());
+            ret.insert(
+                "energy".into(),
+                FLOAT::from(report.progress.total_energy()).into(),
+            );
+            Ok(ret)
+        },
+    );
+
+    // Exports.
+    let ctx = Rc::clone(context);
+    engine.register_fn(
+        "export_design",
+        move |kind: &str, path: &str| -> ScriptResult<()> {
+            let export_type = match kind.to_lowercase().as_str() {
+                "oxdna" => ExportType::Oxdna,
+                "pdb" => ExportType::Pdb,
+                "cadnano" => ExportType::Cadnano,
+                "scadnano" => ExportType::Scadnano,
+                _ => return Err(format!("Unknown export format {kind}").into()),
+            };
+            ctx.borrow_mut()
+                .report
+                .exports
+                .push((export_type, PathBuf::from(path)));
+            Ok(())
+        },
+    );
+
+    engine
+}
+
+fn to_usize(x: INT) -> ScriptResult<usize> {
+    usize::try_from(x).map_err(|e| format!("Expected a non-negative integer, got {x}: {e}").into())
+}
+
+fn to_ids(array: Array) -> ScriptResult<Vec<usize>> {
+    array
+        .into_iter()
+        .map(|d| {
+            let x = d
+                .as_int()
+                .map_err(|t| format!("Expected an integer, got {t}"))?;
+            to_usize(x)
+        })
+        .collect()
+}
+
+fn nucl_from_map(map: &Map) -> ScriptResult<Nucl> {
+    let field = |name: &str| {
+        map.get(name)
+            .ok_or_else(|| format!("Nucleotide without {name}"))
+    };
+    let helix = to_usize(
+        field("helix")?
+            .as_int()
+            .map_err(|t| format!("Invalid helix: {t}"))?,
+    )?;
+    let position = field("position")?
+        .as_int()
+        .map_err(|t| format!("Invalid position: {t}"))?;
+    let forward = field("forward")?
+        .as_bool()
+        .map_err(|t| format!("Invalid direction: {t}"))?;
+    Ok(Nucl {
+        helix,
+        position: position as isize,
+        forward,
+    })
+}
+
+fn nucl_to_dynamic(nucl: Nucl) -> Dynamic {
+    let mut ret = Map::new();
+    ret.insert("helix".into(), (nucl.helix as INT).into());
+    ret.insert("position".into(), (nucl.position as INT).into());
+    ret.insert("forward".into(), nucl.forward.into());
+    ret.into()
+}
+
+fn grid_to_dynamic(grid: GridId) -> Dynamic {
+    match grid {
+        GridId::FreeGrid(id) => (id as INT).into(),
+        GridId::BezierPathGrid(_) => Dynamic::UNIT,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ensnano_design::grid::GridDescriptor;
+    use ultraviolet::{Rotor3, Vec3};
+
+    fn one_helix_design() -> AppState {
+        let mut app_state = AppState::default();
+        app_state
+            .apply_design_op(DesignOperation::AddGrid(GridDescriptor {
+                position: Vec3::zero(),
+                orientation: Rotor3::identity(),
+                helix_parameters: None,
+                grid_type: GridTypeDescr::Square { twist: None },
+                invisible: false,
+                bezier_vertex: None,
+            }))
+            .unwrap();
+        app_state.update();
+        app_state
+    }
+
+    #[test]
+    fn script_reads_what_it_writes() {
+        let mut app_state = one_helix_design();
+        let report = run_script(
+            &mut app_state,
+            r#"
+            add_helix(0, 0, 0, 0, 20);
+            add_helix(0, 1, 0, 0, 20);
+            print(helices().len());
+            set_helix_visibility(1, false);
+            print(helix(1).visible);
+            export_design("oxdna", "out.oxdna");
+            "#,
+        )
+        .unwrap();
+        assert_eq!(report.output, vec!["2", "false"]);
+        assert_eq!(report.exports.len(), 1);
+        assert_eq!(app_state.0.design.design.helices.len(), 2);
+    }
+
+    #[test]
+    fn failed_script_leaves_state_untouched() {
+        let mut app_state = one_helix_design();
+        let error = run_script(
+            &mut app_state,
+            r#"
+            add_helix(0, 0, 0, 0, 20);
+            print("added");
+            print(helix(42));
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(error.output, vec!["added"]);
+        assert_eq!(app_state.0.design.design.helices.len(), 0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Named sets of physical simulation parameters.
+//!
+//! A few presets are built into ENSnano, and users can save their own ones, which are stored
+//! alongside the preferences. The parameters of the last simulations are also saved in the design
+//! file, so that reopening a design gives back the same relaxation.
+
+use ensnano_design::Design;
+use ensnano_physics::{
+    parameters::{RapierParameters, SimulationSetupKind},
+    relaxation::RelaxationCriteria,
+};
+use ensnano_utils::{RigidBodyConstants, consts::APP_NAME};
+use serde::{Deserialize, Serialize};
+
+/// The name of the configuration file holding the presets of the user.
+const USER_PRESETS_CONFIG_NAME: &str = "simulation_presets";
+
+/// The parameters of both the relaxation and the rigid body simulations.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationParameters {
+    pub rapier: RapierParameters,
+    pub rigid_body: RigidBodyConstants,
+}
+
+impl SimulationParameters {
+    /// The parameters that were saved in `design`, if any.
+    pub fn from_design(design: &Design) -> Option<Self> {
+        let value = design.get_simulation_parameters()?;
+        serde_json::from_value(value.clone())
+            .inspect_err(|e| log::error!("Could not read simulation parameters: {e}"))
+            .ok()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        // The serialization of plain numbers and enums cannot fail.
+        serde_json::to_value(self).unwrap()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimulationPreset {
+    pub name: String,
+    pub parameters: SimulationParameters,
+    #[serde(skip)]
+    pub built_in: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct UserPresets {
+    presets: Vec<SimulationPreset>,
+}
+
+/// The presets shipped with ENSnano.
+pub fn built_in_presets() -> Vec<SimulationPreset> {
+    let default = RapierParameters::default();
+    let built_in = |name: &str, rapier: RapierParameters| SimulationPreset {
+        name: name.to_owned(),
+        parameters: SimulationParameters {
+            rapier,
+            rigid_body: Default::default(),
+        },
+        built_in: true,
+    };
+    vec![
+        built_in("Default", default),
+        built_in(
+            "Gentle relax",
+            RapierParameters {
+                speed: 0.5,
+                linear_damping: 0.3,
+                angular_damping: 2.0,
+                repulsion_strength: 0.2,
+                convergence: Some(RelaxationCriteria::default()),
+                ..default
+            },
+        ),
+        built_in(
+            "Fast squish",
+            RapierParameters {
+                speed: 5.0,
+                squish_strength: 20.0,
+                squish_damping: 5.0,
+                ..default
+            },
+        ),
+        built_in(
+            "Large design",
+            RapierParameters {
+                setup: SimulationSetupKind::SlicedRigidHelices,
+                convergence: Some(RelaxationCriteria::default()),
+                ..default
+            },
+        ),
+    ]
+}
+
+fn load_user_presets() -> Result<Vec<SimulationPreset>, confy::ConfyError> {
+    confy::load::<UserPresets>(APP_NAME, USER_PRESETS_CONFIG_NAME).map(|p| p.presets)
+}
+
+/// The presets saved by the user, to be displayed. Errors are logged and result in an empty list.
+pub fn user_presets() -> Vec<SimulationPreset> {
+    load_user_presets()
+        .inspect_err(|e| log::error!("Could not load simulation presets: {e}"))
+        .unwrap_or_default()
+}
+
+/// The built-in presets followed by the ones of the user.
+pub fn all_presets() -> Vec<SimulationPreset> {
+    let mut presets = built_in_presets();
+    presets.extend(user_presets());
+    presets
+}
+
+/// Save `preset` in the presets of the user, replacing the one with the same name if any.
+///
+/// Nothing is written if the existing presets cannot be read, so that they are not lost.
+pub fn save_user_preset(preset: SimulationPreset) -> Result<(), confy::ConfyError> {
+    let mut presets = load_user_presets()?;
+    if let Some(existing) = presets.iter_mut().find(|p| p.name == preset.name) {
+        *existing = preset;
+    } else {
+        presets.push(preset);
+    }
+    confy::store(APP_NAME, USER_PRESETS_CONFIG_NAME, UserPresets { presets })
+}
+
+/// Remove the preset of the user named `name`.
+///
+/// Nothing is written if the existing presets cannot be read, so that they are not lost.
+pub fn delete_user_preset(name: &str) -> Result<(), confy::ConfyError> {
+    let mut presets = load_user_presets()?;
+    presets.retain(|p| p.name != name);
+    confy::store(APP_NAME, USER_PRESETS_CONFIG_NAME, UserPresets { presets })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ensnano_design::SavingInformation;
+
+    #[test]
+    fn simulation_parameters_are_saved_in_the_design() {
+        let parameters = SimulationParameters {
+            rapier: RapierParameters {
+                is_simulation_running: true,
+                speed: 3.0,
+                setup: SimulationSetupKind::RigidHelices,
+                ..Default::default()
+            },
+            rigid_body: RigidBodyConstants {
+                volume_exclusion: true,
+                ..Default::default()
+            },
+        };
+        let mut design = Design::new();
+        design.prepare_for_save(SavingInformation {
+            camera: None,
+            simulation_parameters: Some(parameters.to_json()),
+        });
+
+        let json = serde_json::to_string(&design).unwrap();
+        let read: Design = serde_json::from_str(&json).unwrap();
+        let expected = SimulationParameters {
+            rapier: RapierParameters {
+                is_simulation_running: false,
+                ..parameters.rapier
+            },
+            ..parameters
+        };
+        assert_eq!(SimulationParameters::from_design(&read), Some(expected));
+        assert_eq!(SimulationParameters::from_design(&Design::new()), None);
+    }
+}
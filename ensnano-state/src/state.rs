@@ -5,11 +5,13 @@ use crate::{
         AppState, SaveDesignError,
         action::Action,
         design_interactor::controller::{
-            InteractorNotification, OperationError, clipboard::CopyOperation,
-            simulations::SimulationOperation,
+            InteractorNotification, OperationError,
+            clipboard::CopyOperation,
+            simulations::{SimulationFrame, SimulationOperation},
         },
-        transitions::{AppStateTransition, TransitionLabel},
+        transitions::{AppStateTransition, TransitionLabel, UndoHistory},
     },
+    autosave::Autosave,
     design::{
         operation::DesignOperation,
         selection::{
@@ -18,6 +20,10 @@ use crate::{
         },
     },
     gui::messages::{GuiMessages, TopBarStateFlags},
+    rigidity::RigidityAnalysisResult,
+    scripting::{self, ScriptError, ScriptOutcome, ScriptReport, ScriptThread},
+    simulation_presets::{self, SimulationParameters, SimulationPreset},
+    trajectory::TrajectoryRecorder,
     utils::{
         application::{Application, Camera3D, Notification},
         operation::SimpleOperation,
@@ -31,19 +37,23 @@ use ensnano_design::{
     interaction_modes::{ActionMode, SelectionMode},
     organizer_tree::GroupId,
 };
-use ensnano_physics::parameters::RapierParameters;
+use ensnano_physics::{
+    elastic_network::{ElasticNetworkError, ElasticNetworkParameters},
+    parameters::RapierParameters,
+};
 use ensnano_utils::{
     PastingStatus, RigidBodyConstants,
     app_state_parameters::{
         check_xovers_parameter::CheckXoversParameter, suggestion_parameters::SuggestionParameters,
     },
-    consts::{ENS_BACKUP_EXTENSION, ENS_UNNAMED_FILE_NAME, SEC_PER_YEAR},
+    consts::SEC_PER_YEAR,
     graphics::{Background3D, GuiComponentType, HBondDisplay, RenderingMode},
     keyboard_priority::KeyboardPriorityId,
     surfaces::{RevolutionSurfaceSystemDescriptor, UnrootedRevolutionSurfaceDescriptor},
 };
 use std::{
     collections::VecDeque,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
     time::{Duration, Instant},
@@ -57,6 +67,9 @@ pub struct MainState {
     pub pending_actions: VecDeque<Action>,
     pub undo_stack: Vec<AppStateTransition>,
     pub redo_stack: Vec<AppStateTransition>,
+    /// The number of transitions that were removed from the bottom of the undo stack to respect
+    /// the memory budget.
+    pub nb_evicted_transitions: usize,
     pub messages: Arc<Mutex<GuiMessages>>,
     pub applications: HashMap<GuiComponentType, Arc<Mutex<dyn Application>>>,
     pub focused_component: Option<GuiComponentType>,
@@ -74,6 +87,12 @@ pub struct MainState {
     pub wants_fit: bool,
     pub last_backup_date: Instant,
     pub last_backed_up_state: AppState,
+    pub autosave: Autosave,
+    /// The frames recorded during simulations.
+    pub trajectory: TrajectoryRecorder,
+    /// The parameters of the last simulations, saved with the design. `None` if no simulation
+    /// parameters were used or read since the design was opened.
+    pub simulation_parameters: Option<SimulationParameters>,
     pub simulation_cursor: Option<CursorIcon>,
     pub applications_cursor: Option<CursorIcon>,
     pub gui_cursor: CursorIcon,
@@ -86,12 +105,16 @@ impl MainState {
             log::error!("Could not load preferences {e}");
             AppState::default()
         });
+        for conflict in app_state.get_keymap().conflicts() {
+            log::warn!("Keymap conflict: {conflict}");
+        }
 
         Self {
             app_state: app_state.clone(),
             pending_actions: VecDeque::new(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            nb_evicted_transitions: 0,
             messages,
             applications: Default::default(),
             focused_component: None,
@@ -101,6 +124,9 @@ impl MainState {
             wants_fit: false,
             last_backup_date: Instant::now(),
             last_backed_up_state: app_state,
+            autosave: Default::default(),
+            trajectory: Default::default(),
+            simulation_parameters: None,
             simulation_cursor: None,
             applications_cursor: None,
             gui_cursor: Default::default(),
@@ -156,8 +182,20 @@ impl MainState {
     pub fn clear_app_state(&mut self, new_state: AppState) {
         self.undo_stack.clear();
         self.redo_stack.clear();
+        self.nb_evicted_transitions = 0;
         self.app_state = new_state.clone();
+        self.autosave.set_design(&new_state);
         self.last_saved_state = new_state;
+        self.notify_history();
+        self.clear_trajectory();
+        self.simulation_parameters =
+            SimulationParameters::from_design(&self.app_state.0.design.design);
+        if let Some(parameters) = self.simulation_parameters.clone() {
+            self.messages
+                .lock()
+                .unwrap()
+                .update_simulation_parameters(parameters);
+        }
     }
 
     pub fn update(&mut self) {
@@ -172,7 +210,14 @@ impl MainState {
                 .unwrap()
                 .on_notify(Notification::NewStereographicCamera(camera_ptr));
         }
+        let old_state = self.app_state.clone();
         self.app_state.update();
+        self.autosave.follow_update(&old_state, &self.app_state);
+        if self.autosave.snapshot_requested
+            && let Err(e) = self.save_backup()
+        {
+            log::error!("Could not autosave design: {e:?}");
+        }
     }
 
     pub fn update_candidates(&mut self, candidates: Vec<Selection>) {
@@ -204,43 +249,29 @@ impl MainState {
     pub fn apply_design_operation(&mut self, operation: DesignOperation) {
         log::debug!("Applying operation {operation:?}");
 
+        let old_state = self.app_state.clone();
+        let journaled_operation = operation.clone();
         self.modify_state(move |app_state: &mut AppState| {
-            let mut result = app_state.apply_design_op(operation.clone());
-
-            // we ask to finish the current operation, and try again.
-            while matches!(result, Err(OperationError::FinishFirst)) {
-                app_state.notify(InteractorNotification::FinishOperation)?;
-                result = app_state.apply_design_op(operation.clone());
-            }
-
-            // if a new selection is made, we apply it here
-            // note : this could be done better if the operations did
-            // this directly
-            if let Some(selection) = app_state
-                .get_design_interactor()
-                .controller
-                .make_mut()
-                .next_selection
-                .take()
-            {
-                app_state.set_selection(&selection, &None)?;
-            }
-
-            result
+            app_state.apply_design_op_and_select(operation)
         });
+        self.journal_design_operation(&old_state, &journaled_operation);
     }
 
     /// Variant of the apply_design_operation method that does push operations.
     pub fn apply_silent_operation(&mut self, operation: DesignOperation) {
+        let old_state = self.app_state.clone();
         match self.app_state.apply_design_op(operation.clone()) {
-            Ok(_) => (),
+            Ok(_) => self.journal_design_operation(&old_state, &operation),
             Err(OperationError::FinishFirst) => {
                 self.modify_state(|s: &mut AppState| {
                     s.notify(InteractorNotification::FinishOperation)
                 });
                 self.apply_silent_operation(operation);
             }
-            Err(e) => log::warn!("{e:?}"),
+            Err(e) => {
+                self.app_state = old_state;
+                self.report_operation_error(&e);
+            }
         }
     }
 
@@ -300,18 +331,172 @@ impl MainState {
         self.apply_simulation_operation(op);
     }
 
+    pub fn commit_relaxation(&mut self) {
+        let presenter = self.app_state.0.design.presenter.clone();
+        let op = SimulationOperation::CommitRelaxation {
+            presenter: presenter.as_ref(),
+        };
+
+        self.apply_simulation_operation(op);
+    }
+
+    /// Record the frames sampled by the running simulation, if the trajectory is being recorded.
+    pub fn record_simulation_frames(&mut self, frames: Vec<SimulationFrame>) {
+        if !self.trajectory.is_recording() {
+            return;
+        }
+        for frame in frames {
+            let app_state = &self.app_state;
+            self.trajectory.record(frame.step, frame.time, || {
+                app_state.nucleotide_positions_with(frame.state)
+            });
+        }
+        self.notify_trajectory();
+    }
+
+    /// To be called after the positions were updated by a running simulation.
+    pub fn simulation_progressed(&mut self) {
+        if self.trajectory.summary().shown_frame.is_some() {
+            self.trajectory.hide_frame();
+            self.notify_trajectory();
+        }
+    }
+
+    pub fn set_trajectory_recording(&mut self, recording: bool) {
+        self.trajectory.set_recording(recording);
+        self.notify_trajectory();
+    }
+
+    pub fn set_trajectory_interval(&mut self, interval: NonZeroUsize) {
+        self.trajectory.set_interval(interval);
+        self.notify_trajectory();
+    }
+
+    pub fn clear_trajectory(&mut self) {
+        self.trajectory.clear();
+        self.notify_trajectory();
+    }
+
+    /// Move the nucleotides to their positions in a recorded frame.
+    pub fn show_trajectory_frame(&mut self, frame: usize) {
+        if let Some(update) = self.trajectory.show_frame(frame) {
+            self.app_state.apply_simulation_update(Box::new(update));
+            self.notify_trajectory();
+        }
+    }
+
+    /// Start analysing the rigidity of the design. The result is shown as a heat map in the 3D
+    /// scene by [`Self::finish_rigidity_analysis`].
+    pub fn analyse_rigidity(&mut self) {
+        self.messages
+            .lock()
+            .unwrap()
+            .push_message("Analysing rigidity...".to_owned());
+        self.app_state
+            .start_rigidity_analysis(ElasticNetworkParameters::default());
+    }
+
+    /// Show the result of an analysis of the rigidity, unless the design was modified in the
+    /// meantime.
+    pub fn finish_rigidity_analysis(
+        &mut self,
+        result: RigidityAnalysisResult,
+    ) -> Result<(), ElasticNetworkError> {
+        let heat_map = result.heat_map?;
+        let message = if self.app_state.is_current_design(&result.design) {
+            let message = heat_map.analysis().to_string();
+            self.modify_state(|s: &mut AppState| s.set_rigidity_heat_map(Some(heat_map)));
+            message
+        } else {
+            "The design was modified during the analysis of its rigidity".to_owned()
+        };
+        self.messages.lock().unwrap().push_message(message);
+        Ok(())
+    }
+
+    pub fn clear_rigidity_analysis(&mut self) {
+        self.modify_state(|s: &mut AppState| s.set_rigidity_heat_map(None));
+    }
+
+    /// Move the nucleotides to their positions in the equilibrium shape given by the analysis of
+    /// the rigidity of the design.
+    pub fn apply_equilibrium_shape(&mut self) {
+        let presenter = self.app_state.0.design.presenter.clone();
+        if let Some(heat_map) = presenter.rigidity.as_ref() {
+            let positions = heat_map.equilibrium_positions(&presenter.nucleotide_positions());
+            self.apply_design_operation(DesignOperation::SetRelaxedPositions { positions });
+        }
+    }
+
+    fn notify_trajectory(&self) {
+        self.messages
+            .lock()
+            .unwrap()
+            .update_trajectory(self.trajectory.summary());
+    }
+
     pub fn apply_simulation_operation(&mut self, request: SimulationOperation) {
-        self.modify_state(move |app_state: &mut AppState| app_state.update_simulation(request));
+        self.record_simulation_parameters(&request);
+        let sampler = self.trajectory.sampler();
+        self.modify_state(move |app_state: &mut AppState| {
+            app_state.update_simulation(request, sampler)
+        });
+    }
+
+    fn record_simulation_parameters(&mut self, request: &SimulationOperation) {
+        match request {
+            SimulationOperation::StartHelices { parameters, .. }
+            | SimulationOperation::StartGrids { parameters, .. }
+            | SimulationOperation::UpdateParameters {
+                new_parameters: parameters,
+            } => {
+                self.simulation_parameters
+                    .get_or_insert_with(Default::default)
+                    .rigid_body = parameters.clone();
+            }
+            SimulationOperation::UpdateRapierParameters { parameters, .. } => {
+                self.simulation_parameters
+                    .get_or_insert_with(Default::default)
+                    .rapier = *parameters;
+            }
+            _ => (),
+        }
+    }
+
+    pub fn save_simulation_preset(
+        &mut self,
+        preset: SimulationPreset,
+    ) -> Result<(), confy::ConfyError> {
+        simulation_presets::save_user_preset(preset)?;
+        self.notify_simulation_presets();
+        Ok(())
+    }
+
+    pub fn delete_simulation_preset(&mut self, name: &str) -> Result<(), confy::ConfyError> {
+        simulation_presets::delete_user_preset(name)?;
+        self.notify_simulation_presets();
+        Ok(())
+    }
+
+    fn notify_simulation_presets(&self) {
+        self.messages
+            .lock()
+            .unwrap()
+            .update_simulation_presets(simulation_presets::all_presets());
     }
 
     pub fn save_old_state(&mut self, old_state: AppState, label: TransitionLabel) {
         let camera_3d = self.get_camera_3d();
+        let memory_size = old_state.estimated_snapshot_size(&self.app_state);
         self.undo_stack.push(AppStateTransition {
             state: old_state,
             label,
             camera_3d,
+            memory_size,
         });
         self.redo_stack.clear();
+        self.enforce_undo_memory_budget();
+        self.notify_history();
     }
 
     pub fn set_roll_of_selected_helices(&mut self, roll: f32) {
@@ -335,8 +520,10 @@ impl MainState {
                     state: redo_state,
                     label: transition.label,
                     camera_3d: transition.camera_3d,
+                    memory_size: transition.memory_size,
                 });
             }
+            self.notify_history();
         }
     }
 
@@ -353,8 +540,68 @@ impl MainState {
                 state: undo_state,
                 camera_3d: transition.camera_3d,
                 label: transition.label,
+                memory_size: transition.memory_size,
             });
+            self.notify_history();
+        }
+    }
+
+    /// Undo or redo transitions until exactly `position` transitions lead to the current state.
+    pub fn jump_in_history(&mut self, position: usize) {
+        while self.undo_stack.len() > position {
+            self.undo();
+        }
+        while self.undo_stack.len() < position && !self.redo_stack.is_empty() {
+            self.redo();
+        }
+    }
+
+    pub fn undo_history(&self) -> UndoHistory {
+        UndoHistory {
+            labels: self
+                .undo_stack
+                .iter()
+                .chain(self.redo_stack.iter().rev())
+                .map(|t| t.label.as_ref().to_owned())
+                .collect(),
+            position: self.undo_stack.len(),
+            nb_evicted: self.nb_evicted_transitions,
+            memory_usage: self.undo_memory_usage(),
+            memory_budget: self.app_state.get_undo_memory_budget(),
+        }
+    }
+
+    fn undo_memory_usage(&self) -> usize {
+        self.undo_stack
+            .iter()
+            .chain(self.redo_stack.iter())
+            .map(|t| t.memory_size)
+            .sum()
+    }
+
+    /// Drop the oldest transitions until the undo and redo stacks fit in the memory budget.
+    ///
+    /// The most recent transition is always kept so that the last operation can be undone.
+    fn enforce_undo_memory_budget(&mut self) {
+        let budget = self.app_state.get_undo_memory_budget();
+        let mut usage = self.undo_memory_usage();
+        let mut nb_evicted = 0;
+        while usage > budget && self.undo_stack.len() - nb_evicted > 1 {
+            usage -= self.undo_stack[nb_evicted].memory_size;
+            nb_evicted += 1;
         }
+        if nb_evicted > 0 {
+            log::info!("Dropping {nb_evicted} undo steps to respect the memory budget");
+            self.undo_stack.drain(..nb_evicted);
+            self.nb_evicted_transitions += nb_evicted;
+        }
+    }
+
+    fn notify_history(&self) {
+        self.messages
+            .lock()
+            .unwrap()
+            .update_undo_history(self.undo_history());
     }
 
     /// The main method for state modification.
@@ -365,11 +612,7 @@ impl MainState {
     /// on a copy of the state. It then places the old state on the undo stack
     /// depending on what is returned, or reverts all operations if an error
     /// is returned instead.
-    pub fn modify_state(
-        &mut self,
-        modification: impl AppStateOperation,
-        // undo_label: Option<TransitionLabel>,
-    ) {
+    pub fn modify_state(&mut self, modification: impl AppStateOperation) {
         let old_state = self.app_state.clone();
         match modification.apply(&mut self.app_state) {
             Ok(AppStateOperationOutcome::Push { label }) => {
@@ -378,23 +621,27 @@ impl MainState {
                 // We could do this better by using a DerefMut implementation
                 // on AddressPointer.
                 if old_state != self.app_state && old_state.is_in_stable_state() {
-                    let camera_3d = self.get_camera_3d();
-                    self.undo_stack.push(AppStateTransition {
-                        state: old_state,
-                        label: TransitionLabel(label),
-                        camera_3d,
-                    });
-                    self.redo_stack.clear();
+                    self.save_old_state(old_state, TransitionLabel(label));
                 }
             }
             Ok(AppStateOperationOutcome::Replace | AppStateOperationOutcome::NoOp) => {}
             Err(e) => {
                 self.app_state = old_state;
-                log::warn!("{e:?}");
+                self.report_operation_error(&e);
             }
         }
     }
 
+    fn report_operation_error(&self, error: &OperationError) {
+        log::warn!("{error:?}");
+        if error.is_worth_reporting() {
+            self.messages
+                .lock()
+                .unwrap()
+                .push_message(error.to_string());
+        }
+    }
+
     pub fn update_pending_operation(&mut self, operation: Arc<dyn SimpleOperation>) {
         // let result = self.app_state.update_pending_operation(operation.clone());
         // if matches!(result, Err(OperationError::FinishFirst)) {
@@ -416,6 +663,36 @@ impl MainState {
         });
     }
 
+    /// Run a script on the design. All the modifications made by the script form a single undo
+    /// step, and none of them is applied if the script fails.
+    ///
+    /// The script is run on the current thread, use [Self::start_script] to run it in the
+    /// background.
+    pub fn run_script(&mut self, source: &str) -> Result<ScriptReport, ScriptError> {
+        let mut app_state = self.app_state.clone();
+        let outcome =
+            scripting::run_script(&mut app_state, source).map(|report| (app_state, report));
+        self.finish_script(outcome)
+    }
+
+    /// Start running a script on a copy of the current state, in a separate thread.
+    ///
+    /// The design must not be modified until the outcome of the script is passed to
+    /// [Self::finish_script].
+    pub fn start_script(&self, source: String) -> ScriptThread {
+        ScriptThread::spawn(&self.app_state, source)
+    }
+
+    /// Apply the modifications made by a script as a single undo step.
+    pub fn finish_script(&mut self, outcome: ScriptOutcome) -> Result<ScriptReport, ScriptError> {
+        let (new_state, report) = outcome?;
+        self.modify_state(move |app_state: &mut AppState| {
+            *app_state = new_state;
+            Ok(AppStateOperationOutcome::push("Script"))
+        });
+        Ok(report)
+    }
+
     pub fn optimize_shift(&mut self) {
         self.modify_state(|app_state: &mut AppState| app_state.optimize_shift());
     }
@@ -475,6 +752,18 @@ impl MainState {
             )));
         }
 
+        let save_info = self.saving_information();
+        self.app_state.save_design(path, save_info, change_path)?;
+
+        if self.app_state.is_in_stable_state() {
+            self.last_saved_state = self.app_state.clone();
+            self.autosave.set_design(&self.app_state);
+        }
+        self.update_current_file_name();
+        Ok(())
+    }
+
+    fn saving_information(&self) -> SavingInformation {
         let camera = self
             .applications
             .get(&GuiComponentType::Scene)
@@ -486,38 +775,62 @@ impl MainState {
                 orientation: camera.0.orientation,
                 pivot_position: camera.0.pivot_position,
             });
-        let save_info = SavingInformation { camera };
-        self.app_state.save_design(path, save_info, change_path)?;
-
-        if self.app_state.is_in_stable_state() {
-            self.last_saved_state = self.app_state.clone();
+        SavingInformation {
+            camera,
+            simulation_parameters: self
+                .simulation_parameters
+                .as_ref()
+                .map(SimulationParameters::to_json),
         }
-        self.update_current_file_name();
-        Ok(())
     }
 
+    /// Take a new autosave snapshot if the design was modified since the last one.
     pub fn save_backup(&mut self) -> Result<(), SaveDesignError> {
-        let path = if let Some(mut path) = self.app_state.path_to_current_design().cloned() {
-            path.set_extension(ENS_BACKUP_EXTENSION);
-            path
+        if !self.app_state.is_in_stable_state() {
+            return Ok(());
+        }
+        if self.autosave.needs_snapshot(&self.app_state) {
+            let save_info = self.saving_information();
+            if let Err(e) = self.autosave.snapshot(&self.app_state, save_info) {
+                // Do not try again on every frame if the autosave directory cannot be written.
+                self.last_backup_date = Instant::now() + Duration::from_secs(SEC_PER_YEAR);
+                return Err(e);
+            }
         } else {
-            let mut ret = dirs::document_dir()
-                .or_else(dirs::home_dir)
-                .ok_or_else(|| {
-                    self.last_backup_date = Instant::now() + Duration::from_secs(SEC_PER_YEAR);
-                    SaveDesignError::cannot_open_default_dir()
-                })?;
-            ret.push(ENS_UNNAMED_FILE_NAME);
-            ret.set_extension(ENS_BACKUP_EXTENSION);
-            ret
-        };
+            self.autosave.snapshot_requested = false;
+        }
+        Ok(())
+    }
 
-        if self.app_state.is_in_stable_state() {
-            self.save_design(&path, false)?;
-            log::warn!("Saved backup to {}", path.to_string_lossy());
+    /// Start autosaving the current design and mark the session as running.
+    pub fn start_session(&mut self) {
+        self.autosave.start_session(&self.app_state);
+    }
+
+    /// Mark the session as ended normally.
+    pub fn end_session(&mut self) {
+        self.autosave.end_session();
+    }
+
+    /// Replace the current design by the unsaved work of a session that ended unexpectedly.
+    pub fn recover(&mut self, app_state: AppState) {
+        self.clear_app_state(app_state);
+        // The recovered design has not been saved.
+        self.last_saved_state = Default::default();
+        let save_info = self.saving_information();
+        if let Err(e) = self.autosave.snapshot(&self.app_state, save_info) {
+            log::error!("Could not autosave recovered design: {e:?}");
         }
+        self.update_current_file_name();
+    }
 
-        Ok(())
+    fn journal_design_operation(&mut self, old_state: &AppState, operation: &DesignOperation) {
+        if let Err(e) = self
+            .autosave
+            .record_operation(old_state, operation, &self.app_state)
+        {
+            log::error!("Could not autosave operation: {e:?}");
+        }
     }
 
     pub fn change_selection_mode(&mut self, mode: SelectionMode) {
@@ -0,0 +1,202 @@
+//! Recording of simulation trajectories.
+//!
+//! While recording, the positions of the nucleotides are saved every few solver steps of the
+//! rapier, rigid helices, rigid grids and roll simulations. The recorded frames can then be shown
+//! one at a time, or exported as a multi-frame oxDNA or PDB file.
+//!
+//! The frames are sampled by the threads running the simulations, so running the same simulation
+//! twice gives the same frames, however often the graphical interface is refreshed.
+//!
+//! The trajectory is not part of the undoable state of the application: undoing an operation does
+//! not remove recorded frames.
+
+use crate::app_state::design_interactor::{
+    controller::simulations::FrameSampler, presenter::SimulationUpdate,
+};
+use ahash::RandomState;
+use ensnano_design::{Design, helices::NuclCollection, nucl::Nucl};
+use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
+use ultraviolet::Vec3;
+
+/// The number of solver steps between two frames when none was chosen.
+pub const DEFAULT_FRAME_INTERVAL: NonZeroUsize = NonZeroUsize::new(100).unwrap();
+
+/// The positions of the nucleotides at a given step of a simulation.
+#[derive(Clone)]
+pub struct TrajectoryFrame {
+    /// The number of solver steps done since the start of the simulation.
+    pub step: usize,
+    /// The simulated time since the start of the simulation, in the time unit of its solver.
+    pub time: f32,
+    pub positions: Arc<HashMap<Nucl, Vec3>>,
+}
+
+pub struct TrajectoryRecorder {
+    recording: bool,
+    /// The number of solver steps between two frames.
+    interval: NonZeroUsize,
+    frames: Vec<TrajectoryFrame>,
+    /// The frame currently shown instead of the simulated positions.
+    shown_frame: Option<usize>,
+}
+
+impl Default for TrajectoryRecorder {
+    fn default() -> Self {
+        Self {
+            recording: false,
+            interval: DEFAULT_FRAME_INTERVAL,
+            frames: Vec::new(),
+            shown_frame: None,
+        }
+    }
+}
+
+/// What the graphical interface needs to know about the recorded trajectory.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrajectorySummary {
+    pub recording: bool,
+    /// The number of solver steps between two frames.
+    pub interval: usize,
+    pub nb_frames: usize,
+    /// The step of the last recorded frame.
+    pub last_step: usize,
+    /// The simulated time of the last recorded frame.
+    pub last_time: f32,
+    pub shown_frame: Option<usize>,
+}
+
+impl TrajectoryRecorder {
+    pub fn summary(&self) -> TrajectorySummary {
+        let last_frame = self.frames.last();
+        TrajectorySummary {
+            recording: self.recording,
+            interval: self.interval.get(),
+            nb_frames: self.frames.len(),
+            last_step: last_frame.map(|f| f.step).unwrap_or_default(),
+            last_time: last_frame.map(|f| f.time).unwrap_or_default(),
+            shown_frame: self.shown_frame,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+    }
+
+    /// Set the number of solver steps between two frames. The interval is given to the
+    /// simulations when they start, so it does not change the interval of a running simulation.
+    pub fn set_interval(&mut self, interval: NonZeroUsize) {
+        self.interval = interval;
+    }
+
+    /// The sampler of the frames of the simulations that start now.
+    ///
+    /// Frames are sampled even when the recorder is not recording, so that the recording can be
+    /// started while a simulation is running.
+    pub fn sampler(&self) -> FrameSampler {
+        FrameSampler::every(self.interval)
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.shown_frame = None;
+    }
+
+    /// Record the positions of the nucleotides after `step` solver steps, if the recorder is
+    /// recording. Return true if a frame was recorded.
+    pub fn record(
+        &mut self,
+        step: usize,
+        time: f32,
+        positions: impl FnOnce() -> HashMap<Nucl, Vec3>,
+    ) -> bool {
+        if !self.recording {
+            return false;
+        }
+        self.frames.push(TrajectoryFrame {
+            step,
+            time,
+            positions: Arc::new(positions()),
+        });
+        self.shown_frame = None;
+        true
+    }
+
+    pub fn frames(&self) -> &[TrajectoryFrame] {
+        &self.frames
+    }
+
+    /// Select the frame to show, and return the update of the positions that shows it.
+    pub fn show_frame(&mut self, frame: usize) -> Option<TrajectoryFrameUpdate> {
+        let positions = self.frames.get(frame)?.positions.clone();
+        self.shown_frame = Some(frame);
+        Some(TrajectoryFrameUpdate(positions))
+    }
+
+    /// Forget the frame that is shown, for example because the positions were modified by a
+    /// simulation.
+    pub fn hide_frame(&mut self) {
+        self.shown_frame = None;
+    }
+}
+
+/// Moves the nucleotides to the positions of a recorded frame.
+pub struct TrajectoryFrameUpdate(Arc<HashMap<Nucl, Vec3>>);
+
+impl SimulationUpdate for TrajectoryFrameUpdate {
+    fn update_design(&self, _: &mut Design) {
+        // Only the positions of the nucleotides are changed
+    }
+
+    fn update_positions(
+        &self,
+        identifier_nucl: &NuclCollection,
+        space_position: &mut HashMap<u32, [f32; 3], RandomState>,
+    ) {
+        for (nucl, position) in self.0.iter() {
+            if let Some(id) = identifier_nucl.get_identifier(nucl) {
+                space_position.insert(*id, (*position).into());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions() -> HashMap<Nucl, Vec3> {
+        HashMap::new()
+    }
+
+    #[test]
+    fn frames_are_sampled_every_interval() {
+        let mut recorder = TrajectoryRecorder::default();
+        recorder.set_interval(NonZeroUsize::new(10).unwrap());
+        let sampler = recorder.sampler();
+        let sampled_steps = (0..35)
+            .filter(|step| sampler.is_due(*step))
+            .collect::<Vec<_>>();
+        assert_eq!(sampled_steps, [0, 10, 20, 30]);
+    }
+
+    #[test]
+    fn frames_are_only_recorded_while_recording() {
+        let mut recorder = TrajectoryRecorder::default();
+        assert!(!recorder.record(0, 0., positions));
+
+        recorder.set_recording(true);
+        assert!(recorder.record(100, 0.5, positions));
+        assert!(recorder.record(200, 1., positions));
+        recorder.set_recording(false);
+        assert!(!recorder.record(300, 1.5, positions));
+
+        let summary = recorder.summary();
+        assert_eq!(summary.nb_frames, 2);
+        assert_eq!(summary.last_step, 200);
+        assert!((summary.last_time - 1.).abs() < f32::EPSILON);
+    }
+}
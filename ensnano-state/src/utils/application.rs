@@ -1,6 +1,9 @@
 use crate::{design::selection::Selection, state::MainState};
 use ensnano_design::group_attributes::GroupPivot;
-use ensnano_utils::graphics::{DrawArea, FogParameters};
+use ensnano_utils::{
+    export::VectorExportFormat,
+    graphics::{DrawArea, FogParameters},
+};
 use std::{path::Path, sync::Arc, time::Duration};
 use ultraviolet::{Rotor3, Vec3};
 use winit::{
@@ -83,6 +86,7 @@ pub enum Notification {
     ScreenShot3D(Option<Arc<Path>>),
     SaveNucleotidesPositions(Option<Arc<Path>>),
     StlExport(Option<Arc<Path>>),
+    VectorExport2D(Option<Arc<Path>>, VectorExportFormat),
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
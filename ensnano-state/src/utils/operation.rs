@@ -3,15 +3,15 @@ use crate::design::operation::{
 };
 use ensnano_design::{
     bezier_plane::{BezierPlaneId, BezierVertexId},
-    curves::bezier::BezierControlPoint,
+    curves::{bezier::BezierControlPoint, parametric::ParametricCurveDescriptor},
     grid::{GridId, HelixGridPosition},
     nucl::Nucl,
     organizer_tree::GroupId,
 };
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
 use ultraviolet::{Bivec3, Rotor3, Vec2, Vec3};
 
-pub trait SimpleOperation: std::fmt::Debug + Sync + Send {
+pub trait SimpleOperation: std::fmt::Debug + Sync + Send + Any {
     /// The effect of self that must be sent as a notifications to the targeted designs.
     fn effect(&self) -> DesignOperation;
 
@@ -39,6 +39,13 @@ pub trait SimpleOperation: std::fmt::Debug + Sync + Send {
     }
 }
 
+impl dyn SimpleOperation {
+    /// True if the operation is a `T`.
+    pub fn is<T: SimpleOperation>(&self) -> bool {
+        (self as &dyn Any).is::<T>()
+    }
+}
+
 pub struct CurrentOpState {
     pub current_operation: Arc<dyn SimpleOperation>,
     pub operation_id: usize,
@@ -467,6 +474,63 @@ impl SimpleOperation for GridHelixCreation {
     }
 }
 
+/// Create an helix following a parametric curve. Editing the parameters replaces the helix created
+/// previously, which gives a live preview of the curve.
+#[derive(Debug, Clone)]
+pub struct ParametricHelixCreation {
+    pub descriptor: ParametricCurveDescriptor,
+    pub replace: bool,
+}
+
+impl ParametricHelixCreation {
+    pub const PARAMETERS: &'static [&'static str] = &["x(t)", "y(t)", "z(t)", "t min", "t max"];
+}
+
+impl SimpleOperation for ParametricHelixCreation {
+    fn parameters(&self) -> &[&'static str] {
+        Self::PARAMETERS
+    }
+
+    fn values(&self) -> Vec<String> {
+        vec![
+            self.descriptor.x.clone(),
+            self.descriptor.y.clone(),
+            self.descriptor.z.clone(),
+            self.descriptor.t_min.to_string(),
+            self.descriptor.t_max.to_string(),
+        ]
+    }
+
+    fn effect(&self) -> DesignOperation {
+        DesignOperation::AddParametricHelix(self.descriptor.clone())
+    }
+
+    fn description(&self) -> String {
+        "Create parametric helix".into()
+    }
+
+    fn with_new_value(&self, n: usize, val: String) -> Option<Arc<dyn SimpleOperation>> {
+        let mut descriptor = self.descriptor.clone();
+        match n {
+            0 => descriptor.x = val,
+            1 => descriptor.y = val,
+            2 => descriptor.z = val,
+            3 => descriptor.t_min = val.parse().ok()?,
+            4 => descriptor.t_max = val.parse().ok()?,
+            _ => return None,
+        }
+        descriptor.check().ok()?;
+        Some(Arc::new(Self {
+            descriptor,
+            replace: true,
+        }))
+    }
+
+    fn replace_previous(&self) -> bool {
+        self.replace
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Cut a strand at a given nucleotide.
 ///
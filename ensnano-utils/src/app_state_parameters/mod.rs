@@ -6,6 +6,7 @@ use self::{
 };
 use crate::{
     graphics::{Background3D, HBondDisplay, RenderingMode},
+    keymap::Keymap,
     ui_size::UiSize,
 };
 use serde::{Deserialize, Serialize};
@@ -31,6 +32,9 @@ pub struct AppStateParameters {
     pub ui_size: UiSize,
     #[serde(default = "show")]
     pub show_external_objects: bool,
+    pub keymap: Keymap,
+    /// The maximum amount of memory, in megabytes, used to store the states that can be undone.
+    pub undo_memory_budget_mb: usize,
 }
 
 impl Default for AppStateParameters {
@@ -49,6 +53,8 @@ impl Default for AppStateParameters {
             show_bezier_paths: false,
             ui_size: Default::default(),
             show_external_objects: true,
+            keymap: Default::default(),
+            undo_memory_budget_mb: 1024,
         }
     }
 }
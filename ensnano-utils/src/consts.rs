@@ -119,8 +119,8 @@ pub const ORIGAMI_EXTENSION: &str = "origami";
 pub const ENS_EXTENSION: &str = "ens";
 pub const ENS_BACKUP_EXTENSION: &str = "ensbackup";
 pub const ENS_UNNAMED_FILE_NAME: &str = "Unnamed_design";
-pub const CANNOT_OPEN_DEFAULT_DIR: &str = "Unable to open document or home directory.
-No backup will be saved for this unnamed design";
+pub const CANNOT_OPEN_DEFAULT_DIR: &str = "Unable to open the local data directory.
+No autosave will be made during this session";
 
 pub const NO_DESIGN_TITLE: &str = "New file";
 
@@ -131,6 +131,8 @@ pub const BEZIER_END_COLOR: u32 = 0xFF_F0_CA_22;
 pub const BEZIER_CONTROL1_COLOR: u32 = 0xFF_37_85_30;
 pub const BEZIER_CONTROL2_COLOR: u32 = 0xFF_1A_15_70;
 pub const SEC_BETWEEN_BACKUPS: u64 = 60;
+/// The number of snapshots kept in the autosave directory of a design.
+pub const NB_AUTOSAVES: usize = 5;
 pub const SEC_PER_YEAR: u64 = 31_536_000;
 
 pub const DEFAULT_STEREOGRAPHIC_ZOOM: f32 = 3.0;
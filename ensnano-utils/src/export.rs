@@ -2,6 +2,23 @@
 #[derive(Debug, Clone)]
 pub enum ExportType {
     Cadnano,
+    Scadnano,
     Pdb,
     Oxdna,
 }
+
+/// The file formats to which the 2D view can be exported as vector graphics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorExportFormat {
+    Svg,
+    Pdf,
+}
+
+impl VectorExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Pdf => "pdf",
+        }
+    }
+}
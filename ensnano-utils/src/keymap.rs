@@ -0,0 +1,662 @@
+//! User-configurable keyboard shortcuts.
+//!
+//! A [Keymap] associates [key chords](KeyChord) to named [actions](KeymapAction). Each action
+//! belongs to a [KeymapScope]: global actions are intercepted by the multiplexer before the event
+//! reaches any view, while the other actions are only handled by the 3D or the 2D view.
+//!
+//! The keymap is stored with the other user preferences in the `confy` configuration file. A
+//! keymap read from the configuration file is completed with the default bindings of the actions
+//! that it does not mention, so that newly introduced actions always get a shortcut.
+
+use crate::consts::{
+    ALT, BACKSPACE_CHAR, CTRL, KEY_DOWN, KEY_LEFT, KEY_RIGHT, KEY_UP, SHIFT, SUPPR_CHAR,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+/// The region of the application in which a [KeymapAction] is handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeymapScope {
+    /// Handled by the multiplexer, whatever the view under the cursor.
+    Global,
+    /// Handled by the 3D view when it is under the cursor.
+    Scene3D,
+    /// Handled by the 2D view when it is under the cursor.
+    Scene2D,
+}
+
+impl KeymapScope {
+    pub const ALL: [Self; 3] = [Self::Global, Self::Scene3D, Self::Scene2D];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Global => "2D/3D view",
+            Self::Scene3D => "3D view",
+            Self::Scene2D => "2D view",
+        }
+    }
+
+    /// Return true if a key chord bound in both scopes would be ambiguous.
+    ///
+    /// Global shortcuts are intercepted before reaching the views, so they shadow the shortcuts
+    /// of every other scope.
+    fn overlaps(&self, other: &Self) -> bool {
+        self == other || *self == Self::Global || *other == Self::Global
+    }
+}
+
+/// A named action that can be triggered by a keyboard shortcut.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeymapAction {
+    Undo,
+    Redo,
+    Copy,
+    Paste,
+    Duplicate,
+    TurnIntoAnchor,
+    Save,
+    Open,
+    Quit,
+    DeleteSelection,
+    RecolorStaples,
+    ToggleAllHelicesOnAxis,
+    MakeAllSuggestedXovers,
+    MakeAllSuggestedDoubleXovers,
+    ActionModeSelect,
+    ActionModeTranslate,
+    ActionModeRotate,
+    SelectionModeNucleotide,
+    SelectionModeStrand,
+    SelectionModeHelix,
+    AlignWithStereographicCamera,
+    CheckXovers,
+    PivotAtOrigin,
+    ReverseSurfaceDirection,
+    ToggleWidgetBasis,
+    RotateCameraLeft,
+    RotateCameraRight,
+    RotateCameraUp,
+    RotateCameraDown,
+    TiltCamera2DLeft,
+    TiltCamera2DRight,
+    SymmetryX2D,
+    SymmetryY2D,
+    MoveHelixBackward2D,
+    MoveHelixForward2D,
+    CommandPalette,
+}
+
+impl KeymapAction {
+    pub const ALL: [Self; 36] = [
+        Self::Undo,
+        Self::Redo,
+        Self::Copy,
+        Self::Paste,
+        Self::Duplicate,
+        Self::TurnIntoAnchor,
+        Self::Save,
+        Self::Open,
+        Self::Quit,
+        Self::DeleteSelection,
+        Self::RecolorStaples,
+        Self::ToggleAllHelicesOnAxis,
+        Self::MakeAllSuggestedXovers,
+        Self::MakeAllSuggestedDoubleXovers,
+        Self::ActionModeSelect,
+        Self::ActionModeTranslate,
+        Self::ActionModeRotate,
+        Self::SelectionModeNucleotide,
+        Self::SelectionModeStrand,
+        Self::SelectionModeHelix,
+        Self::AlignWithStereographicCamera,
+        Self::CheckXovers,
+        Self::PivotAtOrigin,
+        Self::ReverseSurfaceDirection,
+        Self::ToggleWidgetBasis,
+        Self::RotateCameraLeft,
+        Self::RotateCameraRight,
+        Self::RotateCameraUp,
+        Self::RotateCameraDown,
+        Self::TiltCamera2DLeft,
+        Self::TiltCamera2DRight,
+        Self::SymmetryX2D,
+        Self::SymmetryY2D,
+        Self::MoveHelixBackward2D,
+        Self::MoveHelixForward2D,
+        Self::CommandPalette,
+    ];
+
+    pub fn scope(&self) -> KeymapScope {
+        match self {
+            Self::AlignWithStereographicCamera
+            | Self::CheckXovers
+            | Self::PivotAtOrigin
+            | Self::ReverseSurfaceDirection
+            | Self::ToggleWidgetBasis
+            | Self::RotateCameraLeft
+            | Self::RotateCameraRight
+            | Self::RotateCameraUp
+            | Self::RotateCameraDown => KeymapScope::Scene3D,
+            Self::TiltCamera2DLeft
+            | Self::TiltCamera2DRight
+            | Self::SymmetryX2D
+            | Self::SymmetryY2D
+            | Self::MoveHelixBackward2D
+            | Self::MoveHelixForward2D => KeymapScope::Scene2D,
+            _ => KeymapScope::Global,
+        }
+    }
+
+    /// A short description of the action, meant to be displayed in the help.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Undo => "Undo",
+            Self::Redo => "Redo",
+            Self::Copy => "Copy selection",
+            Self::Paste => "Paste",
+            Self::Duplicate => "Paste & repeat",
+            Self::TurnIntoAnchor => "Turn selection into anchors",
+            Self::Save => "Save design",
+            Self::Open => "Open design",
+            Self::Quit => "Quit",
+            Self::DeleteSelection => "Delete selection",
+            Self::RecolorStaples => "Recolor staples",
+            Self::ToggleAllHelicesOnAxis => "Toggle helices on/off axis",
+            Self::MakeAllSuggestedXovers => "Make all suggested crossovers",
+            Self::MakeAllSuggestedDoubleXovers => "Make all suggested double crossovers",
+            Self::ActionModeSelect => "Select mode",
+            Self::ActionModeTranslate => "Translation mode",
+            Self::ActionModeRotate => "Rotation mode",
+            Self::SelectionModeNucleotide => "Nucleotide selection mode",
+            Self::SelectionModeStrand => "Strand selection mode",
+            Self::SelectionModeHelix => "Helix selection mode",
+            Self::AlignWithStereographicCamera => "Align camera with stereographic camera",
+            Self::CheckXovers => "Check selected crossovers",
+            Self::PivotAtOrigin => "Set pivot at origin",
+            Self::ReverseSurfaceDirection => "Reverse surface direction",
+            Self::ToggleWidgetBasis => "Toggle widget basis",
+            Self::RotateCameraLeft => "Rotate camera left",
+            Self::RotateCameraRight => "Rotate camera right",
+            Self::RotateCameraUp => "Rotate camera up",
+            Self::RotateCameraDown => "Rotate camera down",
+            Self::TiltCamera2DLeft => "Tilt camera left",
+            Self::TiltCamera2DRight => "Tilt camera right",
+            Self::SymmetryX2D => "Horizontal symmetry of the view",
+            Self::SymmetryY2D => "Vertical symmetry of the view",
+            Self::MoveHelixBackward2D => "Move helix backward",
+            Self::MoveHelixForward2D => "Move helix forward",
+            Self::CommandPalette => "Open the command palette",
+        }
+    }
+}
+
+/// A key together with the modifiers that must be held when it is pressed.
+///
+/// Key chords are serialized as human readable strings such as `"Ctrl+Shift+Z"` or `"Delete"`.
+/// On macOS, `Ctrl` stands for the command key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyChord {
+    /// The lowercase character of the key, or the name of a non-character key.
+    pub key: String,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+/// Names of the non-character keys that can be used in a [KeyChord].
+const NAMED_KEYS: [(&str, NamedKey); 9] = [
+    ("Escape", NamedKey::Escape),
+    ("Delete", NamedKey::Delete),
+    ("Backspace", NamedKey::Backspace),
+    ("Space", NamedKey::Space),
+    ("Enter", NamedKey::Enter),
+    ("Left", NamedKey::ArrowLeft),
+    ("Right", NamedKey::ArrowRight),
+    ("Up", NamedKey::ArrowUp),
+    ("Down", NamedKey::ArrowDown),
+];
+
+impl KeyChord {
+    fn new(key: &str, ctrl: bool, shift: bool, alt: bool) -> Self {
+        Self {
+            key: key.to_owned(),
+            ctrl,
+            shift,
+            alt,
+        }
+    }
+
+    fn plain(key: &str) -> Self {
+        Self::new(key, false, false, false)
+    }
+
+    fn ctrl(key: &str) -> Self {
+        Self::new(key, true, false, false)
+    }
+
+    fn shift(key: &str) -> Self {
+        Self::new(key, false, true, false)
+    }
+
+    fn alt(key: &str) -> Self {
+        Self::new(key, false, false, true)
+    }
+
+    /// The chord corresponding to a key pressed while `modifiers` are held.
+    ///
+    /// Return `None` for keys that cannot be bound.
+    pub fn from_key(key: &Key, modifiers: &ModifiersState) -> Option<Self> {
+        let key = match key.as_ref() {
+            Key::Character(c) => c.to_lowercase(),
+            Key::Named(named) => NAMED_KEYS
+                .iter()
+                .find(|(_, n)| *n == named)
+                .map(|(name, _)| (*name).to_owned())?,
+            _ => return None,
+        };
+        Some(Self {
+            key,
+            ctrl: control_key(modifiers),
+            shift: modifiers.shift_key(),
+            alt: modifiers.alt_key(),
+        })
+    }
+
+    /// Return true if the chord is made with `key`, whatever the modifiers.
+    pub fn uses_key(&self, key: &Key) -> bool {
+        Self::from_key(key, &ModifiersState::empty()).is_some_and(|chord| chord.key == self.key)
+    }
+
+    /// A representation of the chord using the same symbols as the rest of the GUI.
+    pub fn display(&self) -> String {
+        let mut parts = Vec::new();
+        if self.ctrl {
+            parts.push(CTRL.to_owned());
+        }
+        if self.alt {
+            parts.push(ALT.to_owned());
+        }
+        if self.shift {
+            parts.push(SHIFT.to_string());
+        }
+        parts.push(match self.key.as_str() {
+            "Escape" => "ESC".to_owned(),
+            "Delete" => SUPPR_CHAR.to_string(),
+            "Backspace" => BACKSPACE_CHAR.to_string(),
+            "Space" => "\u{23b5}".to_owned(),
+            "Enter" => "\u{23ce}".to_owned(),
+            "Left" => KEY_LEFT.to_string(),
+            "Right" => KEY_RIGHT.to_string(),
+            "Up" => KEY_UP.to_string(),
+            "Down" => KEY_DOWN.to_string(),
+            c => c.to_uppercase(),
+        });
+        parts.join(" + ")
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.key.chars().count() == 1 {
+            write!(f, "{}", self.key.to_uppercase())
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyChordError(String);
+
+impl fmt::Display for ParseKeyChordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid key chord \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseKeyChordError {}
+
+impl FromStr for KeyChord {
+    type Err = ParseKeyChordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseKeyChordError(s.to_owned());
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        // "Ctrl++" binds the '+' key.
+        if s.ends_with("++") {
+            parts.pop();
+            parts.pop();
+            parts.push("+");
+        }
+        let key = parts.pop().filter(|k| !k.is_empty()).ok_or_else(err)?;
+        let mut ret = Self::plain("");
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "cmd" => ret.ctrl = true,
+                "shift" => ret.shift = true,
+                "alt" => ret.alt = true,
+                _ => return Err(err()),
+            }
+        }
+        ret.key = if key.chars().count() == 1 {
+            key.to_lowercase()
+        } else {
+            NAMED_KEYS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(name, _)| (*name).to_owned())
+                .ok_or_else(err)?
+        };
+        Ok(ret)
+    }
+}
+
+impl TryFrom<String> for KeyChord {
+    type Error = ParseKeyChordError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<KeyChord> for String {
+    fn from(chord: KeyChord) -> Self {
+        chord.to_string()
+    }
+}
+
+/// macOS, Windows and Linux compatible modifier key.
+pub fn control_key(modifiers: &ModifiersState) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.super_key() // ❖ or ⌘
+    } else {
+        modifiers.control_key() // Ctrl
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub chord: KeyChord,
+    pub action: KeymapAction,
+}
+
+/// Two bindings of the keymap that cannot be told apart.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeymapConflict {
+    pub chord: KeyChord,
+    pub actions: (KeymapAction, KeymapAction),
+}
+
+impl fmt::Display for KeymapConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is bound to both \"{}\" and \"{}\"",
+            self.chord,
+            self.actions.0.description(),
+            self.actions.1.description()
+        )
+    }
+}
+
+/// The set of keyboard shortcuts of the application.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<KeyBinding>", into = "Vec<KeyBinding>")]
+pub struct Keymap {
+    bindings: Vec<KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use KeymapAction as A;
+
+        let mut bindings = vec![
+            (KeyChord::plain("Escape"), A::ActionModeSelect),
+            (KeyChord::alt("x"), A::MakeAllSuggestedXovers),
+            (
+                KeyChord::new("x", false, true, true),
+                A::MakeAllSuggestedDoubleXovers,
+            ),
+            (KeyChord::plain("x"), A::ToggleAllHelicesOnAxis),
+            (KeyChord::ctrl("z"), A::Undo),
+            (KeyChord::new("z", true, true, false), A::Redo),
+            (KeyChord::ctrl("r"), A::Redo),
+            (KeyChord::ctrl("c"), A::Copy),
+            (KeyChord::ctrl("v"), A::Paste),
+            (KeyChord::ctrl("j"), A::Duplicate),
+            (KeyChord::ctrl("l"), A::TurnIntoAnchor),
+            (KeyChord::ctrl("s"), A::Save),
+            (KeyChord::ctrl("o"), A::Open),
+            (KeyChord::new("p", true, true, false), A::CommandPalette),
+            (KeyChord::plain("r"), A::ActionModeRotate),
+            (KeyChord::plain("t"), A::ActionModeTranslate),
+            (KeyChord::plain("n"), A::SelectionModeNucleotide),
+            (KeyChord::plain("s"), A::SelectionModeStrand),
+            (KeyChord::plain("h"), A::SelectionModeHelix),
+            (KeyChord::plain("k"), A::RecolorStaples),
+            (KeyChord::plain("Delete"), A::DeleteSelection),
+            (KeyChord::plain("Backspace"), A::DeleteSelection),
+            (KeyChord::plain("a"), A::AlignWithStereographicCamera),
+            (KeyChord::plain("c"), A::CheckXovers),
+            (KeyChord::plain("q"), A::PivotAtOrigin),
+            (KeyChord::plain("w"), A::ReverseSurfaceDirection),
+            (KeyChord::plain("Space"), A::ToggleWidgetBasis),
+            // Camera rotations use shift so that they do not collide with the global selection
+            // mode and recoloring shortcuts.
+            (KeyChord::shift("h"), A::RotateCameraLeft),
+            (KeyChord::shift("l"), A::RotateCameraRight),
+            (KeyChord::shift("j"), A::RotateCameraUp),
+            (KeyChord::shift("k"), A::RotateCameraDown),
+            (KeyChord::alt("Left"), A::TiltCamera2DLeft),
+            (KeyChord::alt("Right"), A::TiltCamera2DRight),
+            (KeyChord::ctrl("Left"), A::SymmetryX2D),
+            (KeyChord::ctrl("Right"), A::SymmetryX2D),
+            (KeyChord::ctrl("Up"), A::SymmetryY2D),
+            (KeyChord::ctrl("Down"), A::SymmetryY2D),
+            (KeyChord::shift("j"), A::MoveHelixBackward2D),
+            (KeyChord::shift("k"), A::MoveHelixForward2D),
+        ];
+        if cfg!(target_os = "macos") {
+            bindings.push((KeyChord::ctrl("q"), A::Quit));
+        }
+
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|(chord, action)| KeyBinding { chord, action })
+                .collect(),
+        }
+    }
+}
+
+impl From<Vec<KeyBinding>> for Keymap {
+    fn from(mut bindings: Vec<KeyBinding>) -> Self {
+        let defaults = Self::default();
+        for action in KeymapAction::ALL {
+            if !bindings.iter().any(|b| b.action == action) {
+                bindings.extend(
+                    defaults
+                        .bindings
+                        .iter()
+                        .filter(|b| b.action == action)
+                        .cloned(),
+                );
+            }
+        }
+        Self { bindings }
+    }
+}
+
+impl From<Keymap> for Vec<KeyBinding> {
+    fn from(keymap: Keymap) -> Self {
+        keymap.bindings
+    }
+}
+
+impl Keymap {
+    pub fn bindings(&self) -> &[KeyBinding] {
+        &self.bindings
+    }
+
+    /// The action bound to `chord` in `scope`, if any.
+    pub fn action(&self, scope: KeymapScope, chord: &KeyChord) -> Option<KeymapAction> {
+        self.bindings
+            .iter()
+            .find(|b| b.action.scope() == scope && b.chord == *chord)
+            .map(|b| b.action)
+    }
+
+    /// The action bound in `scope` to the key pressed while `modifiers` are held, if any.
+    pub fn action_for_key(
+        &self,
+        scope: KeymapScope,
+        key: &Key,
+        modifiers: &ModifiersState,
+    ) -> Option<KeymapAction> {
+        KeyChord::from_key(key, modifiers).and_then(|chord| self.action(scope, &chord))
+    }
+
+    /// The chords bound to `action`.
+    pub fn chords(&self, action: KeymapAction) -> impl Iterator<Item = &KeyChord> {
+        self.bindings
+            .iter()
+            .filter(move |b| b.action == action)
+            .map(|b| &b.chord)
+    }
+
+    /// Return the pairs of bindings that share the same chord in overlapping scopes.
+    pub fn conflicts(&self) -> Vec<KeymapConflict> {
+        let mut ret = Vec::new();
+        for (i, b1) in self.bindings.iter().enumerate() {
+            for b2 in &self.bindings[i + 1..] {
+                if b1.chord == b2.chord
+                    && b1.action != b2.action
+                    && b1.action.scope().overlaps(&b2.action.scope())
+                {
+                    ret.push(KeymapConflict {
+                        chord: b1.chord.clone(),
+                        actions: (b1.action, b2.action),
+                    });
+                }
+            }
+        }
+        ret
+    }
+
+    /// The list of (shortcut, description) pairs of the actions of `scope`.
+    ///
+    /// Actions bound to several chords appear once, with their chords separated by "or".
+    pub fn cheat_sheet(&self, scope: KeymapScope) -> Vec<(String, String)> {
+        KeymapAction::ALL
+            .iter()
+            .filter(|action| action.scope() == scope)
+            .filter_map(|action| {
+                self.shortcut(*action)
+                    .map(|shortcut| (shortcut, action.description().to_owned()))
+            })
+            .collect()
+    }
+
+    /// The chords bound to `action` separated by "or", or `None` if `action` is not bound.
+    pub fn shortcut(&self, action: KeymapAction) -> Option<String> {
+        let chords: Vec<String> = self.chords(action).map(KeyChord::display).collect();
+        (!chords.is_empty()).then(|| chords.join(" or "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_keymap_has_no_conflicts() {
+        let conflicts = Keymap::default().conflicts();
+        assert!(conflicts.is_empty(), "{conflicts:?}");
+    }
+
+    #[test]
+    fn global_bindings_shadow_view_bindings() {
+        let keymap = Keymap::from(vec![
+            KeyBinding {
+                chord: KeyChord::plain("h"),
+                action: KeymapAction::SelectionModeHelix,
+            },
+            KeyBinding {
+                chord: KeyChord::plain("h"),
+                action: KeymapAction::RotateCameraLeft,
+            },
+        ]);
+        assert_eq!(
+            keymap.conflicts(),
+            vec![KeymapConflict {
+                chord: KeyChord::plain("h"),
+                actions: (
+                    KeymapAction::SelectionModeHelix,
+                    KeymapAction::RotateCameraLeft
+                ),
+            }]
+        );
+    }
+
+    #[test]
+    fn same_chord_in_2d_and_3d_is_not_a_conflict() {
+        let keymap = Keymap::default();
+        let chord = KeyChord::shift("j");
+        assert_eq!(
+            keymap.action(KeymapScope::Scene3D, &chord),
+            Some(KeymapAction::RotateCameraUp)
+        );
+        assert_eq!(
+            keymap.action(KeymapScope::Scene2D, &chord),
+            Some(KeymapAction::MoveHelixBackward2D)
+        );
+    }
+
+    #[test]
+    fn key_chord_round_trip() {
+        for s in [
+            "Ctrl+Shift+Z",
+            "Alt+X",
+            "Delete",
+            "Ctrl+Left",
+            "Shift+K",
+            "Ctrl++",
+        ] {
+            let chord: KeyChord = s.parse().unwrap();
+            assert_eq!(chord.to_string(), s);
+        }
+        assert_eq!(
+            "cmd+shift+z".parse(),
+            Ok(KeyChord::new("z", true, true, false))
+        );
+        assert!("Hyper+A".parse::<KeyChord>().is_err());
+        assert!("Ctrl+".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn missing_actions_get_default_bindings() {
+        let keymap = Keymap::from(vec![KeyBinding {
+            chord: KeyChord::ctrl("u"),
+            action: KeymapAction::Undo,
+        }]);
+        assert_eq!(
+            keymap.chords(KeymapAction::Undo).collect::<Vec<_>>(),
+            vec![&KeyChord::ctrl("u")]
+        );
+        assert_eq!(
+            keymap.action(KeymapScope::Global, &KeyChord::ctrl("c")),
+            Some(KeymapAction::Copy)
+        );
+    }
+}
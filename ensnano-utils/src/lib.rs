@@ -12,6 +12,7 @@ pub mod filename;
 pub mod graphics;
 pub mod instance;
 pub mod keyboard_priority;
+pub mod keymap;
 pub mod obj_loader;
 pub mod overlay;
 pub mod strand_builder;
@@ -23,6 +24,7 @@ pub mod ui_size;
 
 use crate::graphics::PhySize;
 use ensnano_design::{grid::GridId, nucl::Nucl};
+use serde::{Deserialize, Serialize};
 use wgpu::util::{BufferInitDescriptor, DeviceExt as _};
 
 pub const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
@@ -72,7 +74,8 @@ pub enum RapierSimulationRequest {
     Start,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RigidBodyConstants {
     pub k_spring: f32,
     pub k_friction: f32,
@@ -115,3 +115,57 @@ pub enum ErrStl {
     FileErr(std::io::Error),
     StlParseErr(nom_stl::Error),
 }
+
+/// The triangles of the mesh in a stl or gltf file, in nanometers.
+///
+/// As in `load_stl`, the coordinates of stl files are taken to be in Ångström and divided by 10, while those of gltf
+/// files are used as they are.
+pub fn load_triangles<P: AsRef<Path>>(path: P) -> Result<Vec<[Vec3; 3]>, ErrMesh> {
+    let path = path.as_ref();
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("stl") => {
+            // The vertices of `load_stl` are already rescaled to nanometers.
+            let mesh = load_stl(path).map_err(ErrMesh::Stl)?;
+            Ok(mesh
+                .vertices
+                .chunks_exact(3)
+                .map(|t| [0, 1, 2].map(|i| Vec3::from(t[i].position)))
+                .collect())
+        }
+        Some("gltf") => {
+            let (doc, data, _) = gltf::import(path).ok().ok_or(ErrMesh::Gltf)?;
+            let mut triangles = Vec::new();
+            for primitive in doc
+                .meshes()
+                .flat_map(|m| m.primitives().collect::<Vec<_>>())
+            {
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    continue;
+                }
+                let reader = primitive.reader(|b| Some(&data.get(b.index())?.0[..b.length()]));
+                let Some(positions) = reader.read_positions() else {
+                    continue;
+                };
+                let positions: Vec<Vec3> = positions.map(Vec3::from).collect();
+                let indices: Vec<usize> = match reader.read_indices() {
+                    Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+                    None => (0..positions.len()).collect(),
+                };
+                for t in indices.chunks_exact(3) {
+                    if let [Some(a), Some(b), Some(c)] = [0, 1, 2].map(|i| positions.get(t[i])) {
+                        triangles.push([*a, *b, *c]);
+                    }
+                }
+            }
+            Ok(triangles)
+        }
+        _ => Err(ErrMesh::UnknownFormat),
+    }
+}
+
+#[derive(Debug)]
+pub enum ErrMesh {
+    Stl(ErrStl),
+    Gltf,
+    UnknownFormat,
+}
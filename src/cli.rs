@@ -0,0 +1,72 @@
+//! Subcommands that work on design files from the command line, without opening a window.
+//!
+//! * `ensnano diff <old> <new>` prints the differences between two designs.
+//! * `ensnano merge <base> <ours> <theirs> [-o <output>]` merges the changes made in `theirs`
+//!   since `base` into `ours`. The result is written to `ours` unless an output is given, so that
+//!   the command can be used as a git merge driver with `ensnano merge %O %A %B`.
+
+use ensnano_design::{
+    Design,
+    diff::{diff, merge::merge},
+};
+use ensnano_state::app_state::design_interactor::file_parsing::read_file;
+use std::path::Path;
+
+const USAGE: &str = "usage:
+    ensnano diff <old> <new>
+    ensnano merge <base> <ours> <theirs> [-o <output>]";
+
+/// Run the subcommand named by the command line arguments and return the exit status of the
+/// process, or return `None` if the arguments do not name a subcommand.
+#[expect(clippy::print_stderr)] // The errors are reported like those of other command line tools.
+pub(crate) fn run(args: &[String]) -> Option<i32> {
+    let result = match args.first().map(String::as_str) {
+        Some("diff") => run_diff(&args[1..]),
+        Some("merge") => run_merge(&args[1..]),
+        _ => return None,
+    };
+    Some(result.unwrap_or_else(|e| {
+        eprintln!("{e}");
+        2
+    }))
+}
+
+/// Print the differences between two designs. The exit status is 1 if the designs differ, as for
+/// `diff`.
+fn run_diff(args: &[String]) -> Result<i32, String> {
+    let [old, new] = args else {
+        return Err(USAGE.to_owned());
+    };
+    let diff = diff(&load(old)?, &load(new)?);
+    print!("{diff}");
+    Ok(i32::from(!diff.is_empty()))
+}
+
+/// Merge two designs and report the conflicts. The exit status is 1 if there were conflicts, as
+/// expected from a git merge driver.
+fn run_merge(args: &[String]) -> Result<i32, String> {
+    let (inputs, output) = match args {
+        [inputs @ .., flag, output] if flag == "-o" => (inputs, Some(output)),
+        inputs => (inputs, None),
+    };
+    let [base, ours, theirs] = inputs else {
+        return Err(USAGE.to_owned());
+    };
+    let merged = merge(&load(base)?, &load(ours)?, &load(theirs)?);
+    let output = output.unwrap_or(ours);
+    let json = serde_json::to_string_pretty(&merged.design).map_err(|e| e.to_string())?;
+    std::fs::write(output, json).map_err(|e| format!("Could not write {output}: {e}"))?;
+    for conflict in &merged.conflicts {
+        println!("conflict: {conflict}");
+    }
+    Ok(i32::from(!merged.conflicts.is_empty()))
+}
+
+fn load(path: &str) -> Result<Design, String> {
+    if !Path::new(path).is_file() {
+        return Err(format!("File not found {path}"));
+    }
+    let mut design = read_file(path).map_err(|e| format!("Could not read {path}: {e}"))?;
+    design.strands.remove_empty_domains();
+    Ok(design)
+}
@@ -27,6 +27,29 @@ pub(super) fn successful_staples_export_msg<P: AsRef<Path>>(file: P) -> String {
 }
 
 pub(super) const OXDNA_EXPORT_FAILED: &str = "OxDNA export failed";
+
+pub(super) const TRAJECTORY_EXPORT_FAILED: &str = "Trajectory export failed";
+
+pub(super) const NO_TRAJECTORY_RECORDED: &str = "No trajectory recorded. Check \"Record trajectory\" in the simulation tab before running a simulation";
+
+pub(crate) fn rigidity_analysis_failed_msg(reason: &impl std::fmt::Display) -> String {
+    format!("The analysis of the rigidity failed:\n{reason}")
+}
+
+pub(super) fn simulation_preset_failed_msg(reason: &impl std::fmt::Display) -> String {
+    format!("Could not update the simulation presets:\n{reason}")
+}
+
+pub(super) fn script_failed_msg(reason: &str) -> String {
+    format!("The script failed and the design was left unchanged:\n{reason}")
+}
+pub(super) fn recovery_msg(design_name: &str) -> String {
+    format!(
+        "ENSnano did not close properly during the previous session.\n\
+        Do you want to recover the unsaved changes made to {design_name}?"
+    )
+}
+
 pub(super) const SAVE_DESIGN_FAILED: &str = "Could not save design";
 pub(super) const SAVE_BEFORE_EXIT: &str = "Do you want to save your design before exiting?";
 pub(super) const SAVE_BEFORE_LOAD: &str =
@@ -56,6 +79,7 @@ pub(super) fn invalid_sequence_file(first_invalid_char_position: usize) -> Strin
 }
 
 pub(super) const CADNANO_FILTERS: DialogFilters = &[DialogFilter::new("Cadnano files", &["json"])];
+pub(super) const SCADNANO_FILTERS: DialogFilters = &[DialogFilter::new("Scadnano files", &["sc"])];
 pub(super) const DESIGN_LOAD_FILTERS: DialogFilters = &[
     DialogFilter::new(
         "All supported files",
@@ -80,8 +104,12 @@ pub(super) const OXDNA_CONFIG_FILTERS: DialogFilters = &[DialogFilter::new(
 )];
 pub(super) const PDB_FILTERS: DialogFilters = &[DialogFilter::new("Pdb files", &["pdb"])];
 pub(super) const SEQUENCE_FILTERS: DialogFilters = &[DialogFilter::new("Text files", &["txt"])];
+pub(super) const SCRIPT_FILTERS: DialogFilters = &[DialogFilter::new("Rhai scripts", &["rhai"])];
 pub(super) const STAPLES_FILTERS: DialogFilters = &[DialogFilter::new("Excel files", &["xlsx"])];
 pub(super) const SVG_FILTERS: DialogFilters = &[DialogFilter::new("Svg files", &["svg"])];
+pub(super) const LATTICE_FILTERS: DialogFilters = &[DialogFilter::new("Lattice files", &["json"])];
+pub(super) const AXIS_FILTERS: DialogFilters =
+    &[DialogFilter::new("Point files", &["csv", "xyz", "txt"])];
 
 pub(super) const SET_DESIGN_DIRECTORY_FIRST: &str =
     "It is not possible to import 3D objects in an unnamed design.
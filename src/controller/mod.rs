@@ -2,9 +2,10 @@
 
 mod download_intervals;
 mod download_staples;
-mod messages;
+pub(crate) mod messages;
 mod normal_state;
 mod quit;
+mod recovery;
 pub(crate) mod set_scaffold_sequence;
 
 use self::normal_state::NormalState;
@@ -12,6 +13,7 @@ use crate::{
     MainStateView,
     dialog::{self, MustAckMessage, YesNoQuestion},
 };
+use ensnano_state::autosave::Recovery;
 use std::borrow::Cow;
 
 pub(crate) struct AutomataController {
@@ -26,6 +28,13 @@ impl AutomataController {
         }
     }
 
+    /// Start by asking the user whether the work of the previous session must be recovered.
+    pub(crate) fn with_recovery(recovery: Recovery) -> Self {
+        Self {
+            state: recovery::ask_recovery(recovery),
+        }
+    }
+
     /// This function is called to update the state of ENSnano. Its behavior depends on the state
     /// of the [Controller](`Controller`).
     pub(crate) fn make_progress(&mut self, main_state: &mut MainStateView) {
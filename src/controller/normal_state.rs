@@ -3,10 +3,11 @@ use super::{
     download_intervals::DownloadIntervals,
     download_staples::DownloadStaples,
     messages::{
-        CHANGING_DNA_PARAMETERS_WARNING, OXDNA_EXPORT_FAILED, SAVE_DESIGN_FAILED,
-        SET_DESIGN_DIRECTORY_FIRST,
+        CHANGING_DNA_PARAMETERS_WARNING, NO_TRAJECTORY_RECORDED, OXDNA_EXPORT_FAILED,
+        SAVE_DESIGN_FAILED, SET_DESIGN_DIRECTORY_FIRST, TRAJECTORY_EXPORT_FAILED,
+        script_failed_msg, simulation_preset_failed_msg,
     },
-    quit::{Exporting, Load, LoadType, NewDesign, Quit, SaveAs, SaveWithPath},
+    quit::{Exporting, ImportHelixAxes, Load, LoadType, NewDesign, Quit, SaveAs, SaveWithPath},
     set_scaffold_sequence::SetScaffoldSequence,
 };
 use crate::MainStateView;
@@ -21,6 +22,7 @@ use ensnano_state::{
         operation::{DesignOperation, HyperboloidOperation},
         selection::{all_helices_no_grid, extract_grids, extract_strands_from_selection},
     },
+    scripting::ScriptThread,
     utils::application::Notification,
 };
 use ensnano_utils::{consts::ENS_EXTENSION, export::ExportType};
@@ -84,6 +86,10 @@ impl AutomataState for NormalState {
                 main_state.redo();
                 self
             }
+            Action::JumpInHistory(position) => {
+                main_state.jump_in_history(position);
+                self
+            }
             Action::NotifyApps(notification) => {
                 main_state.notify_apps(notification);
                 self
@@ -112,12 +118,21 @@ impl AutomataState for NormalState {
                     )
                 }
             }
-            Action::ImportSvg => Load::load(None, LoadType::SvgPath),
+            Action::ImportSvg(parameters) => Load::load(None, LoadType::SvgPath(parameters)),
+            Action::ImportHelixAxes(parameters) => ImportHelixAxes::new(parameters),
+            Action::InsertDesign => Load::load(None, LoadType::InsertedDesign),
+            Action::ImportLattice => Load::load(None, LoadType::Lattice),
+            Action::RunScript(source) => run_script(source, main_state),
+            Action::RunScriptFile => Load::load(None, LoadType::Script),
             Action::SuspendOp => {
                 log::info!("Suspending operation");
                 main_state.finish_operation();
                 self
             }
+            Action::FinishPendingOp => {
+                main_state.finish_pending_operation();
+                self
+            }
             Action::Copy => {
                 main_state.request_copy();
                 self
@@ -199,6 +214,69 @@ impl AutomataState for NormalState {
                 main_state.main_state().update_rapier_parameters(parameters);
                 self
             }
+            Action::CommitRelaxation => {
+                main_state.main_state().commit_relaxation();
+                self
+            }
+            Action::SetTrajectoryRecording(recording) => {
+                main_state.main_state().set_trajectory_recording(recording);
+                self
+            }
+            Action::SetTrajectoryInterval(interval) => {
+                main_state.main_state().set_trajectory_interval(interval);
+                self
+            }
+            Action::ShowTrajectoryFrame(frame) => {
+                main_state.main_state().show_trajectory_frame(frame);
+                self
+            }
+            Action::ClearTrajectory => {
+                main_state.main_state().clear_trajectory();
+                self
+            }
+            Action::ExportTrajectory(export_type) => {
+                if main_state.main_state().trajectory.frames().is_empty() {
+                    TransitionMessage::new(
+                        NO_TRAJECTORY_RECORDED,
+                        rfd::MessageLevel::Error,
+                        Box::new(Self),
+                    )
+                } else {
+                    export_trajectory(export_type)
+                }
+            }
+            Action::AnalyseRigidity => {
+                main_state.main_state().analyse_rigidity();
+                self
+            }
+            Action::ClearRigidityAnalysis => {
+                main_state.main_state().clear_rigidity_analysis();
+                self
+            }
+            Action::ApplyEquilibriumShape => {
+                main_state.main_state().apply_equilibrium_shape();
+                self
+            }
+            Action::SaveSimulationPreset(preset) => {
+                match main_state.main_state().save_simulation_preset(preset) {
+                    Ok(()) => self,
+                    Err(err) => TransitionMessage::new(
+                        simulation_preset_failed_msg(&err),
+                        rfd::MessageLevel::Error,
+                        Box::new(Self),
+                    ),
+                }
+            }
+            Action::DeleteSimulationPreset(name) => {
+                match main_state.main_state().delete_simulation_preset(&name) {
+                    Ok(()) => self,
+                    Err(err) => TransitionMessage::new(
+                        simulation_preset_failed_msg(&err),
+                        rfd::MessageLevel::Error,
+                        Box::new(Self),
+                    ),
+                }
+            }
             Action::ResetSimulation => {
                 main_state.update_simulation(SimulationOperation::Reset);
                 self
@@ -323,7 +401,7 @@ impl NormalState {
         self
     }
 
-    fn add_grid(
+    pub(super) fn add_grid(
         self: Box<Self>,
         main_state: &mut MainStateView,
         descr: GridTypeDescr,
@@ -399,6 +477,41 @@ fn quicksave<P: AsRef<Path>>(starting_path: P) -> Box<dyn AutomataState> {
     })
 }
 
+pub(super) fn run_script(source: String, main_state: &mut MainStateView) -> Box<dyn AutomataState> {
+    Box::new(RunningScript(main_state.start_script(source)))
+}
+
+/// Wait for a script running in a separate thread. The actions of the user are processed once
+/// the script has finished, so that they apply to the design modified by the script.
+struct RunningScript(ScriptThread);
+
+impl AutomataState for RunningScript {
+    fn make_progress(self: Box<Self>, main_state: &mut MainStateView) -> Box<dyn AutomataState> {
+        let Some(outcome) = self.0.outcome() else {
+            return self;
+        };
+        if let Err(err) = main_state.finish_script(outcome) {
+            TransitionMessage::new(
+                script_failed_msg(&err),
+                rfd::MessageLevel::Error,
+                Box::new(NormalState),
+            )
+        } else {
+            Box::new(NormalState)
+        }
+    }
+}
+
+fn export_trajectory(export_type: ExportType) -> Box<dyn AutomataState> {
+    let on_success = Box::new(NormalState);
+    let on_error = TransitionMessage::new(
+        TRAJECTORY_EXPORT_FAILED,
+        rfd::MessageLevel::Error,
+        Box::new(NormalState),
+    );
+    Box::new(Exporting::trajectory(on_success, on_error, export_type))
+}
+
 fn export(export_type: ExportType) -> Box<dyn AutomataState> {
     let on_success = Box::new(NormalState);
     let on_error = TransitionMessage::new(
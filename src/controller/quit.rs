@@ -1,18 +1,24 @@
+use ensnano_design::{
+    bezier_plane::import_from_svg::SvgImportParameters,
+    curves::import_from_points::AxisImportParameters,
+    grid::{GridTypeDescr, lattice::Lattice},
+};
 use ensnano_utils::export::ExportType;
 
 use super::{
     AutomataState, TransitionMessage, YesNo,
     messages::{
-        CADNANO_FILTERS, DESIGN_LOAD_FILTERS, DESIGN_WRITE_FILTERS, NO_FILE_RECEIVED_LOAD,
-        NO_FILE_RECEIVED_OXDNA, NO_FILE_RECEIVED_SAVE, OBJECT3D_FILTERS, OXDNA_CONFIG_EXTENSION,
-        OXDNA_CONFIG_FILTERS, PDB_FILTERS, SAVE_BEFORE_EXIT, SAVE_BEFORE_LOAD, SAVE_BEFORE_NEW,
-        SAVE_BEFORE_RELOAD, SVG_FILTERS, failed_to_save_msg,
+        AXIS_FILTERS, CADNANO_FILTERS, DESIGN_LOAD_FILTERS, DESIGN_WRITE_FILTERS, LATTICE_FILTERS,
+        NO_FILE_RECEIVED_LOAD, NO_FILE_RECEIVED_OXDNA, NO_FILE_RECEIVED_SAVE, OBJECT3D_FILTERS,
+        OXDNA_CONFIG_EXTENSION, OXDNA_CONFIG_FILTERS, PDB_FILTERS, SAVE_BEFORE_EXIT,
+        SAVE_BEFORE_LOAD, SAVE_BEFORE_NEW, SAVE_BEFORE_RELOAD, SCADNANO_FILTERS, SCRIPT_FILTERS,
+        SVG_FILTERS, failed_to_save_msg,
     },
-    normal_state::NormalState,
+    normal_state::{NormalState, run_script},
 };
 use crate::{
     MainStateView,
-    dialog::{self, DialogFilters, PathInput},
+    dialog::{self, DialogFilters, PathInput, PathsInput},
 };
 use std::path::{Path, PathBuf};
 
@@ -136,7 +142,12 @@ enum LoadStep {
 pub(super) enum LoadType {
     Design,
     Object3D,
-    SvgPath,
+    SvgPath(SvgImportParameters),
+    Script,
+    /// A design inserted in the current one.
+    InsertedDesign,
+    /// A lattice on which a new grid is made.
+    Lattice,
 }
 
 impl AutomataState for Load {
@@ -151,7 +162,10 @@ impl AutomataState for Load {
             LoadStep::GotPath(path) => match self.load_type {
                 LoadType::Design => load_design(path, main_state),
                 LoadType::Object3D => load_3d_object(path, main_state),
-                LoadType::SvgPath => load_svg(path, main_state),
+                LoadType::SvgPath(parameters) => load_svg(path, parameters, main_state),
+                LoadType::Script => load_script(&path, main_state),
+                LoadType::InsertedDesign => insert_design(path, main_state),
+                LoadType::Lattice => load_lattice(&path, main_state),
             },
         }
     }
@@ -228,8 +242,10 @@ fn ask_path<P: AsRef<Path>>(
     } else {
         let filters = match load_type {
             LoadType::Object3D => OBJECT3D_FILTERS,
-            LoadType::Design => DESIGN_LOAD_FILTERS,
-            LoadType::SvgPath => SVG_FILTERS,
+            LoadType::Design | LoadType::InsertedDesign => DESIGN_LOAD_FILTERS,
+            LoadType::SvgPath(_) => SVG_FILTERS,
+            LoadType::Script => SCRIPT_FILTERS,
+            LoadType::Lattice => LATTICE_FILTERS,
         };
         let path_input = dialog::load(starting_directory, filters);
         Box::new(Load {
@@ -253,16 +269,100 @@ fn load_design(path: PathBuf, state: &mut MainStateView) -> Box<dyn AutomataStat
     }
 }
 
+fn insert_design(path: PathBuf, state: &mut MainStateView) -> Box<dyn AutomataState> {
+    state.insert_design(path);
+    Box::new(NormalState)
+}
+
+fn load_lattice(path: &Path, state: &mut MainStateView) -> Box<dyn AutomataState> {
+    let lattice = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str::<Lattice>(&json).map_err(|e| e.to_string()))
+        .and_then(|lattice| lattice.check().map(|()| lattice).map_err(|e| e.to_string()));
+    match lattice {
+        Ok(lattice) => Box::new(NormalState).add_grid(
+            state,
+            GridTypeDescr::Lattice {
+                lattice,
+                twist: None,
+            },
+        ),
+        Err(err) => TransitionMessage::new(
+            format!("Could not read the lattice {}:\n{err}", path.display()),
+            rfd::MessageLevel::Error,
+            Box::new(NormalState),
+        ),
+    }
+}
+
 fn load_3d_object(path: PathBuf, state: &mut MainStateView) -> Box<dyn AutomataState> {
     state.load_3d_object(path);
     Box::new(NormalState)
 }
 
-fn load_svg(path: PathBuf, state: &mut MainStateView) -> Box<dyn AutomataState> {
-    state.load_svg(path);
+fn load_svg(
+    path: PathBuf,
+    parameters: SvgImportParameters,
+    state: &mut MainStateView,
+) -> Box<dyn AutomataState> {
+    state.load_svg(path, parameters);
     Box::new(NormalState)
 }
 
+fn load_script(path: &Path, state: &mut MainStateView) -> Box<dyn AutomataState> {
+    match std::fs::read_to_string(path) {
+        Ok(source) => run_script(source, state),
+        Err(err) => TransitionMessage::new(
+            format!("Could not read {}:\n{err}", path.display()),
+            rfd::MessageLevel::Error,
+            Box::new(NormalState),
+        ),
+    }
+}
+
+/// Ask the user for files of points and import a helix axis from each of them.
+pub(super) struct ImportHelixAxes {
+    parameters: AxisImportParameters,
+    paths_input: Option<PathsInput>,
+}
+
+impl ImportHelixAxes {
+    pub(super) fn new(parameters: AxisImportParameters) -> Box<Self> {
+        Box::new(Self {
+            parameters,
+            paths_input: None,
+        })
+    }
+}
+
+impl AutomataState for ImportHelixAxes {
+    fn make_progress(self: Box<Self>, main_state: &mut MainStateView) -> Box<dyn AutomataState> {
+        let Some(paths_input) = self.paths_input else {
+            let paths_input =
+                dialog::load_many(main_state.get_current_design_directory(), AXIS_FILTERS);
+            return Box::new(Self {
+                paths_input: Some(paths_input),
+                ..*self
+            });
+        };
+        match paths_input.get() {
+            None => Box::new(Self {
+                paths_input: Some(paths_input),
+                ..*self
+            }),
+            Some(paths) if paths.is_empty() => TransitionMessage::new(
+                NO_FILE_RECEIVED_LOAD,
+                rfd::MessageLevel::Error,
+                Box::new(NormalState),
+            ),
+            Some(paths) => {
+                main_state.import_helix_axes(paths, self.parameters);
+                Box::new(NormalState)
+            }
+        }
+    }
+}
+
 pub(super) struct NewDesign {
     step: NewStep,
 }
@@ -417,6 +517,8 @@ pub(super) struct Exporting {
     on_success: Box<dyn AutomataState>,
     on_error: Box<dyn AutomataState>,
     export_type: ExportType,
+    /// Export the recorded trajectory instead of the current design.
+    trajectory: bool,
 }
 
 impl Exporting {
@@ -430,6 +532,18 @@ impl Exporting {
             on_success,
             on_error,
             export_type,
+            trajectory: false,
+        }
+    }
+
+    pub(super) fn trajectory(
+        on_success: Box<dyn AutomataState>,
+        on_error: Box<dyn AutomataState>,
+        export_type: ExportType,
+    ) -> Self {
+        Self {
+            trajectory: true,
+            ..Self::new(on_success, on_error, export_type)
         }
     }
 }
@@ -442,7 +556,12 @@ impl AutomataState for Exporting {
         if let Some(getter) = &self.file_getter {
             if let Some(path_opt) = getter.get() {
                 if let Some(path) = &path_opt {
-                    match main_state.export(path, self.export_type) {
+                    let result = if self.trajectory {
+                        main_state.export_trajectory(path, self.export_type)
+                    } else {
+                        main_state.export(path, self.export_type)
+                    };
+                    match result {
                         Err(err) => TransitionMessage::new(
                             failed_to_save_msg(&err),
                             rfd::MessageLevel::Error,
@@ -486,6 +605,7 @@ fn export_extension(export_type: ExportType) -> &'static str {
         ExportType::Oxdna => OXDNA_CONFIG_EXTENSION,
         ExportType::Pdb => "pdb",
         ExportType::Cadnano => "json",
+        ExportType::Scadnano => "sc",
     }
 }
 
@@ -494,5 +614,6 @@ fn export_filters(export_type: ExportType) -> DialogFilters {
         ExportType::Oxdna => OXDNA_CONFIG_FILTERS,
         ExportType::Pdb => PDB_FILTERS,
         ExportType::Cadnano => CADNANO_FILTERS,
+        ExportType::Scadnano => SCADNANO_FILTERS,
     }
 }
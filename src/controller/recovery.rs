@@ -0,0 +1,48 @@
+//! Recovery of the unsaved work of a session that ended unexpectedly.
+
+use super::{AutomataState, NormalState, TransitionMessage, YesNo, messages::recovery_msg};
+use crate::MainStateView;
+use ensnano_state::autosave::Recovery;
+
+/// Ask the user whether the unsaved work of the previous session should be recovered.
+pub(super) fn ask_recovery(recovery: Recovery) -> Box<dyn AutomataState> {
+    Box::new(YesNo::new(
+        recovery_msg(&recovery.design_name()),
+        Box::new(Recover {
+            recovery: recovery.clone(),
+            discard: false,
+        }),
+        Box::new(Recover {
+            recovery,
+            discard: true,
+        }),
+    ))
+}
+
+struct Recover {
+    recovery: Recovery,
+    discard: bool,
+}
+
+impl AutomataState for Recover {
+    fn make_progress(self: Box<Self>, main_state: &mut MainStateView) -> Box<dyn AutomataState> {
+        // The session starts first so that the recovered design is autosaved right away.
+        main_state.main_state().start_session();
+        let result = if self.discard {
+            Ok(())
+        } else {
+            main_state.recover(&self.recovery)
+        };
+        // The recovered design is journaled by the new session.
+        self.recovery.discard();
+        if let Err(e) = result {
+            TransitionMessage::new(
+                format!("Could not recover design: {e}"),
+                rfd::MessageLevel::Error,
+                Box::new(NormalState),
+            )
+        } else {
+            Box::new(NormalState)
+        }
+    }
+}
@@ -216,3 +216,35 @@ pub(crate) fn load<P: AsRef<Path>>(
     });
     PathInput(rcv)
 }
+
+/// The files chosen by the user in a dialog that accepts several files.
+pub(crate) struct PathsInput(mpsc::Receiver<Vec<PathBuf>>);
+impl PathsInput {
+    pub(crate) fn get(&self) -> Option<Vec<PathBuf>> {
+        self.0.try_recv().ok()
+    }
+}
+
+pub(crate) fn load_many<P: AsRef<Path>>(
+    starting_path: Option<P>,
+    dialog_filters: DialogFilters,
+) -> PathsInput {
+    let mut dialog = rfd::AsyncFileDialog::new();
+    for dialog_filter in dialog_filters {
+        dialog = dialog.add_filter(dialog_filter.name, dialog_filter.extensions);
+    }
+    if let Some(path) = starting_path {
+        dialog = dialog.set_directory(path);
+    }
+    let future_files = dialog.pick_files();
+    let (snd, rcv) = mpsc::channel();
+    thread::spawn(move || {
+        let load_op = async move {
+            let files = future_files.await.unwrap_or_default();
+            let path_bufs = files.iter().map(|handle| handle.path().into()).collect();
+            log_err![snd.send(path_bufs)];
+        };
+        futures::executor::block_on(load_op);
+    });
+    PathsInput(rcv)
+}
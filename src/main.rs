@@ -73,6 +73,7 @@
 #[cfg(test)]
 mod main_tests;
 
+mod cli;
 mod controller;
 mod dialog;
 mod overlay_manager;
@@ -81,6 +82,7 @@ mod scheduler;
 use crate::{
     controller::{
         AutomataController,
+        messages::rigidity_analysis_failed_msg,
         set_scaffold_sequence::{
             SetScaffoldSequenceError, SetScaffoldSequenceOk, TargetScaffoldLength,
         },
@@ -88,7 +90,10 @@ use crate::{
     overlay_manager::OverlayManager,
     scheduler::Scheduler,
 };
-use ensnano_design::{CameraId, grid::GridId, group_attributes::GroupPivot};
+use ensnano_design::{
+    CameraId, bezier_plane::import_from_svg::SvgImportParameters,
+    curves::import_from_points::AxisImportParameters, grid::GridId, group_attributes::GroupPivot,
+};
 use ensnano_exports::ExportResult;
 use ensnano_flatscene::FlatScene;
 use ensnano_gui::{
@@ -111,6 +116,7 @@ use ensnano_state::{
             },
         },
     },
+    autosave::{self, Recovery},
     design::{
         operation::{DesignOperation, DesignRotation, DesignTranslation, IsometryTarget},
         selection::{
@@ -121,6 +127,7 @@ use ensnano_state::{
     gui::messages::GuiMessages,
     multiplexer::Multiplexer,
     requests::{Requests, poll::poll_all},
+    scripting::{ScriptOutcome, ScriptThread},
     state::MainState,
     utils::application::{Camera3D, Notification},
 };
@@ -208,8 +215,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         pretty_env_logger::init();
     }
 
-    // Parse arguments. If an argument was given it is treated as a file to open.
-    let path = std::env::args().nth(1).map(PathBuf::from);
+    // Parse arguments. Unless they name a subcommand, the first argument is treated as a file to
+    // open.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(status) = cli::run(&args) {
+        std::process::exit(status);
+    }
+    let path = args.first().map(PathBuf::from);
 
     // Initialize winit. Create an event_loop and a window.
     let event_loop = EventLoop::new()?;
@@ -413,7 +425,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     main_state.update();
     main_state.last_saved_state = main_state.app_state.clone();
 
-    let mut controller = AutomataController::new();
+    // If the previous session ended unexpectedly, the current session starts once the user has
+    // decided what to do with its unsaved work.
+    let mut controller = if let Some(recovery) = autosave::unclean_session() {
+        AutomataController::with_recovery(recovery)
+    } else {
+        main_state.start_session();
+        AutomataController::new()
+    };
 
     // Run event loop
     let mut last_render_time = Instant::now();
@@ -531,9 +550,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 log::warn!("{:?}", result.err().unwrap());
                             }
                         }
+                        ChannelReaderUpdate::RigidityAnalysisResult(result) => {
+                            if let Err(err) = main_state.finish_rigidity_analysis(result) {
+                                main_state.pending_actions.push_back(Action::ErrorMsg(
+                                    rigidity_analysis_failed_msg(&err),
+                                ));
+                            }
+                        }
                     }
                 }
 
+                let mut simulation_progressed = false;
                 for update in main_state
                     .app_state
                     .0
@@ -542,17 +569,46 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     .get_updates()
                 {
                     match update {
+                        SimulationInterfaceUpdate::Frames(frames) => {
+                            main_state.record_simulation_frames(frames);
+                        }
                         SimulationInterfaceUpdate::SimulationUpdate(update) => {
                             main_state.app_state.apply_simulation_update(update);
+                            simulation_progressed = true;
                         }
                         SimulationInterfaceUpdate::SimulationExpired => {
                             main_state.apply_simulation_operation(SimulationOperation::Stop);
                         }
+                        SimulationInterfaceUpdate::Progress(Some((name, progress))) => {
+                            main_state
+                                .messages
+                                .lock()
+                                .unwrap()
+                                .push_progress(name, progress);
+                        }
+                        SimulationInterfaceUpdate::Progress(None) => {
+                            main_state.messages.lock().unwrap().finish_progress();
+                        }
+                        SimulationInterfaceUpdate::Finished(report) => {
+                            let mut messages = main_state.messages.lock().unwrap();
+                            messages.finish_progress();
+                            messages.relaxation_finished();
+                            match report {
+                                Ok(msg) => messages.push_message(msg),
+                                Err(msg) => {
+                                    drop(messages);
+                                    main_state.pending_actions.push_back(Action::ErrorMsg(msg));
+                                }
+                            }
+                        }
                     }
                 }
 
                 log::trace!("call update from main");
                 main_state.update();
+                if simulation_progressed {
+                    main_state.simulation_progressed();
+                }
 
                 let new_title = format!(
                     "{} {}",
@@ -858,10 +914,12 @@ fn handle_window_event(
             //let modifiers = multiplexer.modifiers();
 
             // Feed the event to the multiplexer
-            let event =
-                main_state_view
-                    .multiplexer
-                    .event(window_event, resized, scale_factor_changed);
+            let event = main_state_view.multiplexer.event(
+                window_event,
+                resized,
+                scale_factor_changed,
+                main_state_view.main_state.app_state.get_keymap(),
+            );
 
             if let Some((event, gui_component_type)) = event {
                 // Update the focused gui component
@@ -966,7 +1024,8 @@ impl MainStateView<'_> {
         self.main_state.last_backup_date.elapsed() > Duration::from_secs(SEC_BETWEEN_BACKUPS)
     }
 
-    fn exit_control_flow(&self) {
+    fn exit_control_flow(&mut self) {
+        self.main_state.end_session();
         self.window_target.exit();
     }
 
@@ -1011,6 +1070,35 @@ impl MainStateView<'_> {
         ret
     }
 
+    fn export_trajectory(&self, path: &PathBuf, export_type: ExportType) -> ExportResult {
+        let frames = self
+            .main_state
+            .trajectory
+            .frames()
+            .iter()
+            .map(|frame| ensnano_exports::TrajectoryFrame {
+                step: frame.step,
+                positions: frame.positions.as_ref().clone(),
+            })
+            .collect::<Vec<_>>();
+        ensnano_exports::export_trajectory(
+            &self.main_state.app_state.0.design.design,
+            export_type,
+            Some(
+                &self
+                    .main_state
+                    .app_state
+                    .0
+                    .design
+                    .presenter
+                    .content
+                    .letter_map,
+            ),
+            path,
+            &frames,
+        )
+    }
+
     fn load_design(&mut self, path: PathBuf) -> Result<(), LoadDesignError> {
         let state = AppState::import_design(path)?;
         self.notify_apps(Notification::ClearDesigns);
@@ -1033,6 +1121,14 @@ impl MainStateView<'_> {
         Ok(())
     }
 
+    fn recover(&mut self, recovery: &Recovery) -> Result<(), LoadDesignError> {
+        let state = recovery.restore()?;
+        self.notify_apps(Notification::ClearDesigns);
+        self.main_state.recover(state);
+        self.main_state.wants_fit = true;
+        Ok(())
+    }
+
     fn apply_design_operation(&mut self, operation: DesignOperation) {
         self.main_state.apply_design_operation(operation);
     }
@@ -1049,6 +1145,10 @@ impl MainStateView<'_> {
         self.main_state.redo();
     }
 
+    fn jump_in_history(&mut self, position: usize) {
+        self.main_state.jump_in_history(position);
+    }
+
     fn get_design_interactor(&self) -> DesignInteractor {
         self.main_state.app_state.get_design_interactor()
     }
@@ -1120,6 +1220,13 @@ impl MainStateView<'_> {
         self.main_state.app_state.finish_operation();
     }
 
+    fn finish_pending_operation(&mut self) {
+        self.main_state.modify_state(|s: &mut AppState| {
+            s.notify(InteractorNotification::FinishPendingOperation)
+        });
+        self.main_state.app_state.finish_operation();
+    }
+
     fn request_copy(&mut self) {
         self.main_state.request_copy();
     }
@@ -1370,8 +1477,60 @@ impl MainStateView<'_> {
         });
     }
 
-    fn load_svg(&mut self, path: PathBuf) {
-        self.apply_design_operation(DesignOperation::ImportSvgPath { path });
+    fn load_svg(&mut self, path: PathBuf, parameters: SvgImportParameters) {
+        self.apply_design_operation(DesignOperation::ImportSvgPath { path, parameters });
+    }
+
+    fn insert_design(&mut self, path: PathBuf) {
+        self.apply_design_operation(DesignOperation::InsertDesign { path });
+    }
+
+    fn import_helix_axes(&mut self, paths: Vec<PathBuf>, parameters: AxisImportParameters) {
+        self.apply_design_operation(DesignOperation::ImportHelixAxes { paths, parameters });
+    }
+
+    /// Start running a script on the design in a separate thread.
+    fn start_script(&self, source: String) -> ScriptThread {
+        self.main_state.start_script(source)
+    }
+
+    /// Apply the modifications made by a script, perform the exports that it requested and send
+    /// its output to the script console.
+    fn finish_script(&mut self, outcome: ScriptOutcome) -> Result<(), String> {
+        match self.main_state.finish_script(outcome) {
+            Ok(report) => {
+                let mut output = report.output;
+                for (export_type, path) in report.exports {
+                    // Relative paths are relative to the directory of the design.
+                    let path = match self.get_current_design_directory() {
+                        Some(directory) if path.is_relative() => directory.join(path),
+                        _ => path,
+                    };
+                    match self.export(&path, export_type) {
+                        Ok(success) => output.push(success.message()),
+                        Err(err) => {
+                            output.push(format!("Export to {} failed: {err:?}", path.display()));
+                        }
+                    }
+                }
+                self.main_state
+                    .messages
+                    .lock()
+                    .unwrap()
+                    .script_output(output);
+                Ok(())
+            }
+            Err(err) => {
+                let mut output = err.output;
+                output.push(err.message.clone());
+                self.main_state
+                    .messages
+                    .lock()
+                    .unwrap()
+                    .script_output(output);
+                Err(err.message)
+            }
+        }
     }
 
     fn set_scaffold_sequence(
@@ -117,6 +117,95 @@ fn recolor_staple_undoable() {
     assert!(!state.undo_stack.is_empty());
 }
 
+#[test]
+fn script_run_is_one_undo_step() {
+    let mut state = new_state();
+    state.clear_app_state(pastable_design());
+    state
+        .run_script(
+            r#"
+            for s in strands() {
+                set_strand_name(s, "staple " + s);
+                insert_after(strand(s).prime3, 5);
+            }
+            "#,
+        )
+        .unwrap();
+    assert_eq!(state.undo_stack.len(), 1);
+    state.undo();
+    let design = state.app_state.get_design_interactor().design;
+    assert!(design.strands.get(&0).unwrap().name.is_none());
+}
+
+#[test]
+fn script_run_in_background_is_one_undo_step() {
+    let mut state = new_state();
+    state.clear_app_state(pastable_design());
+    let thread = state.start_script(r#"set_strand_name(0, "staple");"#.to_owned());
+    let outcome = loop {
+        if let Some(outcome) = thread.outcome() {
+            break outcome;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    state.finish_script(outcome).unwrap();
+    assert_eq!(state.undo_stack.len(), 1);
+    let design = state.app_state.get_design_interactor().design;
+    assert_eq!(
+        design.strands.get(&0).unwrap().name.as_deref(),
+        Some("staple")
+    );
+}
+
+#[test]
+fn failed_script_is_not_undoable() {
+    let mut state = new_state();
+    state.clear_app_state(pastable_design());
+    assert!(state.run_script("recolor_staples(); cut(42);").is_err());
+    assert!(state.undo_stack.is_empty());
+}
+
+#[test]
+fn jump_in_labelled_history() {
+    let mut state = new_state();
+    state.update_selection(vec![Selection::Strand(0, 0)], None);
+    state.apply_design_operation(DesignOperation::RecolorStaples);
+    state.update_selection(vec![Selection::Strand(0, 1)], None);
+    let history = state.undo_history();
+    assert_eq!(
+        history.labels,
+        ["Selection", "Staple recoloring", "Selection"]
+    );
+    assert_eq!(history.position, 3);
+
+    state.jump_in_history(1);
+    assert_eq!(state.app_state.get_selection(), [Selection::Strand(0, 0)]);
+    let history = state.undo_history();
+    assert_eq!(history.labels.len(), 3);
+    assert_eq!(history.position, 1);
+
+    state.jump_in_history(3);
+    assert_eq!(state.app_state.get_selection(), [Selection::Strand(0, 1)]);
+}
+
+#[test]
+fn undo_stack_respects_memory_budget() {
+    let mut state = new_state();
+    state
+        .app_state
+        .0
+        .make_mut()
+        .parameters
+        .undo_memory_budget_mb = 0;
+    for s_id in 0..5 {
+        state.update_selection(vec![Selection::Strand(0, s_id)], None);
+    }
+    let history = state.undo_history();
+    assert_eq!(history.labels.len(), 1);
+    assert_eq!(history.nb_evicted, 4);
+    // Undoing is not tested here because it would store the modified preferences on disk.
+}
+
 /// A design with one strand h1: -1 -> 7 ; h2: -1 <- 7 ; h3: 0 -> 9 that can be pasted on
 /// helices 4, 5 and 6.
 fn pastable_design() -> AppState {